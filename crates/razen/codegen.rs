@@ -0,0 +1,29 @@
+mod abc_constant_pool;
+pub use abc_constant_pool::*;
+
+mod abc_emitter;
+pub use abc_emitter::*;
+
+mod abc_method_body;
+pub use abc_method_body::*;
+
+mod abc_name_lowering;
+pub use abc_name_lowering::*;
+
+mod asdoc_site;
+pub use asdoc_site::*;
+
+mod markdown;
+pub use markdown::*;
+
+mod mxml_lowering;
+pub use mxml_lowering::*;
+
+mod swc_library;
+pub use swc_library::*;
+
+mod swf_movie;
+pub use swf_movie::*;
+
+mod zip_archive;
+pub use zip_archive::*;
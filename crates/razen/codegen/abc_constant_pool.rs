@@ -0,0 +1,168 @@
+use crate::ns::*;
+use hydroper_swf::avm2::types as abc;
+
+/// Deduplicating builder of an ABC constant pool.
+///
+/// Every `add_*` method returns a one-based index into the respective
+/// constant table, as the index zero is reserved by the AVM2
+/// (for example, the empty string or the any name `*`).
+pub struct AbcConstantPool {
+    pool: abc::ConstantPool,
+    int_indices: HashMap<i32, u32>,
+    uint_indices: HashMap<u32, u32>,
+    double_indices: HashMap<u64, u32>,
+    string_indices: HashMap<String, u32>,
+    namespace_indices: HashMap<(u8, u32), u32>,
+    ns_set_indices: HashMap<Vec<u32>, u32>,
+    multiname_indices: HashMap<(u8, u32, u32, Vec<u32>), u32>,
+}
+
+impl Default for AbcConstantPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AbcConstantPool {
+    pub fn new() -> Self {
+        Self {
+            pool: abc::ConstantPool {
+                ints: vec![],
+                uints: vec![],
+                doubles: vec![],
+                strings: vec![],
+                namespaces: vec![],
+                namespace_sets: vec![],
+                multinames: vec![],
+            },
+            int_indices: HashMap::new(),
+            uint_indices: HashMap::new(),
+            double_indices: HashMap::new(),
+            string_indices: HashMap::new(),
+            namespace_indices: HashMap::new(),
+            ns_set_indices: HashMap::new(),
+            multiname_indices: HashMap::new(),
+        }
+    }
+
+    pub fn add_int(&mut self, value: i32) -> abc::Index<i32> {
+        if let Some(i) = self.int_indices.get(&value) {
+            return abc::Index::new(*i);
+        }
+        self.pool.ints.push(value);
+        let i = self.pool.ints.len() as u32;
+        self.int_indices.insert(value, i);
+        abc::Index::new(i)
+    }
+
+    pub fn add_uint(&mut self, value: u32) -> abc::Index<u32> {
+        if let Some(i) = self.uint_indices.get(&value) {
+            return abc::Index::new(*i);
+        }
+        self.pool.uints.push(value);
+        let i = self.pool.uints.len() as u32;
+        self.uint_indices.insert(value, i);
+        abc::Index::new(i)
+    }
+
+    /// Adds a double. Doubles are compared by their bit pattern,
+    /// so that `NaN` and `-0` are interned correctly.
+    pub fn add_double(&mut self, value: f64) -> abc::Index<f64> {
+        if let Some(i) = self.double_indices.get(&value.to_bits()) {
+            return abc::Index::new(*i);
+        }
+        self.pool.doubles.push(value);
+        let i = self.pool.doubles.len() as u32;
+        self.double_indices.insert(value.to_bits(), i);
+        abc::Index::new(i)
+    }
+
+    pub fn add_string(&mut self, value: &str) -> abc::Index<String> {
+        if let Some(i) = self.string_indices.get(value) {
+            return abc::Index::new(*i);
+        }
+        self.pool.strings.push(value.as_bytes().to_vec());
+        let i = self.pool.strings.len() as u32;
+        self.string_indices.insert(value.to_owned(), i);
+        abc::Index::new(i)
+    }
+
+    pub fn add_namespace(&mut self, ns: &abc::Namespace) -> abc::Index<abc::Namespace> {
+        let key = match ns {
+            abc::Namespace::Namespace(name) => (0, name.0),
+            abc::Namespace::Package(name) => (1, name.0),
+            abc::Namespace::PackageInternal(name) => (2, name.0),
+            abc::Namespace::Protected(name) => (3, name.0),
+            abc::Namespace::Explicit(name) => (4, name.0),
+            abc::Namespace::StaticProtected(name) => (5, name.0),
+            // Private namespaces are never shared, even if they
+            // have the same name.
+            abc::Namespace::Private(_) => {
+                self.pool.namespaces.push(ns.clone());
+                return abc::Index::new(self.pool.namespaces.len() as u32);
+            },
+        };
+        if let Some(i) = self.namespace_indices.get(&key) {
+            return abc::Index::new(*i);
+        }
+        self.pool.namespaces.push(ns.clone());
+        let i = self.pool.namespaces.len() as u32;
+        self.namespace_indices.insert(key, i);
+        abc::Index::new(i)
+    }
+
+    pub fn add_ns_set(&mut self, ns_set: &[abc::Index<abc::Namespace>]) -> abc::Index<abc::NamespaceSet> {
+        let key: Vec<u32> = ns_set.iter().map(|ns| ns.0).collect();
+        if let Some(i) = self.ns_set_indices.get(&key) {
+            return abc::Index::new(*i);
+        }
+        self.pool.namespace_sets.push(ns_set.to_vec());
+        let i = self.pool.namespace_sets.len() as u32;
+        self.ns_set_indices.insert(key, i);
+        abc::Index::new(i)
+    }
+
+    pub fn add_multiname(&mut self, mn: &abc::Multiname) -> abc::Index<abc::Multiname> {
+        let key = match mn {
+            abc::Multiname::QName { namespace, name } => (0, namespace.0, name.0, vec![]),
+            abc::Multiname::QNameA { namespace, name } => (1, namespace.0, name.0, vec![]),
+            abc::Multiname::RTQName { name } => (2, 0, name.0, vec![]),
+            abc::Multiname::RTQNameA { name } => (3, 0, name.0, vec![]),
+            abc::Multiname::RTQNameL => (4, 0, 0, vec![]),
+            abc::Multiname::RTQNameLA => (5, 0, 0, vec![]),
+            abc::Multiname::Multiname { namespace_set, name } => (6, namespace_set.0, name.0, vec![]),
+            abc::Multiname::MultinameA { namespace_set, name } => (7, namespace_set.0, name.0, vec![]),
+            abc::Multiname::MultinameL { namespace_set } => (8, namespace_set.0, 0, vec![]),
+            abc::Multiname::MultinameLA { namespace_set } => (9, namespace_set.0, 0, vec![]),
+            abc::Multiname::TypeName { base_type, parameters } => (10, base_type.0, 0, parameters.iter().map(|p| p.0).collect()),
+        };
+        if let Some(i) = self.multiname_indices.get(&key) {
+            return abc::Index::new(*i);
+        }
+        self.pool.multinames.push(mn.clone());
+        let i = self.pool.multinames.len() as u32;
+        self.multiname_indices.insert(key, i);
+        abc::Index::new(i)
+    }
+
+    /// Adds a `QName` multiname from a namespace and a local name.
+    pub fn add_qname(&mut self, ns: &abc::Namespace, local_name: &str) -> abc::Index<abc::Multiname> {
+        let namespace = self.add_namespace(ns);
+        let name = self.add_string(local_name);
+        self.add_multiname(&abc::Multiname::QName { namespace, name })
+    }
+
+    /// Adds a `QName` multiname in the public namespace of a package.
+    pub fn add_package_qname(&mut self, package_name: &str, local_name: &str) -> abc::Index<abc::Multiname> {
+        let package_name = self.add_string(package_name);
+        self.add_qname(&abc::Namespace::Package(package_name), local_name)
+    }
+
+    pub fn pool(&self) -> &abc::ConstantPool {
+        &self.pool
+    }
+
+    pub fn finish(self) -> abc::ConstantPool {
+        self.pool
+    }
+}
@@ -0,0 +1,2481 @@
+use crate::ns::*;
+use hydroper_swf::avm2::types as abc;
+use hydroper_swf::avm2::write::Writer as AbcWriter;
+
+/// ActionScript 3 bytecode (ABC) emitter.
+///
+/// The emitter walks verified programs and takes the semantic things
+/// attached to nodes from `SemanticHost::node_mapping()`. Expressions whose mapping
+/// is a constant yield a cheap AVM2 constant, conversion values are lowered to the
+/// respective AVM2 conversion instructions, and fixture references are lowered to
+/// compile-time `QName` multinames. Nodes that have no mapping fall back to dynamic
+/// lookups through the open namespace set.
+///
+/// Classes, interfaces and enums are emitted from the semantic model
+/// (their properties and prototype), taking method bodies and
+/// variable initializers from their definitions.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut emitter = AbcEmitter::new(&host);
+/// emitter.emit_program(&program);
+/// let payload = emitter.finish_do_abc2("main");
+/// ```
+pub struct AbcEmitter<'a> {
+    host: &'a SemanticHost,
+    pool: AbcConstantPool,
+    methods: Vec<abc::Method>,
+    metadata: Vec<abc::Metadata>,
+    instances: Vec<abc::Instance>,
+    classes: Vec<abc::Class>,
+    scripts: Vec<abc::Script>,
+    method_bodies: Vec<abc::MethodBody>,
+    names: AbcNameLowering<'a>,
+    class_indices: HashMap<Thingy, abc::Index<abc::Class>>,
+    referenced_types: Vec<Thingy>,
+}
+
+/// A `DoABC2` tag payload produced by `AbcEmitter::finish_do_abc2()`.
+pub struct AbcDoAbc2Payload {
+    pub name: String,
+    pub lazy_initialize: bool,
+    /// The encoded ABC file.
+    pub data: Vec<u8>,
+}
+
+impl AbcDoAbc2Payload {
+    pub fn to_tag(&self) -> hydroper_swf::Tag<'_> {
+        hydroper_swf::Tag::DoAbc2(hydroper_swf::DoAbc2 {
+            flags: if self.lazy_initialize { hydroper_swf::DoAbc2Flag::LAZY_INITIALIZE } else { hydroper_swf::DoAbc2Flag::empty() },
+            name: hydroper_swf::SwfStr::from_utf8_str(&self.name),
+            data: &self.data,
+        })
+    }
+
+    /// Encodes the tag body: the flags, the null-terminated name
+    /// and the ABC file.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut r = vec![];
+        r.extend_from_slice(&(if self.lazy_initialize { 1u32 } else { 0 }).to_le_bytes());
+        r.extend_from_slice(self.name.as_bytes());
+        r.push(0);
+        r.extend_from_slice(&self.data);
+        r
+    }
+}
+
+/// Reference produced by `AbcEmitter::emit_reference()`.
+#[derive(Copy, Clone)]
+enum AbcReference {
+    /// Property resolved through the scope chain; nothing is on the stack.
+    Lex(abc::Index<abc::Multiname>),
+    /// Property of the object on the top of the stack.
+    Property(abc::Index<abc::Multiname>),
+    /// Property whose name is resolved at runtime; the object
+    /// and the key are on the top of the stack.
+    RuntimeProperty(abc::Index<abc::Multiname>),
+    /// Property of the base class; the `this` object is on the top of the stack.
+    Super(abc::Index<abc::Multiname>),
+    /// Not a reference; the value is on the top of the stack.
+    Value,
+}
+
+enum AbcJumpContext {
+    Breakable {
+        labels: Vec<String>,
+        /// Whether an unlabeled `break` targets this statement.
+        implicit: bool,
+        break_label: AbcLabel,
+        continue_label: Option<AbcLabel>,
+        scope_count: usize,
+    },
+    Finally {
+        block: Rc<Block>,
+        scope_count: usize,
+    },
+}
+
+/// State of the method body being emitted.
+struct AbcFunctionState {
+    builder: AbcMethodBodyBuilder,
+    /// Registers holding the objects in the local scope stack,
+    /// along with whether they were pushed through `pushwith`.
+    /// Exception handlers push these again.
+    scopes: Vec<(u32, bool)>,
+    /// Register holding the activation object.
+    activation: Option<u32>,
+    jumps: Vec<AbcJumpContext>,
+    pending_labels: Vec<String>,
+    open_ns_set: abc::Index<abc::NamespaceSet>,
+    /// Traits of variables declared in the body. These belong to
+    /// the activation, or to the global object in script initializers.
+    var_traits: Vec<abc::Trait>,
+    var_trait_names: HashSet<u32>,
+    /// Where unmapped declarations are looked up by local name.
+    declaration_names: Option<NameMap>,
+    /// Whether declarations are stored into the `this` object
+    /// (used for instance variable initializers).
+    declaration_target_this: bool,
+    optional_chaining_registers: Vec<u32>,
+}
+
+impl AbcFunctionState {
+    fn new(builder: AbcMethodBodyBuilder, open_ns_set: abc::Index<abc::NamespaceSet>) -> Self {
+        Self {
+            builder,
+            scopes: vec![],
+            activation: None,
+            jumps: vec![],
+            pending_labels: vec![],
+            open_ns_set,
+            var_traits: vec![],
+            var_trait_names: HashSet::new(),
+            declaration_names: None,
+            declaration_target_this: false,
+            optional_chaining_registers: vec![],
+        }
+    }
+
+    fn emit(&mut self, op: abc::Op) {
+        self.builder.emit(op);
+    }
+
+    fn push_scope(&mut self, register: u32, with: bool) {
+        self.emit(abc::Op::GetLocal { index: register });
+        self.emit(if with { abc::Op::PushWith } else { abc::Op::PushScope });
+        self.scopes.push((register, with));
+    }
+}
+
+/// Methods, variables and initialization code taken from the block
+/// of a class, interface or enum definition.
+#[derive(Default)]
+struct AbcClassDefinitions {
+    /// Function definitions by whether they are static, their kind
+    /// (0 = method, 1 = getter, 2 = setter) and their local name.
+    functions: HashMap<(bool, u8, String), Rc<Directive>>,
+    constructor: Option<Rc<Directive>>,
+    instance_variables: Vec<Rc<Directive>>,
+    static_directives: Vec<Rc<Directive>>,
+}
+
+impl<'a> AbcEmitter<'a> {
+    pub fn new(host: &'a SemanticHost) -> Self {
+        Self {
+            host,
+            pool: AbcConstantPool::new(),
+            methods: vec![],
+            metadata: vec![],
+            instances: vec![],
+            classes: vec![],
+            scripts: vec![],
+            method_bodies: vec![],
+            names: AbcNameLowering::new(host),
+            class_indices: HashMap::new(),
+            referenced_types: vec![],
+        }
+    }
+
+    pub fn pool(&mut self) -> &mut AbcConstantPool {
+        &mut self.pool
+    }
+
+    /// Types referenced by emitted expressions, in order of first reference.
+    pub fn referenced_types(&self) -> &[Thingy] {
+        &self.referenced_types
+    }
+
+    fn add_referenced_type(&mut self, type_thingy: &Thingy) {
+        if !self.referenced_types.contains(type_thingy) {
+            self.referenced_types.push(type_thingy.clone());
+        }
+    }
+
+    pub fn finish(self) -> abc::AbcFile {
+        abc::AbcFile {
+            major_version: 46,
+            minor_version: 16,
+            constant_pool: self.pool.finish(),
+            methods: self.methods,
+            metadata: self.metadata,
+            instances: self.instances,
+            classes: self.classes,
+            scripts: self.scripts,
+            method_bodies: self.method_bodies,
+        }
+    }
+
+    /// Finishes the ABC file and encodes it as a lazily
+    /// initialized `DoABC2` payload.
+    pub fn finish_do_abc2(self, name: &str) -> AbcDoAbc2Payload {
+        let abc_file = self.finish();
+        let mut data = vec![];
+        AbcWriter::new(&mut data).write(abc_file).unwrap();
+        AbcDoAbc2Payload {
+            name: name.to_owned(),
+            lazy_initialize: true,
+            data,
+        }
+    }
+
+    /// Lowers a namespace.
+    pub fn namespace(&mut self, ns: &Thingy) -> abc::Index<abc::Namespace> {
+        self.names.namespace(&mut self.pool, ns)
+    }
+
+    /// Lowers a qualified name to a `QName` multiname.
+    pub fn qname(&mut self, name: &QName) -> abc::Index<abc::Multiname> {
+        self.names.qname(&mut self.pool, name)
+    }
+
+    /// `QName` in the public namespace of the top-level package.
+    fn public_name(&mut self, local_name: &str) -> abc::Index<abc::Multiname> {
+        self.pool.add_package_qname("", local_name)
+    }
+
+    /// Lowers a type to the multiname used in type annotations.
+    /// See `AbcNameLowering::type_name()`.
+    pub fn type_name(&mut self, type_thingy: &Thingy) -> abc::Index<abc::Multiname> {
+        self.names.type_name(&mut self.pool, type_thingy)
+    }
+
+    /// Open namespace set used for properties that were not
+    /// resolved at compile time.
+    fn open_ns_set(&mut self, package: Option<&Thingy>, class: Option<&Thingy>) -> abc::Index<abc::NamespaceSet> {
+        let top_level_name = self.pool.add_string("");
+        let mut set = vec![self.pool.add_namespace(&abc::Namespace::Package(top_level_name))];
+        if let Some(package) = package {
+            for ns in [package.public_ns(), package.internal_ns()].into_iter().flatten() {
+                set.push(self.namespace(&ns));
+            }
+        }
+        if let Some(class) = class {
+            for ns in [class.private_ns(), class.protected_ns(), class.static_protected_ns()].into_iter().flatten() {
+                set.push(self.namespace(&ns));
+            }
+        }
+        set.dedup();
+        self.pool.add_ns_set(&set)
+    }
+
+    fn dynamic_name(&mut self, f: &AbcFunctionState, local_name: &str) -> abc::Index<abc::Multiname> {
+        let name = self.pool.add_string(local_name);
+        self.pool.add_multiname(&abc::Multiname::Multiname { namespace_set: f.open_ns_set, name })
+    }
+
+    fn runtime_name(&mut self, f: &AbcFunctionState) -> abc::Index<abc::Multiname> {
+        self.pool.add_multiname(&abc::Multiname::MultinameL { namespace_set: f.open_ns_set })
+    }
+
+    fn metadata_indices(&mut self, list: &SharedArray<Rc<Metadata>>) -> Vec<abc::Index<abc::Metadata>> {
+        let mut r = vec![];
+        for m in list.iter() {
+            let name = self.pool.add_string(&m.name.0);
+            let mut items = vec![];
+            for entry in m.entries.iter().flatten() {
+                let key = entry.key.as_ref().map(|k| k.0.clone()).unwrap_or_default();
+                let value = match entry.value.as_ref() {
+                    MetadataValue::IdentifierString((s, _)) => s.clone(),
+                    MetadataValue::String((s, _)) => s.clone(),
+                };
+                items.push(abc::MetadataItem {
+                    key: self.pool.add_string(&key),
+                    value: self.pool.add_string(&value),
+                });
+            }
+            self.metadata.push(abc::Metadata { name, items });
+            r.push(abc::Index::new((self.metadata.len() - 1) as u32));
+        }
+        r
+    }
+
+    fn constant_default_value(&mut self, constant: &Thingy) -> Option<abc::DefaultValue> {
+        if constant.is::<NumberConstant>() {
+            Some(match constant.number_value() {
+                NumberVariant::Int(v) => abc::DefaultValue::Int(self.pool.add_int(v)),
+                NumberVariant::Uint(v) => abc::DefaultValue::Uint(self.pool.add_uint(v)),
+                NumberVariant::Number(v) => abc::DefaultValue::Double(self.pool.add_double(v)),
+                NumberVariant::Float(v) => abc::DefaultValue::Double(self.pool.add_double(v.into())),
+            })
+        } else if constant.is::<StringConstant>() {
+            Some(abc::DefaultValue::String(self.pool.add_string(&constant.string_value())))
+        } else if constant.is::<BooleanConstant>() {
+            Some(if constant.boolean_value() { abc::DefaultValue::True } else { abc::DefaultValue::False })
+        } else if constant.is::<NullConstant>() {
+            Some(abc::DefaultValue::Null)
+        } else if constant.is::<UndefinedConstant>() {
+            Some(abc::DefaultValue::Undefined)
+        } else if constant.is::<NamespaceConstant>() {
+            Some(abc::DefaultValue::Namespace(self.namespace(&constant.referenced_ns())))
+        } else {
+            None
+        }
+    }
+
+    /// Default value of an optional parameter.
+    fn parameter_default_value(&mut self, exp: &Rc<Expression>) -> abc::DefaultValue {
+        let mut m = self.host.node_mapping().get(exp);
+        while let Some(m1) = m.clone().filter(|m1| m1.is::<ConversionValue>()) {
+            m = Some(m1.base());
+        }
+        if let Some(v) = m.and_then(|m| self.constant_default_value(&m)) {
+            return v;
+        }
+        match exp.as_ref() {
+            Expression::NumericLiteral(lit) => abc::DefaultValue::Double(self.pool.add_double(lit.parse_double(false).unwrap_or(f64::NAN))),
+            Expression::Unary(UnaryExpression { operator: Operator::Negative, expression, .. }) => {
+                if let Expression::NumericLiteral(lit) = expression.as_ref() {
+                    abc::DefaultValue::Double(self.pool.add_double(lit.parse_double(true).unwrap_or(f64::NAN)))
+                } else {
+                    abc::DefaultValue::Undefined
+                }
+            },
+            Expression::StringLiteral(lit) => abc::DefaultValue::String(self.pool.add_string(&lit.value)),
+            Expression::BooleanLiteral(lit) => if lit.value { abc::DefaultValue::True } else { abc::DefaultValue::False },
+            Expression::NullLiteral(_) => abc::DefaultValue::Null,
+            _ => abc::DefaultValue::Undefined,
+        }
+    }
+
+    /// Emits an ABC script from a verified program. Every class, interface
+    /// and enum defined in the program is emitted and initialized
+    /// by the script initializer, ordered by hierarchy depth.
+    pub fn emit_program(&mut self, program: &Rc<Program>) -> abc::Index<abc::Script> {
+        let mut units: Vec<(Thingy, Vec<Rc<Directive>>)> = vec![];
+        for pckg in &program.packages {
+            let package = self.host.factory().create_package(pckg.name.iter().map(|(s, _)| s.as_str()));
+            units.push((package, flatten_directives(self.host, &pckg.block.directives)));
+        }
+        units.push((self.host.top_level_package(), flatten_directives(self.host, &program.directives)));
+
+        let open_ns_set = self.open_ns_set(None, None);
+        let mut f = AbcFunctionState::new(AbcMethodBodyBuilder::new(0, 0), open_ns_set);
+        f.push_scope(0, false);
+
+        // Emit classes, interfaces and enums
+        let mut class_things: Vec<(Thingy, Rc<Directive>)> = vec![];
+        for (package, directives) in &units {
+            for drtv in directives {
+                let name = match drtv.as_ref() {
+                    Directive::ClassDefinition(defn) => &defn.name.0,
+                    Directive::InterfaceDefinition(defn) => &defn.name.0,
+                    Directive::EnumDefinition(defn) => &defn.name.0,
+                    _ => continue,
+                };
+                if let Ok(Some(class)) = package.properties(self.host).get_in_any_ns(name) {
+                    class_things.push((class, drtv.clone()));
+                }
+            }
+        }
+        class_things.sort_by_key(|(class, _)| self.hierarchy_depth(class));
+
+        let mut traits = vec![];
+        for (class, drtv) in &class_things {
+            let block = match drtv.as_ref() {
+                Directive::ClassDefinition(defn) => defn.block.clone(),
+                Directive::InterfaceDefinition(defn) => defn.block.clone(),
+                Directive::EnumDefinition(defn) => defn.block.clone(),
+                _ => unreachable!(),
+            };
+            let class_index = self.emit_class(class, Some(&block));
+            self.emit_new_class(&mut f, class, class_index);
+            let metadata = self.metadata_indices(&class.metadata());
+            traits.push(abc::Trait {
+                name: self.qname(&class.name()),
+                kind: abc::TraitKind::Class { slot_id: 0, class: class_index },
+                metadata,
+                is_final: false,
+                is_override: false,
+            });
+        }
+
+        // Package functions, variables and statements
+        for (package, directives) in &units {
+            f.open_ns_set = self.open_ns_set(Some(package), None);
+            f.declaration_names = Some(package.properties(self.host));
+            for drtv in directives {
+                if let Directive::FunctionDefinition(defn) = drtv.as_ref() {
+                    if let Some(t) = self.emit_package_function(&f, package, defn) {
+                        traits.push(t);
+                    }
+                }
+            }
+            for drtv in directives {
+                if !matches!(drtv.as_ref(), Directive::FunctionDefinition(_)) {
+                    self.emit_directive(&mut f, drtv);
+                }
+            }
+        }
+
+        f.emit(abc::Op::ReturnVoid);
+        traits.extend(std::mem::take(&mut f.var_traits));
+        let init_method = self.push_method(abc::Method {
+            name: abc::Index::new(0),
+            params: vec![],
+            return_type: abc::Index::new(0),
+            flags: abc::MethodFlags::empty(),
+        });
+        self.method_bodies.push(f.builder.finish(init_method));
+        self.scripts.push(abc::Script { init_method, traits });
+        abc::Index::new((self.scripts.len() - 1) as u32)
+    }
+
+    fn hierarchy_depth(&self, class: &Thingy) -> usize {
+        let mut depth = 0;
+        let mut c = class.clone();
+        while c.is::<ClassType>() || c.is::<EnumType>() {
+            let Some(base) = c.extends_class(self.host) else {
+                break;
+            };
+            depth += 1;
+            c = base;
+        }
+        depth
+    }
+
+    fn push_method(&mut self, method: abc::Method) -> abc::Index<abc::Method> {
+        self.methods.push(method);
+        abc::Index::new((self.methods.len() - 1) as u32)
+    }
+
+    /// Emits the code that creates a class object and assigns it
+    /// to the global object, within a script initializer.
+    fn emit_new_class(&mut self, f: &mut AbcFunctionState, class: &Thingy, class_index: abc::Index<abc::Class>) {
+        f.emit(abc::Op::GetScopeObject { index: 0 });
+        let mut ancestors = vec![];
+        if !class.is::<InterfaceType>() {
+            let mut c = class.extends_class(self.host);
+            while let Some(c1) = c {
+                ancestors.insert(0, c1.clone());
+                c = if c1.is::<ClassType>() || c1.is::<EnumType>() { c1.extends_class(self.host) } else { None };
+            }
+        }
+        for ancestor in &ancestors {
+            let name = self.type_name(ancestor);
+            f.emit(abc::Op::GetLex { index: name });
+            f.emit(abc::Op::PushScope);
+        }
+        if let Some(base) = ancestors.last() {
+            let name = self.type_name(base);
+            f.emit(abc::Op::GetLex { index: name });
+        } else {
+            f.emit(abc::Op::PushNull);
+        }
+        f.emit(abc::Op::NewClass { index: class_index });
+        for _ in &ancestors {
+            f.emit(abc::Op::PopScope);
+        }
+        let name = self.qname(&class.name());
+        f.emit(abc::Op::InitProperty { index: name });
+    }
+
+    fn emit_package_function(&mut self, f: &AbcFunctionState, package: &Thingy, defn: &FunctionDefinition) -> Option<abc::Trait> {
+        let local_name = defn.name_identifier().0;
+        let slot = package.properties(self.host).get_in_any_ns(&local_name).ok().flatten();
+        let method_slot = slot.as_ref().and_then(|slot| {
+            if slot.is::<VirtualSlot>() {
+                if defn.is_getter() { slot.getter(self.host) } else { slot.setter(self.host) }
+            } else if slot.is::<MethodSlot>() {
+                Some(slot.clone())
+            } else {
+                None
+            }
+        });
+        let name = match &slot {
+            Some(slot) => self.qname(&slot.name()),
+            None => self.pool.add_package_qname(&package.fully_qualified_name(), &local_name),
+        };
+        let signature = method_slot.as_ref().map(|m| m.signature(self.host));
+        let native = Attribute::find_native(&defn.attributes).is_some();
+        let method = self.emit_method(&local_name, Some(&defn.common), signature.as_ref(), native, f.open_ns_set, None);
+        let metadata = match &method_slot {
+            Some(m) => self.metadata_indices(&m.metadata()),
+            None => vec![],
+        };
+        let kind = if defn.is_getter() {
+            abc::TraitKind::Getter { disp_id: 0, method }
+        } else if defn.is_setter() {
+            abc::TraitKind::Setter { disp_id: 0, method }
+        } else {
+            abc::TraitKind::Method { disp_id: 0, method }
+        };
+        Some(abc::Trait { name, kind, metadata, is_final: false, is_override: false })
+    }
+
+    fn class_definitions(&self, block: Option<&Rc<Block>>) -> AbcClassDefinitions {
+        let mut r = AbcClassDefinitions::default();
+        let Some(block) = block else {
+            return r;
+        };
+        for drtv in flatten_directives(self.host, &block.directives) {
+            match drtv.as_ref() {
+                Directive::FunctionDefinition(defn) => {
+                    if defn.is_constructor() {
+                        r.constructor = Some(drtv.clone());
+                        continue;
+                    }
+                    let is_static = Attribute::find_static(&defn.attributes).is_some();
+                    let kind = if defn.is_getter() { 1 } else if defn.is_setter() { 2 } else { 0 };
+                    r.functions.insert((is_static, kind, defn.name_identifier().0), drtv.clone());
+                },
+                Directive::VariableDefinition(defn) => {
+                    if Attribute::find_static(&defn.attributes).is_some() {
+                        r.static_directives.push(drtv.clone());
+                    } else {
+                        r.instance_variables.push(drtv.clone());
+                    }
+                },
+                Directive::ClassDefinition(_) | Directive::InterfaceDefinition(_) |
+                Directive::EnumDefinition(_) | Directive::TypeDefinition(_) |
+                Directive::NamespaceDefinition(_) | Directive::ImportDirective(_) |
+                Directive::UseNamespaceDirective(_) => {},
+                _ => r.static_directives.push(drtv.clone()),
+            }
+        }
+        r
+    }
+
+    /// Emits the instance and class information of a class, interface or enum.
+    /// The optional block is the block of its definition, from where
+    /// method bodies and variable initializers are taken.
+    pub fn emit_class(&mut self, class: &Thingy, block: Option<&Rc<Block>>) -> abc::Index<abc::Class> {
+        if let Some(i) = self.class_indices.get(class) {
+            return *i;
+        }
+        let is_interface = class.is::<InterfaceType>();
+        let package = class.parent().filter(|p| p.is::<Package>());
+        let open_ns_set = self.open_ns_set(package.as_ref(), Some(class));
+        let defs = self.class_definitions(block);
+
+        // Instance traits
+        let instance_traits = self.emit_traits(class, &class.prototype(self.host), false, &defs, open_ns_set);
+
+        // Instance initializer
+        let iinit = if is_interface {
+            self.push_method(abc::Method {
+                name: abc::Index::new(0),
+                params: vec![],
+                return_type: abc::Index::new(0),
+                flags: abc::MethodFlags::empty(),
+            })
+        } else {
+            let ctor_defn = defs.constructor.clone();
+            let ctor_common = ctor_defn.as_ref().map(|d| match d.as_ref() {
+                Directive::FunctionDefinition(defn) => defn.common.clone(),
+                _ => unreachable!(),
+            });
+            let signature = class.constructor_method(self.host).map(|m| m.signature(self.host));
+            self.emit_method(&class.name().local_name(), ctor_common.as_ref(), signature.as_ref(), false, open_ns_set, Some((&defs.instance_variables, class.prototype(self.host))))
+        };
+
+        // Class traits
+        let class_traits = self.emit_traits(class, &class.properties(self.host), true, &defs, open_ns_set);
+
+        // Class initializer
+        let mut f = AbcFunctionState::new(AbcMethodBodyBuilder::new(0, 0), open_ns_set);
+        f.push_scope(0, false);
+        f.declaration_names = Some(class.properties(self.host));
+        // Static variables assigned a class, such as `[Embed]` variables,
+        // have no default value representable in traits.
+        let mut class_constants: Vec<(QName, Thingy)> = class.properties(self.host).borrow().iter()
+            .filter(|(_, v)| v.is::<VariableSlot>())
+            .filter_map(|(k, v)| v.var_constant().filter(|k| k.is::<TypeConstant>()).map(|c| (k.clone(), c.referenced_type())))
+            .collect();
+        class_constants.sort_by_key(|(k, _)| k.local_name());
+        for (name, referenced_type) in class_constants {
+            let name = self.qname(&name);
+            f.emit(abc::Op::GetLocal { index: 0 });
+            for op in self.names.type_value(&mut self.pool, &referenced_type) {
+                f.emit(op);
+            }
+            f.emit(abc::Op::InitProperty { index: name });
+        }
+        for drtv in &defs.static_directives {
+            self.emit_directive(&mut f, drtv);
+        }
+        f.emit(abc::Op::ReturnVoid);
+        let cinit = self.push_method(abc::Method {
+            name: abc::Index::new(0),
+            params: vec![],
+            return_type: abc::Index::new(0),
+            flags: abc::MethodFlags::empty(),
+        });
+        // Static variables are traits taken from the semantic model,
+        // thus `f.var_traits` is ignored.
+        self.method_bodies.push(f.builder.finish(cinit));
+
+        let name = self.qname(&class.name());
+        let super_name = if is_interface {
+            abc::Index::new(0)
+        } else {
+            match class.extends_class(self.host) {
+                Some(base) => self.type_name(&base),
+                None => abc::Index::new(0),
+            }
+        };
+        let interface_list = if is_interface { class.extends_interfaces(self.host) } else { class.implements(self.host) };
+        let mut interfaces = vec![];
+        for itrfc in interface_list.iter() {
+            let itrfc = itrfc.escape_of_nullable_or_non_nullable();
+            if !itrfc.is::<InterfaceType>() {
+                continue;
+            }
+            let qn = itrfc.name();
+            let ns = self.namespace(&qn.namespace());
+            let namespace_set = self.pool.add_ns_set(&[ns]);
+            let name = self.pool.add_string(&qn.local_name());
+            interfaces.push(self.pool.add_multiname(&abc::Multiname::Multiname { namespace_set, name }));
+        }
+        let protected_namespace = if is_interface { None } else { class.protected_ns().map(|ns| self.namespace(&ns)) };
+        let is_enum = class.is::<EnumType>();
+        self.instances.push(abc::Instance {
+            name,
+            super_name,
+            is_sealed: is_enum || (!is_interface && !class.is_dynamic()),
+            is_final: is_enum || (!is_interface && class.is_final()),
+            is_interface,
+            protected_namespace,
+            interfaces,
+            init_method: iinit,
+            traits: instance_traits,
+        });
+        self.classes.push(abc::Class {
+            init_method: cinit,
+            traits: class_traits,
+        });
+        let i = abc::Index::new((self.classes.len() - 1) as u32);
+        self.class_indices.insert(class.clone(), i);
+        i
+    }
+
+    fn emit_traits(&mut self, class: &Thingy, names: &NameMap, is_static: bool, defs: &AbcClassDefinitions, open_ns_set: abc::Index<abc::NamespaceSet>) -> Vec<abc::Trait> {
+        let is_interface = class.is::<InterfaceType>();
+        let mut entries: Vec<(QName, Thingy)> = names.borrow().iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        // The "prototype" property is defined implicitly by the AVM2.
+        entries.retain(|(k, _)| !(is_static && k.local_name() == "prototype"));
+        entries.sort_by_key(|(k, _)| (k.local_name(), k.namespace().to_string()));
+
+        let mut traits = vec![];
+        for (qn, slot) in entries {
+            let name = self.qname(&qn);
+            if slot.is::<VariableSlot>() {
+                let type_name = self.type_name(&slot.static_type(self.host));
+                let value = slot.var_constant().and_then(|c| self.constant_default_value(&c));
+                let metadata = if slot.is::<OriginalVariableSlot>() { self.metadata_indices(&slot.metadata()) } else { vec![] };
+                let kind = if slot.read_only(self.host) {
+                    abc::TraitKind::Const { slot_id: 0, type_name, value }
+                } else {
+                    abc::TraitKind::Slot { slot_id: 0, type_name, value }
+                };
+                traits.push(abc::Trait { name, kind, metadata, is_final: false, is_override: false });
+            } else if slot.is::<VirtualSlot>() {
+                let bindable_event = slot.bindable_event().filter(|_| !(is_static || is_interface));
+                for (kind, m) in [(1u8, slot.getter(self.host)), (2u8, slot.setter(self.host))] {
+                    let Some(m) = m else {
+                        continue;
+                    };
+                    let method = match &bindable_event {
+                        Some(event) => self.emit_bindable_accessor(&slot, &m, kind, event, &mut traits, defs, open_ns_set),
+                        None => self.emit_method_slot(&m, kind, is_static, is_interface, defs, open_ns_set),
+                    };
+                    let metadata = if m.is::<OriginalMethodSlot>() { self.metadata_indices(&m.metadata()) } else { vec![] };
+                    let kind = if kind == 1 {
+                        abc::TraitKind::Getter { disp_id: 0, method }
+                    } else {
+                        abc::TraitKind::Setter { disp_id: 0, method }
+                    };
+                    traits.push(abc::Trait { name, kind, metadata, is_final: m.is_final(), is_override: m.is_overriding() });
+                }
+            } else if slot.is::<MethodSlot>() {
+                let method = self.emit_method_slot(&slot, 0, is_static, is_interface, defs, open_ns_set);
+                let metadata = if slot.is::<OriginalMethodSlot>() { self.metadata_indices(&slot.metadata()) } else { vec![] };
+                traits.push(abc::Trait {
+                    name,
+                    kind: abc::TraitKind::Method { disp_id: 0, method },
+                    metadata,
+                    is_final: slot.is_final(),
+                    is_override: slot.is_overriding(),
+                });
+            }
+        }
+        traits
+    }
+
+    fn emit_method_slot(&mut self, slot: &Thingy, kind: u8, is_static: bool, is_interface: bool, defs: &AbcClassDefinitions, open_ns_set: abc::Index<abc::NamespaceSet>) -> abc::Index<abc::Method> {
+        let local_name = slot.name().local_name();
+        let signature = slot.signature(self.host);
+        let defn = defs.functions.get(&(is_static, kind, local_name.clone())).cloned();
+        let defn = defn.as_ref().map(|d| match d.as_ref() {
+            Directive::FunctionDefinition(defn) => defn,
+            _ => unreachable!(),
+        });
+        let native = defn.map(|d| Attribute::find_native(&d.attributes).is_some()).unwrap_or(false);
+        if is_interface {
+            return self.emit_method(&local_name, None, Some(&signature), true, open_ns_set, None);
+        }
+        self.emit_method(&local_name, defn.map(|d| &d.common), Some(&signature), native, open_ns_set, None)
+    }
+
+    /// Emits the getter or setter of a bindable instance virtual slot.
+    ///
+    /// Getters and setters of bindable variables are synthesized, accessing the
+    /// backing variable. A user-defined setter is emitted as a private method named
+    /// with `Bindable::SETTER_PREFIX`, invoked by a synthesized setter. Synthesized setters
+    /// dispatch the bindable event only if `newValue !== oldValue`.
+    #[allow(clippy::too_many_arguments)]
+    fn emit_bindable_accessor(&mut self, slot: &Thingy, m: &Thingy, kind: u8, event: &str, traits: &mut Vec<abc::Trait>, defs: &AbcClassDefinitions, open_ns_set: abc::Index<abc::NamespaceSet>) -> abc::Index<abc::Method> {
+        let local_name = slot.name().local_name();
+        let has_definition = defs.functions.contains_key(&(false, kind, local_name.clone()));
+        let backing = Bindable(self.host).backing_variable(slot).map(|v| self.qname(&v.name()));
+        let signature = m.signature(self.host);
+
+        if kind == 1 {
+            let Some(backing) = backing.filter(|_| !has_definition) else {
+                return self.emit_method_slot(m, kind, false, false, defs, open_ns_set);
+            };
+            let method = self.push_signature_method(&local_name, &signature);
+            let mut builder = AbcMethodBodyBuilder::new(0, 0);
+            builder.emit(abc::Op::GetLocal { index: 0 });
+            builder.emit(abc::Op::PushScope);
+            builder.emit(abc::Op::GetLocal { index: 0 });
+            builder.emit(abc::Op::GetProperty { index: backing });
+            builder.emit(abc::Op::ReturnValue);
+            self.method_bodies.push(builder.finish(method));
+            return method;
+        }
+
+        // The store is either the backing variable or the renamed user setter.
+        let store = if has_definition {
+            let user_setter = self.emit_method_slot(m, kind, false, false, defs, open_ns_set);
+            let class = slot.parent().unwrap();
+            let ns = class.private_ns().unwrap_or_else(|| class.parent().and_then(|p| p.internal_ns()).unwrap());
+            let renamed = self.host.factory().create_qname(&ns, format!("{}{}", Bindable::SETTER_PREFIX, local_name));
+            let name = self.qname(&renamed);
+            traits.push(abc::Trait { name, kind: abc::TraitKind::Method { disp_id: 0, method: user_setter }, metadata: vec![], is_final: false, is_override: false });
+            Err(name)
+        } else if let Some(backing) = backing {
+            Ok(backing)
+        } else {
+            return self.emit_method_slot(m, kind, false, false, defs, open_ns_set);
+        };
+
+        let name = self.qname(&slot.name());
+        let method = self.push_signature_method(&local_name, &signature);
+        let mut builder = AbcMethodBodyBuilder::new(1, 0);
+        let old_value = builder.alloc_local();
+        builder.emit(abc::Op::GetLocal { index: 0 });
+        builder.emit(abc::Op::PushScope);
+
+        // var oldValue = this.x;
+        match store {
+            Ok(backing) => {
+                builder.emit(abc::Op::GetLocal { index: 0 });
+                builder.emit(abc::Op::GetProperty { index: backing });
+            },
+            Err(_) if slot.getter(self.host).is_some() => {
+                builder.emit(abc::Op::GetLocal { index: 0 });
+                builder.emit(abc::Op::GetProperty { index: name });
+            },
+            Err(_) => builder.emit(abc::Op::PushUndefined),
+        }
+        builder.emit(abc::Op::SetLocal { index: old_value });
+
+        // if (oldValue !== newValue)
+        let end = builder.new_label();
+        builder.emit(abc::Op::GetLocal { index: old_value });
+        builder.emit(abc::Op::GetLocal { index: 1 });
+        builder.emit_branch(abc::Op::IfStrictEq { offset: 0 }, end);
+
+        builder.emit(abc::Op::GetLocal { index: 0 });
+        builder.emit(abc::Op::GetLocal { index: 1 });
+        match store {
+            Ok(backing) => builder.emit(abc::Op::SetProperty { index: backing }),
+            Err(user_setter) => builder.emit(abc::Op::CallPropVoid { index: user_setter, num_args: 1 }),
+        }
+
+        // Dispatch the event
+        builder.emit(abc::Op::GetLocal { index: 0 });
+        if event == Bindable::DEFAULT_EVENT {
+            let event_class = self.pool.add_package_qname("mx.events", "PropertyChangeEvent");
+            let create_update_event = self.public_name("createUpdateEvent");
+            let property_name = self.pool.add_string(&local_name);
+            builder.emit(abc::Op::GetLex { index: event_class });
+            builder.emit(abc::Op::GetLocal { index: 0 });
+            builder.emit(abc::Op::PushString { value: property_name });
+            builder.emit(abc::Op::GetLocal { index: old_value });
+            builder.emit(abc::Op::GetLocal { index: 1 });
+            builder.emit(abc::Op::CallProperty { index: create_update_event, num_args: 4 });
+        } else {
+            let event_class = self.pool.add_package_qname("flash.events", "Event");
+            let event_type = self.pool.add_string(event);
+            builder.emit(abc::Op::FindPropStrict { index: event_class });
+            builder.emit(abc::Op::PushString { value: event_type });
+            builder.emit(abc::Op::ConstructProp { index: event_class, num_args: 1 });
+        }
+        let dispatch_event = self.public_name("dispatchEvent");
+        builder.emit(abc::Op::CallPropVoid { index: dispatch_event, num_args: 1 });
+
+        builder.bind(end);
+        builder.emit(abc::Op::ReturnVoid);
+        self.method_bodies.push(builder.finish(method));
+        method
+    }
+
+    /// Pushes a method whose parameters and result type are
+    /// taken from a function type, without emitting a body.
+    fn push_signature_method(&mut self, name: &str, signature: &Thingy) -> abc::Index<abc::Method> {
+        let param_types: Vec<Thingy> = signature.params().iter().map(|p| p.static_type.clone()).collect();
+        let params = param_types.iter().map(|t| abc::MethodParam { name: None, kind: self.type_name(t), default_value: None }).collect();
+        let return_type = self.type_name(&signature.result_type());
+        let name = self.pool.add_string(name);
+        self.push_method(abc::Method { name, params, return_type, flags: abc::MethodFlags::empty() })
+    }
+
+    /// Emits a method and, unless it is native or an interface method
+    /// (`body_less`), its body.
+    ///
+    /// If `instance_initializer` is given, the method is an instance initializer:
+    /// the given instance variables are initialized, being looked up in the given
+    /// prototype, and the base class constructor is implicitly invoked
+    /// if the body does not contain a `super()` statement.
+    fn emit_method(&mut self, name: &str, common: Option<&Rc<FunctionCommon>>, signature: Option<&Thingy>, body_less: bool, open_ns_set: abc::Index<abc::NamespaceSet>, instance_initializer: Option<(&[Rc<Directive>], NameMap)>) -> abc::Index<abc::Method> {
+        let is_constructor = instance_initializer.is_some();
+        let mut flags = abc::MethodFlags::empty();
+        let mut params = vec![];
+        let mut param_names: Vec<Option<Rc<Expression>>> = vec![];
+
+        // Parameters
+        let ast_params = common.map(|c| c.signature.parameters.clone()).unwrap_or_default();
+        let sig_params = signature.map(|s| s.params()).map(|p| p.iter().collect::<Vec<_>>()).unwrap_or_default();
+        let count = if common.is_some() { ast_params.len() } else { sig_params.len() };
+        for i in 0..count {
+            let ast_param = ast_params.get(i);
+            let sig_param = sig_params.get(i);
+            let kind = ast_param.map(|p| p.kind).or(sig_param.map(|p| p.kind)).unwrap_or(ParameterKind::Required);
+            param_names.push(ast_param.map(|p| p.destructuring.destructuring.clone()));
+            if kind == ParameterKind::Rest {
+                flags |= abc::MethodFlags::NEED_REST;
+                continue;
+            }
+            let type_name = match sig_param {
+                Some(p) => self.type_name(&p.static_type),
+                None => ast_param
+                    .and_then(|p| p.destructuring.type_annotation.as_ref())
+                    .and_then(|t| self.host.node_mapping().get(t))
+                    .filter(|t| t.is::<TypeConstant>())
+                    .map(|t| self.type_name(&t.referenced_type()))
+                    .unwrap_or(abc::Index::new(0)),
+            };
+            let default_value = if kind == ParameterKind::Optional {
+                flags |= abc::MethodFlags::HAS_OPTIONAL;
+                Some(match ast_param.and_then(|p| p.default_value.as_ref()) {
+                    Some(exp) => self.parameter_default_value(exp),
+                    None => abc::DefaultValue::Undefined,
+                })
+            } else {
+                None
+            };
+            params.push(abc::MethodParam { name: None, kind: type_name, default_value });
+        }
+
+        let return_type = match signature {
+            Some(s) if !is_constructor => self.type_name(&s.result_type()),
+            _ => abc::Index::new(0),
+        };
+        let body = common.and_then(|c| c.body.as_ref());
+        if body_less {
+            flags |= abc::MethodFlags::NATIVE;
+        }
+        let method_name = self.pool.add_string(name);
+        let method = self.push_method(abc::Method { name: method_name, params, return_type, flags });
+        if body_less {
+            return method;
+        }
+        flags |= abc::MethodFlags::NEED_ACTIVATION;
+        self.methods[method.0 as usize].flags = flags;
+
+        // Prologue
+        let mut f = AbcFunctionState::new(AbcMethodBodyBuilder::new(param_names.len() as u32, 0), open_ns_set);
+        f.push_scope(0, false);
+        let activation = f.builder.alloc_local();
+        f.emit(abc::Op::NewActivation);
+        f.emit(abc::Op::SetLocal { index: activation });
+        f.push_scope(activation, false);
+        f.activation = Some(activation);
+
+        // Copy parameters into the activation
+        for (i, pattern) in param_names.iter().enumerate() {
+            let Some(pattern) = pattern else {
+                continue;
+            };
+            f.emit(abc::Op::GetLocal { index: i as u32 + 1 });
+            self.emit_declaration_store(&mut f, pattern, false, None);
+        }
+
+        // Hoist nested functions
+        if let Some(FunctionBody::Block(block)) = body {
+            for drtv in flatten_directives(self.host, &block.directives) {
+                if let Directive::FunctionDefinition(defn) = drtv.as_ref() {
+                    let local_name = defn.name_identifier().0;
+                    let name = self.pool.add_package_qname("", &local_name);
+                    self.declare_var_trait(&mut f, name, abc::Index::new(0), false, None);
+                    let m = self.emit_method(&local_name, Some(&defn.common), None, false, open_ns_set, None);
+                    f.emit(abc::Op::GetLocal { index: activation });
+                    f.emit(abc::Op::NewFunction { index: m });
+                    f.emit(abc::Op::SetProperty { index: name });
+                }
+            }
+        }
+
+        if let Some((instance_variables, declaration_names)) = instance_initializer {
+            // Instance variable initializers
+            f.declaration_names = Some(declaration_names);
+            f.declaration_target_this = true;
+            for drtv in instance_variables {
+                self.emit_directive(&mut f, drtv);
+            }
+            f.declaration_names = None;
+            f.declaration_target_this = false;
+
+            let has_super_statement = match body {
+                Some(FunctionBody::Block(block)) => block.directives.iter().any(|d| matches!(d.as_ref(), Directive::SuperStatement(_))),
+                _ => false,
+            };
+            if !has_super_statement {
+                f.emit(abc::Op::GetLocal { index: 0 });
+                f.emit(abc::Op::ConstructSuper { num_args: 0 });
+            }
+        }
+
+        match body {
+            Some(FunctionBody::Block(block)) => {
+                self.emit_directives(&mut f, &block.directives);
+                f.emit(abc::Op::ReturnVoid);
+            },
+            Some(FunctionBody::Expression(exp)) => {
+                self.emit_exp(&mut f, exp);
+                f.emit(abc::Op::ReturnValue);
+            },
+            None => {
+                f.emit(abc::Op::ReturnVoid);
+            },
+        }
+
+        for t in std::mem::take(&mut f.var_traits) {
+            f.builder.add_trait(t);
+        }
+        self.method_bodies.push(f.builder.finish(method));
+        method
+    }
+
+    fn declare_var_trait(&mut self, f: &mut AbcFunctionState, name: abc::Index<abc::Multiname>, type_name: abc::Index<abc::Multiname>, constant: bool, value: Option<abc::DefaultValue>) {
+        // Instance variables are traits taken from the semantic model.
+        if f.declaration_target_this {
+            return;
+        }
+        if !f.var_trait_names.insert(name.0) {
+            return;
+        }
+        let kind = if constant {
+            abc::TraitKind::Const { slot_id: 0, type_name, value }
+        } else {
+            abc::TraitKind::Slot { slot_id: 0, type_name, value }
+        };
+        f.var_traits.push(abc::Trait { name, kind, metadata: vec![], is_final: false, is_override: false });
+    }
+
+    fn emit_directives(&mut self, f: &mut AbcFunctionState, list: &[Rc<Directive>]) {
+        for drtv in flatten_directives(self.host, list) {
+            self.emit_directive(f, &drtv);
+        }
+    }
+
+    fn emit_directive(&mut self, f: &mut AbcFunctionState, drtv: &Rc<Directive>) {
+        let labels = std::mem::take(&mut f.pending_labels);
+        match drtv.as_ref() {
+            Directive::ExpressionStatement(stmt) => {
+                self.emit_exp(f, &stmt.expression);
+                f.emit(abc::Op::Pop);
+            },
+            Directive::SuperStatement(stmt) => {
+                f.emit(abc::Op::GetLocal { index: 0 });
+                for arg in &stmt.arguments {
+                    self.emit_exp(f, arg);
+                }
+                f.emit(abc::Op::ConstructSuper { num_args: stmt.arguments.len() as u32 });
+            },
+            Directive::Block(block) => {
+                if labels.is_empty() {
+                    self.emit_directives(f, &block.directives);
+                } else {
+                    let end = f.builder.new_label();
+                    f.jumps.push(AbcJumpContext::Breakable { labels, implicit: false, break_label: end, continue_label: None, scope_count: f.scopes.len() });
+                    self.emit_directives(f, &block.directives);
+                    f.jumps.pop();
+                    f.builder.bind(end);
+                }
+            },
+            Directive::LabeledStatement(stmt) => {
+                let mut labels = labels;
+                labels.push(stmt.label.0.clone());
+                let is_loop_or_switch = matches!(stmt.substatement.as_ref(),
+                    Directive::WhileStatement(_) | Directive::DoStatement(_) | Directive::ForStatement(_) |
+                    Directive::ForInStatement(_) | Directive::SwitchStatement(_) | Directive::LabeledStatement(_) |
+                    Directive::Block(_));
+                if is_loop_or_switch {
+                    f.pending_labels = labels;
+                    self.emit_directive(f, &stmt.substatement);
+                } else {
+                    let end = f.builder.new_label();
+                    f.jumps.push(AbcJumpContext::Breakable { labels, implicit: false, break_label: end, continue_label: None, scope_count: f.scopes.len() });
+                    self.emit_directive(f, &stmt.substatement);
+                    f.jumps.pop();
+                    f.builder.bind(end);
+                }
+            },
+            Directive::IfStatement(stmt) => {
+                let else_label = f.builder.new_label();
+                self.emit_exp(f, &stmt.test);
+                f.builder.emit_branch(abc::Op::IfFalse { offset: 0 }, else_label);
+                self.emit_directive(f, &stmt.consequent);
+                if let Some(alternative) = &stmt.alternative {
+                    let end = f.builder.new_label();
+                    f.builder.emit_branch(abc::Op::Jump { offset: 0 }, end);
+                    f.builder.bind(else_label);
+                    self.emit_directive(f, alternative);
+                    f.builder.bind(end);
+                } else {
+                    f.builder.bind(else_label);
+                }
+            },
+            Directive::WhileStatement(stmt) => {
+                let head = f.builder.new_label();
+                let end = f.builder.new_label();
+                f.builder.bind_loop_head(head);
+                self.emit_exp(f, &stmt.test);
+                f.builder.emit_branch(abc::Op::IfFalse { offset: 0 }, end);
+                f.jumps.push(AbcJumpContext::Breakable { labels, implicit: true, break_label: end, continue_label: Some(head), scope_count: f.scopes.len() });
+                self.emit_directive(f, &stmt.body);
+                f.jumps.pop();
+                f.builder.emit_branch(abc::Op::Jump { offset: 0 }, head);
+                f.builder.bind(end);
+            },
+            Directive::DoStatement(stmt) => {
+                let head = f.builder.new_label();
+                let continue_label = f.builder.new_label();
+                let end = f.builder.new_label();
+                f.builder.bind_loop_head(head);
+                f.jumps.push(AbcJumpContext::Breakable { labels, implicit: true, break_label: end, continue_label: Some(continue_label), scope_count: f.scopes.len() });
+                self.emit_directive(f, &stmt.body);
+                f.jumps.pop();
+                f.builder.bind(continue_label);
+                self.emit_exp(f, &stmt.test);
+                f.builder.emit_branch(abc::Op::IfTrue { offset: 0 }, head);
+                f.builder.bind(end);
+            },
+            Directive::ForStatement(stmt) => {
+                match &stmt.init {
+                    Some(ForInitializer::Expression(exp)) => {
+                        self.emit_exp(f, exp);
+                        f.emit(abc::Op::Pop);
+                    },
+                    Some(ForInitializer::VariableDefinition(defn)) => {
+                        let constant = defn.kind.0 == VariableDefinitionKind::Const;
+                        for binding in &defn.bindings {
+                            self.emit_variable_binding(f, binding, constant);
+                        }
+                    },
+                    None => {},
+                }
+                let head = f.builder.new_label();
+                let continue_label = f.builder.new_label();
+                let end = f.builder.new_label();
+                f.builder.bind_loop_head(head);
+                if let Some(test) = &stmt.test {
+                    self.emit_exp(f, test);
+                    f.builder.emit_branch(abc::Op::IfFalse { offset: 0 }, end);
+                }
+                f.jumps.push(AbcJumpContext::Breakable { labels, implicit: true, break_label: end, continue_label: Some(continue_label), scope_count: f.scopes.len() });
+                self.emit_directive(f, &stmt.body);
+                f.jumps.pop();
+                f.builder.bind(continue_label);
+                if let Some(update) = &stmt.update {
+                    self.emit_exp(f, update);
+                    f.emit(abc::Op::Pop);
+                }
+                f.builder.emit_branch(abc::Op::Jump { offset: 0 }, head);
+                f.builder.bind(end);
+            },
+            Directive::ForInStatement(stmt) => {
+                let object_register = f.builder.alloc_local();
+                let index_register = f.builder.alloc_local();
+                self.emit_exp(f, &stmt.right);
+                f.emit(abc::Op::CoerceA);
+                f.emit(abc::Op::SetLocal { index: object_register });
+                f.emit(abc::Op::PushByte { value: 0 });
+                f.emit(abc::Op::SetLocal { index: index_register });
+
+                let head = f.builder.new_label();
+                let end = f.builder.new_label();
+                f.builder.bind_loop_head(head);
+                f.emit(abc::Op::HasNext2 { object_register, index_register });
+                f.builder.emit_branch(abc::Op::IfFalse { offset: 0 }, end);
+                f.emit(abc::Op::GetLocal { index: object_register });
+                f.emit(abc::Op::GetLocal { index: index_register });
+                f.emit(if stmt.each { abc::Op::NextValue } else { abc::Op::NextName });
+                match &stmt.left {
+                    ForInBinding::Expression(exp) => self.emit_pattern_store(f, exp),
+                    ForInBinding::VariableDefinition(defn) => {
+                        let constant = defn.kind.0 == VariableDefinitionKind::Const;
+                        let binding = &defn.bindings[0];
+                        self.emit_declaration_store(f, &binding.destructuring.destructuring, constant, binding.destructuring.type_annotation.as_ref());
+                    },
+                }
+                f.jumps.push(AbcJumpContext::Breakable { labels, implicit: true, break_label: end, continue_label: Some(head), scope_count: f.scopes.len() });
+                self.emit_directive(f, &stmt.body);
+                f.jumps.pop();
+                f.builder.emit_branch(abc::Op::Jump { offset: 0 }, head);
+                f.builder.bind(end);
+                f.emit(abc::Op::Kill { index: object_register });
+                f.emit(abc::Op::Kill { index: index_register });
+            },
+            Directive::SwitchStatement(stmt) => {
+                let discriminant = f.builder.alloc_local();
+                self.emit_exp(f, &stmt.discriminant);
+                f.emit(abc::Op::SetLocal { index: discriminant });
+                let end = f.builder.new_label();
+                let mut default_label = None;
+                let case_labels: Vec<AbcLabel> = stmt.cases.iter().map(|_| f.builder.new_label()).collect();
+                for (case, case_label) in stmt.cases.iter().zip(case_labels.iter()) {
+                    for label in &case.labels {
+                        match label {
+                            CaseLabel::Case((exp, _)) => {
+                                f.emit(abc::Op::GetLocal { index: discriminant });
+                                self.emit_exp(f, exp);
+                                f.builder.emit_branch(abc::Op::IfStrictEq { offset: 0 }, *case_label);
+                            },
+                            CaseLabel::Default(_) => {
+                                default_label = Some(*case_label);
+                            },
+                        }
+                    }
+                }
+                f.builder.emit_branch(abc::Op::Jump { offset: 0 }, default_label.unwrap_or(end));
+                f.jumps.push(AbcJumpContext::Breakable { labels, implicit: true, break_label: end, continue_label: None, scope_count: f.scopes.len() });
+                for (case, case_label) in stmt.cases.iter().zip(case_labels.iter()) {
+                    f.builder.bind(*case_label);
+                    self.emit_directives(f, &case.directives);
+                }
+                f.jumps.pop();
+                f.builder.bind(end);
+                f.emit(abc::Op::Kill { index: discriminant });
+            },
+            Directive::BreakStatement(stmt) => {
+                self.emit_jump(f, stmt.label.as_ref().map(|l| l.0.as_str()), false);
+            },
+            Directive::ContinueStatement(stmt) => {
+                self.emit_jump(f, stmt.label.as_ref().map(|l| l.0.as_str()), true);
+            },
+            Directive::WithStatement(stmt) => {
+                let register = f.builder.alloc_local();
+                self.emit_exp(f, &stmt.object);
+                f.emit(abc::Op::SetLocal { index: register });
+                f.push_scope(register, true);
+                self.emit_directive(f, &stmt.body);
+                f.emit(abc::Op::PopScope);
+                f.scopes.pop();
+                f.emit(abc::Op::Kill { index: register });
+            },
+            Directive::ReturnStatement(stmt) => {
+                let has_finally = f.jumps.iter().any(|j| matches!(j, AbcJumpContext::Finally { .. }));
+                if let Some(exp) = &stmt.expression {
+                    self.emit_exp(f, exp);
+                    if has_finally {
+                        let register = f.builder.alloc_local();
+                        f.emit(abc::Op::SetLocal { index: register });
+                        self.emit_finally_blocks(f, 0);
+                        f.emit(abc::Op::GetLocal { index: register });
+                    }
+                    f.emit(abc::Op::ReturnValue);
+                } else {
+                    self.emit_finally_blocks(f, 0);
+                    f.emit(abc::Op::ReturnVoid);
+                }
+            },
+            Directive::ThrowStatement(stmt) => {
+                self.emit_exp(f, &stmt.expression);
+                f.emit(abc::Op::Throw);
+            },
+            Directive::TryStatement(stmt) => {
+                self.emit_try_statement(f, stmt);
+            },
+            Directive::VariableDefinition(defn) => {
+                let constant = defn.kind.0 == VariableDefinitionKind::Const;
+                for binding in &defn.bindings {
+                    self.emit_variable_binding(f, binding, constant);
+                }
+            },
+            // Hoisted or emitted from the semantic model
+            Directive::FunctionDefinition(_) => {},
+            _ => {},
+        }
+    }
+
+    /// Emits a `break` or `continue` jump, leaving scopes and executing
+    /// `finally` blocks of enclosing `try` statements.
+    fn emit_jump(&mut self, f: &mut AbcFunctionState, label: Option<&str>, is_continue: bool) {
+        let mut target = None;
+        for (i, j) in f.jumps.iter().enumerate().rev() {
+            if let AbcJumpContext::Breakable { labels, implicit, break_label, continue_label, scope_count } = j {
+                let matches = match label {
+                    Some(label) => labels.iter().any(|l| l == label),
+                    None => *implicit && (!is_continue || continue_label.is_some()),
+                };
+                if matches {
+                    let jump_label = if is_continue { *continue_label } else { Some(*break_label) };
+                    if let Some(jump_label) = jump_label {
+                        target = Some((i, jump_label, *scope_count));
+                    }
+                    break;
+                }
+            }
+        }
+        // The verifier reports invalid jumps.
+        let Some((i, jump_label, scope_count)) = target else {
+            return;
+        };
+        let scope_depth = f.builder.scope_depth();
+        self.emit_finally_blocks(f, i + 1);
+        for _ in scope_count..f.scopes.len() {
+            f.emit(abc::Op::PopScope);
+        }
+        f.builder.emit_branch(abc::Op::Jump { offset: 0 }, jump_label);
+        f.builder.set_scope_depth(scope_depth);
+    }
+
+    /// Inlines the `finally` blocks of the jump contexts starting
+    /// at the given index, from the innermost.
+    fn emit_finally_blocks(&mut self, f: &mut AbcFunctionState, from: usize) {
+        let mut i = f.jumps.len();
+        while i > from {
+            i -= 1;
+            if let AbcJumpContext::Finally { block, scope_count } = &f.jumps[i] {
+                let block = block.clone();
+                let scope_count = *scope_count;
+                let scope_depth = f.builder.scope_depth();
+                for _ in scope_count..f.scopes.len() {
+                    f.emit(abc::Op::PopScope);
+                }
+                let saved_jumps: Vec<AbcJumpContext> = f.jumps.drain(i..).collect();
+                let saved_scopes: Vec<(u32, bool)> = f.scopes.drain(scope_count..).collect();
+                self.emit_directives(f, &block.directives);
+                f.jumps.extend(saved_jumps);
+                for (register, with) in saved_scopes {
+                    f.emit(abc::Op::GetLocal { index: register });
+                    f.emit(if with { abc::Op::PushWith } else { abc::Op::PushScope });
+                    f.scopes.push((register, with));
+                }
+                f.builder.set_scope_depth(scope_depth);
+            }
+        }
+    }
+
+    /// Pushes the local scope stack again at the start of an exception handler.
+    fn restore_scopes(&mut self, f: &mut AbcFunctionState, scope_count: usize) {
+        let scopes: Vec<(u32, bool)> = f.scopes.drain(..).collect();
+        for (register, with) in scopes.into_iter().take(scope_count) {
+            f.push_scope(register, with);
+        }
+    }
+
+    fn emit_try_statement(&mut self, f: &mut AbcFunctionState, stmt: &TryStatement) {
+        let scope_count = f.scopes.len();
+        let from = f.builder.new_label();
+        let to = f.builder.new_label();
+        let end = f.builder.new_label();
+        let finally_block = stmt.finally_clause.as_ref().map(|c| c.block.clone());
+        let push_finally = |f: &mut AbcFunctionState| {
+            if let Some(block) = &finally_block {
+                f.jumps.push(AbcJumpContext::Finally { block: block.clone(), scope_count });
+            }
+        };
+        let pop_finally = |f: &mut AbcFunctionState| {
+            if finally_block.is_some() {
+                f.jumps.pop();
+            }
+        };
+
+        f.builder.bind(from);
+        push_finally(f);
+        self.emit_directives(f, &stmt.block.directives);
+        pop_finally(f);
+        f.builder.bind(to);
+        if let Some(block) = &finally_block {
+            self.emit_directives(f, &block.directives);
+        }
+        f.builder.emit_branch(abc::Op::Jump { offset: 0 }, end);
+
+        for catch_clause in &stmt.catch_clauses {
+            let pattern = &catch_clause.parameter.destructuring;
+            let variable_name = self.declaration_name(f, pattern);
+            let type_name = match self.host.node_mapping().get(pattern) {
+                Some(slot) if slot.is::<VariableSlot>() => self.type_name(&slot.static_type(self.host)),
+                _ => abc::Index::new(0),
+            };
+            let target = f.builder.new_label();
+            let exception_index = f.builder.add_exception(from, to, target, type_name, variable_name);
+            f.builder.bind_exception_target(target);
+            self.restore_scopes(f, scope_count);
+
+            // Store the exception into the catch scope
+            let catch_scope = f.builder.alloc_local();
+            f.emit(abc::Op::NewCatch { index: abc::Index::new(exception_index) });
+            f.emit(abc::Op::Dup);
+            f.emit(abc::Op::SetLocal { index: catch_scope });
+            f.emit(abc::Op::PushScope);
+            f.scopes.push((catch_scope, false));
+            f.emit(abc::Op::GetLocal { index: catch_scope });
+            f.emit(abc::Op::Swap);
+            f.emit(abc::Op::SetSlot { index: 1 });
+
+            push_finally(f);
+            self.emit_directives(f, &catch_clause.block.directives);
+            pop_finally(f);
+            f.emit(abc::Op::PopScope);
+            f.scopes.pop();
+            f.emit(abc::Op::Kill { index: catch_scope });
+            if let Some(block) = &finally_block {
+                self.emit_directives(f, &block.directives);
+            }
+            f.builder.emit_branch(abc::Op::Jump { offset: 0 }, end);
+        }
+
+        // A catch-all handler executes the finally block and rethrows.
+        if let Some(block) = &finally_block {
+            let handlers_end = f.builder.new_label();
+            f.builder.bind(handlers_end);
+            let target = f.builder.new_label();
+            f.builder.add_exception(from, handlers_end, target, abc::Index::new(0), abc::Index::new(0));
+            f.builder.bind_exception_target(target);
+            self.restore_scopes(f, scope_count);
+            let exception = f.builder.alloc_local();
+            f.emit(abc::Op::SetLocal { index: exception });
+            self.emit_directives(f, &block.directives);
+            f.emit(abc::Op::GetLocal { index: exception });
+            f.emit(abc::Op::Kill { index: exception });
+            f.emit(abc::Op::Throw);
+        }
+
+        f.builder.bind(end);
+    }
+
+    fn emit_variable_binding(&mut self, f: &mut AbcFunctionState, binding: &Rc<VariableBinding>, constant: bool) {
+        let pattern = &binding.destructuring.destructuring;
+        let type_annotation = binding.destructuring.type_annotation.as_ref();
+        let Some(init) = &binding.initializer else {
+            self.declare_pattern(f, pattern, constant, type_annotation);
+            return;
+        };
+        // Constant initializers are stored in the trait.
+        if let Some(slot) = self.declaration_slot(f, pattern) {
+            if slot.var_constant().is_some() && f.declaration_names.is_some() {
+                return;
+            }
+        }
+        self.emit_exp(f, init);
+        self.emit_declaration_store(f, pattern, constant, type_annotation);
+    }
+
+    /// Variable slot of a declaration pattern, either from the node mapping
+    /// or, if it has no mapping, by local name from `declaration_names`.
+    ///
+    /// Bindable instance variables, which are replaced by virtual slots,
+    /// are initialized through their backing variable.
+    fn declaration_slot(&self, f: &AbcFunctionState, pattern: &Rc<Expression>) -> Option<Thingy> {
+        if let Some(slot) = self.host.node_mapping().get(pattern).filter(|s| s.is::<VariableSlot>()) {
+            if f.declaration_target_this {
+                let replacement = f.declaration_names.as_ref().and_then(|names| names.get(&slot.name()));
+                if let Some(backing) = replacement.and_then(|s| Bindable(self.host).backing_variable(&s)) {
+                    return Some(backing);
+                }
+            }
+            return Some(slot);
+        }
+        let Expression::QualifiedIdentifier(id) = pattern.as_ref() else {
+            return None;
+        };
+        let (local_name, _) = id.to_identifier_name()?;
+        let slot = f.declaration_names.as_ref()?.get_in_any_ns(&local_name).ok().flatten()?;
+        if slot.is::<VirtualSlot>() {
+            return Bindable(self.host).backing_variable(&slot);
+        }
+        Some(slot).filter(|s| s.is::<VariableSlot>())
+    }
+
+    fn declaration_name(&mut self, f: &AbcFunctionState, pattern: &Rc<Expression>) -> abc::Index<abc::Multiname> {
+        if let Some(slot) = self.declaration_slot(f, pattern) {
+            return self.qname(&slot.name());
+        }
+        let local_name = match pattern.as_ref() {
+            Expression::QualifiedIdentifier(id) => id.to_identifier_name().map(|n| n.0).unwrap_or_default(),
+            _ => "".into(),
+        };
+        self.pool.add_package_qname("", &local_name)
+    }
+
+    /// Declares the variables of a pattern without initializing them.
+    fn declare_pattern(&mut self, f: &mut AbcFunctionState, pattern: &Rc<Expression>, constant: bool, type_annotation: Option<&Rc<Expression>>) {
+        match pattern.as_ref() {
+            Expression::QualifiedIdentifier(_) => {
+                let name = self.declaration_name(f, pattern);
+                let slot = self.declaration_slot(f, pattern);
+                let type_name = match &slot {
+                    Some(slot) => self.type_name(&slot.static_type(self.host)),
+                    None => type_annotation
+                        .and_then(|t| self.host.node_mapping().get(t))
+                        .filter(|t| t.is::<TypeConstant>())
+                        .map(|t| self.type_name(&t.referenced_type()))
+                        .unwrap_or(abc::Index::new(0)),
+                };
+                let value = slot.and_then(|s| s.var_constant()).and_then(|c| self.constant_default_value(&c));
+                self.declare_var_trait(f, name, type_name, constant, value);
+            },
+            Expression::ArrayLiteral(lit) => {
+                for el in &lit.elements {
+                    match el {
+                        Element::Expression(e) | Element::Rest((e, _)) => self.declare_pattern(f, e, constant, None),
+                        Element::Elision => {},
+                    }
+                }
+            },
+            Expression::ObjectInitializer(init) => {
+                for field in &init.fields {
+                    match field.as_ref() {
+                        InitializerField::Field { value: Some(value), .. } => self.declare_pattern(f, value, constant, None),
+                        InitializerField::Field { name: (FieldName::Identifier(id), _), value: None, .. } => {
+                            let local_name = id.to_identifier_name().map(|n| n.0).unwrap_or_default();
+                            let name = self.shorthand_declaration_name(f, field, &local_name);
+                            self.declare_var_trait(f, name, abc::Index::new(0), constant, None);
+                        },
+                        _ => {},
+                    }
+                }
+            },
+            Expression::Unary(UnaryExpression { operator: Operator::NonNull, expression, .. }) => {
+                self.declare_pattern(f, expression, constant, type_annotation);
+            },
+            _ => {},
+        }
+    }
+
+    fn shorthand_declaration_name(&mut self, f: &AbcFunctionState, field: &Rc<InitializerField>, local_name: &str) -> abc::Index<abc::Multiname> {
+        if let Some(slot) = self.host.node_mapping().get(field).filter(|s| s.is::<VariableSlot>()) {
+            return self.qname(&slot.name());
+        }
+        if let Some(slot) = f.declaration_names.as_ref().and_then(|n| n.get_in_any_ns(local_name).ok().flatten()) {
+            return self.qname(&slot.name());
+        }
+        self.pool.add_package_qname("", local_name)
+    }
+
+    /// Declares the variables of a pattern and stores the value
+    /// on the top of the stack into them.
+    fn emit_declaration_store(&mut self, f: &mut AbcFunctionState, pattern: &Rc<Expression>, constant: bool, type_annotation: Option<&Rc<Expression>>) {
+        self.declare_pattern(f, pattern, constant, type_annotation);
+        self.emit_store(f, pattern, true);
+    }
+
+    /// Stores the value on the top of the stack into an assignment pattern.
+    fn emit_pattern_store(&mut self, f: &mut AbcFunctionState, pattern: &Rc<Expression>) {
+        self.emit_store(f, pattern, false);
+    }
+
+    fn emit_store(&mut self, f: &mut AbcFunctionState, pattern: &Rc<Expression>, declaration: bool) {
+        match pattern.as_ref() {
+            Expression::QualifiedIdentifier(_) if declaration => {
+                let name = self.declaration_name(f, pattern);
+                self.emit_declaration_target_store(f, name);
+            },
+            Expression::Paren(p) => self.emit_store(f, &p.expression, declaration),
+            Expression::Unary(UnaryExpression { operator: Operator::NonNull, expression, .. }) => {
+                self.emit_store(f, expression, declaration);
+            },
+            Expression::ArrayLiteral(lit) => {
+                let register = f.builder.alloc_local();
+                f.emit(abc::Op::SetLocal { index: register });
+                for (i, el) in lit.elements.iter().enumerate() {
+                    match el {
+                        Element::Elision => {},
+                        Element::Expression(e) => {
+                            f.emit(abc::Op::GetLocal { index: register });
+                            self.emit_int(f, i as i32);
+                            let name = self.runtime_name(f);
+                            f.builder.emit_with_runtime_name(abc::Op::GetProperty { index: name }, 1);
+                            self.emit_store(f, e, declaration);
+                        },
+                        Element::Rest((e, _)) => {
+                            f.emit(abc::Op::GetLocal { index: register });
+                            self.emit_int(f, i as i32);
+                            let slice = self.public_name("slice");
+                            f.emit(abc::Op::CallProperty { index: slice, num_args: 1 });
+                            self.emit_store(f, e, declaration);
+                        },
+                    }
+                }
+                f.emit(abc::Op::Kill { index: register });
+            },
+            Expression::ObjectInitializer(init) => {
+                let register = f.builder.alloc_local();
+                f.emit(abc::Op::SetLocal { index: register });
+                for field in &init.fields {
+                    let InitializerField::Field { name: (field_name, _), value, .. } = field.as_ref() else {
+                        continue;
+                    };
+                    f.emit(abc::Op::GetLocal { index: register });
+                    self.emit_field_get(f, field_name);
+                    match value {
+                        Some(value) => self.emit_store(f, value, declaration),
+                        None => {
+                            let local_name = match field_name {
+                                FieldName::Identifier(id) => id.to_identifier_name().map(|n| n.0).unwrap_or_default(),
+                                _ => "".into(),
+                            };
+                            if declaration {
+                                let name = self.shorthand_declaration_name(f, field, &local_name);
+                                self.emit_declaration_target_store(f, name);
+                            } else {
+                                let name = self.dynamic_name(f, &local_name);
+                                f.emit(abc::Op::FindProperty { index: name });
+                                f.emit(abc::Op::Swap);
+                                f.emit(abc::Op::SetProperty { index: name });
+                            }
+                        },
+                    }
+                }
+                f.emit(abc::Op::Kill { index: register });
+            },
+            _ => {
+                let register = f.builder.alloc_local();
+                f.emit(abc::Op::SetLocal { index: register });
+                let r = self.emit_reference(f, pattern, Some(false));
+                f.emit(abc::Op::GetLocal { index: register });
+                self.emit_set(f, r);
+                f.emit(abc::Op::Kill { index: register });
+            },
+        }
+    }
+
+    fn emit_declaration_target_store(&mut self, f: &mut AbcFunctionState, name: abc::Index<abc::Multiname>) {
+        if f.declaration_target_this {
+            f.emit(abc::Op::GetLocal { index: 0 });
+        } else if let Some(activation) = f.activation {
+            f.emit(abc::Op::GetLocal { index: activation });
+        } else {
+            f.emit(abc::Op::FindProperty { index: name });
+        }
+        f.emit(abc::Op::Swap);
+        f.emit(abc::Op::InitProperty { index: name });
+    }
+
+    /// Replaces the object on the top of the stack by the given field.
+    fn emit_field_get(&mut self, f: &mut AbcFunctionState, field_name: &FieldName) {
+        match field_name {
+            FieldName::Identifier(id) => {
+                let name = match id.to_identifier_name() {
+                    Some((n, _)) => self.dynamic_name(f, &n),
+                    None => self.dynamic_name(f, ""),
+                };
+                f.emit(abc::Op::GetProperty { index: name });
+            },
+            FieldName::StringLiteral(lit) => {
+                let value = match lit.as_ref() {
+                    Expression::StringLiteral(lit) => lit.value.clone(),
+                    _ => "".into(),
+                };
+                let name = self.dynamic_name(f, &value);
+                f.emit(abc::Op::GetProperty { index: name });
+            },
+            FieldName::NumericLiteral(lit) => {
+                self.emit_exp(f, lit);
+                let name = self.runtime_name(f);
+                f.builder.emit_with_runtime_name(abc::Op::GetProperty { index: name }, 1);
+            },
+            FieldName::Brackets(exp) => {
+                self.emit_exp(f, exp);
+                let name = self.runtime_name(f);
+                f.builder.emit_with_runtime_name(abc::Op::GetProperty { index: name }, 1);
+            },
+        }
+    }
+
+    fn emit_int(&mut self, f: &mut AbcFunctionState, value: i32) {
+        if (-128..=127).contains(&value) {
+            f.emit(abc::Op::PushByte { value: value as u8 });
+        } else if (i16::MIN as i32..=i16::MAX as i32).contains(&value) {
+            f.emit(abc::Op::PushShort { value: value as i16 });
+        } else {
+            let value = self.pool.add_int(value);
+            f.emit(abc::Op::PushInt { value });
+        }
+    }
+
+    fn emit_constant(&mut self, f: &mut AbcFunctionState, constant: &Thingy) {
+        if constant.is::<NumberConstant>() {
+            match constant.number_value() {
+                NumberVariant::Int(v) => self.emit_int(f, v),
+                NumberVariant::Uint(v) => {
+                    let value = self.pool.add_uint(v);
+                    f.emit(abc::Op::PushUint { value });
+                },
+                NumberVariant::Number(v) => self.emit_double(f, v),
+                NumberVariant::Float(v) => self.emit_double(f, v.into()),
+            }
+        } else if constant.is::<StringConstant>() {
+            let value = self.pool.add_string(&constant.string_value());
+            f.emit(abc::Op::PushString { value });
+        } else if constant.is::<BooleanConstant>() {
+            f.emit(if constant.boolean_value() { abc::Op::PushTrue } else { abc::Op::PushFalse });
+        } else if constant.is::<NullConstant>() {
+            f.emit(abc::Op::PushNull);
+        } else if constant.is::<NamespaceConstant>() {
+            let value = self.namespace(&constant.referenced_ns());
+            f.emit(abc::Op::PushNamespace { value });
+        } else if constant.is::<TypeConstant>() {
+            self.add_referenced_type(&constant.referenced_type());
+            for op in self.names.type_value(&mut self.pool, &constant.referenced_type()) {
+                f.emit(op);
+            }
+        } else {
+            f.emit(abc::Op::PushUndefined);
+        }
+    }
+
+    fn emit_double(&mut self, f: &mut AbcFunctionState, value: f64) {
+        if value.is_nan() {
+            f.emit(abc::Op::PushNaN);
+        } else {
+            let value = self.pool.add_double(value);
+            f.emit(abc::Op::PushDouble { value });
+        }
+    }
+
+    /// Emits the conversion of the value on the top of the stack.
+    fn emit_conversion(&mut self, f: &mut AbcFunctionState, conversion: &Thingy) {
+        let target = conversion.conversion_target().escape_of_nullable_or_non_nullable();
+        let variant = conversion.conversion_variant();
+        if conversion.conversion_is_opt() {
+            let type_name = self.type_name(&target);
+            if type_name.0 != 0 {
+                f.emit(abc::Op::AsType { type_name });
+            }
+            return;
+        }
+        // A tuple is already an Array
+        if [TypeConversionVariant::TupleToCovariantTuple, TypeConversionVariant::TupleToArray].contains(&variant) {
+            return;
+        }
+        let host = self.host;
+        if target == host.int_type() {
+            f.emit(abc::Op::ConvertI);
+        } else if target == host.uint_type() {
+            f.emit(abc::Op::ConvertU);
+        } else if target == host.number_type() || target == host.float_type() {
+            f.emit(abc::Op::ConvertD);
+        } else if target == host.boolean_type() {
+            f.emit(abc::Op::ConvertB);
+        } else if target == host.string_type() {
+            f.emit(if variant == TypeConversionVariant::ToString { abc::Op::ConvertS } else { abc::Op::CoerceS });
+        } else if target == host.object_type() || target.is::<AnyType>() {
+            // No conversion needed
+        } else {
+            let type_name = self.type_name(&target);
+            if type_name.0 != 0 {
+                f.emit(abc::Op::Coerce { index: type_name });
+            }
+        }
+    }
+
+    /// Emits an expression, pushing its value onto the stack.
+    fn emit_exp(&mut self, f: &mut AbcFunctionState, exp: &Rc<Expression>) {
+        let mapping = self.host.node_mapping().get(exp);
+        self.emit_exp_with_mapping(f, exp, mapping);
+    }
+
+    fn emit_exp_with_mapping(&mut self, f: &mut AbcFunctionState, exp: &Rc<Expression>, mapping: Option<Thingy>) {
+        if let Some(m) = &mapping {
+            if m.is::<ConversionValue>() {
+                self.emit_exp_with_mapping(f, exp, Some(m.base()));
+                self.emit_conversion(f, m);
+                return;
+            }
+            if m.is::<Constant>() {
+                self.emit_constant(f, m);
+                return;
+            }
+            if m.is::<ThisObject>() {
+                f.emit(abc::Op::GetLocal { index: 0 });
+                return;
+            }
+        }
+        match exp.as_ref() {
+            Expression::Paren(p) => self.emit_exp(f, &p.expression),
+            Expression::NullLiteral(_) => f.emit(abc::Op::PushNull),
+            Expression::BooleanLiteral(lit) => f.emit(if lit.value { abc::Op::PushTrue } else { abc::Op::PushFalse }),
+            Expression::NumericLiteral(lit) => self.emit_double(f, lit.parse_double(false).unwrap_or(f64::NAN)),
+            Expression::StringLiteral(lit) => {
+                let value = self.pool.add_string(&lit.value);
+                f.emit(abc::Op::PushString { value });
+            },
+            Expression::ThisLiteral(_) => f.emit(abc::Op::GetLocal { index: 0 }),
+            Expression::RegExpLiteral(lit) => {
+                let reg_exp = self.public_name("RegExp");
+                f.emit(abc::Op::FindPropStrict { index: reg_exp });
+                let body = self.pool.add_string(&lit.body);
+                f.emit(abc::Op::PushString { value: body });
+                let flags = self.pool.add_string(&lit.flags);
+                f.emit(abc::Op::PushString { value: flags });
+                f.emit(abc::Op::ConstructProp { index: reg_exp, num_args: 2 });
+            },
+            Expression::ArrayLiteral(lit) => self.emit_array_literal(f, lit),
+            Expression::ObjectInitializer(init) => {
+                let mut num_args = 0;
+                for field in &init.fields {
+                    // Object rest is not supported by `newobject`.
+                    let InitializerField::Field { name: (field_name, _), value, .. } = field.as_ref() else {
+                        continue;
+                    };
+                    let local_name = match field_name {
+                        FieldName::Identifier(id) => id.to_identifier_name().map(|n| n.0),
+                        FieldName::StringLiteral(lit) => match lit.as_ref() {
+                            Expression::StringLiteral(lit) => Some(lit.value.clone()),
+                            _ => None,
+                        },
+                        FieldName::NumericLiteral(lit) => match lit.as_ref() {
+                            Expression::NumericLiteral(lit) => Some(lit.parse_double(false).unwrap_or(f64::NAN).to_string()),
+                            _ => None,
+                        },
+                        FieldName::Brackets(_) => None,
+                    };
+                    match (&local_name, field_name) {
+                        (Some(local_name), _) => {
+                            let value = self.pool.add_string(local_name);
+                            f.emit(abc::Op::PushString { value });
+                        },
+                        (None, FieldName::Brackets(exp)) => {
+                            self.emit_exp(f, exp);
+                            f.emit(abc::Op::ConvertS);
+                        },
+                        _ => f.emit(abc::Op::PushUndefined),
+                    }
+                    match value {
+                        Some(value) => self.emit_exp(f, value),
+                        None => {
+                            let name = self.dynamic_name(f, local_name.as_deref().unwrap_or(""));
+                            f.emit(abc::Op::GetLex { index: name });
+                        },
+                    }
+                    num_args += 1;
+                }
+                f.emit(abc::Op::NewObject { num_args });
+            },
+            Expression::Function(fe) => {
+                let name = fe.name.as_ref().map(|n| n.0.clone()).unwrap_or_default();
+                let m = self.emit_method(&name, Some(&fe.common), None, false, f.open_ns_set, None);
+                f.emit(abc::Op::NewFunction { index: m });
+            },
+            Expression::New(ne) => {
+                let base_mapping = self.host.node_mapping().get(&ne.base);
+                let r = self.emit_reference_with_mapping(f, &ne.base, base_mapping, Some(true));
+                let args = ne.arguments.clone().unwrap_or_default();
+                match r {
+                    AbcReference::Property(name) => {
+                        for arg in &args {
+                            self.emit_exp(f, arg);
+                        }
+                        f.emit(abc::Op::ConstructProp { index: name, num_args: args.len() as u32 });
+                    },
+                    _ => {
+                        self.emit_get(f, r);
+                        for arg in &args {
+                            self.emit_exp(f, arg);
+                        }
+                        f.emit(abc::Op::Construct { num_args: args.len() as u32 });
+                    },
+                }
+            },
+            Expression::Call(call) => self.emit_call(f, call),
+            Expression::QualifiedIdentifier(_) | Expression::Member(_) | Expression::ComputedMember(_) => {
+                let r = self.emit_reference_with_mapping(f, exp, mapping, None);
+                self.emit_get(f, r);
+            },
+            Expression::OptionalChainingPlaceholder(_) => {
+                let register = *f.optional_chaining_registers.last().unwrap();
+                f.emit(abc::Op::GetLocal { index: register });
+            },
+            Expression::OptionalChaining(oc) => {
+                let null_label = f.builder.new_label();
+                let end = f.builder.new_label();
+                self.emit_exp(f, &oc.base);
+                f.emit(abc::Op::Dup);
+                f.emit(abc::Op::PushNull);
+                f.builder.emit_branch(abc::Op::IfEq { offset: 0 }, null_label);
+                let register = f.builder.alloc_local();
+                f.emit(abc::Op::SetLocal { index: register });
+                f.optional_chaining_registers.push(register);
+                self.emit_exp(f, &oc.expression);
+                f.optional_chaining_registers.pop();
+                f.emit(abc::Op::Kill { index: register });
+                f.builder.emit_branch(abc::Op::Jump { offset: 0 }, end);
+                f.builder.bind(null_label);
+                f.emit(abc::Op::Pop);
+                f.emit(abc::Op::PushUndefined);
+                f.builder.bind(end);
+            },
+            Expression::WithTypeArguments(wta) => {
+                self.emit_exp(f, &wta.base);
+                // Only vectors have runtime type arguments.
+                let base_type = self.host.node_mapping().get(&wta.base).filter(|m| m.is::<TypeConstant>()).map(|m| m.referenced_type());
+                if base_type.is_some_and(|t| t != self.host.vector_type()) {
+                    return;
+                }
+                for arg in &wta.arguments {
+                    self.emit_exp(f, arg);
+                }
+                f.emit(abc::Op::ApplyType { num_types: wta.arguments.len() as u32 });
+            },
+            Expression::Unary(ue) => self.emit_unary(f, ue),
+            Expression::Binary(be) => self.emit_binary(f, be),
+            Expression::Conditional(ce) => {
+                let else_label = f.builder.new_label();
+                let end = f.builder.new_label();
+                self.emit_exp(f, &ce.test);
+                f.builder.emit_branch(abc::Op::IfFalse { offset: 0 }, else_label);
+                self.emit_exp(f, &ce.consequent);
+                f.builder.emit_branch(abc::Op::Jump { offset: 0 }, end);
+                f.builder.bind(else_label);
+                self.emit_exp(f, &ce.alternative);
+                f.builder.bind(end);
+            },
+            Expression::Assignment(ae) => self.emit_assignment(f, ae),
+            Expression::Sequence(se) => {
+                self.emit_exp(f, &se.left);
+                f.emit(abc::Op::Pop);
+                self.emit_exp(f, &se.right);
+            },
+            // XML, filters, descendants, `import.meta`, vector
+            // literals and type expressions without a type constant
+            // mapping are not supported yet.
+            _ => f.emit(abc::Op::PushUndefined),
+        }
+    }
+
+    fn emit_array_literal(&mut self, f: &mut AbcFunctionState, lit: &ArrayLiteral) {
+        let concat = self.public_name("concat");
+        let mut pending = 0;
+        let mut has_array = false;
+        for el in &lit.elements {
+            match el {
+                Element::Elision => {
+                    f.emit(abc::Op::PushUndefined);
+                    pending += 1;
+                },
+                Element::Expression(e) => {
+                    self.emit_exp(f, e);
+                    pending += 1;
+                },
+                Element::Rest((e, _)) => {
+                    if pending != 0 || !has_array {
+                        f.emit(abc::Op::NewArray { num_args: pending });
+                        if has_array {
+                            f.emit(abc::Op::CallProperty { index: concat, num_args: 1 });
+                        }
+                        has_array = true;
+                        pending = 0;
+                    }
+                    self.emit_exp(f, e);
+                    f.emit(abc::Op::CallProperty { index: concat, num_args: 1 });
+                },
+            }
+        }
+        if pending != 0 || !has_array {
+            f.emit(abc::Op::NewArray { num_args: pending });
+            if has_array {
+                f.emit(abc::Op::CallProperty { index: concat, num_args: 1 });
+            }
+        }
+    }
+
+    fn emit_call(&mut self, f: &mut AbcFunctionState, call: &CallExpression) {
+        let num_args = call.arguments.len() as u32;
+        let base_mapping = self.host.node_mapping().get(&call.base);
+        let r = self.emit_reference_with_mapping(f, &call.base, base_mapping, Some(true));
+        match r {
+            AbcReference::Property(name) => {
+                for arg in &call.arguments {
+                    self.emit_exp(f, arg);
+                }
+                f.emit(abc::Op::CallProperty { index: name, num_args });
+            },
+            AbcReference::Super(name) => {
+                for arg in &call.arguments {
+                    self.emit_exp(f, arg);
+                }
+                f.emit(abc::Op::CallSuper { index: name, num_args });
+            },
+            AbcReference::RuntimeProperty(name) => {
+                let key = f.builder.alloc_local();
+                let object = f.builder.alloc_local();
+                f.emit(abc::Op::SetLocal { index: key });
+                f.emit(abc::Op::Dup);
+                f.emit(abc::Op::SetLocal { index: object });
+                f.emit(abc::Op::GetLocal { index: key });
+                f.builder.emit_with_runtime_name(abc::Op::GetProperty { index: name }, 1);
+                f.emit(abc::Op::GetLocal { index: object });
+                for arg in &call.arguments {
+                    self.emit_exp(f, arg);
+                }
+                f.emit(abc::Op::Call { num_args });
+                f.emit(abc::Op::Kill { index: key });
+                f.emit(abc::Op::Kill { index: object });
+            },
+            AbcReference::Lex(_) | AbcReference::Value => {
+                self.emit_get(f, r);
+                f.emit(abc::Op::PushNull);
+                for arg in &call.arguments {
+                    self.emit_exp(f, arg);
+                }
+                f.emit(abc::Op::Call { num_args });
+            },
+        }
+    }
+
+    /// Lowers a qualified identifier to a compile-time multiname. Returns `None`
+    /// if the name is given by a brackets expression.
+    fn identifier_name(&mut self, f: &AbcFunctionState, id: &QualifiedIdentifier) -> Option<abc::Index<abc::Multiname>> {
+        let QualifiedIdentifierIdentifier::Id((local_name, _)) = &id.id else {
+            return None;
+        };
+        let name = self.pool.add_string(if local_name == "*" { "" } else { local_name });
+        let qualifier = id.qualifier.as_ref()
+            .and_then(|q| self.host.node_mapping().get(q))
+            .filter(|q| q.is::<NamespaceConstant>());
+        Some(match (qualifier, id.attribute) {
+            (Some(q), attribute) => {
+                let namespace = self.namespace(&q.referenced_ns());
+                if attribute {
+                    self.pool.add_multiname(&abc::Multiname::QNameA { namespace, name })
+                } else {
+                    self.pool.add_multiname(&abc::Multiname::QName { namespace, name })
+                }
+            },
+            (None, true) => self.pool.add_multiname(&abc::Multiname::MultinameA { namespace_set: f.open_ns_set, name }),
+            (None, false) => self.pool.add_multiname(&abc::Multiname::Multiname { namespace_set: f.open_ns_set, name }),
+        })
+    }
+
+    /// Pushes the components of a reference onto the stack.
+    ///
+    /// If `lex_as_object` is given, properties resolved through the scope chain
+    /// push their scope object, through `findpropstrict` (`true`) or
+    /// `findproperty` (`false`), resulting in a `Property` reference.
+    fn emit_reference(&mut self, f: &mut AbcFunctionState, exp: &Rc<Expression>, lex_as_object: Option<bool>) -> AbcReference {
+        let mapping = self.host.node_mapping().get(exp);
+        self.emit_reference_with_mapping(f, exp, mapping, lex_as_object)
+    }
+
+    fn emit_reference_with_mapping(&mut self, f: &mut AbcFunctionState, exp: &Rc<Expression>, mapping: Option<Thingy>, lex_as_object: Option<bool>) -> AbcReference {
+        let r = self.emit_reference_1(f, exp, mapping);
+        match (r, lex_as_object) {
+            (AbcReference::Lex(name), Some(strict)) => {
+                f.emit(if strict { abc::Op::FindPropStrict { index: name } } else { abc::Op::FindProperty { index: name } });
+                AbcReference::Property(name)
+            },
+            _ => r,
+        }
+    }
+
+    fn emit_reference_1(&mut self, f: &mut AbcFunctionState, exp: &Rc<Expression>, mapping: Option<Thingy>) -> AbcReference {
+        let is_value = mapping.as_ref().map(|m| m.is::<ConversionValue>() || m.is::<Constant>() || m.is::<ThisObject>()).unwrap_or(false);
+        if is_value {
+            self.emit_exp_with_mapping(f, exp, mapping);
+            return AbcReference::Value;
+        }
+        let fixture_property = mapping.as_ref().filter(|m| m.is::<FixtureReferenceValue>()).map(|m| m.property());
+        if let Some(property) = fixture_property.as_ref().filter(|p| p.is::<ClassType>() || p.is::<EnumType>() || p.is::<InterfaceType>()) {
+            self.add_referenced_type(property);
+        }
+        match exp.as_ref() {
+            Expression::Paren(p) => self.emit_reference(f, &p.expression, None),
+            Expression::QualifiedIdentifier(id) => {
+                if let Some(property) = fixture_property {
+                    return AbcReference::Lex(self.qname(&property.name()));
+                }
+                match self.identifier_name(f, id) {
+                    Some(name) => AbcReference::Lex(name),
+                    None => {
+                        self.emit_exp_with_mapping(f, exp, None);
+                        AbcReference::Value
+                    },
+                }
+            },
+            Expression::Member(me) => {
+                if let Expression::Super(_) = me.base.as_ref() {
+                    f.emit(abc::Op::GetLocal { index: 0 });
+                    let name = match fixture_property {
+                        Some(property) => self.qname(&property.name()),
+                        None => self.identifier_name(f, &me.identifier).unwrap_or_else(|| self.dynamic_name(f, "")),
+                    };
+                    return AbcReference::Super(name);
+                }
+                if let Some(property) = fixture_property {
+                    let name = self.qname(&property.name());
+                    if mapping.as_ref().unwrap().is::<PackageReferenceValue>() {
+                        return AbcReference::Lex(name);
+                    }
+                    self.emit_exp(f, &me.base);
+                    return AbcReference::Property(name);
+                }
+                self.emit_exp(f, &me.base);
+                match self.identifier_name(f, &me.identifier) {
+                    Some(name) => AbcReference::Property(name),
+                    None => {
+                        if let QualifiedIdentifierIdentifier::Brackets(key) = &me.identifier.id {
+                            self.emit_exp(f, key);
+                        } else {
+                            f.emit(abc::Op::PushUndefined);
+                        }
+                        AbcReference::RuntimeProperty(self.runtime_name(f))
+                    },
+                }
+            },
+            Expression::ComputedMember(cm) => {
+                if let Expression::Super(_) = cm.base.as_ref() {
+                    f.emit(abc::Op::GetLocal { index: 0 });
+                } else {
+                    self.emit_exp(f, &cm.base);
+                }
+                match mapping.as_ref().filter(|m| m.is::<TupleReferenceValue>()) {
+                    Some(m) => self.emit_int(f, m.tuple_index() as i32),
+                    None => self.emit_exp(f, &cm.key),
+                }
+                AbcReference::RuntimeProperty(self.runtime_name(f))
+            },
+            _ => {
+                self.emit_exp_with_mapping(f, exp, mapping);
+                AbcReference::Value
+            },
+        }
+    }
+
+    /// Replaces the reference components on the stack by the reference value.
+    fn emit_get(&mut self, f: &mut AbcFunctionState, r: AbcReference) {
+        match r {
+            AbcReference::Lex(name) => f.emit(abc::Op::GetLex { index: name }),
+            AbcReference::Property(name) => f.emit(abc::Op::GetProperty { index: name }),
+            AbcReference::RuntimeProperty(name) => f.builder.emit_with_runtime_name(abc::Op::GetProperty { index: name }, 1),
+            AbcReference::Super(name) => f.emit(abc::Op::GetSuper { index: name }),
+            AbcReference::Value => {},
+        }
+    }
+
+    /// Assigns the value on the top of the stack to the reference, consuming
+    /// both the value and the reference components.
+    fn emit_set(&mut self, f: &mut AbcFunctionState, r: AbcReference) {
+        match r {
+            AbcReference::Lex(name) => {
+                f.emit(abc::Op::FindProperty { index: name });
+                f.emit(abc::Op::Swap);
+                f.emit(abc::Op::SetProperty { index: name });
+            },
+            AbcReference::Property(name) => f.emit(abc::Op::SetProperty { index: name }),
+            AbcReference::RuntimeProperty(name) => f.builder.emit_with_runtime_name(abc::Op::SetProperty { index: name }, 1),
+            AbcReference::Super(name) => f.emit(abc::Op::SetSuper { index: name }),
+            AbcReference::Value => {
+                f.emit(abc::Op::Pop);
+                f.emit(abc::Op::Pop);
+            },
+        }
+    }
+
+    /// Moves the reference components from the stack into registers.
+    fn spill_reference(&mut self, f: &mut AbcFunctionState, r: AbcReference) -> Vec<u32> {
+        let count = match r {
+            AbcReference::Property(_) | AbcReference::Super(_) => 1,
+            AbcReference::RuntimeProperty(_) => 2,
+            AbcReference::Lex(_) | AbcReference::Value => 0,
+        };
+        let registers: Vec<u32> = (0..count).map(|_| f.builder.alloc_local()).collect();
+        for register in registers.iter().rev() {
+            f.emit(abc::Op::SetLocal { index: *register });
+        }
+        registers
+    }
+
+    fn reload_reference(&mut self, f: &mut AbcFunctionState, registers: &[u32]) {
+        for register in registers {
+            f.emit(abc::Op::GetLocal { index: *register });
+        }
+    }
+
+    fn kill_registers(&mut self, f: &mut AbcFunctionState, registers: &[u32]) {
+        for register in registers {
+            f.emit(abc::Op::Kill { index: *register });
+        }
+    }
+
+    fn emit_assignment(&mut self, f: &mut AbcFunctionState, ae: &AssignmentExpression) {
+        let Some(compound) = ae.compound else {
+            if matches!(ae.left.as_ref(), Expression::ArrayLiteral(_) | Expression::ObjectInitializer(_)) {
+                self.emit_exp(f, &ae.right);
+                f.emit(abc::Op::Dup);
+                self.emit_pattern_store(f, &ae.left);
+                return;
+            }
+            let r = self.emit_reference(f, &ae.left, Some(false));
+            self.emit_exp(f, &ae.right);
+            let result = f.builder.alloc_local();
+            f.emit(abc::Op::Dup);
+            f.emit(abc::Op::SetLocal { index: result });
+            self.emit_set(f, r);
+            f.emit(abc::Op::GetLocal { index: result });
+            f.emit(abc::Op::Kill { index: result });
+            return;
+        };
+
+        let r = self.emit_reference(f, &ae.left, Some(true));
+        let registers = self.spill_reference(f, r);
+        self.reload_reference(f, &registers);
+        self.reload_reference(f, &registers);
+        self.emit_get(f, r);
+        match compound {
+            Operator::LogicalAnd | Operator::LogicalOr | Operator::NullCoalescing => {
+                let keep = f.builder.new_label();
+                f.emit(abc::Op::Dup);
+                match compound {
+                    Operator::LogicalAnd => f.builder.emit_branch(abc::Op::IfFalse { offset: 0 }, keep),
+                    Operator::LogicalOr => f.builder.emit_branch(abc::Op::IfTrue { offset: 0 }, keep),
+                    _ => {
+                        f.emit(abc::Op::PushNull);
+                        f.builder.emit_branch(abc::Op::IfNe { offset: 0 }, keep);
+                    },
+                }
+                f.emit(abc::Op::Pop);
+                self.emit_exp(f, &ae.right);
+                f.builder.bind(keep);
+            },
+            _ => {
+                self.emit_exp(f, &ae.right);
+                self.emit_binary_operator(f, compound);
+            },
+        }
+        let result = f.builder.alloc_local();
+        f.emit(abc::Op::Dup);
+        f.emit(abc::Op::SetLocal { index: result });
+        self.emit_set(f, r);
+        f.emit(abc::Op::GetLocal { index: result });
+        f.emit(abc::Op::Kill { index: result });
+        self.kill_registers(f, &registers);
+    }
+
+    fn emit_unary(&mut self, f: &mut AbcFunctionState, ue: &UnaryExpression) {
+        match ue.operator {
+            Operator::PreIncrement | Operator::PreDecrement | Operator::PostIncrement | Operator::PostDecrement => {
+                let post = matches!(ue.operator, Operator::PostIncrement | Operator::PostDecrement);
+                let increment = matches!(ue.operator, Operator::PreIncrement | Operator::PostIncrement);
+                let r = self.emit_reference(f, &ue.expression, Some(true));
+                let registers = self.spill_reference(f, r);
+                self.reload_reference(f, &registers);
+                self.reload_reference(f, &registers);
+                self.emit_get(f, r);
+                let result = f.builder.alloc_local();
+                if post {
+                    f.emit(abc::Op::ConvertD);
+                    f.emit(abc::Op::Dup);
+                    f.emit(abc::Op::SetLocal { index: result });
+                    f.emit(if increment { abc::Op::Increment } else { abc::Op::Decrement });
+                } else {
+                    f.emit(if increment { abc::Op::Increment } else { abc::Op::Decrement });
+                    f.emit(abc::Op::Dup);
+                    f.emit(abc::Op::SetLocal { index: result });
+                }
+                self.emit_set(f, r);
+                f.emit(abc::Op::GetLocal { index: result });
+                f.emit(abc::Op::Kill { index: result });
+                self.kill_registers(f, &registers);
+            },
+            Operator::Delete => {
+                let r = self.emit_reference(f, &ue.expression, Some(false));
+                match r {
+                    AbcReference::Property(name) => f.emit(abc::Op::DeleteProperty { index: name }),
+                    AbcReference::RuntimeProperty(name) => f.builder.emit_with_runtime_name(abc::Op::DeleteProperty { index: name }, 1),
+                    _ => {
+                        f.emit(abc::Op::Pop);
+                        f.emit(abc::Op::PushFalse);
+                    },
+                }
+            },
+            Operator::Void => {
+                self.emit_exp(f, &ue.expression);
+                f.emit(abc::Op::Pop);
+                f.emit(abc::Op::PushUndefined);
+            },
+            Operator::Typeof => {
+                self.emit_exp(f, &ue.expression);
+                f.emit(abc::Op::TypeOf);
+            },
+            Operator::Positive => {
+                self.emit_exp(f, &ue.expression);
+                f.emit(abc::Op::ConvertD);
+            },
+            Operator::Negative => {
+                self.emit_exp(f, &ue.expression);
+                f.emit(abc::Op::Negate);
+            },
+            Operator::BitwiseNot => {
+                self.emit_exp(f, &ue.expression);
+                f.emit(abc::Op::BitNot);
+            },
+            Operator::LogicalNot => {
+                self.emit_exp(f, &ue.expression);
+                f.emit(abc::Op::Not);
+            },
+            // The non-null operator generates no assertion code, and asynchronous
+            // control flow is not supported yet.
+            _ => self.emit_exp(f, &ue.expression),
+        }
+    }
+
+    fn emit_binary(&mut self, f: &mut AbcFunctionState, be: &BinaryExpression) {
+        match be.operator {
+            Operator::LogicalAnd | Operator::LogicalOr => {
+                let end = f.builder.new_label();
+                self.emit_exp(f, &be.left);
+                f.emit(abc::Op::Dup);
+                if be.operator == Operator::LogicalAnd {
+                    f.builder.emit_branch(abc::Op::IfFalse { offset: 0 }, end);
+                } else {
+                    f.builder.emit_branch(abc::Op::IfTrue { offset: 0 }, end);
+                }
+                f.emit(abc::Op::Pop);
+                self.emit_exp(f, &be.right);
+                f.builder.bind(end);
+            },
+            Operator::NullCoalescing => {
+                let end = f.builder.new_label();
+                self.emit_exp(f, &be.left);
+                f.emit(abc::Op::Dup);
+                f.emit(abc::Op::PushNull);
+                f.builder.emit_branch(abc::Op::IfNe { offset: 0 }, end);
+                f.emit(abc::Op::Pop);
+                self.emit_exp(f, &be.right);
+                f.builder.bind(end);
+            },
+            Operator::Power => {
+                let math = self.public_name("Math");
+                f.emit(abc::Op::GetLex { index: math });
+                self.emit_exp(f, &be.left);
+                self.emit_exp(f, &be.right);
+                let pow = self.public_name("pow");
+                f.emit(abc::Op::CallProperty { index: pow, num_args: 2 });
+            },
+            Operator::LogicalXor => {
+                self.emit_exp(f, &be.left);
+                f.emit(abc::Op::ConvertB);
+                self.emit_exp(f, &be.right);
+                f.emit(abc::Op::ConvertB);
+                f.emit(abc::Op::Equals);
+                f.emit(abc::Op::Not);
+            },
+            operator => {
+                self.emit_exp(f, &be.left);
+                self.emit_exp(f, &be.right);
+                self.emit_binary_operator(f, operator);
+            },
+        }
+    }
+
+    /// Applies a binary operator to the two values on the top of the stack.
+    fn emit_binary_operator(&mut self, f: &mut AbcFunctionState, operator: Operator) {
+        let ops: &[abc::Op] = match operator {
+            Operator::Multiply => &[abc::Op::Multiply],
+            Operator::Divide => &[abc::Op::Divide],
+            Operator::Remainder => &[abc::Op::Modulo],
+            Operator::Add => &[abc::Op::Add],
+            Operator::Subtract => &[abc::Op::Subtract],
+            Operator::ShiftLeft => &[abc::Op::LShift],
+            Operator::ShiftRight => &[abc::Op::RShift],
+            Operator::ShiftRightUnsigned => &[abc::Op::URShift],
+            Operator::Lt => &[abc::Op::LessThan],
+            Operator::Gt => &[abc::Op::GreaterThan],
+            Operator::Le => &[abc::Op::LessEquals],
+            Operator::Ge => &[abc::Op::GreaterEquals],
+            Operator::Instanceof => &[abc::Op::InstanceOf],
+            Operator::In => &[abc::Op::In],
+            Operator::NotIn => &[abc::Op::In, abc::Op::Not],
+            Operator::Is => &[abc::Op::IsTypeLate],
+            Operator::IsNot => &[abc::Op::IsTypeLate, abc::Op::Not],
+            Operator::As => &[abc::Op::AsTypeLate],
+            Operator::Equals => &[abc::Op::Equals],
+            Operator::NotEquals => &[abc::Op::Equals, abc::Op::Not],
+            Operator::StrictEquals => &[abc::Op::StrictEquals],
+            Operator::StrictNotEquals => &[abc::Op::StrictEquals, abc::Op::Not],
+            Operator::BitwiseAnd => &[abc::Op::BitAnd],
+            Operator::BitwiseXor => &[abc::Op::BitXor],
+            Operator::BitwiseOr => &[abc::Op::BitOr],
+            Operator::LogicalXor => &[abc::Op::Equals, abc::Op::Not],
+            Operator::Power => {
+                // Math.pow(left, right)
+                let left = f.builder.alloc_local();
+                let right = f.builder.alloc_local();
+                f.emit(abc::Op::SetLocal { index: right });
+                f.emit(abc::Op::SetLocal { index: left });
+                let math = self.public_name("Math");
+                f.emit(abc::Op::GetLex { index: math });
+                f.emit(abc::Op::GetLocal { index: left });
+                f.emit(abc::Op::GetLocal { index: right });
+                let pow = self.public_name("pow");
+                f.emit(abc::Op::CallProperty { index: pow, num_args: 2 });
+                f.emit(abc::Op::Kill { index: left });
+                f.emit(abc::Op::Kill { index: right });
+                return;
+            },
+            _ => &[abc::Op::Add],
+        };
+        for op in ops {
+            f.emit(op.clone());
+        }
+    }
+}
+
+/// Expands configuration directives whose constant is `true`
+/// and include directives.
+pub(crate) fn flatten_directives(host: &SemanticHost, list: &[Rc<Directive>]) -> Vec<Rc<Directive>> {
+    let mut r = vec![];
+    for drtv in list {
+        match drtv.as_ref() {
+            Directive::IncludeDirective(d) => r.extend(flatten_directives(host, &d.nested_directives)),
+            Directive::NormalConfigurationDirective(d) => {
+                let name = format!("{}::{}", d.namespace.0, d.constant_name.0);
+                if host.config_constants().get(&name).map(|v| v == "true").unwrap_or(false) {
+                    match d.directive.as_ref() {
+                        Directive::Block(block) => r.extend(flatten_directives(host, &block.directives)),
+                        _ => r.extend(flatten_directives(host, std::slice::from_ref(&d.directive))),
+                    }
+                }
+            },
+            _ => r.push(drtv.clone()),
+        }
+    }
+    r
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+    use hydroper_swf::avm2::types as abc;
+
+    fn string(abc_file: &abc::AbcFile, i: abc::Index<String>) -> &str {
+        std::str::from_utf8(&abc_file.constant_pool.strings[i.0 as usize - 1]).unwrap()
+    }
+
+    fn qname_local_name(abc_file: &abc::AbcFile, i: abc::Index<abc::Multiname>) -> &str {
+        match &abc_file.constant_pool.multinames[i.0 as usize - 1] {
+            abc::Multiname::QName { name, .. } => string(abc_file, *name),
+            _ => panic!("Expected QName"),
+        }
+    }
+
+    #[test]
+    fn test_do_abc2_round_trip() {
+        let host = SemanticHost::new(SemanticHostOptions::default());
+        let package = host.factory().create_package(["com", "example"]);
+        let public_ns = package.public_ns().unwrap();
+        let class = define_class(&host, &package, "Foo", None);
+        define_property(&host, &class, "x", false, &host.any_type());
+
+        let f_name = host.factory().create_qname(&public_ns, "f".into());
+        let f_slot = host.factory().create_method_slot(&f_name, &host.factory().create_function_type(vec![], host.void_type()));
+        class.prototype(&host).set(f_name, f_slot);
+
+        let cu = CompilationUnit::new(None, r#"
+            package com.example {
+                public class Foo {
+                    public var x = 10;
+                    public function f(): void {
+                        try {
+                            x = 1;
+                        } catch (e) {
+                            x = 2;
+                        } finally {
+                            x += 3;
+                        }
+                        for (var i = 0; i < 10; i++) {
+                            if (i == 5) break;
+                        }
+                    }
+                }
+            }
+        "#.into());
+        let program = ParserFacade(&cu, ParserOptions::default()).parse_program();
+
+        let mut emitter = AbcEmitter::new(&host);
+        emitter.emit_program(&program);
+        let payload = emitter.finish_do_abc2("com.example.Foo");
+
+        // Write and read a SWF containing the DoABC2 tag
+        let header = hydroper_swf::Header {
+            compression: hydroper_swf::Compression::None,
+            version: 10,
+            stage_size: hydroper_swf::Rectangle::default(),
+            frame_rate: hydroper_swf::Fixed8::from_f32(24.0),
+            num_frames: 1,
+        };
+        let mut swf_bytes = vec![];
+        hydroper_swf::write_swf(&header, &[payload.to_tag()], &mut swf_bytes).unwrap();
+        let swf_buf = hydroper_swf::decompress_swf(&swf_bytes[..]).unwrap();
+        let swf = hydroper_swf::parse_swf(&swf_buf).unwrap();
+        let do_abc = swf.tags.iter().find_map(|t| match t {
+            hydroper_swf::Tag::DoAbc2(d) => Some(d.clone()),
+            _ => None,
+        }).unwrap();
+        assert!(do_abc.name == "com.example.Foo");
+        assert!(do_abc.flags.contains(hydroper_swf::DoAbc2Flag::LAZY_INITIALIZE));
+        assert_eq!(do_abc.data, &payload.data[..]);
+
+        let abc_file = hydroper_swf::avm2::read::Reader::new(do_abc.data).read().unwrap();
+
+        // Instance information
+        assert_eq!(abc_file.instances.len(), 1);
+        let instance = &abc_file.instances[0];
+        assert_eq!(qname_local_name(&abc_file, instance.name), "Foo");
+        let abc::Multiname::QName { namespace, .. } = &abc_file.constant_pool.multinames[instance.name.0 as usize - 1] else {
+            panic!();
+        };
+        let abc::Namespace::Package(package_name) = &abc_file.constant_pool.namespaces[namespace.0 as usize - 1] else {
+            panic!();
+        };
+        assert_eq!(string(&abc_file, *package_name), "com.example");
+        let trait_names: Vec<&str> = instance.traits.iter().map(|t| qname_local_name(&abc_file, t.name)).collect();
+        assert_eq!(trait_names, ["f", "x"]);
+
+        // The "prototype" property is not emitted
+        assert!(abc_file.classes[0].traits.is_empty());
+
+        // Method body of f(): one catch clause and a catch-all handler
+        let abc::TraitKind::Method { method, .. } = &instance.traits[0].kind else {
+            panic!();
+        };
+        let body = abc_file.method_bodies.iter().find(|b| b.method == *method).unwrap();
+        assert_eq!(body.exceptions.len(), 2);
+        assert!(body.max_stack > 0);
+        assert!(body.max_scope_depth >= 2);
+        assert!(body.exceptions.iter().all(|e| e.from_offset < e.to_offset && (e.target_offset as usize) < body.code.len()));
+
+        // The script initializes the class
+        assert_eq!(abc_file.scripts.len(), 1);
+        let class_traits: Vec<&abc::Trait> = abc_file.scripts[0].traits.iter().filter(|t| matches!(t.kind, abc::TraitKind::Class { .. })).collect();
+        assert_eq!(class_traits.len(), 1);
+        assert_eq!(qname_local_name(&abc_file, class_traits[0].name), "Foo");
+    }
+}
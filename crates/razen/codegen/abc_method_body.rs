@@ -0,0 +1,321 @@
+use hydroper_swf::avm2::types as abc;
+use hydroper_swf::avm2::write::Writer as AbcWriter;
+
+/// A branch target inside an `AbcMethodBodyBuilder`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct AbcLabel(usize);
+
+struct AbcLabelState {
+    position: Option<usize>,
+    stack_depth: Option<u32>,
+    scope_depth: Option<u32>,
+}
+
+struct AbcExceptionEntry {
+    from: AbcLabel,
+    to: AbcLabel,
+    target: AbcLabel,
+    type_name: abc::Index<abc::Multiname>,
+    variable_name: abc::Index<abc::Multiname>,
+}
+
+/// Builder of an AVM2 method body.
+///
+/// The builder encodes instructions and keeps track of the operand stack
+/// depth, scope stack depth and number of local registers, which are
+/// required in the resulting `MethodBody`. Branch offsets are resolved
+/// through labels once the body is finished.
+///
+/// Multinames given to instructions are assumed to be compile-time
+/// multinames, that is, they do not consume runtime name components
+/// from the operand stack.
+pub struct AbcMethodBodyBuilder {
+    code: Vec<u8>,
+    stack_depth: u32,
+    max_stack: u32,
+    scope_depth: u32,
+    max_scope_depth: u32,
+    init_scope_depth: u32,
+    num_locals: u32,
+    labels: Vec<AbcLabelState>,
+    branch_patches: Vec<(usize, AbcLabel)>,
+    exceptions: Vec<AbcExceptionEntry>,
+    traits: Vec<abc::Trait>,
+}
+
+impl AbcMethodBodyBuilder {
+    /// Constructs a method body builder. The local registers
+    /// are initialized with the `this` receiver and the given number of parameters.
+    pub fn new(num_params: u32, init_scope_depth: u32) -> Self {
+        Self {
+            code: vec![],
+            stack_depth: 0,
+            max_stack: 0,
+            scope_depth: init_scope_depth,
+            max_scope_depth: init_scope_depth,
+            init_scope_depth,
+            num_locals: num_params + 1,
+            labels: vec![],
+            branch_patches: vec![],
+            exceptions: vec![],
+            traits: vec![],
+        }
+    }
+
+    /// Allocates a new local register.
+    pub fn alloc_local(&mut self) -> u32 {
+        let i = self.num_locals;
+        self.num_locals += 1;
+        i
+    }
+
+    pub fn stack_depth(&self) -> u32 {
+        self.stack_depth
+    }
+
+    pub fn scope_depth(&self) -> u32 {
+        self.scope_depth
+    }
+
+    /// Current offset in the bytecode.
+    pub fn offset(&self) -> usize {
+        self.code.len()
+    }
+
+    pub fn add_trait(&mut self, t: abc::Trait) {
+        self.traits.push(t);
+    }
+
+    /// Emits a non-branch instruction.
+    pub fn emit(&mut self, op: abc::Op) {
+        assert!(Self::branch_offset(&op).is_none(), "Branch instructions must be emitted through emit_branch()");
+        AbcWriter::new(&mut self.code).write_op(&op).unwrap();
+        self.apply_effects(&op);
+    }
+
+    /// Emits an instruction whose multiname consumes runtime name
+    /// components from the operand stack, such as a `MultinameL`
+    /// given to `getproperty`.
+    pub fn emit_with_runtime_name(&mut self, op: abc::Op, runtime_name_components: u32) {
+        self.emit(op);
+        self.stack_depth = self.stack_depth.saturating_sub(runtime_name_components);
+    }
+
+    /// Overrides the tracked scope depth. This is used after an unconditional
+    /// jump that popped scopes, where the following code is reached
+    /// from a different scope depth.
+    pub fn set_scope_depth(&mut self, depth: u32) {
+        self.scope_depth = depth;
+    }
+
+    /// Emits a branch instruction (such as `jump` or `iftrue`) targetting a label.
+    /// The offset contained in the given instruction is ignored.
+    pub fn emit_branch(&mut self, op: abc::Op, label: AbcLabel) {
+        assert!(Self::branch_offset(&op).is_some(), "Expected a branch instruction");
+        AbcWriter::new(&mut self.code).write_op(&op).unwrap();
+        // Branch offsets are always encoded as a signed 24-bit integer
+        // at the end of the instruction.
+        self.branch_patches.push((self.code.len() - 3, label));
+        self.apply_effects(&op);
+
+        let state = &mut self.labels[label.0];
+        state.stack_depth.get_or_insert(self.stack_depth);
+        state.scope_depth.get_or_insert(self.scope_depth);
+    }
+
+    pub fn new_label(&mut self) -> AbcLabel {
+        self.labels.push(AbcLabelState {
+            position: None,
+            stack_depth: None,
+            scope_depth: None,
+        });
+        AbcLabel(self.labels.len() - 1)
+    }
+
+    /// Binds a label to the current offset. If a branch to this label
+    /// has already been emitted, the stack depths are restored to those
+    /// at the branch.
+    pub fn bind(&mut self, label: AbcLabel) {
+        let state = &mut self.labels[label.0];
+        assert!(state.position.is_none(), "Label bound twice");
+        state.position = Some(self.code.len());
+        if let Some(d) = state.stack_depth {
+            self.stack_depth = d;
+        }
+        if let Some(d) = state.scope_depth {
+            self.scope_depth = d;
+        }
+        state.stack_depth = Some(self.stack_depth);
+        state.scope_depth = Some(self.scope_depth);
+    }
+
+    /// Binds a label that is targetted by backward branches, emitting
+    /// a `label` instruction as the AVM2 expects.
+    pub fn bind_loop_head(&mut self, label: AbcLabel) {
+        self.bind(label);
+        self.emit(abc::Op::Label);
+    }
+
+    /// Adds an entry to the exception table and returns its index,
+    /// which is used by the `newcatch` instruction.
+    pub fn add_exception(&mut self, from: AbcLabel, to: AbcLabel, target: AbcLabel, type_name: abc::Index<abc::Multiname>, variable_name: abc::Index<abc::Multiname>) -> u32 {
+        self.exceptions.push(AbcExceptionEntry { from, to, target, type_name, variable_name });
+        (self.exceptions.len() - 1) as u32
+    }
+
+    /// Binds the target label of an exception handler. At the start
+    /// of the handler, the scope stack is reset and the operand stack
+    /// contains only the thrown value.
+    pub fn bind_exception_target(&mut self, label: AbcLabel) {
+        self.bind(label);
+        self.stack_depth = 1;
+        self.max_stack = self.max_stack.max(1);
+        self.scope_depth = self.init_scope_depth;
+        self.labels[label.0].stack_depth = Some(1);
+        self.labels[label.0].scope_depth = Some(self.init_scope_depth);
+    }
+
+    pub fn finish(mut self, method: abc::Index<abc::Method>) -> abc::MethodBody {
+        for (pos, label) in std::mem::take(&mut self.branch_patches) {
+            let target = self.labels[label.0].position.expect("Unbound label") as i32;
+            let offset = target - (pos as i32 + 3);
+            let bytes = offset.to_le_bytes();
+            self.code[pos..pos + 3].copy_from_slice(&bytes[0..3]);
+        }
+        let exceptions = self.exceptions.iter().map(|e| abc::Exception {
+            from_offset: self.labels[e.from.0].position.expect("Unbound label") as u32,
+            to_offset: self.labels[e.to.0].position.expect("Unbound label") as u32,
+            target_offset: self.labels[e.target.0].position.expect("Unbound label") as u32,
+            type_name: e.type_name,
+            variable_name: e.variable_name,
+        }).collect();
+        abc::MethodBody {
+            method,
+            max_stack: self.max_stack,
+            num_locals: self.num_locals,
+            init_scope_depth: self.init_scope_depth,
+            max_scope_depth: self.max_scope_depth,
+            code: self.code,
+            exceptions,
+            traits: self.traits,
+        }
+    }
+
+    fn apply_effects(&mut self, op: &abc::Op) {
+        let (pops, pushes) = Self::stack_effect(op);
+        self.stack_depth = self.stack_depth.saturating_sub(pops) + pushes;
+        self.max_stack = self.max_stack.max(self.stack_depth);
+
+        match op {
+            abc::Op::PushScope | abc::Op::PushWith => {
+                self.scope_depth += 1;
+                self.max_scope_depth = self.max_scope_depth.max(self.scope_depth);
+            },
+            abc::Op::PopScope => {
+                self.scope_depth = self.scope_depth.saturating_sub(1);
+            },
+            _ => {},
+        }
+
+        // Keep track of used local registers.
+        let local = match op {
+            abc::Op::GetLocal { index } |
+            abc::Op::SetLocal { index } |
+            abc::Op::Kill { index } |
+            abc::Op::IncLocal { index } |
+            abc::Op::IncLocalI { index } |
+            abc::Op::DecLocal { index } |
+            abc::Op::DecLocalI { index } => Some(*index),
+            abc::Op::HasNext2 { object_register, index_register } => Some(*object_register.max(index_register)),
+            _ => None,
+        };
+        if let Some(local) = local {
+            self.num_locals = self.num_locals.max(local + 1);
+        }
+    }
+
+    fn branch_offset(op: &abc::Op) -> Option<i32> {
+        match op {
+            abc::Op::IfEq { offset } |
+            abc::Op::IfFalse { offset } |
+            abc::Op::IfGe { offset } |
+            abc::Op::IfGt { offset } |
+            abc::Op::IfLe { offset } |
+            abc::Op::IfLt { offset } |
+            abc::Op::IfNe { offset } |
+            abc::Op::IfNge { offset } |
+            abc::Op::IfNgt { offset } |
+            abc::Op::IfNle { offset } |
+            abc::Op::IfNlt { offset } |
+            abc::Op::IfStrictEq { offset } |
+            abc::Op::IfStrictNe { offset } |
+            abc::Op::IfTrue { offset } |
+            abc::Op::Jump { offset } => Some(*offset),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of values an instruction pops from
+    /// and pushes to the operand stack.
+    pub fn stack_effect(op: &abc::Op) -> (u32, u32) {
+        use abc::Op;
+        match op {
+            // Push a value
+            Op::PushByte { .. } | Op::PushDouble { .. } | Op::PushFalse | Op::PushInt { .. } |
+            Op::PushNamespace { .. } | Op::PushNaN | Op::PushNull | Op::PushShort { .. } |
+            Op::PushString { .. } | Op::PushTrue | Op::PushUint { .. } | Op::PushUndefined |
+            Op::GetLocal { .. } | Op::GetGlobalScope | Op::GetScopeObject { .. } |
+            Op::GetOuterScope { .. } | Op::GetGlobalSlot { .. } | Op::GetLex { .. } |
+            Op::FindDef { .. } | Op::FindProperty { .. } | Op::FindPropStrict { .. } |
+            Op::NewFunction { .. } | Op::NewActivation | Op::NewCatch { .. } => (0, 1),
+
+            // Replace the top value
+            Op::AsType { .. } | Op::BitNot | Op::CheckFilter | Op::Coerce { .. } | Op::CoerceA |
+            Op::CoerceB | Op::CoerceD | Op::CoerceI | Op::CoerceO | Op::CoerceS | Op::CoerceU |
+            Op::ConvertB | Op::ConvertD | Op::ConvertI | Op::ConvertO | Op::ConvertS | Op::ConvertU |
+            Op::Decrement | Op::DecrementI | Op::Increment | Op::IncrementI | Op::EscXAttr |
+            Op::EscXElem | Op::GetProperty { .. } | Op::GetSlot { .. } | Op::GetSuper { .. } |
+            Op::GetDescendants { .. } | Op::DeleteProperty { .. } | Op::IsType { .. } |
+            Op::Negate | Op::NegateI | Op::Not | Op::TypeOf | Op::NewClass { .. } |
+            Op::Lf32 | Op::Lf64 | Op::Li16 | Op::Li32 | Op::Li8 | Op::Sxi1 | Op::Sxi16 | Op::Sxi8 => (1, 1),
+
+            // Binary operators
+            Op::Add | Op::AddI | Op::AsTypeLate | Op::BitAnd | Op::BitOr | Op::BitXor |
+            Op::Divide | Op::Equals | Op::GreaterEquals | Op::GreaterThan | Op::In |
+            Op::InstanceOf | Op::IsTypeLate | Op::LessEquals | Op::LessThan | Op::LShift |
+            Op::Modulo | Op::Multiply | Op::MultiplyI | Op::RShift | Op::StrictEquals |
+            Op::Subtract | Op::SubtractI | Op::URShift | Op::HasNext | Op::NextName | Op::NextValue => (2, 1),
+
+            Op::Dup => (1, 2),
+            Op::Swap => (2, 2),
+            Op::Pop | Op::PushScope | Op::PushWith | Op::SetLocal { .. } | Op::ReturnValue |
+            Op::Throw | Op::SetGlobalSlot { .. } | Op::Dxns { .. } | Op::DxnsLate |
+            Op::IfFalse { .. } | Op::IfTrue { .. } | Op::LookupSwitch(_) => (1, 0),
+            Op::SetProperty { .. } | Op::InitProperty { .. } | Op::SetSlot { .. } | Op::SetSuper { .. } |
+            Op::IfEq { .. } | Op::IfGe { .. } | Op::IfGt { .. } | Op::IfLe { .. } | Op::IfLt { .. } |
+            Op::IfNe { .. } | Op::IfNge { .. } | Op::IfNgt { .. } | Op::IfNle { .. } | Op::IfNlt { .. } |
+            Op::IfStrictEq { .. } | Op::IfStrictNe { .. } |
+            Op::Si8 | Op::Si16 | Op::Si32 | Op::Sf32 | Op::Sf64 => (2, 0),
+            Op::HasNext2 { .. } => (0, 1),
+
+            // Calls
+            Op::Call { num_args } => (num_args + 2, 1),
+            Op::CallMethod { num_args, .. } |
+            Op::CallStatic { num_args, .. } |
+            Op::CallProperty { num_args, .. } |
+            Op::CallPropLex { num_args, .. } |
+            Op::CallSuper { num_args, .. } |
+            Op::Construct { num_args } |
+            Op::ConstructProp { num_args, .. } => (num_args + 1, 1),
+            Op::CallPropVoid { num_args, .. } |
+            Op::CallSuperVoid { num_args, .. } |
+            Op::ConstructSuper { num_args } => (num_args + 1, 0),
+            Op::ApplyType { num_types } => (num_types + 1, 1),
+            Op::NewArray { num_args } => (*num_args, 1),
+            Op::NewObject { num_args } => (num_args * 2, 1),
+
+            // No effect on the operand stack
+            _ => (0, 0),
+        }
+    }
+}
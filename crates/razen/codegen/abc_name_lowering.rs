@@ -0,0 +1,259 @@
+use crate::ns::*;
+use hydroper_swf::avm2::types as abc;
+
+/// Lowers semantic namespaces, qualified names and types
+/// to AVM2 namespaces and multinames.
+///
+/// # Vectors
+///
+/// `Vector.<T>` types lower to `TypeName` multinames, as in:
+///
+/// ```plain
+/// TypeName(QName(PackageNamespace("__AS3__.vec"),"Vector")<QName(PackageNamespace(""),"Number")>)
+/// ```
+///
+/// and their class objects are produced by a `getlex` and `applytype` sequence:
+///
+/// ```plain
+/// getlex QName(PackageNamespace("__AS3__.vec"),"Vector")
+/// getlex QName(PackageNamespace(""),"Number")
+/// applytype (1)
+/// ```
+///
+/// # Erasure
+///
+/// Other parameterized types, such as `Array.<T>` and user generic types,
+/// have no runtime representation; their type arguments are erased,
+/// lowering to the multiname of their origin type.
+pub struct AbcNameLowering<'a> {
+    host: &'a SemanticHost,
+    namespace_indices: HashMap<Thingy, abc::Index<abc::Namespace>>,
+}
+
+impl<'a> AbcNameLowering<'a> {
+    pub fn new(host: &'a SemanticHost) -> Self {
+        Self {
+            host,
+            namespace_indices: HashMap::new(),
+        }
+    }
+
+    /// Lowers a namespace.
+    pub fn namespace(&mut self, pool: &mut AbcConstantPool, ns: &Thingy) -> abc::Index<abc::Namespace> {
+        if let Some(i) = self.namespace_indices.get(ns) {
+            return *i;
+        }
+        let abc_ns = if let Some(kind) = ns.system_ns_kind() {
+            match kind {
+                SystemNamespaceKind::Public | SystemNamespaceKind::Internal => {
+                    let package_name = ns.parent()
+                        .filter(|p| p.is::<Package>())
+                        .map(|p| p.fully_qualified_name_list().join("."))
+                        .unwrap_or_default();
+                    let package_name = pool.add_string(&package_name);
+                    if kind == SystemNamespaceKind::Public {
+                        abc::Namespace::Package(package_name)
+                    } else {
+                        abc::Namespace::PackageInternal(package_name)
+                    }
+                },
+                SystemNamespaceKind::Private => {
+                    let name = ns.parent().map(|p| Self::class_ns_name(&p)).unwrap_or_default();
+                    abc::Namespace::Private(pool.add_string(&name))
+                },
+                SystemNamespaceKind::Protected => {
+                    let name = ns.parent().map(|p| Self::class_ns_name(&p)).unwrap_or_default();
+                    abc::Namespace::Protected(pool.add_string(&name))
+                },
+                SystemNamespaceKind::StaticProtected => {
+                    let name = ns.parent().map(|p| Self::class_ns_name(&p)).unwrap_or_default();
+                    abc::Namespace::StaticProtected(pool.add_string(&name))
+                },
+            }
+        } else if ns.is::<ExplicitNamespace>() {
+            abc::Namespace::Explicit(pool.add_string(&ns.uri()))
+        } else {
+            abc::Namespace::Namespace(pool.add_string(&ns.uri()))
+        };
+        let i = pool.add_namespace(&abc_ns);
+        self.namespace_indices.insert(ns.clone(), i);
+        i
+    }
+
+    /// Name of class namespaces, in the form `"package:Class"`, or
+    /// `"Class"` for classes in the top-level package.
+    fn class_ns_name(class: &Thingy) -> String {
+        if !(class.is::<ClassType>() || class.is::<EnumType>() || class.is::<InterfaceType>()) {
+            return "".into();
+        }
+        let package_name = class.parent().filter(|p| p.is::<Package>()).map(|p| p.fully_qualified_name_list().join(".")).unwrap_or_default();
+        if package_name.is_empty() {
+            class.name().local_name()
+        } else {
+            format!("{}:{}", package_name, class.name().local_name())
+        }
+    }
+
+    /// Lowers a namespace set.
+    pub fn namespace_set(&mut self, pool: &mut AbcConstantPool, set: &[Thingy]) -> abc::Index<abc::NamespaceSet> {
+        let mut indices = vec![];
+        for ns in set {
+            let i = self.namespace(pool, ns);
+            if !indices.contains(&i) {
+                indices.push(i);
+            }
+        }
+        pool.add_ns_set(&indices)
+    }
+
+    /// Lowers a qualified name to a `QName` multiname.
+    pub fn qname(&mut self, pool: &mut AbcConstantPool, name: &QName) -> abc::Index<abc::Multiname> {
+        let namespace = self.namespace(pool, &name.namespace());
+        let name = pool.add_string(&name.local_name());
+        pool.add_multiname(&abc::Multiname::QName { namespace, name })
+    }
+
+    /// Whether a type is `Vector.<T>` for any `T`.
+    pub fn is_vector(&self, type_thingy: &Thingy) -> bool {
+        let t = type_thingy.escape_of_nullable_or_non_nullable();
+        t.is::<TypeAfterSubstitution>() && t.origin() == self.host.vector_type()
+    }
+
+    /// Lowers a type to the multiname used in type annotations. The any type,
+    /// type parameters and unresolved types lower to the index zero.
+    pub fn type_name(&mut self, pool: &mut AbcConstantPool, type_thingy: &Thingy) -> abc::Index<abc::Multiname> {
+        let t = type_thingy.escape_of_nullable_or_non_nullable();
+        if t.is::<ClassType>() || t.is::<EnumType>() || t.is::<InterfaceType>() {
+            self.qname(pool, &t.name())
+        } else if self.is_vector(&t) {
+            let base_type = self.qname(pool, &t.origin().name());
+            let parameters = t.substitute_types().iter().map(|arg| self.type_name(pool, &arg)).collect();
+            pool.add_multiname(&abc::Multiname::TypeName { base_type, parameters })
+        } else if t.is::<TypeAfterSubstitution>() {
+            self.type_name(pool, &t.origin())
+        } else if t.is::<VoidType>() {
+            pool.add_package_qname("", "void")
+        } else if t.is::<TupleType>() {
+            pool.add_package_qname("", "Array")
+        } else if t.is::<FunctionType>() {
+            pool.add_package_qname("", "Function")
+        } else {
+            abc::Index::new(0)
+        }
+    }
+
+    /// Returns the instructions that push the class object of a type.
+    /// Vectors are applied to their type arguments, and types that
+    /// lower to the index zero push `null`.
+    pub fn type_value(&mut self, pool: &mut AbcConstantPool, type_thingy: &Thingy) -> Vec<abc::Op> {
+        let t = type_thingy.escape_of_nullable_or_non_nullable();
+        if self.is_vector(&t) {
+            let base = self.qname(pool, &t.origin().name());
+            let mut r = vec![abc::Op::GetLex { index: base }];
+            let args = t.substitute_types();
+            for arg in args.iter() {
+                r.extend(self.type_value(pool, &arg));
+            }
+            r.push(abc::Op::ApplyType { num_types: args.length() as u32 });
+            return r;
+        }
+        let name = self.type_name(pool, &t);
+        if name.0 == 0 {
+            vec![abc::Op::PushNull]
+        } else {
+            vec![abc::Op::GetLex { index: name }]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+    use hydroper_swf::avm2::types as abc;
+
+    fn define_generic_class(host: &SemanticHost, package: &Thingy, name: &str, type_params: &[&str]) -> Thingy {
+        let class = define_class(host, package, name, None);
+        let ns = package.public_ns().unwrap();
+        let params: Vec<Thingy> = type_params.iter().map(|p| host.factory().create_type_parameter_type(&host.factory().create_qname(&ns, (*p).into()))).collect();
+        class.set_type_params(Some(SharedArray::from(params)));
+        class
+    }
+
+    fn qname_of(pool: &AbcConstantPool, i: abc::Index<abc::Multiname>) -> (String, String) {
+        let pool = pool.pool();
+        let abc::Multiname::QName { namespace, name } = &pool.multinames[i.0 as usize - 1] else {
+            panic!("Expected QName");
+        };
+        let abc::Namespace::Package(package_name) = &pool.namespaces[namespace.0 as usize - 1] else {
+            panic!("Expected package namespace");
+        };
+        let s = |i: &abc::Index<String>| String::from_utf8(pool.strings[i.0 as usize - 1].clone()).unwrap();
+        (s(package_name), s(name))
+    }
+
+    #[test]
+    fn test_name_lowering() {
+        let host = SemanticHost::new(SemanticHostOptions::default());
+        let top = host.top_level_package();
+        let int_type = define_class(&host, &top, "int", None);
+        let array_type = define_generic_class(&host, &top, "Array", &["T"]);
+        let vector_type = define_generic_class(&host, &host.as3_vec_package(), "Vector", &["T"]);
+        assert!(host.vector_type() == vector_type);
+        let user_package = host.factory().create_package(["com", "example"]);
+        let map_type = define_generic_class(&host, &user_package, "Map", &["K", "V"]);
+
+        let mut pool = AbcConstantPool::new();
+        let mut lowering = AbcNameLowering::new(&host);
+
+        // Vector.<int>
+        let vector_of_int = host.factory().create_type_after_substitution(&vector_type, &shared_array![int_type.clone()]);
+        let mn = lowering.type_name(&mut pool, &vector_of_int);
+        let abc::Multiname::TypeName { base_type, parameters } = pool.pool().multinames[mn.0 as usize - 1].clone() else {
+            panic!("Expected TypeName");
+        };
+        assert_eq!(qname_of(&pool, base_type), ("__AS3__.vec".into(), "Vector".into()));
+        assert_eq!(parameters.len(), 1);
+        assert_eq!(qname_of(&pool, parameters[0]), ("".into(), "int".into()));
+
+        // Vector.<Vector.<int>>
+        let nested = host.factory().create_type_after_substitution(&vector_type, &shared_array![vector_of_int.clone()]);
+        let nested_mn = lowering.type_name(&mut pool, &nested);
+        let abc::Multiname::TypeName { parameters, .. } = pool.pool().multinames[nested_mn.0 as usize - 1].clone() else {
+            panic!("Expected TypeName");
+        };
+        assert_eq!(parameters, vec![mn]);
+
+        let ops = lowering.type_value(&mut pool, &nested);
+        let vector_name = lowering.qname(&mut pool, &vector_type.name());
+        let int_name = lowering.qname(&mut pool, &int_type.name());
+        assert_eq!(ops, vec![
+            abc::Op::GetLex { index: vector_name },
+            abc::Op::GetLex { index: vector_name },
+            abc::Op::GetLex { index: int_name },
+            abc::Op::ApplyType { num_types: 1 },
+            abc::Op::ApplyType { num_types: 1 },
+        ]);
+
+        // Vector.<*>
+        let vector_of_any = host.factory().create_type_after_substitution(&vector_type, &shared_array![host.any_type()]);
+        assert_eq!(lowering.type_value(&mut pool, &vector_of_any), vec![
+            abc::Op::GetLex { index: vector_name },
+            abc::Op::PushNull,
+            abc::Op::ApplyType { num_types: 1 },
+        ]);
+
+        // Array.<int> and Map.<int, Array.<int>> are erased.
+        let array_of_int = host.factory().create_type_after_substitution(&array_type, &shared_array![int_type.clone()]);
+        let array_name = lowering.type_name(&mut pool, &array_of_int);
+        assert_eq!(qname_of(&pool, array_name), ("".into(), "Array".into()));
+        assert_eq!(lowering.type_value(&mut pool, &array_of_int), vec![abc::Op::GetLex { index: array_name }]);
+
+        let map_of = host.factory().create_type_after_substitution(&map_type, &shared_array![int_type.clone(), array_of_int.clone()]);
+        let map_name = lowering.type_name(&mut pool, &map_of);
+        assert_eq!(qname_of(&pool, map_name), ("com.example".into(), "Map".into()));
+        assert_eq!(lowering.type_value(&mut pool, &map_of), vec![abc::Op::GetLex { index: map_name }]);
+
+        // Type parameters lower to the any type.
+        assert_eq!(lowering.type_name(&mut pool, &map_type.type_params().unwrap().get(0).unwrap()).0, 0);
+    }
+}
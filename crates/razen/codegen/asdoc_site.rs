@@ -0,0 +1,1088 @@
+use crate::ns::*;
+use std::path::Path;
+
+/// A static HTML documentation site generated from the ASDoc comments of the
+/// definitions of a set of packages.
+///
+/// The site consists of:
+///
+/// * `index.html`, listing the packages and types;
+/// * a `package-summary.html` page in the directory of each package, as in
+///   `com/example/package-summary.html`, describing its types, functions, variables
+///   and namespaces;
+/// * a page for each class, interface and enum, as in `com/example/Foo.html`,
+///   describing its inheritance, constructor, properties, methods, events and styles,
+///   and listing the members, events and styles it inherits;
+/// * `style.css`.
+///
+/// An `AsDocManifest` may additionally describe a home section, mixed into `index.html`,
+/// Markdown sections, each rendered into its own page, and files included verbatim.
+///
+/// Only public definitions and public and protected members are documented, except
+/// those whose ASDoc comment has the `@private` tag. The `__AS3__.vec.Vector` class is documented
+/// in the top-level package, and the `__AS3__.vec` package is not documented.
+///
+/// ASDoc bodies are Markdown, unless the `asdoc_markdown` compiler option of their
+/// compilation unit is off, in which case they are HTML. Markdown links may refer to
+/// API entities, as in `com.example.Foo`, `com.example.Foo#bar` or `com.example`,
+/// and, within sections, to the documents of other sections and to included files,
+/// relative to the linking document. Links that cannot be resolved are reported
+/// as warnings.
+///
+/// ```ignore
+/// let site = AsDocSite::generate(&host, &[host.factory().create_package(["com", "example"])]);
+/// site.write(Path::new("docs"))?;
+/// ```
+pub struct AsDocSite {
+    /// Files of the site, as paths relative to its root directory and their contents.
+    pub files: Vec<(String, String)>,
+    /// Files included verbatim from an ASDoc manifest, as paths relative
+    /// to the root directory and their contents.
+    pub included_files: Vec<(String, Vec<u8>)>,
+}
+
+impl AsDocSite {
+    pub const INDEX_PATH: &'static str = "index.html";
+    pub const STYLE_SHEET_PATH: &'static str = "style.css";
+    pub const PACKAGE_SUMMARY: &'static str = "package-summary.html";
+
+    const STYLE_SHEET: &'static str = "\
+body { font-family: sans-serif; margin: 0; color: #222; }
+nav { background: #2b3a4a; padding: 0.5em 1em; }
+nav a { color: #fff; }
+main { padding: 1em 2em; }
+a { color: #1a5fb4; }
+pre, code { font-family: monospace; }
+pre { background: #f4f4f4; padding: 0.5em; overflow-x: auto; }
+table { border-collapse: collapse; margin-bottom: 1em; }
+td, th { border: 1px solid #ccc; padding: 0.3em 0.6em; text-align: left; vertical-align: top; }
+.member { border-top: 1px solid #ccc; margin-top: 1em; }
+.deprecated { color: #a00; }
+";
+
+    /// Generates the documentation of packages.
+    pub fn generate(host: &SemanticHost, packages: &[Thingy]) -> Self {
+        AsDocGenerator::new(host, packages, None).generate()
+    }
+
+    /// Generates the documentation of packages along with the
+    /// sections and included files of an ASDoc manifest.
+    pub fn generate_with_manifest(host: &SemanticHost, packages: &[Thingy], manifest: &AsDocManifest) -> Self {
+        AsDocGenerator::new(host, packages, Some(manifest)).generate()
+    }
+
+    /// Returns the contents of a file of the site.
+    pub fn file(&self, path: &str) -> Option<&str> {
+        self.files.iter().find(|(p, _)| p == path).map(|(_, c)| c.as_str())
+    }
+
+    /// Writes the site into a directory.
+    pub fn write(&self, directory: &Path) -> std::io::Result<()> {
+        for (path, content) in &self.files {
+            let path = directory.join(path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, content)?;
+        }
+        for (path, content) in &self.included_files {
+            let path = directory.join(path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, content)?;
+        }
+        Ok(())
+    }
+
+    /// Path of the page of a package.
+    pub fn package_path(package_name: &str) -> String {
+        format!("{}{}", Self::package_directory(package_name), Self::PACKAGE_SUMMARY)
+    }
+
+    fn package_directory(package_name: &str) -> String {
+        if package_name.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", package_name.replace('.', "/"))
+        }
+    }
+
+    /// Returns a link from a page to a path relative to the site root.
+    pub fn relative_link(from_page: &str, path: &str) -> String {
+        let mut from: Vec<&str> = from_page.split('/').collect();
+        from.pop();
+        let mut to: Vec<&str> = path.split('/').collect();
+        let file = to.pop().unwrap_or("");
+        let common = from.iter().zip(to.iter()).take_while(|(a, b)| a == b).count();
+        let mut link = "../".repeat(from.len() - common);
+        for directory in &to[common..] {
+            link.push_str(directory);
+            link.push('/');
+        }
+        link.push_str(file);
+        link
+    }
+}
+
+struct AsDocPackage {
+    name: String,
+    definitions: Vec<Thingy>,
+}
+
+struct AsDocGenerator<'a> {
+    host: &'a SemanticHost,
+    packages: Vec<AsDocPackage>,
+    /// Pages of the documented types.
+    pages: HashMap<Thingy, String>,
+    manifest: Option<&'a AsDocManifest>,
+}
+
+impl<'a> AsDocGenerator<'a> {
+    fn new(host: &'a SemanticHost, packages: &[Thingy], manifest: Option<&'a AsDocManifest>) -> Self {
+        let mut list: Vec<AsDocPackage> = vec![];
+        let mut vector_types = vec![];
+        for package in packages {
+            let name = package.fully_qualified_name_list().join(".");
+            let definitions = Self::public_definitions(host, package);
+            if name == "__AS3__.vec" {
+                vector_types.extend(definitions.into_iter().filter(|d| d.name().local_name() == "Vector"));
+                continue;
+            }
+            list.push(AsDocPackage { name, definitions });
+        }
+        if !vector_types.is_empty() {
+            if !list.iter().any(|p| p.name.is_empty()) {
+                list.push(AsDocPackage { name: String::new(), definitions: vec![] });
+            }
+            let top_level = list.iter_mut().find(|p| p.name.is_empty()).unwrap();
+            top_level.definitions.extend(vector_types);
+            top_level.definitions.sort_by_key(|d| d.name().local_name());
+        }
+        list.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut pages = HashMap::new();
+        for package in &list {
+            for definition in package.definitions.iter().filter(|d| Self::is_type(d)) {
+                let path = format!("{}{}.html", AsDocSite::package_directory(&package.name), definition.name().local_name());
+                pages.insert(definition.clone(), path);
+            }
+        }
+        Self { host, packages: list, pages, manifest }
+    }
+
+    fn generate(&self) -> AsDocSite {
+        let mut files = vec![
+            (AsDocSite::INDEX_PATH.to_owned(), self.index_page()),
+            (AsDocSite::STYLE_SHEET_PATH.to_owned(), AsDocSite::STYLE_SHEET.to_owned()),
+        ];
+        for package in &self.packages {
+            let path = AsDocSite::package_path(&package.name);
+            files.push((path.clone(), self.package_page(package, &path)));
+            for definition in package.definitions.iter().filter(|d| Self::is_type(d)) {
+                let path = self.pages[definition].clone();
+                files.push((path.clone(), self.type_page(package, definition, &path)));
+            }
+        }
+        let mut included_files = vec![];
+        if let Some(manifest) = self.manifest {
+            for section in manifest.all_sections().into_iter().filter(|s| s.page != AsDocManifest::HOME_PAGE) {
+                files.push((section.page.clone(), self.section_page(section)));
+            }
+            for file in &manifest.include_files {
+                if let Ok(content) = std::fs::read(&file.resolved_path) {
+                    included_files.push((file.path.clone(), content));
+                }
+            }
+        }
+        AsDocSite { files, included_files }
+    }
+
+    fn index_page(&self) -> String {
+        let mut body = String::new();
+        let mut title = "API Reference".to_owned();
+        if let Some(manifest) = self.manifest {
+            if let Some(home) = &manifest.home {
+                title = home.title.clone();
+                body.push_str(&format!("<h1>{}</h1>\n{}", Markdown::escape_html(&home.title), self.section_html(home)));
+            }
+            if !manifest.sections.is_empty() {
+                body.push_str(&format!("<h2>Sections</h2>\n{}", Self::section_list_html(&manifest.sections, AsDocSite::INDEX_PATH)));
+            }
+        }
+        body.push_str("<h1>API Reference</h1>\n<h2>Packages</h2>\n<table>\n");
+        for package in &self.packages {
+            body.push_str(&format!("<tr><td><a href=\"{}\">{}</a></td></tr>\n", AsDocSite::package_path(&package.name), Self::package_display_name(&package.name)));
+        }
+        body.push_str("</table>\n<h2>All Types</h2>\n<table>\n");
+        let mut types: Vec<(&Thingy, &String)> = self.pages.iter().collect();
+        types.sort_by_key(|(t, path)| (t.name().local_name(), (*path).clone()));
+        for (t, path) in types {
+            body.push_str(&format!("<tr><td><a href=\"{path}\">{}</a></td><td>{}</td></tr>\n", Markdown::escape_html(&t.name().local_name()), self.summary_html(self.asdoc(t, None).as_ref(), AsDocSite::INDEX_PATH)));
+        }
+        body.push_str("</table>\n");
+        Self::page(&title, AsDocSite::INDEX_PATH, &body)
+    }
+
+    fn section_page(&self, section: &AsDocSection) -> String {
+        let mut body = format!("<h1>{}</h1>\n{}", Markdown::escape_html(&section.title), self.section_html(section));
+        if !section.children.is_empty() {
+            body.push_str(&Self::section_list_html(&section.children, &section.page));
+        }
+        Self::page(&section.title, &section.page, &body)
+    }
+
+    /// Renders the Markdown document of a section.
+    fn section_html(&self, section: &AsDocSection) -> String {
+        let Some(cu) = &section.compilation_unit else {
+            return String::new();
+        };
+        let directory = section.source.rsplit_once('/').map(|(d, _)| d).unwrap_or("");
+        let location = Location::with_offsets(cu, 0, cu.text().len());
+        self.markdown_html(cu.text(), &location, directory, &section.page)
+    }
+
+    /// Lists links to sections and their children.
+    fn section_list_html(sections: &[AsDocSection], page: &str) -> String {
+        let mut output = String::from("<ul>\n");
+        for section in sections {
+            output.push_str(&format!("<li><a href=\"{}\">{}</a>", AsDocSite::relative_link(page, &section.page), Markdown::escape_html(&section.title)));
+            if !section.children.is_empty() {
+                output.push_str(&format!("\n{}", Self::section_list_html(&section.children, page)));
+            }
+            output.push_str("</li>\n");
+        }
+        output.push_str("</ul>\n");
+        output
+    }
+
+    fn package_page(&self, package: &AsDocPackage, page: &str) -> String {
+        let display_name = Self::package_display_name(&package.name);
+        let mut body = format!("<h1>{}</h1>\n", Markdown::escape_html(&display_name));
+
+        for title in ["Interfaces", "Classes", "Enums", "Functions", "Namespaces", "Properties"] {
+            let definitions: Vec<&Thingy> = package.definitions.iter().filter(|d| Self::package_section(d) == title).collect();
+            if definitions.is_empty() {
+                continue;
+            }
+            body.push_str(&format!("<h2>{title}</h2>\n<table>\n"));
+            for d in &definitions {
+                let name = Markdown::escape_html(&d.name().local_name());
+                let link = match self.pages.get(d) {
+                    Some(path) => format!("<a href=\"{}\">{name}</a>", AsDocSite::relative_link(page, path)),
+                    None => format!("<a href=\"#{name}\">{name}</a>"),
+                };
+                body.push_str(&format!("<tr><td>{link}</td><td>{}</td></tr>\n", self.summary_html(self.asdoc(d, None).as_ref(), page)));
+            }
+            body.push_str("</table>\n");
+        }
+
+        // Details of package-level functions, properties and namespaces
+        for d in package.definitions.iter().filter(|d| !Self::is_type(d)) {
+            body.push_str(&self.member_html(d, None, page));
+        }
+        Self::page(&format!("{display_name} Summary"), page, &body)
+    }
+
+    fn type_page(&self, package: &AsDocPackage, t: &Thingy, page: &str) -> String {
+        let host = self.host;
+        let name = t.name().local_name();
+        let kind = if t.is::<InterfaceType>() { "Interface" } else if t.is::<EnumType>() { "Enum" } else { "Class" };
+        let mut body = format!("<h1>{kind} {}</h1>\n", Markdown::escape_html(&name));
+        body.push_str(&format!("<p>Package: <a href=\"{}\">{}</a></p>\n",
+            AsDocSite::relative_link(page, &AsDocSite::package_path(&package.name)),
+            Markdown::escape_html(&Self::package_display_name(&package.name))));
+
+        let mut modifiers = vec!["public"];
+        if t.is::<ClassType>() {
+            if t.is_final() {
+                modifiers.push("final");
+            }
+            if t.is_abstract() {
+                modifiers.push("abstract");
+            }
+            if t.is_dynamic() {
+                modifiers.push("dynamic");
+            }
+        }
+        modifiers.push(if t.is::<InterfaceType>() { "interface" } else if t.is::<EnumType>() { "enum" } else { "class" });
+        body.push_str(&format!("<pre class=\"signature\">{} {}</pre>\n", modifiers.join(" "), Markdown::escape_html(&name)));
+
+        // Inheritance summary
+        let ancestors = self.ancestors(t);
+        if t.is::<ClassType>() && !ancestors.is_empty() {
+            let chain: Vec<String> = std::iter::once(Markdown::escape_html(&name))
+                .chain(ancestors.iter().map(|a| self.type_html(a, page)))
+                .collect();
+            body.push_str(&format!("<p class=\"inheritance\">Inheritance: {}</p>\n", chain.join(" &rarr; ")));
+        }
+        let interfaces: Vec<Thingy> = if t.is::<InterfaceType>() {
+            t.extends_interfaces(host).iter().collect()
+        } else {
+            t.implements(host).iter().collect()
+        };
+        if !interfaces.is_empty() {
+            let label = if t.is::<InterfaceType>() { "Extends" } else { "Implements" };
+            let list: Vec<String> = interfaces.iter().map(|i| self.type_html(i, page)).collect();
+            body.push_str(&format!("<p>{label}: {}</p>\n", list.join(", ")));
+        }
+        let subtypes: Vec<String> = self.subtypes(t).iter().map(|s| self.type_html(s, page)).collect();
+        if !subtypes.is_empty() {
+            let label = if t.is::<InterfaceType>() { "Implementors" } else { "Subclasses" };
+            body.push_str(&format!("<p>{label}: {}</p>\n", subtypes.join(", ")));
+        }
+
+        if let Some(asdoc) = self.asdoc(t, None) {
+            body.push_str(&self.description_html(&asdoc, Some(t), page));
+        }
+
+        // Constructor
+        if let Some(ctor) = t.is::<ClassType>().then(|| t.constructor_method(host)).flatten().filter(Self::is_documented) {
+            body.push_str("<h2>Constructor</h2>\n");
+            body.push_str(&self.member_html(&ctor, Some(t), page));
+        }
+
+        let members = self.members(t);
+        let properties: Vec<&Thingy> = members.iter().filter(|m| !m.is::<MethodSlot>()).collect();
+        let methods: Vec<&Thingy> = members.iter().filter(|m| m.is::<MethodSlot>()).collect();
+        for (title, list) in [("Properties", properties), ("Methods", methods)] {
+            if list.is_empty() {
+                continue;
+            }
+            body.push_str(&format!("<h2>{title}</h2>\n<table>\n"));
+            for m in &list {
+                let member_name = Markdown::escape_html(&m.name().local_name());
+                let static_label = if m.is_static() { " <small>[static]</small>" } else { "" };
+                body.push_str(&format!("<tr><td><a href=\"#{member_name}\">{member_name}</a>{static_label}</td><td>{}</td></tr>\n", self.summary_html(self.asdoc(m, Some(t)).as_ref(), page)));
+            }
+            body.push_str("</table>\n");
+            for m in &list {
+                body.push_str(&self.member_html(m, Some(t), page));
+            }
+        }
+        body.push_str(&self.inherited_members_html(t, &members, page));
+
+        // Events and styles
+        body.push_str(&self.events_html(t, page));
+        body.push_str(&self.styles_html(t, page));
+
+        let title = match &package.name[..] {
+            "" => name,
+            package_name => format!("{package_name}.{name}"),
+        };
+        Self::page(&title, page, &body)
+    }
+
+    /// Documents a property or method of a type, or a package-level definition.
+    fn member_html(&self, m: &Thingy, owner: Option<&Thingy>, page: &str) -> String {
+        let host = self.host;
+        let name = m.name().local_name();
+        let mut output = format!("<div class=\"member\" id=\"{0}\">\n<h3>{0}</h3>\n", Markdown::escape_html(&name));
+        let static_modifier = if owner.is_some() && m.is_static() { "static " } else { "" };
+        let access = if m.name().namespace().is_public_ns() { "public" } else { "protected" };
+
+        let signature = if m.is::<MethodSlot>() {
+            let param_names = self.asdoc(m, owner).map(|a| Self::param_names(&a)).unwrap_or_default();
+            let signature = m.signature(host);
+            let params: Vec<String> = signature.params().iter().enumerate().map(|(i, p)| {
+                let name = param_names.get(i).cloned().unwrap_or_else(|| format!("arg{}", i + 1));
+                let name = Markdown::escape_html(&name);
+                let type_html = self.type_html(&p.static_type, page);
+                match p.kind {
+                    ParameterKind::Required => format!("{name}: {type_html}"),
+                    ParameterKind::Optional => format!("{name}: {type_html} = &hellip;"),
+                    ParameterKind::Rest => format!("...{name}: {type_html}"),
+                }
+            }).collect();
+            let result = if m.is_constructor() { String::new() } else { format!(": {}", self.type_html(&signature.result_type(), page)) };
+            format!("{access} {static_modifier}function {}({}){result}", Markdown::escape_html(&name), params.join(", "))
+        } else if m.is::<VirtualSlot>() {
+            let read_write = match (m.getter(host).is_some(), m.setter(host).is_some()) {
+                (true, false) => " <small>[read-only]</small>",
+                (false, true) => " <small>[write-only]</small>",
+                _ => "",
+            };
+            format!("{access} {static_modifier}property {}: {}{read_write}", Markdown::escape_html(&name), self.type_html(&m.property_static_type(host), page))
+        } else if Self::is_namespace_definition(m) {
+            let uri = m.var_constant().unwrap().referenced_ns().uri();
+            format!("public namespace {} = \"{}\"", Markdown::escape_html(&name), Markdown::escape_html(&uri))
+        } else {
+            let keyword = if m.read_only(host) { "const" } else { "var" };
+            format!("{access} {static_modifier}{keyword} {}: {}", Markdown::escape_html(&name), self.type_html(&m.static_type(host), page))
+        };
+        output.push_str(&format!("<pre class=\"signature\">{signature}</pre>\n"));
+
+        if let Some(asdoc) = self.asdoc(m, owner) {
+            output.push_str(&self.description_html(&asdoc, owner, page));
+        }
+        output.push_str("</div>\n");
+        output
+    }
+
+    /// Lists the public and protected members inherited from superclasses, or from
+    /// extended interfaces, that the type does not redefine.
+    fn inherited_members_html(&self, t: &Thingy, own_members: &[Thingy], page: &str) -> String {
+        let mut output = String::new();
+        let mut seen: HashSet<String> = own_members.iter().map(|m| m.name().local_name()).collect();
+        let ancestors: Vec<Thingy> = if t.is::<InterfaceType>() {
+            t.extends_interfaces(self.host).iter().collect()
+        } else {
+            self.ancestors(t)
+        };
+        for ancestor in ancestors {
+            let members: Vec<Thingy> = self.members(&ancestor).into_iter()
+                .filter(|m| !m.is_static() && seen.insert(m.name().local_name()))
+                .collect();
+            if members.is_empty() {
+                continue;
+            }
+            let links: Vec<String> = members.iter().map(|m| {
+                let name = m.name().local_name();
+                self.member_link(&ancestor, &name, &name, page)
+            }).collect();
+            output.push_str(&format!("<p>Members inherited from {}: {}</p>\n", self.type_html(&ancestor, page), links.join(", ")));
+        }
+        if output.is_empty() {
+            output
+        } else {
+            format!("<h2>Inherited Members</h2>\n{output}")
+        }
+    }
+
+    fn events_html(&self, t: &Thingy, page: &str) -> String {
+        let mut output = String::new();
+        let events: Vec<(String, Option<String>, Rc<Metadata>)> = t.metadata().iter()
+            .filter(|m| m.name.0 == "Event")
+            .filter_map(|m| FlexEvents::parse_metadata(&m).map(|(name, event_type)| (name, event_type, m.clone())))
+            .collect();
+        if !events.is_empty() {
+            output.push_str("<h2>Events</h2>\n");
+            for (name, event_type, metadata) in &events {
+                output.push_str(&format!("<div class=\"member\" id=\"event:{0}\">\n<h3>{0}</h3>\n", Markdown::escape_html(name)));
+                if let Some(event_type) = event_type {
+                    output.push_str(&format!("<p>Event object type: {}</p>\n", self.type_name_html(event_type, page)));
+                }
+                if let Some(asdoc) = &metadata.asdoc {
+                    output.push_str(&self.description_html(asdoc, Some(t), page));
+                }
+                output.push_str("</div>\n");
+            }
+        }
+        let mut seen: HashSet<String> = events.into_iter().map(|(name, _, _)| name).collect();
+        for ancestor in self.ancestors(t) {
+            let names: Vec<String> = ancestor.metadata().iter()
+                .filter(|m| m.name.0 == "Event")
+                .filter_map(|m| FlexEvents::parse_metadata(&m).map(|(name, _)| name))
+                .filter(|name| seen.insert(name.clone()))
+                .collect();
+            if !names.is_empty() {
+                let links: Vec<String> = names.iter().map(|n| self.member_link(&ancestor, &format!("event:{n}"), n, page)).collect();
+                output.push_str(&format!("<p>Events inherited from {}: {}</p>\n", self.type_html(&ancestor, page), links.join(", ")));
+            }
+        }
+        output
+    }
+
+    fn styles_html(&self, t: &Thingy, page: &str) -> String {
+        let mut output = String::new();
+        let styles: Vec<StyleDefinition> = t.metadata().iter().filter_map(|m| StyleDefinition::from_metadata(&m)).collect();
+        if !styles.is_empty() {
+            output.push_str("<h2>Styles</h2>\n<table>\n<tr><th>Name</th><th>Type</th><th>Inherit</th><th>Description</th></tr>\n");
+            for style in &styles {
+                let mut style_type = style.style_type.as_ref().map(|s| self.type_name_html(s, page)).unwrap_or_default();
+                if let Some(format) = &style.format {
+                    style_type.push_str(&format!(" <small>(format: {})</small>", Markdown::escape_html(format)));
+                }
+                let description = style.metadata.asdoc.as_ref().map(|a| self.description_html(a, Some(t), page)).unwrap_or_default();
+                output.push_str(&format!("<tr id=\"style:{0}\"><td>{0}</td><td>{style_type}</td><td>{1}</td><td>{description}</td></tr>\n",
+                    Markdown::escape_html(&style.name), if style.inherit { "yes" } else { "no" }));
+            }
+            output.push_str("</table>\n");
+        }
+        let mut seen: HashSet<String> = styles.into_iter().map(|s| s.name).collect();
+        for ancestor in self.ancestors(t) {
+            let names: Vec<String> = ancestor.metadata().iter()
+                .filter_map(|m| StyleDefinition::from_metadata(&m))
+                .map(|s| s.name)
+                .filter(|name| seen.insert(name.clone()))
+                .collect();
+            if !names.is_empty() {
+                let links: Vec<String> = names.iter().map(|n| self.member_link(&ancestor, &format!("style:{n}"), n, page)).collect();
+                output.push_str(&format!("<p>Styles inherited from {}: {}</p>\n", self.type_html(&ancestor, page), links.join(", ")));
+            }
+        }
+        output
+    }
+
+    /// Renders the body and tags of an ASDoc comment.
+    fn description_html(&self, asdoc: &AsDoc, owner: Option<&Thingy>, page: &str) -> String {
+        let mut output = String::new();
+        for (tag, _) in &asdoc.tags {
+            if let AsDocTag::Deprecated { message } = tag {
+                let message = message.as_ref().map(|m| format!(" {}", self.text_html(m, &asdoc.location, page))).unwrap_or_default();
+                output.push_str(&format!("<div class=\"deprecated\"><strong>Deprecated.</strong>{message}</div>\n"));
+            }
+        }
+        if let Some((body, _)) = &asdoc.main_body {
+            output.push_str(&self.text_html(body, &asdoc.location, page));
+        }
+
+        let params: Vec<(&String, &String)> = asdoc.tags.iter().filter_map(|(tag, _)| match tag {
+            AsDocTag::Param { name, description } => Some((name, description)),
+            _ => None,
+        }).collect();
+        if !params.is_empty() {
+            output.push_str("<h4>Parameters</h4>\n<dl>\n");
+            for (name, description) in params {
+                output.push_str(&format!("<dt><code>{}</code></dt><dd>{}</dd>\n", Markdown::escape_html(name), self.text_html(description, &asdoc.location, page)));
+            }
+            output.push_str("</dl>\n");
+        }
+        for (tag, _) in &asdoc.tags {
+            match tag {
+                AsDocTag::Return(description) => {
+                    output.push_str(&format!("<h4>Returns</h4>\n{}", self.text_html(description, &asdoc.location, page)));
+                },
+                AsDocTag::Default(value) => {
+                    output.push_str(&format!("<p>The default value is <code>{}</code>.</p>\n", Markdown::escape_html(value)));
+                },
+                _ => {},
+            }
+        }
+
+        let throws: Vec<String> = asdoc.tags.iter().filter_map(|(tag, _)| match tag {
+            AsDocTag::Throws { class_reference, description } => {
+                let description = description.as_ref().map(|d| self.text_html(d, &asdoc.location, page)).unwrap_or_default();
+                Some(format!("<dt>{}</dt><dd>{description}</dd>\n", self.type_name_html(&class_reference.location().text(), page)))
+            },
+            _ => None,
+        }).collect();
+        if !throws.is_empty() {
+            output.push_str(&format!("<h4>Throws</h4>\n<dl>\n{}</dl>\n", throws.concat()));
+        }
+
+        for (tag, _) in &asdoc.tags {
+            if let AsDocTag::Example(example) = tag {
+                output.push_str(&format!("<h4>Example</h4>\n{}", self.text_html(example, &asdoc.location, page)));
+            }
+        }
+
+        let see: Vec<String> = asdoc.tags.iter().filter_map(|(tag, _)| match tag {
+            AsDocTag::See { reference, display_text } => Some(format!("<li>{}</li>\n", self.reference_html(reference, display_text.as_deref(), owner, page))),
+            _ => None,
+        }).collect();
+        if !see.is_empty() {
+            output.push_str(&format!("<h4>See also</h4>\n<ul>\n{}</ul>\n", see.concat()));
+        }
+        output
+    }
+
+    /// Returns the comment of a definition with `@inheritDoc` and `@copy` resolved,
+    /// as shown on the page of `destination`, which defaults to the page of the
+    /// definition itself, if it is a type, or of its parent.
+    fn asdoc(&self, definition: &Thingy, destination: Option<&Thingy>) -> Option<Rc<AsDoc>> {
+        let destination = destination.cloned()
+            .or_else(|| if Self::is_type(definition) { Some(definition.clone()) } else { definition.parent() })
+            .unwrap_or(definition.clone());
+        AsDocInheritance(self.host).resolve(definition, &destination)
+    }
+
+    /// Renders the first paragraph of an ASDoc body.
+    fn summary_html(&self, asdoc: Option<&Rc<AsDoc>>, page: &str) -> String {
+        let Some(asdoc) = asdoc else {
+            return String::new();
+        };
+        let Some((body, _)) = &asdoc.main_body else {
+            return String::new();
+        };
+        let first_paragraph = body.trim().split("\n\n").next().unwrap_or_default();
+        let html = self.text_html(first_paragraph, &asdoc.location, page);
+        html.trim().strip_prefix("<p>").and_then(|h| h.strip_suffix("</p>")).map(|h| h.to_owned()).unwrap_or(html)
+    }
+
+    /// Renders ASDoc text as Markdown, or as HTML if Markdown is disabled
+    /// for the compilation unit of the comment.
+    fn text_html(&self, text: &str, location: &Location, page: &str) -> String {
+        let markdown = location.compilation_unit().compiler_options()
+            .and_then(|o| Rc::downcast::<CompilerOptions>(o).ok())
+            .map(|o| o.asdoc_markdown)
+            .unwrap_or(true);
+        if markdown {
+            self.markdown_html(text, location, "", page)
+        } else {
+            format!("<p>{}</p>\n", text.trim())
+        }
+    }
+
+    /// Renders Markdown whose source is within `location`, resolving its links
+    /// relative to `directory` within the base path of the manifest and
+    /// reporting the links that cannot be resolved.
+    fn markdown_html(&self, text: &str, location: &Location, directory: &str, page: &str) -> String {
+        let cu = location.compilation_unit();
+        let source = cu.text();
+        let mut cursor = location.first_offset();
+        Markdown::to_html_with_links(text, &mut |href| {
+            // Links are visited in order, so each one is looked for after the previous one.
+            let needle = format!("]({href}");
+            let href_location = source.get(cursor..location.last_offset())
+                .and_then(|s| s.find(&needle))
+                .map(|i| {
+                    let first = cursor + i + 2;
+                    cursor = first + href.len();
+                    Location::with_offsets(&cu, first, cursor)
+                })
+                .unwrap_or(location.clone());
+            match self.resolve_link(href, directory, page) {
+                Some(link) => link,
+                None => {
+                    FxDiagnostic::add_warning(&href_location, FxDiagnosticKind::UnresolvedAsDocLink, diagarg![href.to_owned()]);
+                    href.to_owned()
+                },
+            }
+        })
+    }
+
+    /// Resolves the destination of a Markdown link into a link relative to a page.
+    fn resolve_link(&self, href: &str, directory: &str, page: &str) -> Option<String> {
+        if href.is_empty() || href.starts_with('#') || href.contains("://") || href.starts_with("mailto:") {
+            return Some(href.to_owned());
+        }
+        let (path, fragment) = match href.split_once('#') {
+            Some((path, fragment)) => (path, Some(fragment)),
+            None => (href, None),
+        };
+        let with_fragment = |link: String| match fragment {
+            Some(fragment) => format!("{link}#{fragment}"),
+            None => link,
+        };
+
+        // Section documents and included files
+        if let Some((manifest, source)) = self.manifest.zip(AsDocManifest::normalize_path(&format!("{directory}/{path}"))) {
+            if let Some(section) = manifest.all_sections().into_iter().find(|s| s.source == source) {
+                return Some(with_fragment(AsDocSite::relative_link(page, &section.page)));
+            }
+            if manifest.include_files.iter().any(|f| f.path == source) {
+                return Some(with_fragment(AsDocSite::relative_link(page, &source)));
+            }
+        }
+
+        // API entities
+        if let Some(t) = self.lookup_type(path) {
+            if fragment.is_some_and(|f| !self.has_anchor(&t, f)) {
+                return None;
+            }
+            return Some(with_fragment(AsDocSite::relative_link(page, &self.pages[&t])));
+        }
+        if self.packages.iter().any(|p| !p.name.is_empty() && p.name == path) {
+            return Some(with_fragment(AsDocSite::relative_link(page, &AsDocSite::package_path(path))));
+        }
+        None
+    }
+
+    /// Indicates whether the page of a type has an anchor for
+    /// a member, as in `bar`, an event, as in `event:change`,
+    /// or a style, as in `style:color`.
+    fn has_anchor(&self, t: &Thingy, anchor: &str) -> bool {
+        if let Some(event) = anchor.strip_prefix("event:") {
+            return t.metadata().iter().any(|m| m.name.0 == "Event" && FlexEvents::parse_metadata(&m).is_some_and(|(name, _)| name == event));
+        }
+        if let Some(style) = anchor.strip_prefix("style:") {
+            return t.metadata().iter().any(|m| StyleDefinition::from_metadata(&m).is_some_and(|s| s.name == style));
+        }
+        self.members(t).iter().any(|m| m.name().local_name() == anchor)
+            || t.is::<ClassType>() && t.constructor_method(self.host).is_some_and(|c| c.name().local_name() == anchor)
+    }
+
+    /// Renders a `@see` reference, as in `com.example.Foo#bar`, `#bar` or `Foo`.
+    fn reference_html(&self, reference: &AsDocReference, display_text: Option<&str>, owner: Option<&Thingy>, page: &str) -> String {
+        let base = reference.base.as_ref().map(|b| b.location().text());
+        let property = reference.instance_property.as_ref().map(|p| p.location.text());
+        let text = display_text.filter(|t| !t.is_empty()).map(|t| t.to_owned()).unwrap_or_else(|| match (&base, &property) {
+            (Some(base), Some(property)) => format!("{base}.{property}"),
+            (Some(base), None) => base.clone(),
+            (None, Some(property)) => property.clone(),
+            (None, None) => String::new(),
+        });
+        let target = match &base {
+            Some(base) => self.lookup_type(base),
+            None => owner.cloned(),
+        };
+        let href = target.as_ref().and_then(|t| self.pages.get(t)).map(|path| {
+            let path = AsDocSite::relative_link(page, path);
+            match &property {
+                Some(property) => format!("{path}#{property}"),
+                None => path,
+            }
+        });
+        match href {
+            Some(href) => format!("<a href=\"{}\">{}</a>", Markdown::escape_html(&href), Markdown::escape_html(&text)),
+            None => format!("<code>{}</code>", Markdown::escape_html(&text)),
+        }
+    }
+
+    /// Renders a type, linking it if documented.
+    fn type_html(&self, t: &Thingy, page: &str) -> String {
+        let t = t.escape_of_nullable_or_non_nullable();
+        match self.pages.get(&t) {
+            Some(path) => format!("<a href=\"{}\">{}</a>", AsDocSite::relative_link(page, path), Markdown::escape_html(&t.name().local_name())),
+            None => Markdown::escape_html(&t.to_string()),
+        }
+    }
+
+    /// Renders a type given by name, linking it if documented.
+    fn type_name_html(&self, name: &str, page: &str) -> String {
+        match self.lookup_type(name) {
+            Some(t) => self.type_html(&t, page),
+            None => Markdown::escape_html(name),
+        }
+    }
+
+    /// Links a member, event or style anchor of a type.
+    fn member_link(&self, t: &Thingy, anchor: &str, label: &str, page: &str) -> String {
+        match self.pages.get(t) {
+            Some(path) => format!("<a href=\"{}#{}\">{}</a>", AsDocSite::relative_link(page, path), Markdown::escape_html(anchor), Markdown::escape_html(label)),
+            None => format!("<code>{}</code>", Markdown::escape_html(label)),
+        }
+    }
+
+    /// Looks up a documented type by its fully qualified or local name.
+    fn lookup_type(&self, name: &str) -> Option<Thingy> {
+        let mut types: Vec<&Thingy> = self.pages.keys().collect();
+        types.sort_by_key(|t| self.pages[*t].clone());
+        types.iter().find(|t| t.fully_qualified_name() == name)
+            .or_else(|| types.iter().find(|t| t.name().local_name() == name))
+            .map(|t| (*t).clone())
+    }
+
+    /// Superclasses of a class, from the nearest.
+    fn ancestors(&self, t: &Thingy) -> Vec<Thingy> {
+        if !t.is::<ClassType>() {
+            return vec![];
+        }
+        t.descending_class_hierarchy(self.host).skip(1).take_while(|c| !c.is::<UnresolvedThingy>()).collect()
+    }
+
+    /// Documented subclasses of a class or implementors of an interface.
+    fn subtypes(&self, t: &Thingy) -> Vec<Thingy> {
+        let mut list: Vec<Thingy> = self.pages.keys().filter(|s| {
+            if t.is::<InterfaceType>() {
+                s.is::<ClassType>() && s.implements(self.host).iter().any(|i| &i == t)
+                    || s.is::<InterfaceType>() && s.extends_interfaces(self.host).iter().any(|i| &i == t)
+            } else {
+                s.is::<ClassType>() && s.extends_class(self.host).as_ref() == Some(t)
+            }
+        }).cloned().collect();
+        list.sort_by_key(|s| self.pages[s].clone());
+        list
+    }
+
+    /// Public and protected, documented members of a type, static ones
+    /// first, sorted by name, excluding the constructor and the
+    /// implicit `static const prototype`.
+    fn members(&self, t: &Thingy) -> Vec<Thingy> {
+        let mut list = vec![];
+        let tables = if t.is::<InterfaceType>() {
+            vec![(false, t.prototype(self.host))]
+        } else {
+            vec![(true, t.properties(self.host)), (false, t.prototype(self.host))]
+        };
+        for (is_static, names) in tables {
+            let mut members: Vec<Thingy> = names.borrow().iter()
+                .filter(|(name, slot)| Self::is_documented_ns(&name.namespace()) && !slot.is_constructor() && Self::is_documented(slot))
+                .filter(|(name, _)| !(is_static && name.local_name() == "prototype"))
+                .map(|(_, slot)| slot.clone())
+                .filter(|slot| slot.is::<VariableSlot>() || slot.is::<VirtualSlot>() || slot.is::<MethodSlot>())
+                .collect();
+            members.sort_by_key(|m| m.name().local_name());
+            list.extend(members);
+        }
+        list
+    }
+
+    fn public_definitions(host: &SemanticHost, package: &Thingy) -> Vec<Thingy> {
+        let mut list: Vec<Thingy> = package.properties(host).borrow().iter()
+            .filter(|(name, d)| name.namespace().is_public_ns() && Self::is_documented(d))
+            .map(|(_, d)| d.clone())
+            .filter(|d| Self::is_type(d) || d.is::<VariableSlot>() || d.is::<VirtualSlot>() || d.is::<MethodSlot>())
+            .collect();
+        list.sort_by_key(|d| d.name().local_name());
+        list
+    }
+
+    /// Title of the package summary section listing a definition.
+    fn package_section(d: &Thingy) -> &'static str {
+        if d.is::<InterfaceType>() {
+            "Interfaces"
+        } else if d.is::<ClassType>() {
+            "Classes"
+        } else if d.is::<EnumType>() {
+            "Enums"
+        } else if d.is::<MethodSlot>() {
+            "Functions"
+        } else if Self::is_namespace_definition(d) {
+            "Namespaces"
+        } else {
+            "Properties"
+        }
+    }
+
+    fn is_type(thingy: &Thingy) -> bool {
+        thingy.is::<ClassType>() || thingy.is::<InterfaceType>() || thingy.is::<EnumType>()
+    }
+
+    fn is_namespace_definition(thingy: &Thingy) -> bool {
+        thingy.is::<VariableSlot>() && thingy.var_constant().is_some_and(|c| c.is::<NamespaceConstant>())
+    }
+
+    /// Indicates whether members in a namespace are documented,
+    /// which public and protected members are.
+    fn is_documented_ns(ns: &Thingy) -> bool {
+        ns.is_public_ns() || ns.is_protected_ns() || ns.is_static_protected_ns()
+    }
+
+    /// Indicates whether a definition is documented, which it is not if
+    /// its ASDoc comment has the `@private` tag.
+    fn is_documented(thingy: &Thingy) -> bool {
+        !thingy.asdoc().is_some_and(|a| a.tags.iter().any(|(tag, _)| matches!(tag, AsDocTag::Private)))
+    }
+
+    fn param_names(asdoc: &AsDoc) -> Vec<String> {
+        asdoc.tags.iter().filter_map(|(tag, _)| match tag {
+            AsDocTag::Param { name, .. } => Some(name.clone()),
+            _ => None,
+        }).collect()
+    }
+
+    fn package_display_name(name: &str) -> String {
+        if name.is_empty() { "Top Level".to_owned() } else { format!("Package {name}") }
+    }
+
+    fn page(title: &str, path: &str, body: &str) -> String {
+        format!("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<link rel=\"stylesheet\" href=\"{}\">\n</head>\n<body>\n<nav><a href=\"{}\">All Packages</a></nav>\n<main>\n{body}</main>\n</body>\n</html>\n",
+            Markdown::escape_html(title),
+            AsDocSite::relative_link(path, AsDocSite::STYLE_SHEET_PATH),
+            AsDocSite::relative_link(path, AsDocSite::INDEX_PATH))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    #[test]
+    fn test_asdoc_site() {
+        let host = SemanticHost::new(SemanticHostOptions::default());
+        let top_level = host.top_level_package();
+        let object_type = define_class(&host, &top_level, "Object", None);
+        let number_type = define_class(&host, &top_level, "Number", Some(&object_type));
+        let vec_package = host.factory().create_package(["__AS3__", "vec"]);
+        define_class(&host, &vec_package, "Vector", Some(&object_type));
+
+        let package = host.factory().create_package(["com", "example"]);
+        let public_ns = package.public_ns().unwrap();
+        let shape = host.factory().create_interface_type(host.factory().create_qname(&public_ns, "IShape".into()));
+        shape.set_parent(Some(package.clone()));
+        package.properties(&host).set(shape.name(), shape.clone());
+        let base = define_class(&host, &package, "Base", Some(&object_type));
+        let circle = define_class(&host, &package, "Circle", Some(&base));
+        circle.implements(&host).push(shape.clone());
+
+        let cu = CompilationUnit::new(None, r#"
+            package com.example {
+                /** A shape. */
+                public interface IShape {}
+
+                [Event(name="change", type="flash.events.Event")]
+                [Style(name="color", type="uint", inherit="yes")]
+                /**
+                 * The base class.
+                 */
+                public class Base {
+                    /** Computes the area. */
+                    public function area(): Number { return 0; }
+                    /** Invalidates the area. */
+                    protected function invalidate(): void {}
+                    private function validate(): void {}
+                }
+
+                /**
+                 * A **circle**.
+                 *
+                 * Second paragraph.
+                 * @see Base#area
+                 * @see #radius The radius
+                 */
+                public class Circle extends Base implements IShape {
+                    /** The radius. */
+                    public var radius: Number;
+                    /**
+                     * Scales the circle.
+                     * @param factor The factor.
+                     * @return The scaled circle.
+                     * @throws ArgumentError If the factor is negative.
+                     */
+                    public function scale(factor: Number): Circle { return this; }
+                    /** @private */
+                    public function hidden(): void {}
+                }
+            }
+        "#.into());
+        let program = ParserFacade(&cu, ParserOptions::default()).parse_program();
+        let directives = &program.packages[0].block.directives;
+        let Directive::InterfaceDefinition(shape_defn) = directives[0].as_ref() else {
+            panic!();
+        };
+        shape.set_asdoc(shape_defn.asdoc.clone());
+        for (i, class) in [(1, &base), (2, &circle)] {
+            let Directive::ClassDefinition(defn) = directives[i].as_ref() else {
+                panic!();
+            };
+            class.set_asdoc(defn.asdoc.clone());
+            for m in Attribute::find_metadata(&defn.attributes) {
+                class.metadata().push(m);
+            }
+            for drtv in &defn.block.directives {
+                let (name, slot, asdoc) = match drtv.as_ref() {
+                    Directive::FunctionDefinition(f) => {
+                        let name = f.name_identifier().0;
+                        let params = if name == "scale" {
+                            vec![Rc::new(SemanticFunctionTypeParameter { kind: ParameterKind::Required, static_type: number_type.clone() })]
+                        } else {
+                            vec![]
+                        };
+                        let result_type = match &name[..] {
+                            "area" => number_type.clone(),
+                            "scale" => circle.clone(),
+                            _ => host.void_type(),
+                        };
+                        let ns = match &name[..] {
+                            "invalidate" => class.protected_ns().unwrap(),
+                            "validate" => class.private_ns().unwrap(),
+                            _ => public_ns.clone(),
+                        };
+                        let qname = host.factory().create_qname(&ns, name.clone());
+                        (qname.clone(), host.factory().create_method_slot(&qname, &host.factory().create_function_type(params, result_type)), f.asdoc.clone())
+                    },
+                    Directive::VariableDefinition(v) => {
+                        let qname = host.factory().create_qname(&public_ns, "radius".into());
+                        (qname.clone(), host.factory().create_variable_slot(&qname, false, &number_type), v.asdoc.clone())
+                    },
+                    _ => panic!(),
+                };
+                slot.set_asdoc(asdoc);
+                slot.set_parent(Some(class.clone()));
+                class.prototype(&host).set(name, slot);
+            }
+        }
+
+        let site = AsDocSite::generate(&host, &[top_level, vec_package, package]);
+        let mut paths: Vec<&str> = site.files.iter().map(|(p, _)| p.as_str()).collect();
+        paths.sort();
+        assert_eq!(paths, [
+            "Number.html", "Object.html", "Vector.html",
+            "com/example/Base.html", "com/example/Circle.html", "com/example/IShape.html", "com/example/package-summary.html",
+            "index.html", "package-summary.html", "style.css",
+        ]);
+
+        let index = site.file("index.html").unwrap();
+        assert!(index.contains("<a href=\"com/example/package-summary.html\">Package com.example</a>"));
+        assert!(index.contains("<a href=\"com/example/Circle.html\">Circle</a></td><td>A <strong>circle</strong>.</td>"));
+
+        let circle_page = site.file("com/example/Circle.html").unwrap();
+        assert!(circle_page.contains("<link rel=\"stylesheet\" href=\"../../style.css\">"));
+        assert!(circle_page.contains("Inheritance: Circle &rarr; <a href=\"Base.html\">Base</a> &rarr; <a href=\"../../Object.html\">Object</a>"));
+        assert!(circle_page.contains("Implements: <a href=\"IShape.html\">IShape</a>"));
+        assert!(circle_page.contains("<p>Second paragraph.</p>"));
+        assert!(circle_page.contains("<li><a href=\"Base.html#area\">Base.area</a></li>"));
+        assert!(circle_page.contains("<li><a href=\"Circle.html#radius\">The radius</a></li>"));
+        assert!(circle_page.contains("public function scale(factor: <a href=\"../../Number.html\">Number</a>): <a href=\"Circle.html\">Circle</a>"));
+        assert!(circle_page.contains("<dt><code>factor</code></dt><dd><p>The factor.</p>\n</dd>"));
+        assert!(circle_page.contains("<h4>Returns</h4>\n<p>The scaled circle.</p>"));
+        assert!(circle_page.contains("<dt>ArgumentError</dt>"));
+        assert!(circle_page.contains("public var radius: <a href=\"../../Number.html\">Number</a>"));
+        assert!(!circle_page.contains("hidden"));
+        assert!(circle_page.contains("Members inherited from <a href=\"Base.html\">Base</a>: <a href=\"Base.html#area\">area</a>, <a href=\"Base.html#invalidate\">invalidate</a>"));
+        assert!(circle_page.contains("Events inherited from <a href=\"Base.html\">Base</a>: <a href=\"Base.html#event:change\">change</a>"));
+        assert!(circle_page.contains("Styles inherited from <a href=\"Base.html\">Base</a>: <a href=\"Base.html#style:color\">color</a>"));
+
+        let base_page = site.file("com/example/Base.html").unwrap();
+        assert!(base_page.contains("Subclasses: <a href=\"Circle.html\">Circle</a>"));
+        assert!(base_page.contains("<pre class=\"signature\">protected function invalidate(): void</pre>"));
+        assert!(!base_page.contains("id=\"validate\""));
+        assert!(base_page.contains("<div class=\"member\" id=\"event:change\">"));
+        assert!(base_page.contains("<tr id=\"style:color\"><td>color</td><td>uint</td><td>yes</td>"));
+        assert!(site.file("com/example/IShape.html").unwrap().contains("Implementors: <a href=\"Circle.html\">Circle</a>"));
+
+        let summary = site.file("com/example/package-summary.html").unwrap();
+        assert!(summary.contains("<h2>Interfaces</h2>\n<table>\n<tr><td><a href=\"IShape.html\">IShape</a></td><td>A shape.</td></tr>"));
+        assert!(site.file("package-summary.html").unwrap().contains("<a href=\"Vector.html\">Vector</a>"));
+    }
+    #[test]
+    fn test_asdoc_site_sections() {
+        let directory = std::env::temp_dir().join(format!("razen_test_asdoc_site_sections_{}", std::process::id()));
+        std::fs::create_dir_all(directory.join("docs/guide")).unwrap();
+        std::fs::write(directory.join("docs/home.md"), "Welcome. See [Foo.bar](com.example.Foo#bar), [the guide](guide.md) and [Missing](com.example.Missing).").unwrap();
+        std::fs::write(directory.join("docs/guide.md"), "Read this first.").unwrap();
+        std::fs::write(directory.join("docs/guide/setup.md"), "[Home](../home.md), [Foo](Foo), [logo](../logo.png) and [package](com.example).").unwrap();
+        std::fs::write(directory.join("docs/logo.png"), b"\x89PNG").unwrap();
+        let manifest_cu = CompilationUnit::new(Some(directory.join("asdoc.xml").to_string_lossy().into_owned()), r#"<?xml version="1.0"?>
+            <asdoc>
+                <basePath>docs</basePath>
+                <includeFiles><include>logo.png</include></includeFiles>
+                <home><title>Home</title><path>home.md</path></home>
+                <sections>
+                    <section>
+                        <title>Guide</title>
+                        <path>guide.md</path>
+                        <children>
+                            <section><title>Setup</title><path>setup.md</path></section>
+                        </children>
+                    </section>
+                </sections>
+            </asdoc>"#.into());
+        let manifest = AsDocManifest::parse(&manifest_cu).unwrap();
+        assert!(manifest_cu.nested_diagnostics().is_empty());
+
+        let host = SemanticHost::new(SemanticHostOptions::default());
+        let object_type = define_class(&host, &host.top_level_package(), "Object", None);
+        let package = host.factory().create_package(["com", "example"]);
+        let foo = define_class(&host, &package, "Foo", Some(&object_type));
+        let bar_name = host.factory().create_qname(&package.public_ns().unwrap(), "bar".into());
+        foo.prototype(&host).set(bar_name.clone(), host.factory().create_variable_slot(&bar_name, false, &object_type));
+
+        let cu = CompilationUnit::new(None, "/** See [bar](#bar) and [baz](Foo#baz). */ class Foo {}".into());
+        let program = ParserFacade(&cu, ParserOptions::default()).parse_program();
+        let Directive::ClassDefinition(defn) = program.directives[0].as_ref() else {
+            panic!();
+        };
+        foo.set_asdoc(defn.asdoc.clone());
+
+        let site = AsDocSite::generate_with_manifest(&host, &[package], &manifest);
+        std::fs::remove_dir_all(&directory).unwrap();
+        assert_eq!(site.included_files, [("logo.png".to_owned(), b"\x89PNG".to_vec())]);
+
+        let index = site.file("index.html").unwrap();
+        assert!(index.contains("<title>Home</title>"));
+        assert!(index.contains("<p>Welcome. See <a href=\"com/example/Foo.html#bar\">Foo.bar</a>, <a href=\"guide.html\">the guide</a> and <a href=\"com.example.Missing\">Missing</a>.</p>"));
+        assert!(index.contains("<h2>Sections</h2>\n<ul>\n<li><a href=\"guide.html\">Guide</a>\n<ul>\n<li><a href=\"guide/setup.html\">Setup</a></li>\n</ul>\n</li>\n</ul>\n"));
+        assert!(index.contains("<h1>API Reference</h1>"));
+
+        let guide = site.file("guide.html").unwrap();
+        assert!(guide.contains("<h1>Guide</h1>\n<p>Read this first.</p>\n<ul>\n<li><a href=\"guide/setup.html\">Setup</a></li>\n</ul>\n"));
+        let setup = site.file("guide/setup.html").unwrap();
+        assert!(setup.contains("<link rel=\"stylesheet\" href=\"../style.css\">"));
+        assert!(setup.contains("<p><a href=\"../index.html\">Home</a>, <a href=\"../com/example/Foo.html\">Foo</a>, <a href=\"../logo.png\">logo</a> and <a href=\"../com/example/package-summary.html\">package</a>.</p>"));
+
+        let foo_page = site.file("com/example/Foo.html").unwrap();
+        assert!(foo_page.contains("<p>See <a href=\"#bar\">bar</a> and <a href=\"Foo#baz\">baz</a>.</p>"));
+
+        let home_cu = manifest.home.as_ref().unwrap().compilation_unit.clone().unwrap();
+        let warnings: Vec<(String, String)> = home_cu.nested_diagnostics().iter().chain(cu.nested_diagnostics().iter())
+            .map(|d| (d.location().text(), FxDiagnostic(d).format_message_english()))
+            .collect();
+        assert_eq!(warnings, [
+            ("com.example.Missing".to_owned(), "Could not resolve the link 'com.example.Missing'.".to_owned()),
+            ("Foo#baz".to_owned(), "Could not resolve the link 'Foo#baz'.".to_owned()),
+        ]);
+    }
+}
@@ -0,0 +1,248 @@
+/// Renders the Markdown used in ASDoc comments and documentation sections into HTML.
+///
+/// The supported syntax consists of ATX headings, fenced code blocks, block quotes,
+/// ordered and unordered lists, paragraphs, and the inline code spans, emphasis,
+/// strong emphasis, links and autolinks. Raw HTML is escaped.
+pub struct Markdown;
+
+impl Markdown {
+    /// Renders Markdown text into HTML.
+    pub fn to_html(text: &str) -> String {
+        Self::to_html_with_links(text, &mut |href| href.to_owned())
+    }
+
+    /// Renders Markdown text into HTML, mapping the destination of every link
+    /// through a function, as for resolving references to API entities.
+    pub fn to_html_with_links(text: &str, resolve_link: &mut dyn FnMut(&str) -> String) -> String {
+        let lines: Vec<&str> = text.lines().collect();
+        let mut output = String::new();
+        Self::render_blocks(&lines, resolve_link, &mut output);
+        output
+    }
+
+    /// Escapes text for use in HTML content and attribute values.
+    pub fn escape_html(text: &str) -> String {
+        text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+    }
+
+    fn render_blocks(lines: &[&str], resolve_link: &mut dyn FnMut(&str) -> String, output: &mut String) {
+        let mut i = 0;
+        while i < lines.len() {
+            let line = lines[i];
+            let trimmed = line.trim_start();
+
+            if trimmed.is_empty() {
+                i += 1;
+                continue;
+            }
+
+            // Fenced code block
+            if let Some(info) = trimmed.strip_prefix("```") {
+                let language = info.trim();
+                i += 1;
+                let mut code = vec![];
+                while i < lines.len() && !lines[i].trim_start().starts_with("```") {
+                    code.push(lines[i]);
+                    i += 1;
+                }
+                i += 1;
+                if language.is_empty() {
+                    output.push_str("<pre><code>");
+                } else {
+                    output.push_str(&format!("<pre><code class=\"language-{}\">", Self::escape_html(language)));
+                }
+                output.push_str(&Self::escape_html(&code.join("\n")));
+                output.push_str("</code></pre>\n");
+                continue;
+            }
+
+            // Heading
+            if let Some((level, content)) = Self::heading(trimmed) {
+                output.push_str(&format!("<h{level}>{}</h{level}>\n", Self::render_inline(content, resolve_link)));
+                i += 1;
+                continue;
+            }
+
+            // Block quote
+            if trimmed.starts_with('>') {
+                let mut quoted = vec![];
+                while i < lines.len() && lines[i].trim_start().starts_with('>') {
+                    let l = &lines[i].trim_start()[1..];
+                    quoted.push(l.strip_prefix(' ').unwrap_or(l));
+                    i += 1;
+                }
+                output.push_str("<blockquote>\n");
+                Self::render_blocks(&quoted, resolve_link, output);
+                output.push_str("</blockquote>\n");
+                continue;
+            }
+
+            // List
+            if let Some(ordered) = Self::list_item(trimmed).map(|(ordered, _)| ordered) {
+                let tag = if ordered { "ol" } else { "ul" };
+                output.push_str(&format!("<{tag}>\n"));
+                while i < lines.len() {
+                    let Some((item_ordered, content)) = Self::list_item(lines[i].trim_start()) else {
+                        break;
+                    };
+                    if item_ordered != ordered {
+                        break;
+                    }
+                    let mut item = vec![content];
+                    i += 1;
+                    // Continuation lines are indented.
+                    while i < lines.len() && !lines[i].trim().is_empty() && lines[i].starts_with([' ', '\t']) && Self::list_item(lines[i].trim_start()).is_none() {
+                        item.push(lines[i].trim_start());
+                        i += 1;
+                    }
+                    output.push_str(&format!("<li>{}</li>\n", Self::render_inline(&item.join("\n"), resolve_link)));
+                }
+                output.push_str(&format!("</{tag}>\n"));
+                continue;
+            }
+
+            // Paragraph
+            let mut paragraph = vec![];
+            while i < lines.len() {
+                let l = lines[i].trim_start();
+                if l.is_empty() || l.starts_with("```") || l.starts_with('>') || Self::heading(l).is_some() || (!paragraph.is_empty() && Self::list_item(l).is_some()) {
+                    break;
+                }
+                paragraph.push(l);
+                i += 1;
+            }
+            output.push_str(&format!("<p>{}</p>\n", Self::render_inline(&paragraph.join("\n"), resolve_link)));
+        }
+    }
+
+    fn heading(line: &str) -> Option<(usize, &str)> {
+        let level = line.chars().take_while(|&ch| ch == '#').count();
+        if !(1..=6).contains(&level) {
+            return None;
+        }
+        let rest = &line[level..];
+        if !rest.is_empty() && !rest.starts_with(' ') {
+            return None;
+        }
+        Some((level, rest.trim().trim_end_matches('#').trim_end()))
+    }
+
+    /// Returns whether a list item is ordered and its content.
+    fn list_item(line: &str) -> Option<(bool, &str)> {
+        for bullet in ["- ", "* ", "+ "] {
+            if let Some(content) = line.strip_prefix(bullet) {
+                return Some((false, content));
+            }
+        }
+        let digits = line.chars().take_while(|ch| ch.is_ascii_digit()).count();
+        if digits > 0 {
+            if let Some(content) = line[digits..].strip_prefix(". ") {
+                return Some((true, content));
+            }
+        }
+        None
+    }
+
+    fn render_inline(text: &str, resolve_link: &mut dyn FnMut(&str) -> String) -> String {
+        let chars: Vec<char> = text.chars().collect();
+        let mut output = String::new();
+        let mut i = 0;
+        while i < chars.len() {
+            let ch = chars[i];
+            match ch {
+                '\\' if i + 1 < chars.len() && chars[i + 1].is_ascii_punctuation() => {
+                    output.push_str(&Self::escape_html(&chars[i + 1].to_string()));
+                    i += 2;
+                },
+                '`' => {
+                    let ticks = chars[i..].iter().take_while(|&&c| c == '`').count();
+                    let fence: String = "`".repeat(ticks);
+                    let rest: String = chars[i + ticks..].iter().collect();
+                    if let Some(end) = rest.find(&fence) {
+                        output.push_str(&format!("<code>{}</code>", Self::escape_html(rest[..end].trim())));
+                        i += ticks + rest[..end].chars().count() + ticks;
+                    } else {
+                        output.push_str(&fence);
+                        i += ticks;
+                    }
+                },
+                '*' | '_' => {
+                    let strong = i + 1 < chars.len() && chars[i + 1] == ch;
+                    let delimiter: String = if strong { format!("{ch}{ch}") } else { ch.to_string() };
+                    // Intraword underscores, as in `my_name`, are literal.
+                    let intraword = ch == '_' && i > 0 && chars[i - 1].is_alphanumeric();
+                    let start = i + delimiter.len();
+                    let rest: String = chars[start.min(chars.len())..].iter().collect();
+                    match rest.find(&delimiter).filter(|&end| end > 0 && !intraword && !rest.starts_with(' ')) {
+                        Some(end) => {
+                            let tag = if strong { "strong" } else { "em" };
+                            output.push_str(&format!("<{tag}>{}</{tag}>", Self::render_inline(&rest[..end], resolve_link)));
+                            i = start + rest[..end].chars().count() + delimiter.len();
+                        },
+                        None => {
+                            output.push_str(&delimiter);
+                            i = start;
+                        },
+                    }
+                },
+                '[' => {
+                    let rest: String = chars[i + 1..].iter().collect();
+                    let link = rest.find("](").and_then(|label_end| {
+                        let after = &rest[label_end + 2..];
+                        after.find(')').map(|href_end| (label_end, href_end))
+                    });
+                    match link {
+                        Some((label_end, href_end)) => {
+                            let label = &rest[..label_end];
+                            let href = rest[label_end + 2..label_end + 2 + href_end].trim();
+                            output.push_str(&format!("<a href=\"{}\">{}</a>", Self::escape_html(&resolve_link(href)), Self::render_inline(label, resolve_link)));
+                            i += 1 + rest[..label_end + 2 + href_end + 1].chars().count();
+                        },
+                        None => {
+                            output.push('[');
+                            i += 1;
+                        },
+                    }
+                },
+                '<' => {
+                    let rest: String = chars[i + 1..].iter().collect();
+                    match rest.find('>').map(|end| &rest[..end]).filter(|url| url.starts_with("http://") || url.starts_with("https://")) {
+                        Some(url) => {
+                            output.push_str(&format!("<a href=\"{0}\">{0}</a>", Self::escape_html(url)));
+                            i += 1 + url.chars().count() + 1;
+                        },
+                        None => {
+                            output.push_str("&lt;");
+                            i += 1;
+                        },
+                    }
+                },
+                _ => {
+                    output.push_str(&Self::escape_html(&ch.to_string()));
+                    i += 1;
+                },
+            }
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    #[test]
+    fn test_markdown() {
+        let html = Markdown::to_html("# Title\n\nSome *emphasis*, **strong** text, `a < b` and my_name.\nSee [Foo](com.example.Foo#bar).\n\n- One\n- Two\n  continued\n\n1. First\n\n```as3\nvar x = 1 < 2;\n```\n\n> Quoted <b>");
+        assert_eq!(html, "<h1>Title</h1>\n\
+            <p>Some <em>emphasis</em>, <strong>strong</strong> text, <code>a &lt; b</code> and my_name.\n\
+            See <a href=\"com.example.Foo#bar\">Foo</a>.</p>\n\
+            <ul>\n<li>One</li>\n<li>Two\ncontinued</li>\n</ul>\n\
+            <ol>\n<li>First</li>\n</ol>\n\
+            <pre><code class=\"language-as3\">var x = 1 &lt; 2;</code></pre>\n\
+            <blockquote>\n<p>Quoted &lt;b&gt;</p>\n</blockquote>\n");
+
+        let html = Markdown::to_html_with_links("[Foo](Foo)", &mut |href| format!("{href}.html"));
+        assert_eq!(html, "<p><a href=\"Foo.html\">Foo</a></p>\n");
+    }
+}
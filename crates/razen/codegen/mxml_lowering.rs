@@ -0,0 +1,730 @@
+use std::ops::Range;
+use crate::ns::*;
+
+/// Lowers a verified MXML document to an equivalent ActionScript 3 package definition,
+/// for inspecting what the compiler does with the document and for compiling it
+/// through the regular ActionScript 3 pipeline.
+///
+/// The document class of the lowered code contains:
+///
+/// * the meta-data of the `<fx:Metadata>` tags and the code of the `<fx:Script>` tags;
+/// * a `[Bindable]` public variable for every `id` attribute;
+/// * a constructor that assigns the attributes of the root tag and creates its child
+///   components, each through a method such as `_Main_Button1_c()`;
+/// * a method for every event handler, taking the event;
+/// * a method for every data binding, which assigns the destination property and
+///   listens to the events of the bindable properties accessed by the source.
+///
+/// Child components that are not the value of a property tag are assigned to the default
+/// property of their parent, given by `[DefaultProperty("name")]` meta-data, or `mxmlContent`.
+/// View states and `<fx:Style>` tags are not lowered: state-specific values are omitted and
+/// components are created regardless of `includeIn` and `excludeFrom`.
+///
+/// # Locations
+///
+/// The lowered code is parsed in its own compilation unit. Code copied from the
+/// MXML source, such as scripts, event handlers and binding sources, maps back to it
+/// exactly through `LoweredMxml::original_location()`; other code maps back to the tag
+/// or attribute it is lowered from.
+///
+/// ```ignore
+/// let lowered = MxmlLowering::lower(&host, &mxml).unwrap();
+/// println!("{}", lowered.text());
+/// ```
+pub struct MxmlLowering<'a> {
+    host: &'a SemanticHost,
+    document_name: String,
+    imports: Vec<String>,
+    fields: LoweredCode,
+    methods: LoweredCode,
+    component_counts: HashMap<String, usize>,
+    binding_count: usize,
+    /// The code referring to every component that is the destination of a data binding.
+    binding_destinations: HashMap<NodeAsKey<Rc<MxmlElement>>, String>,
+}
+
+/// The result of lowering a MXML document.
+pub struct LoweredMxml {
+    /// The compilation unit of the lowered code.
+    pub compilation_unit: Rc<CompilationUnit>,
+    pub package: Rc<PackageDefinition>,
+    document_location: Location,
+    source_map: Vec<SourceMapping>,
+}
+
+struct SourceMapping {
+    range: Range<usize>,
+    location: Location,
+    /// The code of the MXML source the code is copied from, if it is copied as is.
+    copied: Option<Rc<MxmlCode>>,
+}
+
+#[derive(Default)]
+struct LoweredCode {
+    text: String,
+    source_map: Vec<SourceMapping>,
+}
+
+impl LoweredCode {
+    fn push(&mut self, code: &str) {
+        self.text.push_str(code);
+    }
+
+    /// Pushes code lowered from a MXML node.
+    fn push_mapped(&mut self, code: &str, location: &Location) {
+        let start = self.text.len();
+        self.text.push_str(code);
+        self.source_map.push(SourceMapping { range: start..self.text.len(), location: location.clone(), copied: None });
+    }
+
+    /// Pushes code copied from the MXML source, given by its location in `code`.
+    fn push_copy(&mut self, code: &Rc<MxmlCode>, location: &Location) {
+        let start = self.text.len();
+        self.text.push_str(&location.text());
+        self.source_map.push(SourceMapping { range: start..self.text.len(), location: location.clone(), copied: Some(code.clone()) });
+    }
+
+    fn append(&mut self, code: LoweredCode, location: Option<&Location>) {
+        let start = self.text.len();
+        self.text.push_str(&code.text);
+        self.source_map.extend(code.source_map.into_iter().map(|m| SourceMapping {
+            range: start + m.range.start..start + m.range.end,
+            ..m
+        }));
+        if let Some(location) = location {
+            self.source_map.push(SourceMapping { range: start..self.text.len(), location: location.clone(), copied: None });
+        }
+    }
+}
+
+impl LoweredMxml {
+    /// The lowered code.
+    pub fn text(&self) -> String {
+        self.compilation_unit.text().to_owned()
+    }
+
+    /// Maps a location of the lowered code back to the MXML source. A location
+    /// outside of any lowered tag maps back to the root tag.
+    pub fn original_location(&self, location: &Location) -> Location {
+        let (first, last) = (location.first_offset(), location.last_offset());
+        let mapping = self.source_map.iter()
+            .filter(|m| m.range.start <= first && last <= m.range.end)
+            .min_by_key(|m| m.range.len());
+        match mapping {
+            Some(SourceMapping { range, location, copied: Some(code) }) => {
+                let start = location.first_offset();
+                code.original_location(&Location::with_offsets(&location.compilation_unit(), start + first - range.start, start + last - range.start))
+            },
+            Some(m) => m.location.clone(),
+            None => self.document_location.clone(),
+        }
+    }
+
+    /// Returns the diagnostics of the lowered code, relocated to the MXML source.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        let mut r = vec![];
+        for d in self.compilation_unit.diagnostics() {
+            let location = self.original_location(&d.location());
+            let relocated = if d.is_warning() {
+                Diagnostic::new_warning(&location, d.kind(), d.arguments())
+            } else if d.is_syntax_error() {
+                Diagnostic::new_syntax_error(&location, d.kind(), d.arguments())
+            } else {
+                Diagnostic::new_verify_error(&location, d.kind(), d.arguments())
+            };
+            relocated.set_custom_kind(d.custom_kind());
+            r.push(relocated);
+        }
+        // Included files have their own locations.
+        r.extend(self.compilation_unit.nested_diagnostics().into_iter().skip(self.compilation_unit.diagnostics().len()));
+        r
+    }
+}
+
+impl<'a> MxmlLowering<'a> {
+    pub const DEFAULT_PROPERTY: &'static str = "mxmlContent";
+
+    /// Lowers a verified MXML document. Returns `None` if the document
+    /// does not declare a class, as when its root tag is unresolved.
+    pub fn lower(host: &'a SemanticHost, mxml: &Rc<Mxml>) -> Option<LoweredMxml> {
+        let document = host.node_mapping().get(mxml).filter(|c| c.is::<ClassType>())?;
+        let root = child_elements(&mxml.content).into_iter().next()?;
+        let base_class = document.extends_class(host)?;
+        let mut lowering = Self {
+            host,
+            document_name: document.name().local_name(),
+            imports: vec![],
+            fields: LoweredCode::default(),
+            methods: LoweredCode::default(),
+            component_counts: HashMap::new(),
+            binding_count: 0,
+            binding_destinations: HashMap::new(),
+        };
+        let bindings: Vec<Rc<MxmlBinding>> = document.mxml_bindings().iter().filter(|b| b.state.is_none()).collect();
+        lowering.binding_destinations.insert(NodeAsKey(root.clone()), "this".into());
+
+        let mut constructor = LoweredCode::default();
+        lowering.lower_attributes(&root, &base_class, "this", &bindings, &mut constructor);
+        lowering.lower_children(&root, &base_class, "this", &bindings, &mut constructor, true);
+        for binding in &bindings {
+            lowering.lower_binding(binding, &mut constructor);
+        }
+
+        let mut code = LoweredCode::default();
+        let package_name = document.parent().unwrap().fully_qualified_name();
+        code.push(&format!("package {package_name} {{\n"));
+        let base_name = lowering.type_name(&base_class);
+        lowering.imports.sort();
+        lowering.imports.dedup();
+        for import in &lowering.imports {
+            code.push(&format!("import {import};\n"));
+        }
+        for element in MxmlSubverifier::language_tags(&root, "Metadata") {
+            for inline_code in Self::inline_code(&element) {
+                code.push_copy(&inline_code, &inline_code.location());
+                code.push("\n");
+            }
+        }
+        code.push(&format!("public class {} extends {base_name} {{\n", lowering.document_name));
+        for element in MxmlSubverifier::language_tags(&root, "Script") {
+            if let Some(source) = MxmlSubverifier::find_attribute(&element, "source") {
+                code.push_mapped(&format!("include {};\n", Self::string_literal(&source.value.0)), &source.value.1);
+                continue;
+            }
+            for inline_code in Self::inline_code(&element) {
+                code.push_copy(&inline_code, &inline_code.location());
+                code.push("\n");
+            }
+        }
+        code.append(std::mem::take(&mut lowering.fields), None);
+        code.push(&format!("public function {}() {{\nsuper();\n", lowering.document_name));
+        code.append(constructor, None);
+        code.push("}\n");
+        code.append(std::mem::take(&mut lowering.methods), None);
+        code.push("}\n}\n");
+
+        let cu = mxml.location.compilation_unit();
+        let lowered_cu = CompilationUnit::new(cu.file_path().map(|p| format!("{p}.as")), code.text);
+        lowered_cu.set_compiler_options(cu.compiler_options());
+        let program = ParserFacade(&lowered_cu, ParserOptions::default()).parse_program();
+        Some(LoweredMxml {
+            compilation_unit: lowered_cu,
+            package: program.packages.first()?.clone(),
+            document_location: root.location.clone(),
+            source_map: code.source_map,
+        })
+    }
+
+    /// Returns the code of the text and CDATA nodes of a tag, decoding the XML entities of text.
+    fn inline_code(element: &Rc<MxmlElement>) -> Vec<Rc<MxmlCode>> {
+        element.content.as_deref().unwrap_or(&[]).iter().filter_map(|content| match content.as_ref() {
+            MxmlContent::CData((_, location)) => Some(MxmlCode::new(&location.compilation_unit(), Some((location.first_offset() + "<![CDATA[".len(), location.last_offset() - "]]>".len())))),
+            MxmlContent::Characters((_, location)) => Some(MxmlCode::decode(&location.compilation_unit(), location.first_offset(), location.last_offset())),
+            _ => None,
+        }).collect()
+    }
+
+    /// Returns the name of a class in the lowered code, importing it.
+    fn type_name(&mut self, class: &Thingy) -> String {
+        let name = class.fully_qualified_name();
+        if name.contains('.') {
+            self.imports.push(name.clone());
+        }
+        name
+    }
+
+    fn lower_attributes(&mut self, element: &Rc<MxmlElement>, component: &Thingy, target: &str, bindings: &[Rc<MxmlBinding>], code: &mut LoweredCode) {
+        for attr in &element.attributes {
+            if attr.xmlns || attr.name.prefix.is_some() || MxmlState::split_name(&attr.name.name).1.is_some() {
+                continue;
+            }
+            if ["id", "includeIn", "excludeFrom"].contains(&attr.name.name.as_str()) {
+                continue;
+            }
+            self.lower_member_value(element, component, target, &attr.name.name, &attr.value.0, &attr.value.1, None, bindings, code);
+        }
+    }
+
+    /// Lowers the value of a member given by an attribute or, if `tag` is given, by a property
+    /// tag. `location` is the location of the value, which includes the quotes of an attribute.
+    #[allow(clippy::too_many_arguments)]
+    fn lower_member_value(&mut self, element: &Rc<MxmlElement>, component: &Thingy, target: &str, name: &str, value: &str, location: &Location, tag: Option<&Rc<MxmlElement>>, bindings: &[Rc<MxmlBinding>], code: &mut LoweredCode) {
+        let host = self.host;
+        if bindings.iter().any(|b| Rc::ptr_eq(&b.element, element) && b.property_name == name) {
+            return;
+        }
+        if let Some(reference) = self.lookup_property(component, name) {
+            let property_type = reference.static_type(host);
+            let (name, value) = match value.trim().strip_suffix('%') {
+                Some(percent) if self.lookup_property(component, &Self::percent_name(name)).is_some() => (Self::percent_name(name), percent.to_owned()),
+                _ => (name.to_owned(), value.to_owned()),
+            };
+            let value = self.lower_text_value(&value, &property_type);
+            code.push_mapped(&format!("{target}.{name} = {value};\n"), location);
+            return;
+        }
+        if let Some(event_type) = FlexEvents(host).lookup(component, name) {
+            let handler_name = format!("__{}_{name}", self.component_base_name(element, target));
+            let event_type = self.type_name(&event_type);
+            let mut method = LoweredCode::default();
+            method.push(&format!("private function {handler_name}(event: {event_type}): void {{\n"));
+            let handler_code = match tag {
+                Some(tag) => Self::inline_code(tag),
+                // The code of an attribute excludes the quotes.
+                None => vec![MxmlCode::decode(&location.compilation_unit(), location.first_offset() + 1, location.last_offset() - 1)],
+            };
+            for handler_code in handler_code {
+                method.push_copy(&handler_code, &handler_code.location());
+                method.push("\n");
+            }
+            method.push("}\n");
+            self.methods.append(method, Some(location));
+            code.push_mapped(&format!("{target}.addEventListener({}, this.{handler_name});\n", Self::string_literal(name)), location);
+            return;
+        }
+        if let Some(style) = MxmlComponents(host).lookup_style(component, name) {
+            let style_type = match Self::metadata_entry(&style, "type").as_deref() {
+                Some("Boolean") => host.boolean_type(),
+                Some("Number") => host.number_type(),
+                Some("int") => host.int_type(),
+                Some("uint") => host.uint_type(),
+                _ => host.string_type(),
+            };
+            let value = self.lower_text_value(value, &style_type);
+            code.push_mapped(&format!("{target}.setStyle({}, {value});\n", Self::string_literal(name)), location);
+        }
+    }
+
+    fn lower_children(&mut self, element: &Rc<MxmlElement>, component: &Thingy, target: &str, bindings: &[Rc<MxmlBinding>], code: &mut LoweredCode, is_root: bool) {
+        let uri = element.name.resolve_prefix(&element.namespace).ok();
+        let mut items = vec![];
+        for child in child_elements(element.content.as_deref().unwrap_or(&[])) {
+            let Ok((child_uri, child_name)) = child.name.resolve_name(&child.namespace) else {
+                continue;
+            };
+            if MxmlComponents::is_language_namespace(&child_uri) {
+                if is_root && child_name == "Declarations" {
+                    for declaration in child_elements(child.content.as_deref().unwrap_or(&[])) {
+                        if let Some(create) = self.lower_component(&declaration, bindings) {
+                            code.push_mapped(&format!("{create};\n"), &declaration.location);
+                        }
+                    }
+                }
+                continue;
+            }
+            let (name, state) = MxmlState::split_name(&child_name);
+            if uri.as_ref() == Some(&child_uri) && self.is_member(component, name) {
+                if state.is_none() {
+                    self.lower_member_tag(element, &child, component, target, name, bindings, code);
+                }
+                continue;
+            }
+            if let Some(create) = self.lower_component(&child, bindings) {
+                items.push((create, child.location.clone()));
+            }
+        }
+        if items.is_empty() {
+            return;
+        }
+        let default_property = self.default_property(component);
+        let single = items.len() == 1 && self.lookup_property(component, &default_property)
+            .is_some_and(|r| r.static_type(self.host).escape_of_nullable_or_non_nullable() != self.host.array_type());
+        code.push(&format!("{target}.{default_property} = "));
+        Self::push_items(code, &items, single);
+        code.push(";\n");
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn lower_member_tag(&mut self, parent: &Rc<MxmlElement>, element: &Rc<MxmlElement>, component: &Thingy, target: &str, name: &str, bindings: &[Rc<MxmlBinding>], code: &mut LoweredCode) {
+        let children = child_elements(element.content.as_deref().unwrap_or(&[]));
+        if children.is_empty() {
+            self.lower_member_value(parent, component, target, name, element.inner_text().trim(), &element.location, Some(element), bindings, code);
+            return;
+        }
+        let Some(reference) = self.lookup_property(component, name) else {
+            return;
+        };
+        let items: Vec<(String, Location)> = children.iter()
+            .filter_map(|child| Some((self.lower_component(child, bindings)?, child.location.clone())))
+            .collect();
+        let single = items.len() == 1 && reference.static_type(self.host).escape_of_nullable_or_non_nullable() != self.host.array_type();
+        code.push(&format!("{target}.{name} = "));
+        Self::push_items(code, &items, single);
+        code.push(";\n");
+    }
+
+    fn push_items(code: &mut LoweredCode, items: &[(String, Location)], single: bool) {
+        if single {
+            code.push_mapped(&items[0].0, &items[0].1);
+            return;
+        }
+        code.push("[");
+        for (i, (create, location)) in items.iter().enumerate() {
+            if i != 0 {
+                code.push(", ");
+            }
+            code.push_mapped(create, location);
+        }
+        code.push("]");
+    }
+
+    /// Lowers a component tag into a method creating it,
+    /// returning the code that calls the method.
+    fn lower_component(&mut self, element: &Rc<MxmlElement>, bindings: &[Rc<MxmlBinding>]) -> Option<String> {
+        let host = self.host;
+        let class = host.node_mapping().get(element).filter(|c| c.is::<ClassType>())?;
+        let class_name = self.type_name(&class);
+        let count = self.component_counts.entry(class.name().local_name()).or_insert(0);
+        *count += 1;
+        let name = format!("_{}_{}{count}", self.document_name, class.name().local_name());
+
+        let mut method = LoweredCode::default();
+        method.push(&format!("private function {name}_c(): {class_name} {{\nvar o: {class_name} = new {class_name}();\n"));
+        let id = MxmlSubverifier::find_attribute(element, "id");
+        let id_slot = id.as_ref().and_then(|id| host.node_mapping().get(id)).filter(|s| s.is::<VariableSlot>());
+        if let (Some(id), Some(_)) = (&id, &id_slot) {
+            self.fields.push_mapped(&format!("[Bindable]\npublic var {}: {class_name};\n", id.value.0), &id.location);
+            method.push_mapped(&format!("this.{} = o;\n", id.value.0), &id.location);
+            self.binding_destinations.insert(NodeAsKey(element.clone()), format!("this.{}", id.value.0));
+        } else if bindings.iter().any(|b| Rc::ptr_eq(&b.element, element)) {
+            self.fields.push_mapped(&format!("private var {name}: {class_name};\n"), &element.location);
+            method.push(&format!("this.{name} = o;\n"));
+            self.binding_destinations.insert(NodeAsKey(element.clone()), format!("this.{name}"));
+        }
+        self.lower_attributes(element, &class, "o", bindings, &mut method);
+        self.lower_children(element, &class, "o", bindings, &mut method, false);
+        method.push("return o;\n}\n");
+        self.methods.append(method, Some(&element.location));
+        Some(format!("this.{name}_c()"))
+    }
+
+    /// Lowers a data binding into a method that assigns the destination property
+    /// and listens to changes of the source, and calls the method.
+    fn lower_binding(&mut self, binding: &MxmlBinding, code: &mut LoweredCode) {
+        let Some(destination) = self.binding_destinations.get(&NodeAsKey(binding.element.clone())).cloned() else {
+            return;
+        };
+        let name = self.binding_method_name();
+        let mut method = LoweredCode::default();
+        method.push(&format!("private function {name}(event: Object = null): void {{\ntry {{\n{destination}.{} = ", binding.property_name));
+        match &binding.text_parts {
+            Some(text_parts) => {
+                for (i, text) in text_parts.iter().enumerate() {
+                    if i != 0 {
+                        method.push(" + ");
+                    }
+                    method.push_mapped(&Self::string_literal(text), &binding.location);
+                    if let Some(source) = binding.sources.get(i) {
+                        method.push(" + (");
+                        method.push_copy(&binding.code, &source.location());
+                        method.push(")");
+                    }
+                }
+            },
+            None => method.push_copy(&binding.code, &binding.sources[0].location()),
+        }
+        method.push(";\n} catch (error: Error) {\n}\ntry {\n");
+        let mut watchers = vec![];
+        for source in &binding.sources {
+            self.collect_watchers(source, &mut watchers);
+        }
+        for (receiver, event) in watchers {
+            match receiver {
+                Some(receiver) => method.push_copy(&binding.code, &receiver),
+                None => method.push("this"),
+            }
+            method.push(&format!(".addEventListener({}, this.{name});\n", Self::string_literal(&event)));
+        }
+        method.push("} catch (error: Error) {\n}\n}\n");
+        self.methods.append(method, Some(&binding.location));
+        code.push_mapped(&format!("this.{name}();\n"), &binding.location);
+
+        // The reverse binding of a two-way binding assigns the source when
+        // the destination property changes.
+        if !binding.two_way {
+            return;
+        }
+        let Some(component) = self.host.node_mapping().get(&binding.element) else {
+            return;
+        };
+        let Some(event) = self.lookup_property(&component, &binding.property_name).and_then(|r| r.property().bindable_event()) else {
+            return;
+        };
+        let name = self.binding_method_name();
+        let mut method = LoweredCode::default();
+        method.push(&format!("private function {name}(event: Object = null): void {{\ntry {{\n"));
+        method.push_copy(&binding.code, &binding.sources[0].location());
+        method.push(&format!(" = {destination}.{};\n}} catch (error: Error) {{\n}}\n}}\n", binding.property_name));
+        self.methods.append(method, Some(&binding.location));
+        code.push_mapped(&format!("{destination}.addEventListener({}, this.{name});\n", Self::string_literal(&event)), &binding.location);
+    }
+
+    fn binding_method_name(&mut self) -> String {
+        self.binding_count += 1;
+        format!("_{}_binding{}", self.document_name, self.binding_count)
+    }
+
+    /// Collects the objects and events to listen to for changes of the bindable
+    /// properties accessed by a binding source. A `None` object stands for `this`.
+    fn collect_watchers(&self, exp: &Rc<Expression>, watchers: &mut Vec<(Option<Location>, String)>) {
+        let receiver = match exp.as_ref() {
+            Expression::QualifiedIdentifier(_) => None,
+            Expression::Member(e) => {
+                self.collect_watchers(&e.base, watchers);
+                Some(e.base.location())
+            },
+            Expression::ComputedMember(e) => {
+                self.collect_watchers(&e.base, watchers);
+                self.collect_watchers(&e.key, watchers);
+                return;
+            },
+            Expression::Paren(e) => return self.collect_watchers(&e.expression, watchers),
+            Expression::Unary(e) => return self.collect_watchers(&e.expression, watchers),
+            Expression::Binary(e) => {
+                self.collect_watchers(&e.left, watchers);
+                return self.collect_watchers(&e.right, watchers);
+            },
+            Expression::Sequence(e) => {
+                self.collect_watchers(&e.left, watchers);
+                return self.collect_watchers(&e.right, watchers);
+            },
+            Expression::Conditional(e) => {
+                self.collect_watchers(&e.test, watchers);
+                self.collect_watchers(&e.consequent, watchers);
+                return self.collect_watchers(&e.alternative, watchers);
+            },
+            Expression::Call(e) => {
+                self.collect_watchers(&e.base, watchers);
+                for argument in &e.arguments {
+                    self.collect_watchers(argument, watchers);
+                }
+                return;
+            },
+            _ => return,
+        };
+        let Some(reference) = self.host.node_mapping().get(exp).filter(|r| r.is::<FixtureReferenceValue>()) else {
+            return;
+        };
+        // A lexical reference is watched only if it is a property of `this`.
+        if receiver.is_none() && !reference.is::<InstanceReferenceValue>() {
+            return;
+        }
+        let Some(event) = reference.property().bindable_event() else {
+            return;
+        };
+        let text = receiver.as_ref().map(|r| r.text());
+        if !watchers.iter().any(|(r, e)| r.as_ref().map(|r| r.text()) == text && *e == event) {
+            watchers.push((receiver, event));
+        }
+    }
+
+    /// Lowers a text value to an expression of a type.
+    fn lower_text_value(&mut self, value: &str, target_type: &Thingy) -> String {
+        let host = self.host;
+        let target_type = target_type.escape_of_nullable_or_non_nullable();
+        let trimmed = value.trim();
+        if target_type == host.boolean_type() {
+            trimmed.to_owned()
+        } else if [host.number_type(), host.float_type(), host.int_type(), host.uint_type()].contains(&target_type) {
+            match trimmed.strip_prefix('#') {
+                Some(hex) => format!("0x{hex}"),
+                None => trimmed.strip_suffix('%').unwrap_or(trimmed).trim().to_owned(),
+            }
+        } else if target_type == host.class_type() {
+            let class = host.factory().create_package(trimmed.rsplit_once('.').map(|(p, _)| p).unwrap_or("").split('.').filter(|s| !s.is_empty()));
+            let local_name = trimmed.rsplit_once('.').map(|(_, n)| n).unwrap_or(trimmed);
+            let name = host.factory().create_qname(&class.public_ns().unwrap(), local_name.to_owned());
+            match class.properties(host).get(&name) {
+                Some(class) => self.type_name(&class),
+                None => trimmed.to_owned(),
+            }
+        } else if target_type == host.array_type() {
+            let items: Vec<String> = match trimmed.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+                Some(list) => list.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()).map(Self::string_literal).collect(),
+                None => vec![Self::string_literal(value)],
+            };
+            format!("[{}]", items.join(", "))
+        } else {
+            Self::string_literal(value)
+        }
+    }
+
+    /// Returns the name of the `percentWidth` property for `width`.
+    fn percent_name(name: &str) -> String {
+        let mut chars = name.chars();
+        let first = chars.next().map(|c| c.to_uppercase().collect::<String>()).unwrap_or_default();
+        format!("percent{first}{}", chars.as_str())
+    }
+
+    /// Returns the base name of the generated methods of a component, such as `_Main_Button1`.
+    fn component_base_name(&self, element: &Rc<MxmlElement>, target: &str) -> String {
+        if target == "this" {
+            return format!("_{}", self.document_name);
+        }
+        let class = self.host.node_mapping().get(element).unwrap();
+        let count = self.component_counts.get(&class.name().local_name()).copied().unwrap_or(0);
+        format!("_{}_{}{count}", self.document_name, class.name().local_name())
+    }
+
+    fn is_member(&self, component: &Thingy, name: &str) -> bool {
+        self.lookup_property(component, name).is_some()
+            || FlexEvents(self.host).lookup(component, name).is_some()
+            || MxmlComponents(self.host).lookup_style(component, name).is_some()
+    }
+
+    fn lookup_property(&self, component: &Thingy, name: &str) -> Option<Thingy> {
+        let host = self.host;
+        let value = host.factory().create_value(component);
+        let r = PropertyLookup(host).lookup_in_object(&value, &SharedArray::new(), None, &PropertyLookupKey::LocalName(name.to_owned())).ok()??;
+        r.is::<FixtureReferenceValue>().then_some(r)
+    }
+
+    /// Returns the default property of a component, given by
+    /// `[DefaultProperty("name")]` meta-data.
+    fn default_property(&self, component: &Thingy) -> String {
+        for class in component.descending_class_hierarchy(self.host).collect::<Vec<_>>() {
+            if class.is::<UnresolvedThingy>() {
+                break;
+            }
+            for metadata in class.metadata().iter() {
+                if metadata.name.0 != "DefaultProperty" {
+                    continue;
+                }
+                let value = metadata.entries.as_ref().and_then(|entries| entries.first().map(|entry| match entry.value.as_ref() {
+                    MetadataValue::IdentifierString((s, _)) => s.clone(),
+                    MetadataValue::String((s, _)) => s.clone(),
+                }));
+                if let Some(value) = value {
+                    return value;
+                }
+            }
+        }
+        Self::DEFAULT_PROPERTY.into()
+    }
+
+    fn metadata_entry(metadata: &Rc<Metadata>, key: &str) -> Option<String> {
+        metadata.entries.as_ref()?.iter().find_map(|entry| {
+            if entry.key.as_ref().map(|(k, _)| k.as_str()) != Some(key) {
+                return None;
+            }
+            match entry.value.as_ref() {
+                MetadataValue::IdentifierString((s, _)) => Some(s.clone()),
+                MetadataValue::String((s, _)) => Some(s.clone()),
+            }
+        })
+    }
+
+    fn string_literal(value: &str) -> String {
+        let mut r = String::from("\"");
+        for ch in value.chars() {
+            match ch {
+                '"' => r.push_str("\\\""),
+                '\\' => r.push_str("\\\\"),
+                '\n' => r.push_str("\\n"),
+                '\r' => r.push_str("\\r"),
+                '\t' => r.push_str("\\t"),
+                _ => r.push(ch),
+            }
+        }
+        r.push('"');
+        r
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    #[test]
+    fn test_mxml_lowering() {
+        let host = Rc::new(SemanticHost::new(SemanticHostOptions::default()));
+        let top_level = host.top_level_package();
+        let object_type = define_class(&host, &top_level, "Object", None);
+        for name in ["Class", "Function", "String", "Boolean", "Number", "int", "uint", "float", "Namespace", "Array", "XML", "XMLList"] {
+            define_class(&host, &top_level, name, Some(&object_type));
+        }
+        define_class(&host, &host.factory().create_package(["flash", "utils"]), "Dictionary", Some(&object_type));
+        let flash_events = host.factory().create_package(["flash", "events"]);
+        let event = define_class(&host, &flash_events, "Event", Some(&object_type));
+        define_class(&host, &flash_events, "MouseEvent", Some(&event));
+        let spark = host.factory().create_package(["spark", "components"]);
+        let application = define_class(&host, &spark, "Application", Some(&object_type));
+        define_property(&host, &application, "width", false, &host.number_type());
+        define_property(&host, &application, "percentWidth", false, &host.number_type());
+        define_property(&host, &application, "mxmlContent", false, &host.array_type());
+        let button = define_class(&host, &spark, "Button", Some(&object_type));
+        define_property(&host, &button, "label", false, &host.string_type()).set_bindable_event(Some("labelChange".into()));
+        define_property(&host, &button, "enabled", false, &host.boolean_type());
+        let cu = CompilationUnit::new(None, "[Event(name=\"click\", type=\"flash.events.MouseEvent\")] class Button {}".into());
+        let program = ParserFacade(&cu, ParserOptions::default()).parse_program();
+        let Directive::ClassDefinition(defn) = program.directives[0].as_ref() else {
+            panic!();
+        };
+        for m in Attribute::find_metadata(&defn.attributes) {
+            button.metadata().push(m);
+        }
+        FlexEvents(&host).apply(&button);
+        for name in ["Application", "Button"] {
+            host.define_mxml_component("library://ns.hydroper.com/razen/spark", name, &format!("spark.components.{name}"));
+        }
+
+        let cu = CompilationUnit::new(Some("/project/src/com/example/Main.mxml".into()), r#"<?xml version="1.0"?>
+<s:Application xmlns:fx="http://ns.adobe.com/mxml/2009" xmlns:s="library://ns.hydroper.com/razen/spark" width="100%">
+    <s:Button id="first" label="First" click="first.enabled = false"/>
+    <s:Button label="Hello, {first.label}!" enabled="true">
+        <s:click>first.enabled = first.enabled &amp;&amp; true</s:click>
+    </s:Button>
+</s:Application>"#.into());
+        cu.set_compiler_options(Some(Rc::new(CompilerOptions {
+            source_path: vec!["/project/src".into()],
+            ..default()
+        })));
+        let mxml = ParserFacade(&cu, ParserOptions::default()).parse_mxml();
+        Verifier::new(&host).verify_mxml_list(std::slice::from_ref(&mxml));
+        assert!(cu.nested_diagnostics().is_empty(), "{:?}", cu.nested_diagnostics().iter().map(|d| FxDiagnostic(d).format_message_english()).collect::<Vec<_>>());
+
+        let lowered = MxmlLowering::lower(&host, &mxml).unwrap();
+        let text = lowered.text();
+        assert!(lowered.diagnostics().is_empty(), "{text}");
+        assert_eq!(lowered.package.name.iter().map(|(s, _)| s.as_str()).collect::<Vec<_>>(), ["com", "example"]);
+        for code in [
+            "import spark.components.Button;",
+            "public class Main extends spark.components.Application {",
+            "[Bindable]\npublic var first: spark.components.Button;",
+            "private var _Main_Button2: spark.components.Button;",
+            "this.percentWidth = 100;",
+            "this.mxmlContent = [this._Main_Button1_c(), this._Main_Button2_c()];",
+            "var o: spark.components.Button = new spark.components.Button();\nthis.first = o;\no.label = \"First\";",
+            "o.addEventListener(\"click\", this.___Main_Button1_click);",
+            "private function ___Main_Button1_click(event: flash.events.MouseEvent): void {\nfirst.enabled = false\n}",
+            "private function ___Main_Button2_click(event: flash.events.MouseEvent): void {\nfirst.enabled = first.enabled && true\n}",
+            "o.enabled = true;",
+            "this._Main_Button2.label = \"Hello, \" + (first.label) + \"!\";",
+            "this.addEventListener(\"propertyChange\", this._Main_binding1);",
+            "first.addEventListener(\"labelChange\", this._Main_binding1);",
+            "this._Main_binding1();",
+        ] {
+            assert!(text.contains(code), "{code}\n---\n{text}");
+        }
+
+        // Copied code maps back to the MXML source exactly.
+        let offset = text.find("first.enabled").unwrap();
+        let location = Location::with_offsets(&lowered.compilation_unit, offset, offset + "first.enabled".len());
+        let original = lowered.original_location(&location);
+        assert!(Rc::ptr_eq(&original.compilation_unit(), &cu));
+        assert_eq!(original.text(), "first.enabled");
+        assert_eq!(original.first_line_number(), 3);
+
+        // Decoded code maps back to its XML entities.
+        let offset = text.find("&&").unwrap();
+        let location = Location::with_offsets(&lowered.compilation_unit, offset, offset + 2);
+        let original = lowered.original_location(&location);
+        assert!(Rc::ptr_eq(&original.compilation_unit(), &cu));
+        assert_eq!(original.text(), "&amp;&amp;");
+
+        // Other code maps back to the tag it is lowered from.
+        let offset = text.find("new spark.components.Button()").unwrap();
+        let location = Location::with_offsets(&lowered.compilation_unit, offset, offset + 3);
+        assert_eq!(lowered.original_location(&location).first_line_number(), 3);
+    }
+}
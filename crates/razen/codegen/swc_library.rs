@@ -0,0 +1,496 @@
+use crate::ns::*;
+
+/// Kind of a script dependency in a SWC catalog.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum SwcDependencyKind {
+    /// The definition is a base class or an implemented or extended interface.
+    Inheritance,
+    /// The definition appears in a type annotation of a property or method signature.
+    Signature,
+    /// The definition is a namespace qualifying a property.
+    Namespace,
+    /// The definition is referenced by code.
+    Expression,
+}
+
+impl SwcDependencyKind {
+    /// Returns the `type` attribute used in `catalog.xml`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Inheritance => "i",
+            Self::Signature => "s",
+            Self::Namespace => "n",
+            Self::Expression => "e",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "i" => Some(Self::Inheritance),
+            "s" => Some(Self::Signature),
+            "n" => Some(Self::Namespace),
+            "e" => Some(Self::Expression),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct SwcDependency {
+    /// Definition identifier, in the form `"com.example:Foo"`,
+    /// or `"Foo"` for the top-level package.
+    pub id: String,
+    pub kind: SwcDependencyKind,
+}
+
+/// A script of a SWC catalog, corresponding to one DoABC2 tag
+/// of the `library.swf` file.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SwcScript {
+    /// Script name, in the form `"com/example/Foo"`.
+    pub name: String,
+    pub definitions: Vec<String>,
+    pub dependencies: Vec<SwcDependency>,
+}
+
+/// A SWC component library, consisting of the `library.swf` file,
+/// the `catalog.xml` file and optional ASDoc XML.
+///
+/// # Compiling
+///
+/// `SwcLibrary::compile()` emits a script for each verified program. Definitions
+/// from external programs are not emitted and do not appear in the catalog,
+/// but are still listed as dependencies.
+///
+/// ```ignore
+/// let swc = SwcLibrary::compile(&host, &programs, &external_programs, true);
+/// std::fs::write("library.swc", swc.write())?;
+/// ```
+#[derive(Clone)]
+pub struct SwcLibrary {
+    pub scripts: Vec<SwcScript>,
+    pub library_swf: Vec<u8>,
+    pub asdoc_xml: Option<String>,
+}
+
+impl SwcLibrary {
+    pub const CATALOG_PATH: &'static str = "catalog.xml";
+    pub const LIBRARY_PATH: &'static str = "library.swf";
+    pub const ASDOC_PATH: &'static str = "docs/asdoc.xml";
+    pub const CATALOG_NAMESPACE: &'static str = "http://www.adobe.com/flash/swccatalog/9";
+
+    /// Compiles verified programs into a library.
+    pub fn compile(host: &SemanticHost, programs: &[Rc<Program>], external_programs: &[Rc<Program>], include_asdoc: bool) -> Self {
+        let mut external_ids = HashSet::new();
+        let mut namespace_ids = HashMap::new();
+        for program in external_programs {
+            for definition in SwcDefinition::collect(host, program) {
+                definition.record_namespace(&mut namespace_ids);
+                external_ids.insert(definition.id);
+            }
+        }
+        let definition_lists: Vec<Vec<SwcDefinition>> = programs.iter().map(|program| {
+            let list = SwcDefinition::collect(host, program);
+            for definition in &list {
+                definition.record_namespace(&mut namespace_ids);
+            }
+            list.into_iter().filter(|d| !external_ids.contains(&d.id)).collect()
+        }).collect();
+
+        let mut scripts = vec![];
+        let mut abc_list = vec![];
+        let mut asdoc = String::new();
+        for (i, (program, definitions)) in programs.iter().zip(definition_lists.iter()).enumerate() {
+            let all_external = definitions.is_empty() && !SwcDefinition::collect(host, program).is_empty();
+            if all_external {
+                continue;
+            }
+            let name = definitions.first()
+                .map(|d| d.id.replace([':', '.'], "/"))
+                .unwrap_or_else(|| format!("script{}", i));
+
+            let mut emitter = AbcEmitter::new(host);
+            emitter.emit_program(program);
+
+            let own_ids: HashSet<&String> = definitions.iter().map(|d| &d.id).collect();
+            let mut dependencies: Vec<SwcDependency> = vec![];
+            let mut add_dependency = |id: Option<String>, kind: SwcDependencyKind| {
+                let Some(id) = id else {
+                    return;
+                };
+                if own_ids.contains(&id) || dependencies.iter().any(|d| d.id == id && d.kind == kind) {
+                    return;
+                }
+                dependencies.push(SwcDependency { id, kind });
+            };
+            for definition in definitions {
+                definition.dependencies(host, &namespace_ids, &mut add_dependency);
+            }
+            for t in emitter.referenced_types() {
+                add_dependency(SwcDefinition::type_id(t), SwcDependencyKind::Expression);
+            }
+            dependencies.sort_by(|a, b| (a.kind, &a.id).cmp(&(b.kind, &b.id)));
+
+            if include_asdoc {
+                for definition in definitions {
+                    definition.write_asdoc(&mut asdoc);
+                }
+            }
+
+            abc_list.push(emitter.finish_do_abc2(&name));
+            scripts.push(SwcScript {
+                name,
+                definitions: definitions.iter().map(|d| d.id.clone()).collect(),
+                dependencies,
+            });
+        }
+
+        let library_swf = SwfMovie::new_library(SwfMovieOptions::default()).write(&abc_list);
+        let asdoc_xml = if include_asdoc {
+            Some(format!("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<asdoc>\n{}</asdoc>\n", asdoc))
+        } else {
+            None
+        };
+        Self { scripts, library_swf, asdoc_xml }
+    }
+
+    /// Generates the `catalog.xml` file.
+    pub fn catalog_xml(&self) -> String {
+        let mut r = String::new();
+        r.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        r.push_str(&format!("<swc xmlns=\"{}\">\n", Self::CATALOG_NAMESPACE));
+        r.push_str("  <versions>\n    <swc version=\"1.2\"/>\n  </versions>\n");
+        r.push_str("  <features>\n    <feature-script-deps/>\n");
+        if self.asdoc_xml.is_some() {
+            r.push_str("    <feature-files/>\n");
+        }
+        r.push_str("  </features>\n");
+        r.push_str(&format!("  <libraries>\n    <library path=\"{}\">\n", Self::LIBRARY_PATH));
+        for script in &self.scripts {
+            r.push_str(&format!("      <script name=\"{}\" mod=\"0\">\n", escape_xml(&script.name)));
+            for id in &script.definitions {
+                r.push_str(&format!("        <def id=\"{}\"/>\n", escape_xml(id)));
+            }
+            for dep in &script.dependencies {
+                r.push_str(&format!("        <dep id=\"{}\" type=\"{}\"/>\n", escape_xml(&dep.id), dep.kind.code()));
+            }
+            r.push_str("      </script>\n");
+        }
+        r.push_str("    </library>\n  </libraries>\n");
+        if self.asdoc_xml.is_some() {
+            r.push_str(&format!("  <files>\n    <file path=\"{}\" mod=\"0\"/>\n  </files>\n", Self::ASDOC_PATH));
+        }
+        r.push_str("</swc>\n");
+        r
+    }
+
+    /// Encodes the library as a SWC archive.
+    pub fn write(&self) -> Vec<u8> {
+        let mut archive = ZipArchive::new();
+        archive.add(Self::CATALOG_PATH, self.catalog_xml().into_bytes());
+        archive.add(Self::LIBRARY_PATH, self.library_swf.clone());
+        if let Some(asdoc_xml) = &self.asdoc_xml {
+            archive.add(Self::ASDOC_PATH, asdoc_xml.clone().into_bytes());
+        }
+        archive.write()
+    }
+
+    /// Reads a SWC archive.
+    pub fn read(bytes: &[u8]) -> Result<Self, SwcReadError> {
+        let archive = ZipArchive::read(bytes)?;
+        let catalog = archive.get(Self::CATALOG_PATH).ok_or(SwcReadError::MissingCatalog)?;
+        let catalog = String::from_utf8(catalog.to_vec()).map_err(|_| SwcReadError::InvalidCatalog)?;
+        let cu = CompilationUnit::new(None, catalog);
+        let document = ParserFacade(&cu, ParserOptions::default()).parse_mxml();
+        if cu.invalidated() {
+            return Err(SwcReadError::InvalidCatalog);
+        }
+        let root = child_elements(&document.content).into_iter().next().ok_or(SwcReadError::InvalidCatalog)?;
+        if root.name.name != "swc" {
+            return Err(SwcReadError::InvalidCatalog);
+        }
+
+        let mut scripts = vec![];
+        let mut library_path = None;
+        for libraries in child_elements_named(&root, "libraries") {
+            for library in child_elements_named(&libraries, "library") {
+                library_path = library_path.or(attribute_value(&library, "path"));
+                for script in child_elements_named(&library, "script") {
+                    let name = attribute_value(&script, "name").ok_or(SwcReadError::InvalidCatalog)?;
+                    let mut definitions = vec![];
+                    for def in child_elements_named(&script, "def") {
+                        definitions.push(attribute_value(&def, "id").ok_or(SwcReadError::InvalidCatalog)?);
+                    }
+                    let mut dependencies = vec![];
+                    for dep in child_elements_named(&script, "dep") {
+                        let id = attribute_value(&dep, "id").ok_or(SwcReadError::InvalidCatalog)?;
+                        let kind = attribute_value(&dep, "type")
+                            .and_then(|t| SwcDependencyKind::from_code(&t))
+                            .ok_or(SwcReadError::InvalidCatalog)?;
+                        dependencies.push(SwcDependency { id, kind });
+                    }
+                    scripts.push(SwcScript { name, definitions, dependencies });
+                }
+            }
+        }
+
+        let library_path = library_path.unwrap_or(Self::LIBRARY_PATH.into());
+        let library_swf = archive.get(&library_path).ok_or(SwcReadError::MissingLibrary)?.to_vec();
+        let asdoc_xml = archive.get(Self::ASDOC_PATH).map(|d| String::from_utf8_lossy(d).into_owned());
+        Ok(Self { scripts, library_swf, asdoc_xml })
+    }
+}
+
+/// A package-level definition of a program.
+struct SwcDefinition {
+    id: String,
+    thingy: Option<Thingy>,
+    directive: Rc<Directive>,
+}
+
+impl SwcDefinition {
+    fn collect(host: &SemanticHost, program: &Rc<Program>) -> Vec<SwcDefinition> {
+        let mut r = vec![];
+        for pckg in &program.packages {
+            let package = host.factory().create_package(pckg.name.iter().map(|(s, _)| s.as_str()));
+            Self::collect_from_directives(host, &package, &pckg.block.directives, &mut r);
+        }
+        Self::collect_from_directives(host, &host.top_level_package(), &program.directives, &mut r);
+        r
+    }
+
+    fn collect_from_directives(host: &SemanticHost, package: &Thingy, directives: &[Rc<Directive>], output: &mut Vec<SwcDefinition>) {
+        for drtv in flatten_directives(host, directives) {
+            let names: Vec<String> = match drtv.as_ref() {
+                Directive::ClassDefinition(defn) => vec![defn.name.0.clone()],
+                Directive::InterfaceDefinition(defn) => vec![defn.name.0.clone()],
+                Directive::EnumDefinition(defn) => vec![defn.name.0.clone()],
+                Directive::NamespaceDefinition(defn) => vec![defn.left.0.clone()],
+                Directive::FunctionDefinition(defn) => vec![defn.name_identifier().0],
+                Directive::VariableDefinition(defn) => defn.bindings.iter().filter_map(|b| match b.destructuring.destructuring.as_ref() {
+                    Expression::QualifiedIdentifier(id) => id.to_identifier_name().map(|n| n.0),
+                    _ => None,
+                }).collect(),
+                _ => vec![],
+            };
+            for name in names {
+                let id = Self::id(&package.fully_qualified_name(), &name);
+                if output.iter().any(|d| d.id == id) {
+                    continue;
+                }
+                let thingy = package.properties(host).get_in_any_ns(&name).ok().flatten();
+                output.push(SwcDefinition { id, thingy, directive: drtv.clone() });
+            }
+        }
+    }
+
+    fn id(package_name: &str, local_name: &str) -> String {
+        if package_name.is_empty() {
+            local_name.to_owned()
+        } else {
+            format!("{}:{}", package_name, local_name)
+        }
+    }
+
+    /// Identifier of a type definition, or `None` for types
+    /// that are not definitions, such as `*` and `void`.
+    fn type_id(type_thingy: &Thingy) -> Option<String> {
+        let t = type_thingy.escape_of_nullable_or_non_nullable();
+        if t.is::<TypeAfterSubstitution>() {
+            return Self::type_id(&t.origin());
+        }
+        if t.is::<TupleType>() {
+            return Some("Array".into());
+        }
+        if t.is::<FunctionType>() {
+            return Some("Function".into());
+        }
+        if !(t.is::<ClassType>() || t.is::<EnumType>() || t.is::<InterfaceType>()) {
+            return None;
+        }
+        let package_name = t.parent().filter(|p| p.is::<Package>()).map(|p| p.fully_qualified_name()).unwrap_or_default();
+        Some(Self::id(&package_name, &t.name().local_name()))
+    }
+
+    /// Records the namespace defined by this definition, if any.
+    fn record_namespace(&self, namespace_ids: &mut HashMap<Thingy, String>) {
+        let Some(slot) = self.thingy.as_ref().filter(|t| t.is::<VariableSlot>()) else {
+            return;
+        };
+        if let Some(ns) = slot.var_constant().filter(|c| c.is::<NamespaceConstant>()).map(|c| c.referenced_ns()) {
+            namespace_ids.insert(ns, self.id.clone());
+        }
+    }
+
+    fn dependencies(&self, host: &SemanticHost, namespace_ids: &HashMap<Thingy, String>, add: &mut impl FnMut(Option<String>, SwcDependencyKind)) {
+        let Some(thingy) = &self.thingy else {
+            return;
+        };
+        if thingy.is::<ClassType>() || thingy.is::<EnumType>() {
+            add(thingy.extends_class(host).and_then(|t| Self::type_id(&t)), SwcDependencyKind::Inheritance);
+            for t in thingy.implements(host).iter() {
+                add(Self::type_id(&t), SwcDependencyKind::Inheritance);
+            }
+            if let Some(ctor) = thingy.constructor_method(host) {
+                Self::slot_dependencies(host, &ctor, namespace_ids, add);
+            }
+        } else if thingy.is::<InterfaceType>() {
+            for t in thingy.extends_interfaces(host).iter() {
+                add(Self::type_id(&t), SwcDependencyKind::Inheritance);
+            }
+        }
+        if thingy.is::<ClassType>() || thingy.is::<EnumType>() || thingy.is::<InterfaceType>() {
+            for names in [thingy.prototype(host), thingy.properties(host)] {
+                let slots: Vec<Thingy> = names.borrow().values().cloned().collect();
+                for slot in slots {
+                    Self::slot_dependencies(host, &slot, namespace_ids, add);
+                }
+            }
+        } else {
+            Self::slot_dependencies(host, thingy, namespace_ids, add);
+        }
+    }
+
+    fn slot_dependencies(host: &SemanticHost, slot: &Thingy, namespace_ids: &HashMap<Thingy, String>, add: &mut impl FnMut(Option<String>, SwcDependencyKind)) {
+        let signatures = if slot.is::<VariableSlot>() {
+            add(Self::type_id(&slot.static_type(host)), SwcDependencyKind::Signature);
+            vec![]
+        } else if slot.is::<VirtualSlot>() {
+            [slot.getter(host), slot.setter(host)].into_iter().flatten().map(|m| m.signature(host)).collect()
+        } else if slot.is::<MethodSlot>() {
+            vec![slot.signature(host)]
+        } else {
+            return;
+        };
+        for signature in signatures {
+            for param in signature.params().iter() {
+                add(Self::type_id(&param.static_type), SwcDependencyKind::Signature);
+            }
+            add(Self::type_id(&signature.result_type()), SwcDependencyKind::Signature);
+        }
+        let ns = slot.name().namespace();
+        if ns.is::<UserNamespace>() || ns.is::<ExplicitNamespace>() {
+            add(Some(namespace_ids.get(&ns).cloned().unwrap_or(ns.uri())), SwcDependencyKind::Namespace);
+        }
+    }
+
+    fn write_asdoc(&self, output: &mut String) {
+        let (asdoc, block) = match self.directive.as_ref() {
+            Directive::ClassDefinition(defn) => (defn.asdoc.clone(), Some(defn.block.clone())),
+            Directive::InterfaceDefinition(defn) => (defn.asdoc.clone(), Some(defn.block.clone())),
+            Directive::EnumDefinition(defn) => (defn.asdoc.clone(), Some(defn.block.clone())),
+            Directive::NamespaceDefinition(defn) => (defn.asdoc.clone(), None),
+            Directive::FunctionDefinition(defn) => (defn.asdoc.clone(), None),
+            Directive::VariableDefinition(defn) => (defn.asdoc.clone(), None),
+            _ => (None, None),
+        };
+        output.push_str(&format!("  <definition id=\"{}\">\n", escape_xml(&self.id)));
+        if let Some(description) = asdoc.as_ref().and_then(|a| a.main_body.as_ref()) {
+            output.push_str(&format!("    <description>{}</description>\n", escape_xml(&description.0)));
+        }
+        for drtv in block.iter().flat_map(|b| b.directives.iter()) {
+            let (name, asdoc) = match drtv.as_ref() {
+                Directive::FunctionDefinition(defn) => (defn.name_identifier().0, defn.asdoc.clone()),
+                Directive::VariableDefinition(defn) => {
+                    let name = defn.bindings.first().and_then(|b| match b.destructuring.destructuring.as_ref() {
+                        Expression::QualifiedIdentifier(id) => id.to_identifier_name().map(|n| n.0),
+                        _ => None,
+                    });
+                    (name.unwrap_or_default(), defn.asdoc.clone())
+                },
+                _ => continue,
+            };
+            if let Some(description) = asdoc.as_ref().and_then(|a| a.main_body.as_ref()) {
+                output.push_str(&format!("    <member name=\"{}\">\n      <description>{}</description>\n    </member>\n", escape_xml(&name), escape_xml(&description.0)));
+            }
+        }
+        output.push_str("  </definition>\n");
+    }
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;").replace('\'', "&apos;")
+}
+
+fn child_elements_named(element: &Rc<MxmlElement>, name: &str) -> Vec<Rc<MxmlElement>> {
+    child_elements(element.content.as_deref().unwrap_or(&[]))
+        .into_iter()
+        .filter(|e| e.name.prefix.is_none() && e.name.name == name)
+        .collect()
+}
+
+fn attribute_value(element: &Rc<MxmlElement>, name: &str) -> Option<String> {
+    element.attributes.iter()
+        .find(|a| !a.xmlns && a.name.prefix.is_none() && a.name.name == name)
+        .map(|a| a.value.0.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    #[test]
+    fn test_swc_round_trip() {
+        let host = SemanticHost::new(SemanticHostOptions::default());
+
+        // External class com.base.Base
+        let base = define_class(&host, &host.factory().create_package(["com", "base"]), "Base", None);
+
+        // Class com.example.Foo extends Base, with a method f(): Base
+        let package = host.factory().create_package(["com", "example"]);
+        let public_ns = package.public_ns().unwrap();
+        let foo = define_class(&host, &package, "Foo", Some(&base));
+        let f_name = host.factory().create_qname(&public_ns, "f".into());
+        let f_slot = host.factory().create_method_slot(&f_name, &host.factory().create_function_type(vec![], base.clone()));
+        foo.prototype(&host).set(f_name, f_slot);
+
+        let parse = |text: &str| {
+            let cu = CompilationUnit::new(None, text.into());
+            ParserFacade(&cu, ParserOptions::default()).parse_program()
+        };
+        let external = parse("package com.base { public class Base {} }");
+        let program = parse(r#"
+            package com.example {
+                import com.base.*;
+                /** The Foo class. */
+                public class Foo extends Base {
+                    /** Returns a base. */
+                    public function f(): Base { return null; }
+                }
+            }
+        "#);
+
+        let swc = SwcLibrary::compile(&host, &[program, external.clone()], &[external], true);
+        assert_eq!(swc.scripts.len(), 1);
+        let script = &swc.scripts[0];
+        assert_eq!(script.name, "com/example/Foo");
+        assert_eq!(script.definitions, ["com.example:Foo"]);
+        assert!(script.dependencies.contains(&SwcDependency { id: "com.base:Base".into(), kind: SwcDependencyKind::Inheritance }));
+        assert!(script.dependencies.contains(&SwcDependency { id: "com.base:Base".into(), kind: SwcDependencyKind::Signature }));
+        let asdoc_xml = swc.asdoc_xml.clone().unwrap();
+        assert!(asdoc_xml.contains("The Foo class."));
+        assert!(asdoc_xml.contains("<member name=\"f\">"));
+
+        // The external class is not part of library.swf
+        let swf_buf = hydroper_swf::decompress_swf(&swc.library_swf[..]).unwrap();
+        let swf = hydroper_swf::parse_swf(&swf_buf).unwrap();
+        let abc_tags: Vec<_> = swf.tags.iter().filter(|t| matches!(t, hydroper_swf::Tag::DoAbc2(_))).collect();
+        assert_eq!(abc_tags.len(), 1);
+
+        // Round trip
+        let bytes = swc.write();
+        assert_eq!(bytes, swc.write());
+        let swc2 = SwcLibrary::read(&bytes).unwrap();
+        assert_eq!(swc2.scripts, swc.scripts);
+        assert_eq!(swc2.library_swf, swc.library_swf);
+        assert_eq!(swc2.asdoc_xml, swc.asdoc_xml);
+
+        assert_eq!(SwcLibrary::read(&bytes[1..]).err(), Some(SwcReadError::InvalidArchive));
+
+        // An entry declaring a larger uncompressed size than its data inflates to.
+        let mut bytes = bytes;
+        let central_directory = bytes.windows(4).position(|w| w == [0x50, 0x4b, 0x01, 0x02]).unwrap();
+        bytes[central_directory + 24..central_directory + 28].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(SwcLibrary::read(&bytes).err(), Some(SwcReadError::InvalidArchive));
+    }
+}
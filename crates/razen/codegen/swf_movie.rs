@@ -0,0 +1,290 @@
+use crate::ns::*;
+
+/// Header and movie options of a SWF, usually taken from the `[SWF]`
+/// meta-data of the main class.
+#[derive(Clone)]
+pub struct SwfMovieOptions {
+    pub version: u8,
+    /// Stage width in pixels.
+    pub width: u32,
+    /// Stage height in pixels.
+    pub height: u32,
+    pub frame_rate: f32,
+    /// Background color in the form `0xRRGGBB`.
+    pub background_color: u32,
+    /// Whether the SWF body is compressed with zlib.
+    pub compress: bool,
+    /// Whether the SWF is placed in the network sandbox when run locally.
+    pub use_network: bool,
+    pub script_recursion_limit: Option<u16>,
+    pub script_time_limit: Option<u16>,
+    /// Optional XMP meta-data, written to a Metadata tag.
+    pub metadata: Option<String>,
+}
+
+impl Default for SwfMovieOptions {
+    fn default() -> Self {
+        Self {
+            version: 14,
+            width: 500,
+            height: 375,
+            frame_rate: 24.0,
+            background_color: 0xFFFFFF,
+            compress: true,
+            use_network: true,
+            script_recursion_limit: None,
+            script_time_limit: None,
+            metadata: None,
+        }
+    }
+}
+
+impl SwfMovieOptions {
+    /// Returns the default options updated with the `[SWF]` meta-data
+    /// of the given class, if any.
+    pub fn of_main_class(main_class: &Thingy) -> Self {
+        let mut options = Self::default();
+        for m in main_class.metadata().iter() {
+            if m.name.0 == "SWF" {
+                options.apply_swf_metadata(&m);
+            }
+        }
+        options
+    }
+
+    /// Updates the options from a `[SWF]` meta-data. The recognized
+    /// keys are `width`, `height`, `frameRate`, `backgroundColor`,
+    /// `scriptRecursionLimit` and `scriptTimeLimit`; unrecognized keys,
+    /// percentages and invalid values are ignored.
+    pub fn apply_swf_metadata(&mut self, metadata: &Rc<Metadata>) {
+        for entry in metadata.entries.iter().flatten() {
+            let Some((key, _)) = &entry.key else {
+                continue;
+            };
+            let value = match entry.value.as_ref() {
+                MetadataValue::IdentifierString((s, _)) => s.trim(),
+                MetadataValue::String((s, _)) => s.trim(),
+            };
+            match key.as_str() {
+                "width" => {
+                    if let Ok(v) = value.parse::<u32>() {
+                        self.width = v;
+                    }
+                },
+                "height" => {
+                    if let Ok(v) = value.parse::<u32>() {
+                        self.height = v;
+                    }
+                },
+                "frameRate" => {
+                    if let Ok(v) = value.parse::<f32>() {
+                        self.frame_rate = v;
+                    }
+                },
+                "backgroundColor" => {
+                    let hex = value.strip_prefix('#').or(value.strip_prefix("0x")).or(value.strip_prefix("0X"));
+                    let color = match hex {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                        None => value.parse::<u32>().ok(),
+                    };
+                    if let Some(color) = color {
+                        self.background_color = color & 0xFFFFFF;
+                    }
+                },
+                "scriptRecursionLimit" => {
+                    if let Ok(v) = value.parse::<u16>() {
+                        self.script_recursion_limit = Some(v);
+                    }
+                },
+                "scriptTimeLimit" => {
+                    if let Ok(v) = value.parse::<u16>() {
+                        self.script_time_limit = Some(v);
+                    }
+                },
+                _ => {},
+            }
+        }
+    }
+}
+
+/// Assembles a single-frame ActionScript 3 movie from ABC files.
+///
+/// The movie consists of, in order, the FileAttributes tag, the optional
+/// Metadata and ScriptLimits tags, the SetBackgroundColor tag, the character tags
+/// of embedded assets, one DoABC2 tag for each ABC file, the SymbolClass tag
+/// linking the embedded assets to their classes and the main class, if any,
+/// to the root timeline, and the ShowFrame tag. The output depends only on the inputs, thus
+/// assembling the same inputs results in the same bytes.
+///
+/// # Example
+///
+/// ```ignore
+/// let options = SwfMovieOptions::of_main_class(&main_class);
+/// let bytes = SwfMovie::new(options, SwfMovie::class_symbol_name(&main_class))
+///     .write(&[abc_payload]);
+/// ```
+pub struct SwfMovie {
+    options: SwfMovieOptions,
+    main_class: Option<String>,
+}
+
+impl SwfMovie {
+    /// Constructs a movie whose document class is `main_class`,
+    /// given as a fully qualified name such as `"com.example.Main"`.
+    pub fn new(options: SwfMovieOptions, main_class: String) -> Self {
+        Self { options, main_class: Some(main_class) }
+    }
+
+    /// Constructs a movie without a document class, such as
+    /// the `library.swf` file of a SWC. The SymbolClass tag is omitted.
+    pub fn new_library(options: SwfMovieOptions) -> Self {
+        Self { options, main_class: None }
+    }
+
+    /// Fully qualified name of a class as used in the SymbolClass tag.
+    pub fn class_symbol_name(class: &Thingy) -> String {
+        let local_name = class.name().local_name();
+        match class.parent().filter(|p| p.is::<Package>()).map(|p| p.fully_qualified_name()) {
+            Some(package_name) if !package_name.is_empty() => format!("{}.{}", package_name, local_name),
+            _ => local_name,
+        }
+    }
+
+    /// Writes the movie with the given ABC files, in order.
+    pub fn write(&self, abc_list: &[AbcDoAbc2Payload]) -> Vec<u8> {
+        self.write_with_assets(abc_list, &[])
+    }
+
+    /// Writes the movie with the given ABC files and embedded assets, in order.
+    /// Assets are assigned character IDs starting from 1.
+    pub fn write_with_assets(&self, abc_list: &[AbcDoAbc2Payload], assets: &[Rc<EmbeddedAsset>]) -> Vec<u8> {
+        let options = &self.options;
+        let header = hydroper_swf::Header {
+            compression: if options.compress { hydroper_swf::Compression::Zlib } else { hydroper_swf::Compression::None },
+            version: options.version,
+            stage_size: hydroper_swf::Rectangle {
+                x_min: hydroper_swf::Twips::ZERO,
+                x_max: hydroper_swf::Twips::from_pixels_i32(options.width as i32),
+                y_min: hydroper_swf::Twips::ZERO,
+                y_max: hydroper_swf::Twips::from_pixels_i32(options.height as i32),
+            },
+            frame_rate: hydroper_swf::Fixed8::from_f32(options.frame_rate),
+            num_frames: 1,
+        };
+
+        let mut file_attributes = hydroper_swf::FileAttributes::IS_ACTION_SCRIPT_3;
+        if options.use_network {
+            file_attributes |= hydroper_swf::FileAttributes::USE_NETWORK_SANDBOX;
+        }
+        if options.metadata.is_some() {
+            file_attributes |= hydroper_swf::FileAttributes::HAS_METADATA;
+        }
+
+        let mut tags = vec![hydroper_swf::Tag::FileAttributes(file_attributes)];
+        if let Some(metadata) = &options.metadata {
+            tags.push(hydroper_swf::Tag::Metadata(hydroper_swf::SwfStr::from_utf8_str(metadata)));
+        }
+        if options.script_recursion_limit.is_some() || options.script_time_limit.is_some() {
+            tags.push(hydroper_swf::Tag::ScriptLimits {
+                max_recursion_depth: options.script_recursion_limit.unwrap_or(1000),
+                timeout_in_seconds: options.script_time_limit.unwrap_or(60),
+            });
+        }
+        tags.push(hydroper_swf::Tag::SetBackgroundColor(hydroper_swf::Color::from_rgb(options.background_color, 255)));
+        let asset_class_names: Vec<String> = assets.iter().map(|a| a.linked_class_name()).collect();
+        for (i, asset) in assets.iter().enumerate() {
+            tags.push(asset.define_tag(i as u16 + 1));
+        }
+        for abc in abc_list {
+            tags.push(abc.to_tag());
+        }
+        let mut links: Vec<hydroper_swf::SymbolClassLink> = asset_class_names.iter().enumerate().map(|(i, class_name)| hydroper_swf::SymbolClassLink {
+            id: i as u16 + 1,
+            class_name: hydroper_swf::SwfStr::from_utf8_str(class_name),
+        }).collect();
+        if let Some(main_class) = &self.main_class {
+            links.push(hydroper_swf::SymbolClassLink {
+                id: 0,
+                class_name: hydroper_swf::SwfStr::from_utf8_str(main_class),
+            });
+        }
+        if !links.is_empty() {
+            tags.push(hydroper_swf::Tag::SymbolClass(links));
+        }
+        tags.push(hydroper_swf::Tag::ShowFrame);
+
+        let mut bytes = vec![];
+        hydroper_swf::write_swf(&header, &tags, &mut bytes).unwrap();
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    #[test]
+    fn test_swf_movie() {
+        let cu = CompilationUnit::new(None, r##"
+            package com.example {
+                [SWF(width="800", height="600", frameRate="30", backgroundColor="#336699")]
+                public class Main {}
+            }
+        "##.into());
+        let program = ParserFacade(&cu, ParserOptions::default()).parse_program();
+        let Directive::ClassDefinition(defn) = program.packages[0].block.directives[0].as_ref() else {
+            panic!();
+        };
+        let metadata = Attribute::find_metadata(&defn.attributes);
+        let mut options = SwfMovieOptions::default();
+        for m in &metadata {
+            options.apply_swf_metadata(m);
+        }
+        assert_eq!((options.width, options.height, options.frame_rate, options.background_color), (800, 600, 30.0, 0x336699));
+
+        let payload = AbcDoAbc2Payload {
+            name: "main".into(),
+            lazy_initialize: true,
+            data: vec![0x10, 0x00, 0x2E, 0x00, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+        };
+        let movie = SwfMovie::new(options, "com.example.Main".into());
+        let bytes = movie.write(std::slice::from_ref(&payload));
+
+        // Byte-reproducible output
+        assert_eq!(bytes, movie.write(std::slice::from_ref(&payload)));
+
+        let swf_buf = hydroper_swf::decompress_swf(&bytes[..]).unwrap();
+        let swf = hydroper_swf::parse_swf(&swf_buf).unwrap();
+        let header = swf.header.swf_header();
+        assert_eq!(header.compression, hydroper_swf::Compression::Zlib);
+        assert_eq!(header.version, 14);
+        assert_eq!(header.stage_size.x_max.to_pixels(), 800.0);
+        assert_eq!(header.stage_size.y_max.to_pixels(), 600.0);
+        assert_eq!(header.frame_rate.to_f32(), 30.0);
+        assert_eq!(header.num_frames, 1);
+        assert!(swf.header.is_action_script_3());
+
+        assert!(matches!(swf.tags[0], hydroper_swf::Tag::FileAttributes(_)));
+        let mut found_abc = false;
+        let mut found_symbol = false;
+        for tag in &swf.tags {
+            match tag {
+                hydroper_swf::Tag::SetBackgroundColor(color) => {
+                    assert_eq!((color.r, color.g, color.b), (0x33, 0x66, 0x99));
+                },
+                hydroper_swf::Tag::DoAbc2(do_abc) => {
+                    assert_eq!(do_abc.data, &payload.data[..]);
+                    found_abc = true;
+                },
+                hydroper_swf::Tag::SymbolClass(links) => {
+                    assert!(found_abc);
+                    assert_eq!(links[0].id, 0);
+                    assert!(links[0].class_name == "com.example.Main");
+                    found_symbol = true;
+                },
+                _ => {},
+            }
+        }
+        assert!(found_abc && found_symbol);
+    }
+}
@@ -0,0 +1,185 @@
+use crate::ns::*;
+use std::io::{Read, Write};
+use flate2::Compression as DeflateCompression;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+
+const LOCAL_FILE_HEADER_SIGNATURE: u32 = 0x04034b50;
+const CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x02014b50;
+const END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x06054b50;
+
+/// MS-DOS date of 1980-01-01, used as the modification date of every entry
+/// so that identical inputs result in identical archives.
+const DOS_DATE: u16 = 0x21;
+
+const METHOD_STORE: u16 = 0;
+const METHOD_DEFLATE: u16 = 8;
+
+/// Minimal ZIP archive supporting stored and deflated entries,
+/// used for SWC files.
+#[derive(Clone, Default)]
+pub struct ZipArchive {
+    entries: Vec<(String, Vec<u8>)>,
+}
+
+impl ZipArchive {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an entry, replacing any entry with the same path.
+    pub fn add(&mut self, path: &str, data: Vec<u8>) {
+        if let Some(entry) = self.entries.iter_mut().find(|(p, _)| p == path) {
+            entry.1 = data;
+        } else {
+            self.entries.push((path.to_owned(), data));
+        }
+    }
+
+    pub fn get(&self, path: &str) -> Option<&[u8]> {
+        self.entries.iter().find(|(p, _)| p == path).map(|(_, d)| &d[..])
+    }
+
+    /// Entries in the order they were added or read.
+    pub fn entries(&self) -> &[(String, Vec<u8>)] {
+        &self.entries
+    }
+
+    /// Encodes the archive, deflating every entry.
+    pub fn write(&self) -> Vec<u8> {
+        let mut r = vec![];
+        let mut central_directory = vec![];
+        for (path, data) in &self.entries {
+            let offset = r.len() as u32;
+            let crc = crc32fast::hash(data);
+            let mut encoder = DeflateEncoder::new(vec![], DeflateCompression::default());
+            encoder.write_all(data).unwrap();
+            let compressed = encoder.finish().unwrap();
+
+            write_u32(&mut r, LOCAL_FILE_HEADER_SIGNATURE);
+            Self::write_entry_header(&mut r, path, crc, compressed.len() as u32, data.len() as u32);
+            r.extend_from_slice(path.as_bytes());
+            r.extend_from_slice(&compressed);
+
+            write_u32(&mut central_directory, CENTRAL_DIRECTORY_SIGNATURE);
+            // Version made by
+            write_u16(&mut central_directory, 20);
+            Self::write_entry_header(&mut central_directory, path, crc, compressed.len() as u32, data.len() as u32);
+            // File comment length, disk number, internal and external attributes
+            write_u16(&mut central_directory, 0);
+            write_u16(&mut central_directory, 0);
+            write_u16(&mut central_directory, 0);
+            write_u32(&mut central_directory, 0);
+            write_u32(&mut central_directory, offset);
+            central_directory.extend_from_slice(path.as_bytes());
+        }
+        let central_directory_offset = r.len() as u32;
+        r.extend_from_slice(&central_directory);
+        write_u32(&mut r, END_OF_CENTRAL_DIRECTORY_SIGNATURE);
+        write_u16(&mut r, 0);
+        write_u16(&mut r, 0);
+        write_u16(&mut r, self.entries.len() as u16);
+        write_u16(&mut r, self.entries.len() as u16);
+        write_u32(&mut r, central_directory.len() as u32);
+        write_u32(&mut r, central_directory_offset);
+        write_u16(&mut r, 0);
+        r
+    }
+
+    /// Writes the fields shared by local file headers and central directory entries,
+    /// from the version needed to extract to the extra field length.
+    fn write_entry_header(output: &mut Vec<u8>, path: &str, crc: u32, compressed_size: u32, size: u32) {
+        write_u16(output, 20);
+        // General purpose flags: UTF-8 file names
+        write_u16(output, 1 << 11);
+        write_u16(output, METHOD_DEFLATE);
+        write_u16(output, 0);
+        write_u16(output, DOS_DATE);
+        write_u32(output, crc);
+        write_u32(output, compressed_size);
+        write_u32(output, size);
+        write_u16(output, path.len() as u16);
+        write_u16(output, 0);
+    }
+
+    /// Decodes an archive through its central directory.
+    pub fn read(bytes: &[u8]) -> Result<Self, ZipArchiveError> {
+        // Locate the end of central directory record, which may be
+        // followed by a comment.
+        let mut eocd = None;
+        let mut i = bytes.len().checked_sub(22).ok_or(ZipArchiveError())?;
+        loop {
+            if read_u32(bytes, i)? == END_OF_CENTRAL_DIRECTORY_SIGNATURE {
+                eocd = Some(i);
+                break;
+            }
+            if i == 0 || bytes.len() - i > 22 + u16::MAX as usize {
+                break;
+            }
+            i -= 1;
+        }
+        let eocd = eocd.ok_or(ZipArchiveError())?;
+        let count = read_u16(bytes, eocd + 10)? as usize;
+        let mut offset = read_u32(bytes, eocd + 16)? as usize;
+
+        let mut entries = vec![];
+        for _ in 0..count {
+            if read_u32(bytes, offset)? != CENTRAL_DIRECTORY_SIGNATURE {
+                return Err(ZipArchiveError());
+            }
+            let method = read_u16(bytes, offset + 10)?;
+            let crc = read_u32(bytes, offset + 16)?;
+            let compressed_size = read_u32(bytes, offset + 20)? as usize;
+            let size = read_u32(bytes, offset + 24)? as usize;
+            let name_length = read_u16(bytes, offset + 28)? as usize;
+            let extra_length = read_u16(bytes, offset + 30)? as usize;
+            let comment_length = read_u16(bytes, offset + 32)? as usize;
+            let local_offset = read_u32(bytes, offset + 42)? as usize;
+            let name = bytes.get(offset + 46..offset + 46 + name_length).ok_or(ZipArchiveError())?;
+            let name = String::from_utf8_lossy(name).into_owned();
+            offset += 46 + name_length + extra_length + comment_length;
+
+            if read_u32(bytes, local_offset)? != LOCAL_FILE_HEADER_SIGNATURE {
+                return Err(ZipArchiveError());
+            }
+            let local_name_length = read_u16(bytes, local_offset + 26)? as usize;
+            let local_extra_length = read_u16(bytes, local_offset + 28)? as usize;
+            let data_offset = local_offset + 30 + local_name_length + local_extra_length;
+            let compressed = bytes.get(data_offset..data_offset + compressed_size).ok_or(ZipArchiveError())?;
+            let data = match method {
+                METHOD_STORE => compressed.to_vec(),
+                METHOD_DEFLATE => {
+                    // The declared size is not trusted: DEFLATE expands by at
+                    // most 1032 to 1, and inflating stops past the declared size.
+                    let mut data = Vec::with_capacity(size.min(compressed_size.saturating_mul(1032)));
+                    DeflateDecoder::new(compressed).take(size as u64 + 1).read_to_end(&mut data).map_err(|_| ZipArchiveError())?;
+                    data
+                },
+                _ => return Err(ZipArchiveError()),
+            };
+            if data.len() != size || crc32fast::hash(&data) != crc {
+                return Err(ZipArchiveError());
+            }
+            entries.push((name, data));
+        }
+        Ok(Self { entries })
+    }
+}
+
+fn write_u16(output: &mut Vec<u8>, value: u16) {
+    output.extend_from_slice(&value.to_le_bytes());
+}
+
+fn write_u32(output: &mut Vec<u8>, value: u32) {
+    output.extend_from_slice(&value.to_le_bytes());
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Result<u16, ZipArchiveError> {
+    let b = bytes.get(offset..offset + 2).ok_or(ZipArchiveError())?;
+    Ok(u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, ZipArchiveError> {
+    let b = bytes.get(offset..offset + 4).ok_or(ZipArchiveError())?;
+    Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
@@ -1,2 +1,11 @@
+mod asdoc_manifest;
+pub use asdoc_manifest::*;
+
 mod compiler_options;
-pub use compiler_options::*;
\ No newline at end of file
+pub use compiler_options::*;
+
+mod project_manifest;
+pub use project_manifest::*;
+
+mod source_resolver;
+pub use source_resolver::*;
\ No newline at end of file
@@ -0,0 +1,337 @@
+use std::fs;
+use std::path::Path;
+use crate::ns::*;
+
+/// ASDoc manifest of a Razen package, usually in an `asdoc.xml` file, describing
+/// Markdown sections rendered by `AsDocSite` along with the API reference.
+///
+/// ```xml
+/// <?xml version="1.0"?>
+/// <asdoc>
+///     <basePath>docs</basePath>
+///     <includeFiles>
+///         <include>image.png</include>
+///     </includeFiles>
+///     <home>
+///         <title>My Package's Home</title>
+///         <path>home.md</path>
+///     </home>
+///     <sections>
+///         <section>
+///             <title>Foo</title>
+///             <path>foo.md</path>
+///             <children>
+///                 <section>
+///                     <title>Qux</title>
+///                     <path>qux.md</path>
+///                 </section>
+///             </children>
+///         </section>
+///     </sections>
+/// </asdoc>
+/// ```
+///
+/// `basePath` is relative to the directory of the manifest, and every other path is
+/// relative to `basePath`. The children of a section are relative to a directory named
+/// after the section's document, so that `qux.md` above is `docs/foo/qux.md` and
+/// is rendered into `foo/qux.html`. The home section is mixed into `index.html`.
+///
+/// Malformed elements, paths that do not exist or are outside of the base path, and
+/// top-level sections whose page would be `index.html` are reported to the manifest's
+/// compilation unit at their location and otherwise ignored.
+pub struct AsDocManifest {
+    /// Resolved `basePath` directory.
+    pub base_path: String,
+    pub include_files: Vec<AsDocIncludedFile>,
+    pub home: Option<AsDocSection>,
+    pub sections: Vec<AsDocSection>,
+}
+
+/// A file copied verbatim into the documentation.
+pub struct AsDocIncludedFile {
+    /// Path relative to the base path, which is also the path within the documentation.
+    pub path: String,
+    pub resolved_path: String,
+}
+
+/// A Markdown section of the documentation.
+pub struct AsDocSection {
+    pub title: String,
+    /// Path of the Markdown document relative to the base path, as in `foo/qux.md`.
+    pub source: String,
+    /// Path of the rendered page, as in `foo/qux.html`.
+    pub page: String,
+    /// The Markdown document, to which broken links are reported.
+    /// `None` if the document could not be read.
+    pub compilation_unit: Option<Rc<CompilationUnit>>,
+    pub children: Vec<AsDocSection>,
+}
+
+impl AsDocManifest {
+    pub const HOME_PAGE: &'static str = "index.html";
+
+    /// Parses an ASDoc manifest, reading the documents of its sections.
+    /// Returns `None` if the manifest contains syntax errors or
+    /// has no `asdoc` root element.
+    pub fn parse(compilation_unit: &Rc<CompilationUnit>) -> Option<Self> {
+        let directory = compilation_unit.file_path()
+            .and_then(|p| Path::new(&p).parent().map(|d| d.to_string_lossy().into_owned()))
+            .unwrap_or(".".into());
+
+        let document = ParserFacade(compilation_unit, ParserOptions::default()).parse_mxml();
+        if compilation_unit.invalidated() {
+            return None;
+        }
+        let root = child_elements(&document.content).into_iter().next().filter(|e| e.name.name == "asdoc");
+        let Some(root) = root else {
+            FxDiagnostic::add_verify_error(&document.location, FxDiagnosticKind::MissingAsDocManifestElement, diagarg!["asdoc".to_owned()]);
+            return None;
+        };
+
+        // basePath affects every other element.
+        let mut base_path = directory.clone();
+        for element in Self::child_elements_named(&root, "basePath") {
+            base_path = Path::new(&directory).join(element.inner_text().trim()).to_string_lossy().into_owned();
+        }
+        let mut manifest = Self { base_path, include_files: vec![], home: None, sections: vec![] };
+
+        for element in Self::element_children(&root) {
+            match element.name.name.as_str() {
+                "basePath" => {},
+                "includeFiles" => {
+                    for include in Self::element_children(&element) {
+                        if include.name.name != "include" {
+                            FxDiagnostic::add_verify_error(&include.location, FxDiagnosticKind::UnknownAsDocManifestElement, diagarg![include.name.name.clone()]);
+                            continue;
+                        }
+                        let text = include.inner_text();
+                        let Some(path) = Self::normalize_path(text.trim()) else {
+                            FxDiagnostic::add_verify_error(&include.location, FxDiagnosticKind::AsDocPathOutsideBasePath, diagarg![text.trim().to_owned()]);
+                            continue;
+                        };
+                        let resolved_path = manifest.resolve_path(&path);
+                        if !Path::new(&resolved_path).is_file() {
+                            FxDiagnostic::add_verify_error(&include.location, FxDiagnosticKind::AsDocFileNotFound, diagarg![path]);
+                            continue;
+                        }
+                        manifest.include_files.push(AsDocIncludedFile { path, resolved_path });
+                    }
+                },
+                "home" => {
+                    manifest.home = manifest.parse_section(&element, "").map(|mut home| {
+                        home.page = Self::HOME_PAGE.into();
+                        home
+                    });
+                },
+                "sections" => {
+                    manifest.sections = manifest.parse_sections(&element, "");
+                },
+                name => {
+                    FxDiagnostic::add_verify_error(&element.location, FxDiagnosticKind::UnknownAsDocManifestElement, diagarg![name.to_owned()]);
+                },
+            }
+        }
+
+        Some(manifest)
+    }
+
+    /// Resolves a path relative to the base path.
+    pub fn resolve_path(&self, path: &str) -> String {
+        Path::new(&self.base_path).join(path).to_string_lossy().into_owned()
+    }
+
+    /// Returns the home section and every section, recursively.
+    pub fn all_sections(&self) -> Vec<&AsDocSection> {
+        fn collect<'a>(sections: &'a [AsDocSection], output: &mut Vec<&'a AsDocSection>) {
+            for section in sections {
+                output.push(section);
+                collect(&section.children, output);
+            }
+        }
+        let mut r: Vec<&AsDocSection> = self.home.iter().collect();
+        collect(&self.sections, &mut r);
+        r
+    }
+
+    fn parse_sections(&self, element: &Rc<MxmlElement>, directory: &str) -> Vec<AsDocSection> {
+        let mut r = vec![];
+        for child in Self::element_children(element) {
+            if child.name.name != "section" {
+                FxDiagnostic::add_verify_error(&child.location, FxDiagnosticKind::UnknownAsDocManifestElement, diagarg![child.name.name.clone()]);
+                continue;
+            }
+            let Some(section) = self.parse_section(&child, directory) else {
+                continue;
+            };
+            if section.page == Self::HOME_PAGE {
+                FxDiagnostic::add_verify_error(&child.location, FxDiagnosticKind::AsDocSectionConflictsWithHomePage, diagarg![section.source.clone(), Self::HOME_PAGE.to_owned()]);
+                continue;
+            }
+            r.push(section);
+        }
+        r
+    }
+
+    /// Parses a section whose document is relative to `directory`
+    /// within the base path.
+    fn parse_section(&self, element: &Rc<MxmlElement>, directory: &str) -> Option<AsDocSection> {
+        let mut title = None;
+        let mut path = None;
+        let mut children = None;
+        for child in Self::element_children(element) {
+            match child.name.name.as_str() {
+                "title" => title = Some(child.inner_text().trim().to_owned()),
+                "path" => path = Some(child),
+                "children" => children = Some(child),
+                name => {
+                    FxDiagnostic::add_verify_error(&child.location, FxDiagnosticKind::UnknownAsDocManifestElement, diagarg![name.to_owned()]);
+                },
+            }
+        }
+        let Some(title) = title else {
+            FxDiagnostic::add_verify_error(&element.location, FxDiagnosticKind::MissingAsDocManifestElement, diagarg!["title".to_owned()]);
+            return None;
+        };
+        let Some(path) = path else {
+            FxDiagnostic::add_verify_error(&element.location, FxDiagnosticKind::MissingAsDocManifestElement, diagarg!["path".to_owned()]);
+            return None;
+        };
+
+        let text = path.inner_text();
+        let Some(source) = Self::normalize_path(&format!("{directory}/{}", text.trim())) else {
+            FxDiagnostic::add_verify_error(&path.location, FxDiagnosticKind::AsDocPathOutsideBasePath, diagarg![text.trim().to_owned()]);
+            return None;
+        };
+        let stem = source.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(&source).to_owned();
+        let resolved_path = self.resolve_path(&source);
+        let compilation_unit = match fs::read_to_string(&resolved_path) {
+            Ok(text) => Some(CompilationUnit::new(Some(resolved_path), text)),
+            Err(_) => {
+                FxDiagnostic::add_verify_error(&path.location, FxDiagnosticKind::AsDocFileNotFound, diagarg![source.clone()]);
+                None
+            },
+        };
+        let children = children.map(|c| self.parse_sections(&c, &stem)).unwrap_or_default();
+
+        Some(AsDocSection {
+            title,
+            page: format!("{stem}.html"),
+            source,
+            compilation_unit,
+            children,
+        })
+    }
+
+    /// Normalizes a relative path into `/`-separated components,
+    /// resolving `.` and `..` components. Returns `None` if a `..`
+    /// component goes outside of the base directory.
+    pub fn normalize_path(path: &str) -> Option<String> {
+        let mut components: Vec<&str> = vec![];
+        for component in path.split(['/', '\\']) {
+            match component {
+                "" | "." => {},
+                ".." => {
+                    components.pop()?;
+                },
+                _ => components.push(component),
+            }
+        }
+        Some(components.join("/"))
+    }
+
+    fn element_children(element: &Rc<MxmlElement>) -> Vec<Rc<MxmlElement>> {
+        child_elements(element.content.as_deref().unwrap_or(&[]))
+    }
+
+    fn child_elements_named(element: &Rc<MxmlElement>, name: &str) -> Vec<Rc<MxmlElement>> {
+        Self::element_children(element).into_iter().filter(|e| e.name.name == name).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+    use std::fs;
+
+    #[test]
+    fn test_asdoc_manifest() {
+        let directory = std::env::temp_dir().join(format!("razen_test_asdoc_manifest_{}", std::process::id()));
+        fs::create_dir_all(directory.join("docs/foo")).unwrap();
+        fs::write(directory.join("docs/home.md"), "# Home").unwrap();
+        fs::write(directory.join("docs/foo.md"), "Foo").unwrap();
+        fs::write(directory.join("docs/index.md"), "Index").unwrap();
+        fs::write(directory.join("docs/foo/qux.md"), "Qux").unwrap();
+        fs::write(directory.join("docs/image.png"), b"\x89PNG").unwrap();
+
+        let manifest_path = directory.join("asdoc.xml");
+        let cu = CompilationUnit::new(Some(manifest_path.to_string_lossy().into_owned()), r#"<?xml version="1.0"?>
+            <asdoc>
+                <basePath>docs</basePath>
+                <includeFiles>
+                    <include>image.png</include>
+                    <include>missing.png</include>
+                    <include>../asdoc.xml</include>
+                </includeFiles>
+                <home>
+                    <title>My Package's Home</title>
+                    <path>home.md</path>
+                </home>
+                <sections>
+                    <section>
+                        <title>Foo</title>
+                        <path>foo.md</path>
+                        <children>
+                            <section>
+                                <title>Qux</title>
+                                <path>qux.md</path>
+                            </section>
+                            <section>
+                                <title>Missing</title>
+                                <path>missing.md</path>
+                            </section>
+                            <section>
+                                <title>Outside</title>
+                                <path>../../outside.md</path>
+                            </section>
+                        </children>
+                    </section>
+                    <section>
+                        <path>untitled.md</path>
+                    </section>
+                    <section>
+                        <title>Index</title>
+                        <path>index.md</path>
+                    </section>
+                </sections>
+            </asdoc>"#.into());
+        let manifest = AsDocManifest::parse(&cu).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(manifest.base_path, directory.join("docs").to_string_lossy());
+        assert_eq!(manifest.include_files.iter().map(|f| f.path.as_str()).collect::<Vec<_>>(), ["image.png"]);
+
+        let home = manifest.home.as_ref().unwrap();
+        assert_eq!((home.title.as_str(), home.source.as_str(), home.page.as_str()), ("My Package's Home", "home.md", "index.html"));
+        assert_eq!(home.compilation_unit.as_ref().unwrap().text(), "# Home");
+
+        assert_eq!(manifest.sections.len(), 1);
+        let foo = &manifest.sections[0];
+        assert_eq!((foo.source.as_str(), foo.page.as_str()), ("foo.md", "foo.html"));
+        let children: Vec<(&str, &str, bool)> = foo.children.iter().map(|c| (c.source.as_str(), c.page.as_str(), c.compilation_unit.is_some())).collect();
+        assert_eq!(children, [("foo/qux.md", "foo/qux.html", true), ("foo/missing.md", "foo/missing.html", false)]);
+        assert_eq!(manifest.all_sections().len(), 4);
+
+        let messages: Vec<String> = cu.nested_diagnostics().iter().map(|d| FxDiagnostic(d).format_message_english()).collect();
+        assert_eq!(messages, [
+            "Could not find the file 'missing.png'.",
+            "The path '../asdoc.xml' is outside of the base path.",
+            "Could not find the file 'foo/missing.md'.",
+            "The path '../../outside.md' is outside of the base path.",
+            "Missing the 'title' element.",
+            "The page of the section 'index.md' conflicts with the home page 'index.html'.",
+        ]);
+
+        assert_eq!(AsDocManifest::normalize_path("./a/../b//c.md").as_deref(), Some("b/c.md"));
+        assert_eq!(AsDocManifest::normalize_path("a/../../b.md"), None);
+    }
+}
@@ -0,0 +1,321 @@
+use std::path::Path;
+use crate::ns::*;
+
+/// Project manifest of a Razen package, usually in a `razen.json` file.
+///
+/// The manifest is a JSON object with the following options, all optional:
+///
+/// ```json
+/// {
+///     "includeSources": ["src"],
+///     "excludeSources": ["src/include/**"],
+///     "sourcePath": ["src"],
+///     "externalLibraries": ["libs/playerglobal.swc"],
+///     "define": { "CONFIG::debugging": true, "CONFIG::name": "app" },
+///     "warnings": { "unused": false },
+///     "targetPlayer": "32.0",
+///     "output": "application"
+/// }
+/// ```
+///
+/// Paths are relative to the directory of the manifest. `includeSources` and
+/// `excludeSources` accept glob patterns, expanded by `SourceResolver`. A `define`
+/// value is a boolean, number or string; a string defines a string constant rather
+/// than an expression, so that `"app"` above defines the string `'app'`. The
+/// language switches `as3`, `inferTypes`, `blockScope`, `asdocMarkdown` and
+/// `inheritThisType` map to the `CompilerOptions` fields of the same name.
+///
+/// Invalid options are reported to the manifest's compilation unit
+/// at their location and otherwise ignored.
+#[derive(Clone)]
+pub struct ProjectManifest {
+    /// The directory of the manifest, to which paths are relative.
+    pub directory: String,
+    pub include_sources: Vec<(String, Location)>,
+    pub exclude_sources: Vec<(String, Location)>,
+    /// Resolved `sourcePath` directories.
+    pub source_path: Vec<String>,
+    /// Resolved paths of external libraries.
+    pub external_libraries: Vec<String>,
+    pub config_constants: Vec<ConfigConstantDefinition>,
+    pub target_player: Option<TargetPlayerVersion>,
+    pub output: ProjectOutputKind,
+    /// Options applying to every compilation unit of the project.
+    pub compiler_options: CompilerOptions,
+}
+
+/// Version of the Flash Player or AIR runtime targetted by a project.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct TargetPlayerVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl TargetPlayerVersion {
+    /// Parses a version in the form `major.minor`, where `.minor` is optional.
+    pub fn parse(version: &str) -> Option<Self> {
+        let (major, minor) = version.split_once('.').unwrap_or((version, "0"));
+        Some(Self {
+            major: major.parse().ok()?,
+            minor: minor.parse().ok()?,
+        })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ProjectOutputKind {
+    /// A SWF application.
+    Application,
+    /// A SWC library.
+    Library,
+}
+
+impl ProjectManifest {
+    pub fn new(directory: &str) -> Self {
+        Self {
+            directory: directory.to_owned(),
+            include_sources: vec![],
+            exclude_sources: vec![],
+            source_path: vec![],
+            external_libraries: vec![],
+            config_constants: vec![],
+            target_player: None,
+            output: ProjectOutputKind::Application,
+            compiler_options: CompilerOptions::default(),
+        }
+    }
+
+    /// Parses a project manifest. Returns `None` if the manifest
+    /// contains syntax errors or is not an object.
+    pub fn parse(compilation_unit: &Rc<CompilationUnit>) -> Option<Self> {
+        let directory = compilation_unit.file_path()
+            .and_then(|p| Path::new(&p).parent().map(|d| d.to_string_lossy().into_owned()))
+            .unwrap_or(".".into());
+        let mut manifest = Self::new(&directory);
+
+        let exp = ParserFacade(compilation_unit, ParserOptions::default()).parse_expression();
+        if compilation_unit.invalidated() {
+            return None;
+        }
+        let Expression::ObjectInitializer(object) = exp.as_ref() else {
+            FxDiagnostic::add_verify_error(&exp.location(), FxDiagnosticKind::ProjectManifestMustBeAnObject, diagarg![]);
+            return None;
+        };
+
+        let mut found: Vec<String> = vec![];
+        for (key, value) in Self::fields(object) {
+            if found.contains(&key.0) {
+                FxDiagnostic::add_verify_error(&key.1, FxDiagnosticKind::DuplicateProjectOption, diagarg![key.0.clone()]);
+                continue;
+            }
+            found.push(key.0.clone());
+            manifest.apply_option(&key, &value);
+        }
+
+        Some(manifest)
+    }
+
+    fn apply_option(&mut self, key: &(String, Location), value: &Rc<Expression>) {
+        let name = key.0.as_str();
+        match name {
+            "includeSources" => {
+                if let Some(list) = Self::string_list(name, value) {
+                    self.include_sources = list;
+                }
+            },
+            "excludeSources" => {
+                if let Some(list) = Self::string_list(name, value) {
+                    self.exclude_sources = list;
+                }
+            },
+            "sourcePath" => {
+                if let Some(list) = Self::string_list(name, value) {
+                    self.source_path = list.iter().map(|(p, _)| self.resolve_path(p)).collect();
+                    self.compiler_options.source_path = self.source_path.clone();
+                }
+            },
+            "externalLibraries" => {
+                if let Some(list) = Self::string_list(name, value) {
+                    self.external_libraries = list.iter().map(|(p, _)| self.resolve_path(p)).collect();
+                }
+            },
+            "define" => {
+                let Expression::ObjectInitializer(object) = value.as_ref() else {
+                    FxDiagnostic::add_verify_error(&value.location(), FxDiagnosticKind::InvalidProjectOptionValue, diagarg![name.to_owned(), "an object".to_owned()]);
+                    return;
+                };
+                for ((constant_name, constant_location), constant_exp) in Self::fields(object) {
+                    let constant_value = match constant_exp.as_ref() {
+                        Expression::StringLiteral(literal) => Self::quote(&literal.value),
+                        Expression::BooleanLiteral(literal) => literal.value.to_string(),
+                        Expression::NumericLiteral(literal) => literal.value.clone(),
+                        // A negative number is a unary minus applied to a numeric literal.
+                        Expression::Unary(unary) if unary.operator == Operator::Negative && matches!(unary.expression.as_ref(), Expression::NumericLiteral(_)) => {
+                            format!("-{}", unary.expression.location().text())
+                        },
+                        _ => {
+                            FxDiagnostic::add_verify_error(&constant_exp.location(), FxDiagnosticKind::InvalidProjectOptionValue, diagarg![name.to_owned(), "a string, boolean or number".to_owned()]);
+                            continue;
+                        },
+                    };
+                    match ConfigConstantDefinition::parse_command_line(&format!("{constant_name}={constant_value}")) {
+                        Some(mut definition) => {
+                            definition.location = Some(constant_location.combine_with(constant_exp.location()));
+                            self.config_constants.push(definition);
+                        },
+                        None => {
+                            FxDiagnostic::add_verify_error(&constant_location, FxDiagnosticKind::MalformedConfigConstantDefinition, diagarg![]);
+                        },
+                    }
+                }
+            },
+            "warnings" => {
+                let Expression::ObjectInitializer(object) = value.as_ref() else {
+                    FxDiagnostic::add_verify_error(&value.location(), FxDiagnosticKind::InvalidProjectOptionValue, diagarg![name.to_owned(), "an object".to_owned()]);
+                    return;
+                };
+                for ((warning_name, warning_location), warning_value) in Self::fields(object) {
+                    match warning_name.as_str() {
+                        "unused" => {
+                            if let Some(v) = Self::boolean(&warning_name, &warning_value) {
+                                self.compiler_options.warnings.unused = v;
+                            }
+                        },
+                        _ => {
+                            FxDiagnostic::add_verify_error(&warning_location, FxDiagnosticKind::UnknownProjectOption, diagarg![format!("warnings.{warning_name}")]);
+                        },
+                    }
+                }
+            },
+            "targetPlayer" => {
+                if let Some(version) = Self::string(name, value) {
+                    let Some(version) = TargetPlayerVersion::parse(&version) else {
+                        FxDiagnostic::add_verify_error(&value.location(), FxDiagnosticKind::InvalidProjectOptionValue, diagarg![name.to_owned(), "a version in the form 'major.minor'".to_owned()]);
+                        return;
+                    };
+                    self.target_player = Some(version);
+                }
+            },
+            "output" => {
+                match Self::string(name, value).as_deref() {
+                    Some("application") => self.output = ProjectOutputKind::Application,
+                    Some("library") => self.output = ProjectOutputKind::Library,
+                    Some(_) => {
+                        FxDiagnostic::add_verify_error(&value.location(), FxDiagnosticKind::InvalidProjectOptionValue, diagarg![name.to_owned(), "'application' or 'library'".to_owned()]);
+                    },
+                    None => {},
+                }
+            },
+            "as3" | "inferTypes" | "blockScope" | "asdocMarkdown" | "inheritThisType" => {
+                let Some(v) = Self::boolean(name, value) else {
+                    return;
+                };
+                let options = &mut self.compiler_options;
+                match name {
+                    "as3" => options.as3 = v,
+                    "inferTypes" => options.infer_types = v,
+                    "blockScope" => options.block_scope = v,
+                    "asdocMarkdown" => options.asdoc_markdown = v,
+                    _ => options.inherit_this_type = v,
+                }
+            },
+            _ => {
+                FxDiagnostic::add_verify_error(&key.1, FxDiagnosticKind::UnknownProjectOption, diagarg![name.to_owned()]);
+            },
+        }
+    }
+
+    /// Resolves a path relative to the directory of the manifest.
+    pub fn resolve_path(&self, path: &str) -> String {
+        Path::new(&self.directory).join(path).to_string_lossy().into_owned()
+    }
+
+    /// Returns the name and value of the fields of an object,
+    /// reporting fields that are not in the form `"name": value`.
+    fn fields(object: &ObjectInitializer) -> Vec<((String, Location), Rc<Expression>)> {
+        let mut r = vec![];
+        for field in &object.fields {
+            let InitializerField::Field { name: (name, name_location), value: Some(value), .. } = field.as_ref() else {
+                FxDiagnostic::add_verify_error(&field.location(), FxDiagnosticKind::MalformedProjectManifestField, diagarg![]);
+                continue;
+            };
+            let name = match name {
+                FieldName::StringLiteral(literal) => {
+                    let Expression::StringLiteral(literal) = literal.as_ref() else {
+                        continue;
+                    };
+                    literal.value.clone()
+                },
+                FieldName::Identifier(id) => {
+                    let Some((name, _)) = id.to_identifier_name_or_asterisk() else {
+                        continue;
+                    };
+                    name
+                },
+                _ => {
+                    FxDiagnostic::add_verify_error(name_location, FxDiagnosticKind::MalformedProjectManifestField, diagarg![]);
+                    continue;
+                },
+            };
+            r.push(((name, name_location.clone()), value.clone()));
+        }
+        r
+    }
+
+    /// Quotes a string as an ActionScript string literal.
+    fn quote(string: &str) -> String {
+        let mut r = String::from("\"");
+        for ch in string.chars() {
+            match ch {
+                '"' => r.push_str("\\\""),
+                '\\' => r.push_str("\\\\"),
+                '\n' => r.push_str("\\n"),
+                '\r' => r.push_str("\\r"),
+                '\t' => r.push_str("\\t"),
+                ch if ch.is_control() => r.push_str(&format!("\\u{:04x}", ch as u32)),
+                ch => r.push(ch),
+            }
+        }
+        r.push('"');
+        r
+    }
+
+    fn string(name: &str, value: &Rc<Expression>) -> Option<String> {
+        let Expression::StringLiteral(literal) = value.as_ref() else {
+            FxDiagnostic::add_verify_error(&value.location(), FxDiagnosticKind::InvalidProjectOptionValue, diagarg![name.to_owned(), "a string".to_owned()]);
+            return None;
+        };
+        Some(literal.value.clone())
+    }
+
+    fn boolean(name: &str, value: &Rc<Expression>) -> Option<bool> {
+        let Expression::BooleanLiteral(literal) = value.as_ref() else {
+            FxDiagnostic::add_verify_error(&value.location(), FxDiagnosticKind::InvalidProjectOptionValue, diagarg![name.to_owned(), "a boolean".to_owned()]);
+            return None;
+        };
+        Some(literal.value)
+    }
+
+    fn string_list(name: &str, value: &Rc<Expression>) -> Option<Vec<(String, Location)>> {
+        let Expression::ArrayLiteral(array) = value.as_ref() else {
+            FxDiagnostic::add_verify_error(&value.location(), FxDiagnosticKind::InvalidProjectOptionValue, diagarg![name.to_owned(), "an array of strings".to_owned()]);
+            return None;
+        };
+        let mut r = vec![];
+        for element in &array.elements {
+            match element {
+                Element::Expression(exp) => {
+                    if let Expression::StringLiteral(literal) = exp.as_ref() {
+                        r.push((literal.value.clone(), literal.location.clone()));
+                    } else {
+                        FxDiagnostic::add_verify_error(&exp.location(), FxDiagnosticKind::InvalidProjectOptionValue, diagarg![name.to_owned(), "a string".to_owned()]);
+                    }
+                },
+                _ => {
+                    FxDiagnostic::add_verify_error(&array.location, FxDiagnosticKind::InvalidProjectOptionValue, diagarg![name.to_owned(), "an array of strings".to_owned()]);
+                },
+            }
+        }
+        Some(r)
+    }
+}
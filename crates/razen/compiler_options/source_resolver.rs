@@ -0,0 +1,219 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::ns::*;
+
+/// Resolution of the sources of a project manifest.
+///
+/// `includeSources` and `excludeSources` are paths relative to the manifest's
+/// directory, which may contain glob patterns in their components:
+///
+/// * `*` matches any sequence of characters within a component.
+/// * `?` matches a single character within a component.
+/// * `**` matches zero or more directories.
+///
+/// A matched directory includes or excludes every source inside it recursively.
+/// Only files with the `.as` and `.mxml` extensions are sources.
+pub struct SourceResolver<'a>(pub &'a ProjectManifest);
+
+impl<'a> SourceResolver<'a> {
+    pub const SOURCE_EXTENSIONS: [&'static str; 2] = ["as", "mxml"];
+
+    /// Returns the included sources that are not excluded, sorted
+    /// and without duplicates. Includes that match no file are reported.
+    pub fn resolve(&self) -> Vec<String> {
+        self.resolve_with_locations().into_iter().map(|(p, _)| p).collect()
+    }
+
+    /// Resolves the sources and creates their compilation units,
+    /// assigning them the compiler options of the project.
+    /// Sources that can not be read are reported at their include.
+    pub fn compilation_units(&self) -> Vec<Rc<CompilationUnit>> {
+        let compiler_options: Rc<CompilerOptions> = Rc::new(self.0.compiler_options.clone());
+        let mut r = vec![];
+        for (path, location) in self.resolve_with_locations() {
+            let Ok(text) = fs::read_to_string(&path) else {
+                FxDiagnostic::add_verify_error(&location, FxDiagnosticKind::CouldNotReadSource, diagarg![path]);
+                continue;
+            };
+            let cu = CompilationUnit::new(Some(path), text);
+            cu.set_compiler_options(Some(compiler_options.clone()));
+            r.push(cu);
+        }
+        r
+    }
+
+    /// Resolves the sources, along with the location of the include matching them.
+    fn resolve_with_locations(&self) -> Vec<(String, Location)> {
+        let mut excluded = vec![];
+        for (pattern, _) in &self.0.exclude_sources {
+            excluded.extend(self.expand(pattern));
+        }
+        let mut r: Vec<(PathBuf, Location)> = vec![];
+        for (pattern, location) in &self.0.include_sources {
+            let files = self.expand(pattern);
+            if files.is_empty() {
+                FxDiagnostic::add_verify_error(location, FxDiagnosticKind::SourceNotFound, diagarg![pattern.clone()]);
+            }
+            for file in files {
+                if !excluded.contains(&file) && !r.iter().any(|(f, _)| *f == file) {
+                    r.push((file, location.clone()));
+                }
+            }
+        }
+        r.sort_by(|(a, _), (b, _)| a.cmp(b));
+        r.into_iter().map(|(p, l)| (p.to_string_lossy().into_owned(), l)).collect()
+    }
+
+    /// Expands a pattern into the source files it matches.
+    fn expand(&self, pattern: &str) -> Vec<PathBuf> {
+        // Split the pattern at its first glob component.
+        let pattern = pattern.replace('\\', "/");
+        let components: Vec<&str> = pattern.split('/').collect();
+        let i = components.iter().position(|c| Self::is_glob(c)).unwrap_or(components.len());
+        let base = Path::new(&self.0.directory).join(components[..i].join("/"));
+        let components: Vec<&str> = components[i..].iter().copied().filter(|c| !c.is_empty() && *c != ".").collect();
+        let mut matched = vec![];
+        Self::expand_components(&base, &components, &mut matched);
+        let mut r = vec![];
+        for path in matched {
+            Self::collect_sources(&path, &mut r);
+        }
+        r
+    }
+
+    fn expand_components(path: &Path, components: &[&str], matched: &mut Vec<PathBuf>) {
+        let Some((first, rest)) = components.split_first() else {
+            if path.exists() {
+                matched.push(path.to_owned());
+            }
+            return;
+        };
+        if *first == "**" {
+            Self::expand_components(path, rest, matched);
+            for entry in Self::entries(path) {
+                if entry.is_dir() {
+                    Self::expand_components(&entry, components, matched);
+                }
+            }
+            return;
+        }
+        for entry in Self::entries(path) {
+            let name = entry.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+            if Self::matches(first, &name) {
+                Self::expand_components(&entry, rest, matched);
+            }
+        }
+    }
+
+    fn collect_sources(path: &Path, r: &mut Vec<PathBuf>) {
+        if path.is_dir() {
+            for entry in Self::entries(path) {
+                Self::collect_sources(&entry, r);
+            }
+        } else if path.extension().is_some_and(|e| Self::SOURCE_EXTENSIONS.contains(&e.to_string_lossy().as_ref())) {
+            r.push(path.to_owned());
+        }
+    }
+
+    fn entries(path: &Path) -> Vec<PathBuf> {
+        let Ok(entries) = fs::read_dir(path) else {
+            return vec![];
+        };
+        let mut r: Vec<PathBuf> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+        r.sort();
+        r
+    }
+
+    fn is_glob(component: &str) -> bool {
+        component.contains(['*', '?'])
+    }
+
+    /// Matches a file name against a pattern component.
+    fn matches(pattern: &str, name: &str) -> bool {
+        let pattern: Vec<char> = pattern.chars().collect();
+        let name: Vec<char> = name.chars().collect();
+        let (mut p, mut n) = (0, 0);
+        let mut backtrack: Option<(usize, usize)> = None;
+        while n < name.len() {
+            if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+                p += 1;
+                n += 1;
+            } else if p < pattern.len() && pattern[p] == '*' {
+                backtrack = Some((p, n));
+                p += 1;
+            } else if let Some((bp, bn)) = backtrack {
+                p = bp + 1;
+                n = bn + 1;
+                backtrack = Some((bp, bn + 1));
+            } else {
+                return false;
+            }
+        }
+        pattern[p..].iter().all(|ch| *ch == '*')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use crate::ns::*;
+
+    #[test]
+    fn test_source_resolver() {
+        let directory = std::env::temp_dir().join(format!("razen_test_source_resolver_{}", std::process::id()));
+        for path in ["src/Main.as", "src/com/example/Button.mxml", "src/com/example/Helper.as", "src/com/example/readme.txt", "src/include/inc.as", "test/MainTest.as"] {
+            let path = directory.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+
+        let manifest_path = directory.join("razen.json").to_string_lossy().into_owned();
+        let cu = CompilationUnit::new(Some(manifest_path), r#"{
+            "includeSources": ["src", "test/*Test.as", "missing"],
+            "excludeSources": ["src/include", "**/Helper.*"],
+            "sourcePath": ["src"],
+            "define": { "CONFIG::debugging": true, "CONFIG::offset": -1, "CONFIG::greeting": "say \"hello\"", "debugging": "false" },
+            "warnings": { "unused": false, "shadowing": true },
+            "targetPlayer": "32.0",
+            "output": "swf",
+            "blockScope": false,
+            "outputDirectory": "bin"
+        }"#.into());
+        let manifest = ProjectManifest::parse(&cu).unwrap();
+        let messages: Vec<String> = cu.diagnostics().iter().map(|d| FxDiagnostic(d).format_message_english()).collect();
+        assert_eq!(messages, [
+            "Malformed configuration constant definition; expected 'NS::X = expression'.",
+            "Unknown project option: 'warnings.shadowing'.",
+            "Invalid value for the project option 'output'; expected 'application' or 'library'.",
+            "Unknown project option: 'outputDirectory'.",
+        ]);
+        assert_eq!(manifest.config_constants.len(), 3);
+        assert_eq!(manifest.config_constants[0].value, "true");
+        assert_eq!(manifest.config_constants[1].value, "-1");
+
+        // A string value defines a string constant rather than an expression.
+        assert_eq!(manifest.config_constants[2].value, r#""say \"hello\"""#);
+        let value = CompilationUnit::new(None, manifest.config_constants[2].value.clone());
+        let exp = ParserFacade(&value, ParserOptions::default()).parse_expression();
+        assert!(matches!(exp.as_ref(), Expression::StringLiteral(literal) if literal.value == "say \"hello\""));
+        assert!(manifest.config_constants[0].location.is_some());
+        assert!(manifest.target_player == Some(TargetPlayerVersion { major: 32, minor: 0 }));
+        assert!(manifest.output == ProjectOutputKind::Application);
+        assert!(!manifest.compiler_options.block_scope);
+        assert!(!manifest.compiler_options.warnings.unused);
+        assert_eq!(manifest.compiler_options.source_path, [directory.join("src").to_string_lossy().into_owned()]);
+
+        let units = SourceResolver(&manifest).compilation_units();
+        let files: Vec<String> = units.iter().map(|cu| cu.file_path().unwrap()[directory.to_string_lossy().len() + 1..].replace('\\', "/")).collect();
+        assert_eq!(files, ["src/Main.as", "src/com/example/Button.mxml", "test/MainTest.as"]);
+        assert!(!CompilerOptions::of(&units[0]).block_scope);
+        let messages: Vec<String> = cu.diagnostics().iter().skip(4).map(|d| FxDiagnostic(d).format_message_english()).collect();
+        assert_eq!(messages, ["Could not find the source 'missing'."]);
+
+        let cu = CompilationUnit::new(None, "[\"src\"]".into());
+        assert!(ProjectManifest::parse(&cu).is_none());
+        assert!(FxDiagnostic(&cu.diagnostics()[0]).fx_kind() == Some(FxDiagnosticKind::ProjectManifestMustBeAnObject));
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -24,6 +24,26 @@ impl<'a> FxDiagnostic<'a> {
         d
     }
 
+    /// Adds a verify error to the compilation unit of `location`, unless
+    /// an error was already reported at the same offset.
+    pub fn add_verify_error(location: &Location, kind: FxDiagnosticKind, arguments: Vec<Rc<dyn DiagnosticArgument>>) {
+        let cu = location.compilation_unit();
+        if cu.prevent_equal_offset_error(location) {
+            return;
+        }
+        cu.add_diagnostic(Self::new_verify_error(location, kind, arguments));
+    }
+
+    /// Adds a warning to the compilation unit of `location`, unless
+    /// a warning was already reported at the same offset.
+    pub fn add_warning(location: &Location, kind: FxDiagnosticKind, arguments: Vec<Rc<dyn DiagnosticArgument>>) {
+        let cu = location.compilation_unit();
+        if cu.prevent_equal_offset_warning(location) {
+            return;
+        }
+        cu.add_diagnostic(Self::new_warning(location, kind, arguments));
+    }
+
     pub fn fx_kind(&self) -> Option<FxDiagnosticKind> {
         if let Some(k) = self.custom_kind() {
             if let Ok(k) = Rc::downcast(k) {
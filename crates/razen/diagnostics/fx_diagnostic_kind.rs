@@ -63,6 +63,62 @@ pub enum FxDiagnosticKind {
     CannotResolveConfigConstant = 2107,
     ConcatenatingSelfReferentialPackage = 2108,
     CallOnDateType = 2109,
+    EmbedMustBeStaticClassVariable = 2110,
+    EmbedClassMustExtend = 2111,
+    EmbedMustSpecifySource = 2112,
+    EmbedSourceNotFound = 2113,
+    EmbedUnknownMimeType = 2114,
+    EmbedUnsupportedMimeType = 2115,
+    EmbedInapplicableAttribute = 2116,
+    EmbedMustSpecifyAttribute = 2117,
+    EmbedUnsupportedAttribute = 2118,
+    EmbedInvalidSource = 2119,
+    EmbedFontMustBeCff = 2120,
+    MalformedEventMetadata = 2121,
+    UnknownEventType = 2122,
+    EventTypeMustExtendEvent = 2123,
+    DuplicateEventName = 2124,
+    UnknownOptionForClassDidYouMean = 2125,
+    DuplicateTypeParameter = 2126,
+    CouldNotInferTypeArgument = 2127,
+    CouldNotInferTypeArgumentFromCandidates = 2128,
+    TypeArgumentDoesNotSatisfyBound = 2129,
+    InvalidTypeParameterBound = 2130,
+    TupleIndexOutOfRange = 2131,
+    MalformedConfigConstantDefinition = 2132,
+    ProjectManifestMustBeAnObject = 2133,
+    UnknownProjectOption = 2134,
+    InvalidProjectOptionValue = 2135,
+    DuplicateProjectOption = 2136,
+    SourceNotFound = 2137,
+    CouldNotReadSource = 2138,
+    MalformedProjectManifestField = 2139,
+    UnresolvedMxmlComponent = 2140,
+    UnknownMxmlAttribute = 2141,
+    MxmlTextNotAssignable = 2142,
+    MxmlTypeNotRepresentableInText = 2143,
+    UnknownMxmlLanguageTag = 2144,
+    MxmlLanguageTagMustBeChildOfRoot = 2145,
+    TwoWayBindingSourceNotWritable = 2146,
+    BindingSourceNotBindable = 2147,
+    UnknownMxmlState = 2148,
+    DuplicateMxmlState = 2149,
+    ConflictingMxmlStateInclusion = 2150,
+    DuplicateMxmlId = 2151,
+    UnrecognizedMxmlMetadata = 2152,
+    UndefinedCssNamespacePrefix = 2153,
+    UnresolvedCssTypeSelector = 2154,
+    UnknownStyle = 2155,
+    CssValueNotAssignableToStyle = 2156,
+    UnresolvedCssClassReference = 2157,
+    MalformedCssMetadata = 2158,
+    UnknownAsDocManifestElement = 2159,
+    MissingAsDocManifestElement = 2160,
+    AsDocFileNotFound = 2161,
+    UnresolvedAsDocLink = 2162,
+    EmbedTrueTypeFontUnsupported = 2163,
+    AsDocPathOutsideBasePath = 2164,
+    AsDocSectionConflictsWithHomePage = 2165,
 }
 
 impl FxDiagnosticKind {
@@ -67,6 +67,62 @@ lazy_static! {
         FxDiagnosticKind::CannotResolveConfigConstant.id() => "Can not resolve configuration constant: '{1}'".into(),
         FxDiagnosticKind::ConcatenatingSelfReferentialPackage.id() => "Concatenating a self referential package.".into(),
         FxDiagnosticKind::CallOnDateType.id() => "'Date(...)' is not a cast. It ignores its arguments and returns a String value equal to 'new Date().toString()'. To cast a value to type Date use 'x as Date' instead of 'Date(x)'.".into(),
+        FxDiagnosticKind::EmbedMustBeStaticClassVariable.id() => "Embed meta-data must be applied to a static variable of type Class with a single binding.".into(),
+        FxDiagnosticKind::EmbedClassMustExtend.id() => "Class with embedded asset must extend {1}.".into(),
+        FxDiagnosticKind::EmbedMustSpecifySource.id() => "Embed meta-data must specify a source.".into(),
+        FxDiagnosticKind::EmbedSourceNotFound.id() => "Unable to find embedded source {1}.".into(),
+        FxDiagnosticKind::EmbedUnknownMimeType.id() => "Unable to determine the MIME type of {1}.".into(),
+        FxDiagnosticKind::EmbedUnsupportedMimeType.id() => "Unsupported MIME type {1}.".into(),
+        FxDiagnosticKind::EmbedInapplicableAttribute.id() => "Embed attribute {1} is not applicable to MIME type {2}.".into(),
+        FxDiagnosticKind::EmbedMustSpecifyAttribute.id() => "Embed meta-data must specify {1} for MIME type {2}.".into(),
+        FxDiagnosticKind::EmbedUnsupportedAttribute.id() => "Embed attribute {1} is not supported.".into(),
+        FxDiagnosticKind::EmbedInvalidSource.id() => "Embedded source {1} is not a valid {2} file.".into(),
+        FxDiagnosticKind::EmbedFontMustBeCff.id() => "Embedded font {1} must be an OpenType font with CFF outlines.".into(),
+        FxDiagnosticKind::MalformedEventMetadata.id() => "Malformed Event meta-data.".into(),
+        FxDiagnosticKind::UnknownEventType.id() => "Unknown event type {1}.".into(),
+        FxDiagnosticKind::EventTypeMustExtendEvent.id() => "Event type {1} must extend flash.events.Event.".into(),
+        FxDiagnosticKind::DuplicateEventName.id() => "Duplicate event {1}.".into(),
+        FxDiagnosticKind::UnknownOptionForClassDidYouMean.id() => "Unknown option {1} for {2}. Did you mean {3}?".into(),
+        FxDiagnosticKind::DuplicateTypeParameter.id() => "Duplicate type parameter {1}.".into(),
+        FxDiagnosticKind::CouldNotInferTypeArgument.id() => "Could not infer type argument {1}.".into(),
+        FxDiagnosticKind::CouldNotInferTypeArgumentFromCandidates.id() => "Could not infer type argument {1} from the candidate types {2}.".into(),
+        FxDiagnosticKind::TypeArgumentDoesNotSatisfyBound.id() => "Type argument {1} does not satisfy the bound {2} of type parameter {3}.".into(),
+        FxDiagnosticKind::InvalidTypeParameterBound.id() => "Invalid bound for type parameter {1}.".into(),
+        FxDiagnosticKind::TupleIndexOutOfRange.id() => "Index {1} is out of range for the tuple type {2}.".into(),
+        FxDiagnosticKind::MalformedConfigConstantDefinition.id() => "Malformed configuration constant definition; expected 'NS::X = expression'.".into(),
+        FxDiagnosticKind::ProjectManifestMustBeAnObject.id() => "The project manifest must be an object.".into(),
+        FxDiagnosticKind::UnknownProjectOption.id() => "Unknown project option: '{1}'.".into(),
+        FxDiagnosticKind::InvalidProjectOptionValue.id() => "Invalid value for the project option '{1}'; expected {2}.".into(),
+        FxDiagnosticKind::DuplicateProjectOption.id() => "Duplicate project option: '{1}'.".into(),
+        FxDiagnosticKind::SourceNotFound.id() => "Could not find the source '{1}'.".into(),
+        FxDiagnosticKind::CouldNotReadSource.id() => "Could not read the source '{1}'.".into(),
+        FxDiagnosticKind::MalformedProjectManifestField.id() => "Expected a field in the form '\"name\": value'.".into(),
+        FxDiagnosticKind::UnresolvedMxmlComponent.id() => "Could not resolve the MXML component '{1}'.".into(),
+        FxDiagnosticKind::UnknownMxmlAttribute.id() => "'{1}' is not a property, style or event of {2}.".into(),
+        FxDiagnosticKind::MxmlTextNotAssignable.id() => "The text '{1}' is not a valid {2} value.".into(),
+        FxDiagnosticKind::MxmlTypeNotRepresentableInText.id() => "Values of type {1} cannot be represented in text.".into(),
+        FxDiagnosticKind::UnknownMxmlLanguageTag.id() => "Unknown MXML language tag: '{1}'.".into(),
+        FxDiagnosticKind::MxmlLanguageTagMustBeChildOfRoot.id() => "The '{1}' tag must be a child of the document's root tag.".into(),
+        FxDiagnosticKind::TwoWayBindingSourceNotWritable.id() => "The source of a two-way binding must be writable.".into(),
+        FxDiagnosticKind::BindingSourceNotBindable.id() => "Data binding will not be able to detect assignments to '{1}'.".into(),
+        FxDiagnosticKind::UnknownMxmlState.id() => "Unknown state: '{1}'.".into(),
+        FxDiagnosticKind::DuplicateMxmlState.id() => "Duplicate state: '{1}'.".into(),
+        FxDiagnosticKind::ConflictingMxmlStateInclusion.id() => "'includeIn' and 'excludeFrom' cannot be used on the same tag.".into(),
+        FxDiagnosticKind::DuplicateMxmlId.id() => "Duplicate identifier: '{1}'.".into(),
+        FxDiagnosticKind::UnrecognizedMxmlMetadata.id() => "Unrecognized meta-data syntax.".into(),
+        FxDiagnosticKind::UndefinedCssNamespacePrefix.id() => "Undefined namespace prefix: '{1}'.".into(),
+        FxDiagnosticKind::UnresolvedCssTypeSelector.id() => "Could not resolve the type selector '{1}'.".into(),
+        FxDiagnosticKind::UnknownStyle.id() => "'{1}' is not a style of {2}.".into(),
+        FxDiagnosticKind::CssValueNotAssignableToStyle.id() => "The value is not assignable to the style '{1}' of type {2}.".into(),
+        FxDiagnosticKind::UnresolvedCssClassReference.id() => "Could not resolve the class '{1}'.".into(),
+        FxDiagnosticKind::MalformedCssMetadata.id() => "Malformed CSS meta-data.".into(),
+        FxDiagnosticKind::UnknownAsDocManifestElement.id() => "Unknown element: '{1}'.".into(),
+        FxDiagnosticKind::MissingAsDocManifestElement.id() => "Missing the '{1}' element.".into(),
+        FxDiagnosticKind::AsDocFileNotFound.id() => "Could not find the file '{1}'.".into(),
+        FxDiagnosticKind::UnresolvedAsDocLink.id() => "Could not resolve the link '{1}'.".into(),
+        FxDiagnosticKind::EmbedTrueTypeFontUnsupported.id() => "TrueType font {1} is not supported; embed an OpenType font with CFF outlines instead.".into(),
+        FxDiagnosticKind::AsDocPathOutsideBasePath.id() => "The path '{1}' is outside of the base path.".into(),
+        FxDiagnosticKind::AsDocSectionConflictsWithHomePage.id() => "The page of the section '{1}' conflicts with the home page '{2}'.".into(),
         // FxDiagnosticKind::K.id() => ".".into(),
     };
 }
\ No newline at end of file
@@ -44,4 +44,24 @@ impl Debug for MethodOverridingError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "MethodOverridingError()")
     }
-}
\ No newline at end of file
+}
+
+/// Error used to indicate a malformed or unsupported ZIP archive.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ZipArchiveError();
+
+/// Error used to indicate an unreadable SWC file, either because its
+/// archive is malformed or because its catalog or library is missing or invalid.
+#[derive(Clone, PartialEq, Debug)]
+pub enum SwcReadError {
+    InvalidArchive,
+    MissingCatalog,
+    MissingLibrary,
+    InvalidCatalog,
+}
+
+impl From<ZipArchiveError> for SwcReadError {
+    fn from(_: ZipArchiveError) -> Self {
+        Self::InvalidArchive
+    }
+}
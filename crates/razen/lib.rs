@@ -1,5 +1,6 @@
 #![feature(decl_macro)]
 
+pub mod codegen;
 pub mod compiler_options;
 pub mod diagnostics;
 pub mod errors;
@@ -7,13 +8,19 @@ pub mod semantics;
 pub mod util;
 pub mod verifier;
 
+#[cfg(test)]
+mod test_helpers;
+
 /// Unified compiler module.
 pub mod ns {
     pub use as3_parser::ns::*;
+    pub use super::codegen::*;
     pub use super::compiler_options::*;
     pub use super::diagnostics::*;
     pub use super::errors::*;
     pub use super::semantics::*;
     pub use super::util::*;
     pub use super::verifier::*;
+    #[cfg(test)]
+    pub use super::test_helpers::*;
 }
\ No newline at end of file
@@ -1,21 +1,60 @@
+mod asdoc_inheritance;
+pub use asdoc_inheritance::*;
+
+mod bindable;
+pub use bindable::*;
+
+mod config_constants;
+pub use config_constants::*;
+
+mod constant_folding;
+pub use constant_folding::*;
+
+mod embed;
+pub use embed::*;
+
+mod flex_events;
+pub use flex_events::*;
+
 mod interface_implementations;
 pub use interface_implementations::*;
 
 mod method_overriding;
 pub use method_overriding::*;
 
+mod mxml_binding;
+pub use mxml_binding::*;
+
+mod mxml_code;
+pub use mxml_code::*;
+
+mod mxml_components;
+pub use mxml_components::*;
+
+mod mxml_state;
+pub use mxml_state::*;
+
 mod name_map;
 pub use name_map::*;
 
 mod number_variant;
 pub use number_variant::*;
 
+mod options_class;
+pub use options_class::*;
+
 mod property_lookup;
 pub use property_lookup::*;
 
 mod semantic_host;
 pub use semantic_host::*;
 
+mod style_inheritance;
+pub use style_inheritance::*;
+
+mod style_sheets;
+pub use style_sheets::*;
+
 #[allow(unused_variables)]
 mod thingy;
 pub use thingy::*;
@@ -26,6 +65,12 @@ pub use thingy_factory::*;
 mod type_conversion;
 pub use type_conversion::*;
 
+mod type_inference;
+pub use type_inference::*;
+
+mod type_parameters;
+pub use type_parameters::*;
+
 mod type_substitution;
 pub use type_substitution::*;
 
@@ -0,0 +1,335 @@
+use crate::ns::*;
+
+/// Resolution of the `@inheritDoc` and `@copy` tags of ASDoc comments.
+///
+/// `@inheritDoc` inherits the comment of the method that a method overrides, or,
+/// failing that, of the same-named public instance member of a superclass or of an
+/// implemented or extended interface. `@copy` copies the comment of a referenced
+/// definition, as in `@copy com.example.Foo#bar`, `@copy Foo` or `@copy #bar`.
+///
+/// The main body of the resolved comment is that of the comment followed by that of
+/// its source. The `@param`, `@return`, `@throws`, `@default`, `@example` and `@see`
+/// tags of the source are added unless the comment has its own, `@param` tags being
+/// matched by name.
+///
+/// Anchor links, as in `[bar](#bar)`, and `@see #bar` references are relative to the
+/// page of the type or package of the definition they are written for. When the
+/// comment of a definition is shown on the page of another type or package, as for
+/// a source of `@inheritDoc` or `@copy`, or for a `MethodSlotAfterSubstitution`, whose
+/// comment is that of its origin, they are qualified with the name of the
+/// definition's type or package.
+pub struct AsDocInheritance<'a>(pub &'a SemanticHost);
+
+impl<'a> AsDocInheritance<'a> {
+    /// Returns the comment of a definition with its `@inheritDoc` and `@copy` tags
+    /// resolved, as shown on the page of `destination`, the type or package
+    /// documenting the definition.
+    pub fn resolve(&self, definition: &Thingy, destination: &Thingy) -> Option<Rc<AsDoc>> {
+        self.resolve_visiting(definition, destination, &mut vec![])
+    }
+
+    /// Resolves the comment of a definition without resolving the tags that refer
+    /// to a definition in `visited`, as cyclic `@copy` tags do.
+    fn resolve_visiting(&self, definition: &Thingy, destination: &Thingy, visited: &mut Vec<Thingy>) -> Option<Rc<AsDoc>> {
+        let asdoc = self.relocate(&definition.asdoc()?, definition, destination);
+        visited.push(definition.clone());
+        let mut source = None;
+        for (tag, _) in &asdoc.tags {
+            match tag {
+                AsDocTag::InheritDoc => {
+                    source = source.or_else(|| self.inherited_source(definition));
+                },
+                AsDocTag::Copy(reference) => {
+                    source = source.or_else(|| self.lookup_reference(reference, definition));
+                },
+                _ => {},
+            }
+        }
+        let Some(inherited) = source.filter(|s| !visited.contains(s)).and_then(|s| self.resolve_visiting(&s, destination, visited)) else {
+            return Some(asdoc);
+        };
+        Some(Rc::new(Self::merge(&asdoc, &inherited)))
+    }
+
+    /// Returns the definition whose comment `@inheritDoc` inherits.
+    fn inherited_source(&self, definition: &Thingy) -> Option<Thingy> {
+        let host = self.0;
+        if definition.is::<MethodSlot>() {
+            if let Some(method) = definition.overrides_method(host) {
+                return Some(method);
+            }
+        }
+        if definition.is_static() {
+            return None;
+        }
+        let owner = definition.parent()?;
+        let name = definition.name().local_name();
+        let mut types: Vec<Thingy> = vec![];
+        if owner.is::<ClassType>() {
+            types.extend(owner.descending_class_hierarchy(host).skip(1).take_while(|c| !c.is::<UnresolvedThingy>()));
+        }
+        if owner.is::<ClassType>() || owner.is::<InterfaceType>() {
+            types.extend(owner.all_ascending_types(host).into_iter().filter(|t| t.is::<InterfaceType>()));
+        }
+        types.iter().find_map(|t| t.prototype(host).get_in_any_public_ns(&name).ok().flatten())
+    }
+
+    /// Looks up the definition referenced by `@copy`.
+    fn lookup_reference(&self, reference: &AsDocReference, context: &Thingy) -> Option<Thingy> {
+        let host = self.0;
+        let base = match &reference.base {
+            Some(base) => self.lookup_definition(&base.location().text(), context)?,
+            None => Self::owner(context)?,
+        };
+        let Some(property) = &reference.instance_property else {
+            return Some(base);
+        };
+        let name = property.location.text();
+        if base.is::<Package>() || base.is::<InterfaceType>() {
+            let names = if base.is::<Package>() { base.properties(host) } else { base.prototype(host) };
+            return names.get_in_any_public_ns(&name).ok().flatten();
+        }
+        [base.properties(host), base.prototype(host)].iter().find_map(|names| names.get_in_any_public_ns(&name).ok().flatten())
+    }
+
+    /// Looks up a package or a package-level definition by its fully qualified
+    /// name, or a definition of the package of `context` or of the top-level
+    /// package by its local name.
+    fn lookup_definition(&self, name: &str, context: &Thingy) -> Option<Thingy> {
+        let host = self.0;
+        let top_level = host.top_level_package();
+        if let Some(package) = Self::subpackage(&top_level, name) {
+            return Some(package);
+        }
+        let mut packages: Vec<Thingy> = vec![];
+        match name.rsplit_once('.') {
+            Some((package_name, _)) => packages.extend(Self::subpackage(&top_level, package_name)),
+            None => {
+                packages.extend(context.descending_definition_hierarchy().find(|d| d.is::<Package>()));
+                packages.push(top_level.clone());
+                // Vector is documented as a top-level class.
+                packages.extend(Self::subpackage(&top_level, "__AS3__.vec"));
+            },
+        }
+        let local_name = name.rsplit_once('.').map(|(_, n)| n).unwrap_or(name);
+        packages.iter().find_map(|p| p.properties(host).get_in_any_public_ns(local_name).ok().flatten())
+    }
+
+    fn subpackage(package: &Thingy, name: &str) -> Option<Thingy> {
+        let mut r = package.clone();
+        for component in name.split('.') {
+            r = r.subpackages().get(&component.to_owned())?;
+        }
+        Some(r)
+    }
+
+    /// Returns the type or package to whose page the anchors
+    /// of the comment of a definition are relative.
+    fn owner(definition: &Thingy) -> Option<Thingy> {
+        if definition.is::<Type>() || definition.is::<Package>() {
+            return Some(definition.clone());
+        }
+        let mut parent = definition.parent()?;
+        if parent.is::<VirtualSlot>() {
+            parent = parent.parent()?;
+        }
+        Some(if parent.is::<TypeAfterSubstitution>() { parent.origin() } else { parent })
+    }
+
+    /// Qualifies the anchor links and `@see #x` references of the comment
+    /// of `source` if its owner is not `destination`.
+    fn relocate(&self, asdoc: &Rc<AsDoc>, source: &Thingy, destination: &Thingy) -> Rc<AsDoc> {
+        let Some(owner) = Self::owner(source) else {
+            return asdoc.clone();
+        };
+        let name = if owner.is::<Package>() {
+            owner.fully_qualified_name_list().join(".")
+        } else {
+            owner.fully_qualified_name()
+        };
+        if &owner == destination || name.is_empty() {
+            return asdoc.clone();
+        }
+        let text = |text: &String| text.replace("](#", &format!("]({name}#"));
+        let tags = asdoc.tags.iter().map(|(tag, location)| {
+            let tag = match tag {
+                AsDocTag::Param { name, description } => AsDocTag::Param { name: name.clone(), description: text(description) },
+                AsDocTag::Return(description) => AsDocTag::Return(text(description)),
+                AsDocTag::Example(example) => AsDocTag::Example(text(example)),
+                AsDocTag::Deprecated { message } => AsDocTag::Deprecated { message: message.as_ref().map(text) },
+                AsDocTag::Throws { class_reference, description } => AsDocTag::Throws {
+                    class_reference: class_reference.clone(),
+                    description: description.as_ref().map(text),
+                },
+                AsDocTag::See { reference, display_text } if reference.base.is_none() => {
+                    let cu = CompilationUnit::new(None, name.clone());
+                    let base = ParserFacade(&cu, ParserOptions::default()).parse_expression();
+                    AsDocTag::See {
+                        reference: Rc::new(AsDocReference { base: Some(base), instance_property: reference.instance_property.clone() }),
+                        display_text: display_text.clone(),
+                    }
+                },
+                tag => tag.clone(),
+            };
+            (tag, location.clone())
+        }).collect();
+        Rc::new(AsDoc {
+            location: asdoc.location.clone(),
+            main_body: asdoc.main_body.as_ref().map(|(body, location)| (text(body), location.clone())),
+            tags,
+        })
+    }
+
+    fn merge(asdoc: &AsDoc, inherited: &AsDoc) -> AsDoc {
+        let main_body = match (&asdoc.main_body, &inherited.main_body) {
+            (Some((body, location)), Some((inherited_body, _))) => Some((format!("{body}\n\n{inherited_body}"), location.clone())),
+            (body, inherited_body) => body.clone().or(inherited_body.clone()),
+        };
+        let mut tags: Vec<(AsDocTag, Location)> = asdoc.tags.iter()
+            .filter(|(tag, _)| !matches!(tag, AsDocTag::InheritDoc | AsDocTag::Copy(_)))
+            .cloned()
+            .collect();
+        for (tag, location) in &inherited.tags {
+            let copied = match tag {
+                AsDocTag::Param { name, .. } => !asdoc.tags.iter().any(|(t, _)| matches!(t, AsDocTag::Param { name: n, .. } if n == name)),
+                AsDocTag::Return(_) | AsDocTag::Throws { .. } | AsDocTag::Default(_) | AsDocTag::Example(_) | AsDocTag::See { .. } => {
+                    !asdoc.tags.iter().any(|(t, _)| std::mem::discriminant(t) == std::mem::discriminant(tag))
+                },
+                _ => false,
+            };
+            if copied {
+                tags.push((tag.clone(), location.clone()));
+            }
+        }
+        AsDoc { location: asdoc.location.clone(), main_body, tags }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    #[test]
+    fn test_asdoc_inheritance() {
+        let host = SemanticHost::new(SemanticHostOptions::default());
+        let package = host.factory().create_package(["com", "example"]);
+        let public_ns = package.public_ns().unwrap();
+        let cu = CompilationUnit::new(None, r#"
+            /**
+             * Draws the shape. See [area](#area).
+             * @param scale The scale.
+             */
+            function draw() {}
+            /** Computes the area. */
+            function area() {}
+            /**
+             * Describes [it](#area).
+             * @param verbose Whether to be verbose.
+             * @return A description.
+             * @see #area
+             */
+            function describe() {}
+            /** @inheritDoc */
+            function circleArea() {}
+            /**
+             * Circle-specific.
+             * @inheritDoc
+             * @param verbose Always ignored.
+             */
+            function circleDescribe() {}
+            /** @inheritDoc */
+            function circleDraw() {}
+            /** @copy Base#area */
+            function radius() {}
+            /**
+             * A.
+             * @copy #b
+             */
+            function a() {}
+            /**
+             * B.
+             * @copy #a
+             */
+            function b() {}
+        "#.into());
+        let program = ParserFacade(&cu, ParserOptions::default()).parse_program();
+        let asdoc = |name: &str| program.directives.iter().find_map(|d| match d.as_ref() {
+            Directive::FunctionDefinition(f) if f.name_identifier().0 == name => f.asdoc.clone(),
+            _ => None,
+        });
+        let void_type = host.void_type();
+        let method = |owner: &Thingy, name: &str, source: &str| {
+            let qname = host.factory().create_qname(&public_ns, name.into());
+            let slot = host.factory().create_method_slot(&qname, &host.factory().create_function_type(vec![], void_type.clone()));
+            slot.set_asdoc(asdoc(source));
+            slot.set_parent(Some(owner.clone()));
+            owner.prototype(&host).set(qname, slot.clone());
+            slot
+        };
+
+        let shape = host.factory().create_interface_type(host.factory().create_qname(&public_ns, "IShape".into()));
+        shape.set_parent(Some(package.clone()));
+        package.properties(&host).set(shape.name(), shape.clone());
+        let base = define_class(&host, &package, "Base", None);
+        let circle = define_class(&host, &package, "Circle", Some(&base));
+        circle.implements(&host).push(shape.clone());
+
+        method(&shape, "draw", "draw");
+        let base_area = method(&base, "area", "area");
+        let base_describe = method(&base, "describe", "describe");
+        let circle_area = method(&circle, "area", "circleArea");
+        circle_area.set_overrides_method(Some(base_area.clone()));
+        let circle_describe = method(&circle, "describe", "circleDescribe");
+        circle_describe.set_overrides_method(Some(base_describe.clone()));
+        let circle_draw = method(&circle, "draw", "circleDraw");
+        let radius = method(&circle, "radius", "radius");
+        let a = method(&circle, "a", "a");
+        let b = method(&circle, "b", "b");
+
+        let inheritance = AsDocInheritance(&host);
+        let body = |asdoc: &Rc<AsDoc>| asdoc.main_body.as_ref().map(|(b, _)| b.clone()).unwrap_or_default();
+
+        // @inheritDoc of an overridden method
+        let resolved = inheritance.resolve(&circle_area, &circle).unwrap();
+        assert_eq!(body(&resolved), "Computes the area.");
+        assert!(resolved.tags.is_empty());
+
+        // Own text and @param tags come first; anchors are qualified.
+        let resolved = inheritance.resolve(&circle_describe, &circle).unwrap();
+        assert_eq!(body(&resolved), "Circle-specific.\n\nDescribes [it](com.example.Base#area).");
+        let params: Vec<(String, String)> = resolved.tags.iter().filter_map(|(tag, _)| match tag {
+            AsDocTag::Param { name, description } => Some((name.clone(), description.clone())),
+            _ => None,
+        }).collect();
+        assert_eq!(params, [("verbose".to_owned(), "Always ignored.".to_owned())]);
+        assert!(resolved.tags.iter().any(|(tag, _)| matches!(tag, AsDocTag::Return(d) if d == "A description.")));
+        let see = resolved.tags.iter().find_map(|(tag, _)| match tag {
+            AsDocTag::See { reference, .. } => Some((reference.base.as_ref().unwrap().location().text(), reference.instance_property.as_ref().unwrap().location.text())),
+            _ => None,
+        });
+        assert_eq!(see, Some(("com.example.Base".to_owned(), "area".to_owned())));
+
+        // @inheritDoc of an implemented interface method
+        let resolved = inheritance.resolve(&circle_draw, &circle).unwrap();
+        assert_eq!(body(&resolved), "Draws the shape. See [area](com.example.IShape#area).");
+        assert!(resolved.tags.iter().any(|(tag, _)| matches!(tag, AsDocTag::Param { name, .. } if name == "scale")));
+
+        // @copy
+        assert_eq!(body(&inheritance.resolve(&radius, &circle).unwrap()), "Computes the area.");
+
+        // Anchors of a comment shown on its own page are unchanged.
+        assert_eq!(body(&inheritance.resolve(&base_describe, &base).unwrap()), "Describes [it](#area).");
+
+        // A method after substitution has the comment of its origin,
+        // relative to the page showing it.
+        let substituted = host.factory().create_method_slot_after_substitution(&base_describe, &SharedArray::new(), &SharedArray::new());
+        assert_eq!(body(&inheritance.resolve(&substituted, &circle).unwrap()), "Describes [it](com.example.Base#area).");
+        assert_eq!(body(&inheritance.resolve(&substituted, &base).unwrap()), "Describes [it](#area).");
+
+        // Cyclic @copy tags terminate without duplicating comments.
+        let resolved = inheritance.resolve(&a, &circle).unwrap();
+        assert_eq!(body(&resolved), "A.\n\nB.");
+        assert!(resolved.tags.is_empty());
+        assert_eq!(body(&inheritance.resolve(&b, &circle).unwrap()), "B.\n\nA.");
+    }
+}
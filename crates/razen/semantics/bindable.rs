@@ -0,0 +1,236 @@
+use crate::ns::*;
+
+/// Semantics of the `[Bindable]` meta-data.
+///
+/// The meta-data may be in one of the forms `[Bindable]`, `[Bindable("eventName")]`
+/// and `[Bindable(event="eventName")]`, where the event name defaults to `"propertyChange"`.
+///
+/// `apply_to_class()` turns bindable instance variables into virtual slots whose getter
+/// and setter are synthesized by the bytecode generator, storing the value into a private
+/// backing variable. It is to be invoked once the members of a class definition are
+/// declared; the verifier invokes it for MXML document classes once their `id`
+/// variables are declared. The synthesized setter dispatches the event only if
+/// `newValue !== oldValue`. Since every write, including writes through destructuring
+/// assignments, goes through the setter, no dispatch code is generated at
+/// assignment sites.
+pub struct Bindable<'a>(pub &'a SemanticHost);
+
+impl<'a> Bindable<'a> {
+    pub const DEFAULT_EVENT: &'static str = "propertyChange";
+
+    /// Prefix of the local name of backing variables.
+    pub const BACKING_PREFIX: &'static str = "_bindable_";
+
+    /// Prefix of the local name of the renamed setters
+    /// of bindable virtual slots.
+    pub const SETTER_PREFIX: &'static str = "_bindable_set_";
+
+    /// Returns the event name of a `[Bindable]` meta-data, or `None`
+    /// if the meta-data is not `[Bindable]`.
+    pub fn event_name(metadata: &Rc<Metadata>) -> Option<String> {
+        if metadata.name.0 != "Bindable" {
+            return None;
+        }
+        for entry in metadata.entries.iter().flatten() {
+            let value = match entry.value.as_ref() {
+                MetadataValue::IdentifierString((s, _)) => s,
+                MetadataValue::String((s, _)) => s,
+            };
+            match &entry.key {
+                None => return Some(value.clone()),
+                Some((key, _)) if key == "event" => return Some(value.clone()),
+                _ => {},
+            }
+        }
+        Some(Self::DEFAULT_EVENT.into())
+    }
+
+    /// Returns the event name of the first `[Bindable]` meta-data in a list.
+    pub fn event_name_in(list: &SharedArray<Rc<Metadata>>) -> Option<String> {
+        list.iter().find_map(|m| Self::event_name(&m))
+    }
+
+    /// Applies `[Bindable]` meta-data of a class and of its instance properties.
+    ///
+    /// * A bindable class makes its public instance variables and its
+    ///   public virtual slots having both a getter and a setter bindable.
+    /// * Bindable instance variables are turned into virtual slots.
+    /// * A bindable static variable records its event name, but remains a variable.
+    /// * A bindable setter makes its virtual slot bindable.
+    pub fn apply_to_class(&self, class: &Thingy) {
+        let class_event = Self::event_name_in(&class.metadata());
+        let public_ns = self.0.top_level_package().public_ns().unwrap();
+
+        let prototype = class.prototype(self.0);
+        let entries: Vec<(QName, Thingy)> = prototype.borrow().iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        for (name, slot) in entries {
+            if slot.is::<OriginalVariableSlot>() {
+                let event = Self::event_name_in(&slot.metadata())
+                    .or(class_event.clone().filter(|_| name.namespace() == public_ns && !slot.read_only(self.0)));
+                if let Some(event) = event {
+                    self.make_variable_bindable(class, &slot, event);
+                }
+            } else if slot.is::<OriginalVirtualSlot>() {
+                let setter_event = slot.setter(self.0)
+                    .filter(|s| s.is::<OriginalMethodSlot>())
+                    .and_then(|s| Self::event_name_in(&s.metadata()));
+                let has_pair = slot.getter(self.0).is_some() && slot.setter(self.0).is_some();
+                let event = setter_event.or(class_event.clone().filter(|_| name.namespace() == public_ns && has_pair));
+                if event.is_some() {
+                    slot.set_bindable_event(event);
+                }
+            }
+        }
+
+        let properties = class.properties(self.0);
+        let entries: Vec<Thingy> = properties.borrow().values().cloned().collect();
+        for slot in entries {
+            if slot.is::<OriginalVariableSlot>() {
+                if let Some(event) = Self::event_name_in(&slot.metadata()) {
+                    slot.set_bindable_event(Some(event));
+                }
+            }
+        }
+    }
+
+    /// Replaces an instance variable by a bindable virtual slot and
+    /// defines its backing variable. Returns the virtual slot.
+    pub fn make_variable_bindable(&self, class: &Thingy, variable: &Thingy, event: String) -> Thingy {
+        let factory = self.0.factory();
+        let name = variable.name();
+        let static_type = variable.static_type(self.0);
+
+        let backing_name = self.backing_variable_name(class, &name.local_name());
+        let backing = factory.create_variable_slot(&backing_name, false, &static_type);
+        backing.set_parent(Some(class.clone()));
+        backing.set_var_constant(variable.var_constant());
+
+        let virtual_slot = factory.create_virtual_slot(&name);
+        virtual_slot.set_parent(Some(class.clone()));
+        virtual_slot.set_location(variable.location());
+        virtual_slot.set_asdoc(variable.asdoc());
+        virtual_slot.set_bindable_event(Some(event));
+
+        let getter = factory.create_method_slot(&name, &factory.create_function_type(vec![], static_type.clone()));
+        getter.set_parent(Some(class.clone()));
+        getter.set_of_virtual_slot(Some(virtual_slot.clone()));
+        let setter_param = Rc::new(SemanticFunctionTypeParameter {
+            kind: ParameterKind::Required,
+            static_type: static_type.clone(),
+        });
+        let setter = factory.create_method_slot(&name, &factory.create_function_type(vec![setter_param], self.0.void_type()));
+        setter.set_parent(Some(class.clone()));
+        setter.set_of_virtual_slot(Some(virtual_slot.clone()));
+        for m in variable.metadata().iter() {
+            setter.metadata().push(m.clone());
+        }
+        virtual_slot.set_getter(Some(getter));
+        virtual_slot.set_setter(Some(setter));
+
+        let mut prototype = class.prototype(self.0);
+        prototype.set(name, virtual_slot.clone());
+        prototype.set(backing_name, backing);
+        virtual_slot
+    }
+
+    fn backing_variable_name(&self, class: &Thingy, local_name: &str) -> QName {
+        let ns = class.private_ns().unwrap_or_else(|| self.0.top_level_package().internal_ns().unwrap());
+        self.0.factory().create_qname(&ns, format!("{}{}", Self::BACKING_PREFIX, local_name))
+    }
+
+    /// Returns the backing variable of a bindable virtual slot
+    /// resulting from `make_variable_bindable()`.
+    pub fn backing_variable(&self, virtual_slot: &Thingy) -> Option<Thingy> {
+        virtual_slot.bindable_event()?;
+        let class = virtual_slot.parent()?;
+        let name = self.backing_variable_name(&class, &virtual_slot.name().local_name());
+        class.prototype(self.0).get(&name).filter(|s| s.is::<VariableSlot>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+    use hydroper_swf::avm2::types as abc;
+
+    #[test]
+    fn test_bindable() {
+        let host = SemanticHost::new(SemanticHostOptions::default());
+        let package = host.factory().create_package(["com", "example"]);
+        let top_public_ns = host.top_level_package().public_ns().unwrap();
+        let class = define_class(&host, &package, "Foo", None);
+
+        let cu = CompilationUnit::new(None, r#"
+            package com.example {
+                [Bindable]
+                public class Foo {
+                    public var x = 10;
+                    [Bindable("yChanged")]
+                    var y;
+                    public static var z;
+                }
+            }
+        "#.into());
+        let program = ParserFacade(&cu, ParserOptions::default()).parse_program();
+        let Directive::ClassDefinition(defn) = program.packages[0].block.directives[0].as_ref() else {
+            panic!();
+        };
+        for m in Attribute::find_metadata(&defn.attributes) {
+            class.metadata().push(m);
+        }
+        let internal_ns = package.internal_ns().unwrap();
+        for (ns, name, drtv) in [(&top_public_ns, "x", 0), (&internal_ns, "y", 1)] {
+            let Directive::VariableDefinition(var_defn) = defn.block.directives[drtv].as_ref() else {
+                panic!();
+            };
+            let qname = host.factory().create_qname(ns, name.into());
+            let slot = host.factory().create_variable_slot(&qname, false, &host.any_type());
+            for m in Attribute::find_metadata(&var_defn.attributes) {
+                slot.metadata().push(m);
+            }
+            class.prototype(&host).set(qname, slot);
+        }
+        let z_name = host.factory().create_qname(&top_public_ns, "z".into());
+        class.properties(&host).set(z_name.clone(), host.factory().create_variable_slot(&z_name, false, &host.any_type()));
+
+        Bindable(&host).apply_to_class(&class);
+
+        let x = class.prototype(&host).get_in_any_ns("x").unwrap().unwrap();
+        assert!(x.is::<VirtualSlot>());
+        assert_eq!(x.bindable_event(), Some("propertyChange".into()));
+        assert!(Bindable(&host).backing_variable(&x).is_some());
+        let y = class.prototype(&host).get_in_any_ns("y").unwrap().unwrap();
+        assert_eq!(y.bindable_event(), Some("yChanged".into()));
+        let z = class.properties(&host).get(&z_name).unwrap();
+        assert!(z.is::<VariableSlot>());
+        assert!(z.bindable_event().is_none());
+
+        let mut emitter = AbcEmitter::new(&host);
+        emitter.emit_program(&program);
+        let abc_file = emitter.finish();
+        let pool = &abc_file.constant_pool;
+        let local_name = |i: abc::Index<abc::Multiname>| match &pool.multinames[i.0 as usize - 1] {
+            abc::Multiname::QName { name, .. } => String::from_utf8(pool.strings[name.0 as usize - 1].clone()).unwrap(),
+            _ => panic!(),
+        };
+        let traits: Vec<(String, &abc::TraitKind)> = abc_file.instances[0].traits.iter().map(|t| (local_name(t.name), &t.kind)).collect();
+        let names: Vec<&str> = traits.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, ["_bindable_x", "_bindable_y", "x", "x", "y", "y"]);
+        assert!(matches!(traits[0].1, abc::TraitKind::Slot { .. }));
+        assert!(matches!(traits[2].1, abc::TraitKind::Getter { .. }));
+        let abc::TraitKind::Setter { method, .. } = traits[3].1 else {
+            panic!();
+        };
+
+        // The setter dispatches only if newValue !== oldValue.
+        let body = abc_file.method_bodies.iter().find(|b| b.method == *method).unwrap();
+        let mut reader = hydroper_swf::avm2::read::Reader::new(&body.code);
+        let mut ops = vec![];
+        while let Ok(op) = reader.read_op() {
+            ops.push(op);
+        }
+        assert!(ops.iter().any(|op| matches!(op, abc::Op::IfStrictEq { .. })));
+        assert!(ops.iter().any(|op| matches!(op, abc::Op::CallPropVoid { index, num_args: 1 } if local_name(*index) == "dispatchEvent")));
+        assert!(ops.iter().any(|op| matches!(op, abc::Op::CallProperty { index, num_args: 4 } if local_name(*index) == "createUpdateEvent")));
+    }
+}
@@ -0,0 +1,185 @@
+use crate::ns::*;
+
+/// Definition of a configuration constant used for conditional compilation,
+/// such as `CONFIG::debugging = true`.
+///
+/// The value is the character data of an expression, lazily evaluated
+/// as a compile-time constant the first time the constant is used.
+#[derive(Clone, Debug)]
+pub struct ConfigConstantDefinition {
+    /// The name in the form `NS::X`.
+    pub name: String,
+    /// The expression character data.
+    pub value: String,
+    /// The location of the definition, if it comes from a file.
+    pub location: Option<Location>,
+}
+
+impl ConfigConstantDefinition {
+    pub fn new(name: &str, value: &str, location: Option<Location>) -> Self {
+        Self {
+            name: name.to_owned(),
+            value: value.to_owned(),
+            location,
+        }
+    }
+
+    /// Parses a command-line definition in the form `NS::X=expression`,
+    /// returning `None` if it is malformed.
+    pub fn parse_command_line(argument: &str) -> Option<Self> {
+        let (name, value) = Self::split(argument)?;
+        Some(Self::new(&name, &value, None))
+    }
+
+    /// Parses a file of configuration constants, where every line is either
+    /// empty, a `#` comment or a definition in the form `NS::X = expression`.
+    ///
+    /// Malformed lines are reported to the compilation unit and skipped.
+    pub fn parse_file(compilation_unit: &Rc<CompilationUnit>) -> Vec<Self> {
+        let text = compilation_unit.text().clone();
+        let mut r = vec![];
+        let mut offset = 0;
+        for line in text.split_inclusive('\n') {
+            let start = offset;
+            offset += line.len();
+            let line = line.trim_end_matches(['\r', '\n']);
+            let trimmed = line.trim_start();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            let start = start + (line.len() - trimmed.len());
+            let location = Location::with_offsets(compilation_unit, start, start + trimmed.trim_end().len());
+            let Some((name, value)) = Self::split(trimmed) else {
+                if !compilation_unit.prevent_equal_offset_error(&location) {
+                    compilation_unit.add_diagnostic(FxDiagnostic::new_syntax_error(&location, FxDiagnosticKind::MalformedConfigConstantDefinition, diagarg![]));
+                }
+                continue;
+            };
+            r.push(Self::new(&name, &value, Some(location)));
+        }
+        r
+    }
+
+    fn split(definition: &str) -> Option<(String, String)> {
+        let (name, value) = definition.split_once('=')?;
+        let (ns, local_name) = name.trim().split_once("::")?;
+        let value = value.trim();
+        if !(Self::is_identifier(ns) && Self::is_identifier(local_name)) || value.is_empty() {
+            return None;
+        }
+        Some((format!("{ns}::{local_name}"), value.to_owned()))
+    }
+
+    fn is_identifier(name: &str) -> bool {
+        let mut chars = name.chars();
+        chars.next().is_some_and(|ch| ch.is_alphabetic() || ch == '_' || ch == '$')
+            && chars.all(|ch| ch.is_alphanumeric() || ch == '_' || ch == '$')
+    }
+}
+
+/// Population of the configuration constants of a semantic host.
+///
+/// For every project, `reset()` clears the previously defined constants
+/// and defines again the project's and command line's constants, where
+/// the command line's constants override the project's constants.
+pub struct ConfigConstants<'a>(pub &'a SemanticHost);
+
+impl<'a> ConfigConstants<'a> {
+    pub fn reset(&self, project: &[ConfigConstantDefinition], command_line: &[ConfigConstantDefinition]) {
+        self.0.clear_config_constants();
+        for definition in project.iter().chain(command_line.iter()) {
+            self.define(definition);
+        }
+    }
+
+    /// Defines a configuration constant, overriding any previous definition.
+    pub fn define(&self, definition: &ConfigConstantDefinition) {
+        self.0.config_constants().set(definition.name.clone(), definition.value.clone());
+        self.0.config_constants_eval().remove(&definition.name);
+        if let Some(location) = &definition.location {
+            self.0.config_constant_locations().set(definition.name.clone(), location.clone());
+        } else {
+            self.0.config_constant_locations().remove(&definition.name);
+        }
+    }
+
+    /// Returns the location where a configuration constant is defined,
+    /// if it is defined in a file.
+    pub fn location_of(&self, name: &str) -> Option<Location> {
+        self.0.config_constant_locations().get(&name.to_owned())
+    }
+
+    /// Determines whether a namespace is used by
+    /// any of the configuration constants.
+    pub fn is_config_namespace(&self, ns: &str) -> bool {
+        let prefix = format!("{ns}::");
+        self.0.config_constants().borrow().keys().any(|k| k.starts_with(&prefix))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    #[test]
+    fn test_config_constants() {
+        let host = Rc::new(SemanticHost::new(SemanticHostOptions::default()));
+        let object_type = define_class(&host, &host.top_level_package(), "Object", None);
+        for name in ["Class", "Function", "String", "Boolean", "Number", "int", "uint", "float", "Namespace"] {
+            define_class(&host, &host.top_level_package(), name, Some(&object_type));
+        }
+
+        let file = CompilationUnit::new(Some("config.txt".into()), "# Project constants\r\nCONFIG::debugging = true\r\nCONFIG::version = 1 + 2\r\nCONFIG::random = x\r\nCONFIG = false\r\n".into());
+        let project = ConfigConstantDefinition::parse_file(&file);
+        let kinds: Vec<Option<FxDiagnosticKind>> = file.diagnostics().iter().map(|d| FxDiagnostic(d).fx_kind()).collect();
+        assert!(kinds == vec![Some(FxDiagnosticKind::MalformedConfigConstantDefinition)]);
+        let names: Vec<&str> = project.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, ["CONFIG::debugging", "CONFIG::version", "CONFIG::random"]);
+
+        assert!(ConfigConstantDefinition::parse_command_line("CONFIG::release").is_none());
+        let command_line = [ConfigConstantDefinition::parse_command_line("CONFIG::debugging=false").unwrap()];
+        ConfigConstants(&host).reset(&project, &command_line);
+        assert_eq!(host.config_constants().get(&"CONFIG::debugging".into()), Some("false".into()));
+        assert!(ConfigConstants(&host).location_of("CONFIG::debugging").is_none());
+        assert!(ConfigConstants(&host).location_of("CONFIG::version").is_some());
+
+        let scope = host.factory().create_package_scope(&host.top_level_package());
+        let verify = |source: &str| -> (Option<Thingy>, Vec<String>) {
+            let cu = CompilationUnit::new(None, source.into());
+            let exp = ParserFacade(&cu, ParserOptions::default()).parse_expression();
+            let mut verifier = Verifier::new(&host);
+            verifier.set_scope(&scope);
+            let r = verifier.verify_expression(&exp, &default());
+            let messages = cu.diagnostics().iter().map(|d| FxDiagnostic(d).format_message_english()).collect();
+            (r, messages)
+        };
+
+        let (r, messages) = verify("CONFIG::version");
+        assert!(messages.is_empty(), "{messages:?}");
+        assert!(r.unwrap().number_value() == NumberVariant::Number(3.0));
+        let (r, _) = verify("CONFIG::debugging");
+        assert!(!r.unwrap().boolean_value());
+
+        let (_, messages) = verify("CONFIG::release");
+        assert_eq!(messages, ["Can not resolve configuration constant: 'CONFIG::release'"]);
+
+        // The non-constant definition is reported at its location in the file.
+        let (r, messages) = verify("CONFIG::random");
+        assert!(r.is_none());
+        assert_eq!(messages, ["Could not expand inline constant."]);
+        let messages: Vec<String> = file.diagnostics().iter().skip(1).map(|d| FxDiagnostic(d).format_message_english()).collect();
+        assert_eq!(messages, ["Can not resolve configuration constant: 'CONFIG::random'"]);
+
+        // The failure is cached and reported at each use, but the
+        // definition is not reported again.
+        let (r, messages) = verify("CONFIG::random");
+        assert!(r.is_none());
+        assert_eq!(messages, ["Could not expand inline constant."]);
+        assert_eq!(file.diagnostics().len(), 2);
+
+        // Another project
+        ConfigConstants(&host).reset(&[], &[]);
+        assert_eq!(host.config_constants().length(), 0);
+        assert!(ConfigConstants(&host).location_of("CONFIG::version").is_none());
+    }
+}
@@ -0,0 +1,346 @@
+use crate::ns::*;
+
+/// Compile-time evaluation of unary and binary operators applied
+/// to constants.
+///
+/// Numeric operations are computed in the numeric type of the left operand,
+/// as `NumberVariant` does: `int` and `uint` wrap around on overflow and
+/// `float` is computed in single precision. The `/` operator is the exception,
+/// resulting into a `Number` for `int` and `uint` operands, as in `1 / 0`
+/// resulting into `Infinity`, and so is the `%` operator with a zero divisor,
+/// resulting into `NaN`. Strings are compared by their UTF-16 code units.
+/// The `+` operator concatenates
+/// if either operand is a `String` constant, and `typeof` results into the
+/// type name of a constant.
+///
+/// The verifier folds unary and binary expressions whose operands are constants.
+pub struct ConstantFolding<'a>(pub &'a SemanticHost);
+
+impl<'a> ConstantFolding<'a> {
+    /// Evaluates a unary operator applied to a constant. Returns `None` if the operand
+    /// is not a constant or the operator does not apply to it.
+    pub fn fold_unary(&self, operator: Operator, operand: &Thingy) -> Result<Option<Thingy>, DeferError> {
+        if !operand.is::<Constant>() {
+            return Ok(None);
+        }
+        let operand_type = operand.static_type(self.0);
+        match operator {
+            Operator::Positive if operand.is::<NumberConstant>() => {
+                Ok(Some(operand.clone()))
+            },
+            Operator::Negative if operand.is::<NumberConstant>() => {
+                Ok(Some(self.0.factory().create_number_constant(-operand.number_value(), &operand_type)))
+            },
+            Operator::BitwiseNot if operand.is::<NumberConstant>() => {
+                Ok(Some(self.0.factory().create_number_constant(operand.number_value().bitwise_not(), &operand_type)))
+            },
+            Operator::LogicalNot => {
+                let Some(v) = self.to_boolean(operand) else {
+                    return Ok(None);
+                };
+                Ok(Some(self.0.factory().create_boolean_constant(!v, &self.0.boolean_type().defer()?)))
+            },
+            Operator::Typeof => {
+                let Some(v) = self.type_of(operand) else {
+                    return Ok(None);
+                };
+                Ok(Some(self.0.factory().create_string_constant(v.into(), &self.0.string_type().defer()?)))
+            },
+            _ => Ok(None),
+        }
+    }
+
+    /// Evaluates a binary operator applied to two constants. Returns `None` if either
+    /// operand is not a constant or the operator does not apply to them.
+    pub fn fold_binary(&self, operator: Operator, left: &Thingy, right: &Thingy) -> Result<Option<Thingy>, DeferError> {
+        if !(left.is::<Constant>() && right.is::<Constant>()) {
+            return Ok(None);
+        }
+        let left_type = left.static_type(self.0);
+        let both_numbers = left.is::<NumberConstant>() && right.is::<NumberConstant>();
+
+        match operator {
+            Operator::Add => {
+                if both_numbers {
+                    return Ok(Some(self.0.factory().create_number_constant(left.number_value() + right.number_value(), &left_type)));
+                }
+                if left.is::<StringConstant>() || right.is::<StringConstant>() {
+                    let (Some(l), Some(r)) = (self.to_string(left), self.to_string(right)) else {
+                        return Ok(None);
+                    };
+                    return Ok(Some(self.0.factory().create_string_constant(l + &r, &self.0.string_type().defer()?)));
+                }
+                Ok(None)
+            },
+            Operator::Subtract |
+            Operator::Multiply |
+            Operator::Divide |
+            Operator::Remainder |
+            Operator::Power |
+            Operator::BitwiseAnd |
+            Operator::BitwiseXor |
+            Operator::BitwiseOr |
+            Operator::ShiftLeft |
+            Operator::ShiftRight |
+            Operator::ShiftRightUnsigned => {
+                if !both_numbers {
+                    return Ok(None);
+                }
+                let mut l = left.number_value();
+                let r = right.number_value();
+
+                // `/` results into a Number for int and uint operands,
+                // and so does `%` by zero.
+                let mut result_type = left_type;
+                let to_number = operator == Operator::Divide || (operator == Operator::Remainder && r.force_double() == 0.0);
+                if to_number && matches!(l, NumberVariant::Int(_) | NumberVariant::Uint(_)) {
+                    l = NumberVariant::Number(l.force_double());
+                    result_type = self.0.number_type().defer()?;
+                }
+
+                let v = match operator {
+                    Operator::Subtract => l - r,
+                    Operator::Multiply => l * r,
+                    Operator::Divide => l / r,
+                    Operator::Remainder => l % r,
+                    Operator::Power => Self::power(&l, &r),
+                    Operator::BitwiseAnd => l & r,
+                    Operator::BitwiseXor => l ^ r,
+                    Operator::BitwiseOr => l | r,
+                    Operator::ShiftLeft => l << r,
+                    Operator::ShiftRight => l >> r,
+                    _ => l.shift_right_unsigned(&r),
+                };
+                Ok(Some(self.0.factory().create_number_constant(v, &result_type)))
+            },
+            Operator::Equals |
+            Operator::NotEquals |
+            Operator::StrictEquals |
+            Operator::StrictNotEquals => {
+                let strict = [Operator::StrictEquals, Operator::StrictNotEquals].contains(&operator);
+                let Some(v) = self.equals(left, right, strict) else {
+                    return Ok(None);
+                };
+                let v = if [Operator::Equals, Operator::StrictEquals].contains(&operator) { v } else { !v };
+                Ok(Some(self.0.factory().create_boolean_constant(v, &self.0.boolean_type().defer()?)))
+            },
+            Operator::Lt |
+            Operator::Gt |
+            Operator::Le |
+            Operator::Ge => {
+                let v = if both_numbers {
+                    let l = left.number_value().force_double();
+                    let r = right.number_value().force_double();
+                    match operator {
+                        Operator::Lt => l < r,
+                        Operator::Gt => l > r,
+                        Operator::Le => l <= r,
+                        _ => l >= r,
+                    }
+                } else if left.is::<StringConstant>() && right.is::<StringConstant>() {
+                    let l = left.string_value();
+                    let r = right.string_value();
+                    let (l, r) = (l.encode_utf16(), r.encode_utf16());
+                    match operator {
+                        Operator::Lt => l.lt(r),
+                        Operator::Gt => l.gt(r),
+                        Operator::Le => l.le(r),
+                        _ => l.ge(r),
+                    }
+                } else {
+                    return Ok(None);
+                };
+                Ok(Some(self.0.factory().create_boolean_constant(v, &self.0.boolean_type().defer()?)))
+            },
+            Operator::LogicalAnd |
+            Operator::LogicalOr => {
+                // The result is one of the operands, which must then
+                // be of the same type.
+                if left_type != right.static_type(self.0) {
+                    return Ok(None);
+                }
+                let Some(l) = self.to_boolean(left) else {
+                    return Ok(None);
+                };
+                let pick_left = if operator == Operator::LogicalAnd { !l } else { l };
+                Ok(Some(if pick_left { left.clone() } else { right.clone() }))
+            },
+            Operator::LogicalXor => {
+                if !(left.is::<BooleanConstant>() && right.is::<BooleanConstant>()) {
+                    return Ok(None);
+                }
+                Ok(Some(self.0.factory().create_boolean_constant(left.boolean_value() != right.boolean_value(), &self.0.boolean_type().defer()?)))
+            },
+            _ => Ok(None),
+        }
+    }
+
+    /// Compares two constants for equality. Returns `None` if
+    /// they cannot be compared at compile-time.
+    pub fn equals(&self, left: &Thingy, right: &Thingy, strict: bool) -> Option<bool> {
+        let left_nullish = left.is::<NullConstant>() || left.is::<UndefinedConstant>();
+        let right_nullish = right.is::<NullConstant>() || right.is::<UndefinedConstant>();
+        if left_nullish || right_nullish {
+            if !(left_nullish && right_nullish) {
+                return Some(false);
+            }
+            return Some(!strict || left.is::<NullConstant>() == right.is::<NullConstant>());
+        }
+        if left.is::<NumberConstant>() && right.is::<NumberConstant>() {
+            return Some(left.number_value().force_double() == right.number_value().force_double());
+        }
+        if left.is::<StringConstant>() && right.is::<StringConstant>() {
+            return Some(left.string_value() == right.string_value());
+        }
+        if left.is::<BooleanConstant>() && right.is::<BooleanConstant>() {
+            return Some(left.boolean_value() == right.boolean_value());
+        }
+        None
+    }
+
+    /// Converts a constant to `Boolean`, if it is a primitive constant.
+    pub fn to_boolean(&self, constant: &Thingy) -> Option<bool> {
+        if constant.is::<BooleanConstant>() {
+            Some(constant.boolean_value())
+        } else if constant.is::<NumberConstant>() {
+            let v = constant.number_value();
+            Some(!(v.is_zero() || v.is_nan()))
+        } else if constant.is::<StringConstant>() {
+            Some(!constant.string_value().is_empty())
+        } else if constant.is::<NullConstant>() || constant.is::<UndefinedConstant>() {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// Converts a constant to `String`, if it is a primitive constant.
+    pub fn to_string(&self, constant: &Thingy) -> Option<String> {
+        if constant.is::<StringConstant>() {
+            Some(constant.string_value())
+        } else if constant.is::<NumberConstant>() {
+            Some(match constant.number_value() {
+                NumberVariant::Int(v) => v.to_string(),
+                NumberVariant::Uint(v) => v.to_string(),
+                v => Self::number_to_string(v.force_double()),
+            })
+        } else if constant.is::<BooleanConstant>() {
+            Some(constant.boolean_value().to_string())
+        } else if constant.is::<NullConstant>() {
+            Some("null".into())
+        } else if constant.is::<UndefinedConstant>() {
+            Some("undefined".into())
+        } else {
+            None
+        }
+    }
+
+    /// Returns the result of `typeof` for a constant, if it is a primitive constant.
+    pub fn type_of(&self, constant: &Thingy) -> Option<&'static str> {
+        if constant.is::<StringConstant>() {
+            Some("string")
+        } else if constant.is::<NumberConstant>() {
+            Some("number")
+        } else if constant.is::<BooleanConstant>() {
+            Some("boolean")
+        } else if constant.is::<NullConstant>() {
+            Some("object")
+        } else if constant.is::<UndefinedConstant>() {
+            Some("undefined")
+        } else {
+            None
+        }
+    }
+
+    fn power(base: &NumberVariant, exponent: &NumberVariant) -> NumberVariant {
+        let v = base.force_double().powf(exponent.force_double());
+        match base {
+            NumberVariant::Number(_) => NumberVariant::Number(v),
+            NumberVariant::Float(_) => NumberVariant::Float(v as f32),
+            NumberVariant::Int(_) => NumberVariant::Int(NumberVariant::Number(v).force_int()),
+            NumberVariant::Uint(_) => NumberVariant::Uint(NumberVariant::Number(v).force_uint()),
+        }
+    }
+
+    /// Converts a `Number` to `String` as the ECMAScript `Number.prototype.toString()`
+    /// method with no radix.
+    fn number_to_string(v: f64) -> String {
+        if v.is_nan() {
+            "NaN".into()
+        } else if v.is_infinite() {
+            if v < 0.0 { "-Infinity".into() } else { "Infinity".into() }
+        } else if v == 0.0 {
+            "0".into()
+        } else if v.abs() >= 1e21 || v.abs() < 1e-6 {
+            let s = format!("{:e}", v);
+            if s.contains("e-") { s } else { s.replace('e', "e+") }
+        } else {
+            v.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    #[test]
+    fn test_constant_folding() {
+        let host = Rc::new(SemanticHost::new(SemanticHostOptions::default()));
+        let object_type = define_class(&host, &host.top_level_package(), "Object", None);
+        for name in ["Class", "Function", "String", "Boolean", "Number", "int", "uint", "float", "Namespace"] {
+            define_class(&host, &host.top_level_package(), name, Some(&object_type));
+        }
+        let public_ns = host.top_level_package().public_ns().unwrap();
+
+        // const MAX: int = 2147483647
+        let max = host.factory().create_variable_slot(&host.factory().create_qname(&public_ns, "MAX".into()), true, &host.int_type());
+        max.set_parent(Some(host.top_level_package()));
+        max.set_var_constant(Some(host.factory().create_number_constant(NumberVariant::Int(i32::MAX), &host.int_type())));
+        host.top_level_package().properties(&host).set(max.name(), max.clone());
+
+        let scope = host.factory().create_package_scope(&host.top_level_package());
+        let fold = |source: &str| -> Thingy {
+            let cu = CompilationUnit::new(None, source.into());
+            let exp = ParserFacade(&cu, ParserOptions::default()).parse_expression();
+            let mut verifier = Verifier::new(&host);
+            verifier.set_scope(&scope);
+            let r = verifier.verify_expression(&exp, &default());
+            let messages: Vec<String> = cu.diagnostics().iter().map(|d| FxDiagnostic(d).format_message_english()).collect();
+            let r = r.unwrap_or_else(|| panic!("{source}: {messages:?}"));
+            assert!(r.is::<Constant>(), "{source}");
+            r
+        };
+
+        assert!(fold("MAX + 1").number_value() == NumberVariant::Int(i32::MIN));
+        assert!(fold("MAX * 2").number_value() == NumberVariant::Int(-2));
+        assert!(fold("MAX / 2").number_value() == NumberVariant::Number(1073741823.5));
+        assert!(fold("MAX / 0").number_value().is_positive_infinity());
+        assert!(fold("MAX / 0").static_type(&host) == host.number_type());
+        assert!(fold("MAX % 0").number_value().is_nan());
+        assert!(fold("MAX % 0").static_type(&host) == host.number_type());
+        assert!(fold("MAX % 2").number_value() == NumberVariant::Int(1));
+        assert!(fold("1 << 33").number_value().force_double() == 2.0);
+        assert!(fold("-1 >>> 28").number_value().force_double() == 15.0);
+        assert!(fold("2 ** 10").number_value().force_double() == 1024.0);
+        assert_eq!(fold("\"n = \" + 1.5").string_value(), "n = 1.5");
+        assert_eq!(fold("typeof \"x\"").string_value(), "string");
+        assert!(fold("\"a\" < \"b\" && 1 != 2").boolean_value());
+        // U+FF61 follows the surrogates of U+1F600 in UTF-16.
+        assert!(fold("\"\u{FF61}\" > \"\u{1F600}\"").boolean_value());
+        assert!(!fold("null === void 0").boolean_value());
+        assert!(fold("null == void 0").boolean_value());
+        assert!(fold("!\"\"").boolean_value());
+
+        // var count: int
+        let count = host.factory().create_variable_slot(&host.factory().create_qname(&public_ns, "count".into()), false, &host.int_type());
+        count.set_parent(Some(host.top_level_package()));
+        host.top_level_package().properties(&host).set(count.name(), count.clone());
+        let cu = CompilationUnit::new(None, "count / 2".into());
+        let exp = ParserFacade(&cu, ParserOptions::default()).parse_expression();
+        let mut verifier = Verifier::new(&host);
+        verifier.set_scope(&scope);
+        let r = verifier.verify_expression(&exp, &default()).unwrap();
+        assert!(!r.is::<Constant>() && r.static_type(&host) == host.number_type());
+    }
+}
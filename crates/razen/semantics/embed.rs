@@ -0,0 +1,517 @@
+use crate::ns::*;
+use std::borrow::Cow;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use flate2::Compression as ZlibCompression;
+use flate2::write::ZlibEncoder;
+
+/// Kind of an asset embedded through the `[Embed]` meta-data.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum EmbedKind {
+    /// SWF movie, loaded from a `ByteArray` by an `EmbeddedMovieClip` subclass.
+    Movie,
+    /// PNG, JPEG or GIF image, attached as a `DefineBitsJPEG2` tag,
+    /// or BMP image, attached as a `DefineBitsLossless2` tag.
+    Image,
+    /// Arbitrary data, attached as a `DefineBinaryData` tag.
+    BinaryData,
+    /// OpenType font with CFF outlines, attached as a `DefineFont4` tag.
+    Font,
+}
+
+impl EmbedKind {
+    pub fn of_mime_type(mime_type: &str) -> Option<Self> {
+        match mime_type {
+            "application/x-shockwave-flash" => Some(Self::Movie),
+            "image/png" | "image/jpeg" | "image/gif" | "image/bmp" => Some(Self::Image),
+            "application/octet-stream" => Some(Self::BinaryData),
+            "application/x-font" | "application/x-font-opentype" => Some(Self::Font),
+            _ => None,
+        }
+    }
+
+    /// Fully qualified name of the class that asset classes extend.
+    pub fn base_class_name(&self) -> &'static str {
+        match self {
+            Self::Movie => "flash.compiler.embed.EmbeddedMovieClip",
+            Self::Image => "flash.display.Bitmap",
+            Self::BinaryData => "flash.utils.ByteArray",
+            Self::Font => "flash.text.Font",
+        }
+    }
+
+    /// Attributes applicable to the kind besides `source` and `mimeType`.
+    fn applicable_attributes(&self) -> &'static [&'static str] {
+        match self {
+            Self::Movie => &["symbol"],
+            Self::Image => &["scaleGridTop", "scaleGridBottom", "scaleGridLeft", "scaleGridRight", "smoothing", "compression", "quality"],
+            Self::BinaryData => &["encrypted"],
+            Self::Font => &["fontName", "fontFamily", "fontWeight", "fontStyle", "embedAsCFF", "advancedAntiAliasing", "unicodeRange"],
+        }
+    }
+}
+
+/// Applicable attributes that are recognized, but not implemented.
+const UNSUPPORTED_ATTRIBUTES: [&str; 10] = [
+    "symbol", "encrypted", "unicodeRange", "scaleGridTop", "scaleGridBottom",
+    "scaleGridLeft", "scaleGridRight", "smoothing", "compression", "quality",
+];
+
+/// Asset embedded through the `[Embed]` meta-data, carrying its bytes
+/// so that a SWF writer can attach its character tag.
+pub struct EmbeddedAsset {
+    /// Fully qualified name of the asset class.
+    pub class_name: String,
+    /// Whether the asset class is synthesized, as opposed to
+    /// a user class carrying the `[Embed]` meta-data.
+    pub synthesized: bool,
+    pub source: PathBuf,
+    pub mime_type: String,
+    pub kind: EmbedKind,
+    pub data: Vec<u8>,
+    pub font_name: Option<String>,
+    pub font_bold: bool,
+    pub font_italic: bool,
+    /// Stage size of an embedded SWF in pixels.
+    pub movie_size: Option<(u32, u32)>,
+}
+
+impl EmbeddedAsset {
+    /// Fully qualified name of the class linked to the character tag
+    /// through the SymbolClass tag. For SWF movies, this is the `ByteArray`
+    /// subclass the movie is loaded from.
+    pub fn linked_class_name(&self) -> String {
+        if self.kind == EmbedKind::Movie {
+            format!("{}ByteArray", self.class_name)
+        } else {
+            self.class_name.clone()
+        }
+    }
+
+    /// Character definition tag of the asset.
+    pub fn define_tag(&self, id: u16) -> hydroper_swf::Tag<'_> {
+        match self.kind {
+            EmbedKind::Movie | EmbedKind::BinaryData => {
+                hydroper_swf::Tag::DefineBinaryData(hydroper_swf::DefineBinaryData { id, data: &self.data })
+            },
+            EmbedKind::Image => {
+                let Some((width, height, argb)) = decode_bmp(&self.data).filter(|_| self.mime_type == "image/bmp") else {
+                    return hydroper_swf::Tag::DefineBitsJpeg2 { id, jpeg_data: &self.data };
+                };
+                let mut encoder = ZlibEncoder::new(vec![], ZlibCompression::default());
+                encoder.write_all(&argb).unwrap();
+                hydroper_swf::Tag::DefineBitsLossless(hydroper_swf::DefineBitsLossless {
+                    version: 2,
+                    id,
+                    format: hydroper_swf::BitmapFormat::Rgb32,
+                    width,
+                    height,
+                    data: Cow::Owned(encoder.finish().unwrap()),
+                })
+            },
+            EmbedKind::Font => {
+                hydroper_swf::Tag::DefineFont4(hydroper_swf::Font4 {
+                    id,
+                    is_italic: self.font_italic,
+                    is_bold: self.font_bold,
+                    name: hydroper_swf::SwfStr::from_utf8_str(self.font_name.as_deref().unwrap_or("")),
+                    data: Some(&self.data),
+                })
+            },
+        }
+    }
+}
+
+/// Semantics of the `[Embed]` meta-data.
+///
+/// The meta-data may be applied to a static single-binding variable of type `Class`,
+/// for which an asset class is synthesized and assigned to the variable, or to
+/// a class extending the base class of the asset kind (`EmbedKind::base_class_name()`).
+/// A SWF embedded by a class is embedded as binary data, thus such class must extend `ByteArray`.
+///
+/// The `source` attribute, which may also be given as a keyless entry, is resolved
+/// relative to the directory of the compilation unit. The MIME type is inferred
+/// from the extension unless the `mimeType` attribute is given. Fonts must be
+/// OpenType fonts with CFF outlines; TrueType fonts are rejected.
+///
+/// Verified assets are added to `SemanticHost::embedded_assets()`. Synthesized asset
+/// classes are defined in the package of the class declaring the variable and are
+/// not compiled from source; for a SWF movie, the bytecode generator is to give
+/// the asset class a constructor equivalent to:
+///
+/// ```as3
+/// public function Foo_swf() {
+///     super(new Foo_swfByteArray(), 500, 375);
+/// }
+/// ```
+///
+/// where `Foo_swfByteArray` is the linked class (`EmbeddedAsset::linked_class_name()`)
+/// and the arguments following it are the stage size (`EmbeddedAsset::movie_size`).
+pub struct Embed<'a>(pub &'a SemanticHost);
+
+impl<'a> Embed<'a> {
+    /// Returns the first `[Embed]` meta-data in a list.
+    pub fn find_metadata(list: impl IntoIterator<Item = Rc<Metadata>>) -> Option<Rc<Metadata>> {
+        list.into_iter().find(|m| m.name.0 == "Embed")
+    }
+
+    /// Infers the MIME type of a file from its extension.
+    pub fn mime_type_of_path(path: &Path) -> Option<&'static str> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "swf" => Some("application/x-shockwave-flash"),
+            "png" => Some("image/png"),
+            "jpg" | "jpeg" => Some("image/jpeg"),
+            "gif" => Some("image/gif"),
+            "bmp" => Some("image/bmp"),
+            "ttf" => Some("application/x-font-truetype"),
+            "otf" => Some("application/x-font-opentype"),
+            _ => None,
+        }
+    }
+
+    /// Verifies `[Embed]` meta-data applied to a variable definition
+    /// of a class, synthesizing the asset class. Returns `None` if the definition
+    /// has no `[Embed]` meta-data or if an error was reported.
+    pub fn verify_variable_definition(&self, class: &Thingy, defn: &VariableDefinition, slot: &Thingy) -> Option<Rc<EmbeddedAsset>> {
+        let metadata = Self::find_metadata(Attribute::find_metadata(&defn.attributes))?;
+        let is_static = Attribute::find_static(&defn.attributes).is_some();
+        if !is_static || defn.bindings.len() != 1 || slot.static_type(self.0) != self.0.class_type() {
+            FxDiagnostic::add_verify_error(&metadata.location, FxDiagnosticKind::EmbedMustBeStaticClassVariable, diagarg![]);
+            return None;
+        }
+        let package = class.parent().filter(|p| p.is::<Package>()).unwrap_or(self.0.top_level_package());
+        let local_name = format!("{}_{}", class.name().local_name(), slot.name().local_name());
+        let package_name = package.fully_qualified_name();
+        let class_name = if package_name.is_empty() { local_name.clone() } else { format!("{}.{}", package_name, local_name) };
+        let asset = Rc::new(self.load(&metadata, class_name, true)?);
+
+        let asset_class = self.define_asset_class(&package, &local_name, asset.kind.base_class_name());
+        if asset.kind == EmbedKind::Movie {
+            let linked_class = split_class_name(&asset.linked_class_name()).1.to_owned();
+            self.define_asset_class(&package, &linked_class, EmbedKind::BinaryData.base_class_name());
+        }
+        if let Ok(k) = self.0.factory().create_type_constant(&asset_class) {
+            slot.set_var_constant(Some(k));
+        }
+        self.0.add_embedded_asset(asset.clone());
+        Some(asset)
+    }
+
+    /// Verifies `[Embed]` meta-data applied to a class. Returns `None`
+    /// if the class has no `[Embed]` meta-data or if an error was reported.
+    pub fn verify_class(&self, class: &Thingy) -> Option<Rc<EmbeddedAsset>> {
+        let metadata = Self::find_metadata(class.metadata().iter())?;
+        let mut asset = self.load(&metadata, fully_qualified_class_name(class), false)?;
+        if asset.kind == EmbedKind::Movie {
+            asset.kind = EmbedKind::BinaryData;
+        }
+        let base = asset.kind.base_class_name();
+        if !self.extends_class_named(class, base) {
+            FxDiagnostic::add_verify_error(&metadata.location, FxDiagnosticKind::EmbedClassMustExtend, diagarg![base.to_owned()]);
+            return None;
+        }
+        let asset = Rc::new(asset);
+        self.0.add_embedded_asset(asset.clone());
+        Some(asset)
+    }
+
+    /// Reads the attributes and the source of an `[Embed]` meta-data.
+    fn load(&self, metadata: &Rc<Metadata>, class_name: String, synthesized: bool) -> Option<EmbeddedAsset> {
+        let mut attributes: Vec<(String, String, Location)> = vec![];
+        for entry in metadata.entries.iter().flatten() {
+            let value = match entry.value.as_ref() {
+                MetadataValue::IdentifierString((s, _)) => s.clone(),
+                MetadataValue::String((s, _)) => s.clone(),
+            };
+            let key = entry.key.as_ref().map(|(k, _)| k.clone()).unwrap_or("source".into());
+            attributes.push((key, value, entry.location.clone()));
+        }
+        let attribute = |name: &str| attributes.iter().find(|(k, _, _)| k == name).map(|(_, v, _)| v.clone());
+
+        let Some(source) = attribute("source") else {
+            FxDiagnostic::add_verify_error(&metadata.location, FxDiagnosticKind::EmbedMustSpecifySource, diagarg![]);
+            return None;
+        };
+        let path = Self::resolve_source(&metadata.location.compilation_unit(), &source);
+
+        let mime_type = match attribute("mimeType") {
+            Some(mime_type) => mime_type,
+            None => {
+                let Some(mime_type) = Self::mime_type_of_path(&path) else {
+                    FxDiagnostic::add_verify_error(&metadata.location, FxDiagnosticKind::EmbedUnknownMimeType, diagarg![source]);
+                    return None;
+                };
+                mime_type.to_owned()
+            },
+        };
+        if mime_type == "application/x-font-truetype" {
+            FxDiagnostic::add_verify_error(&metadata.location, FxDiagnosticKind::EmbedTrueTypeFontUnsupported, diagarg![source]);
+            return None;
+        }
+        let Some(kind) = EmbedKind::of_mime_type(&mime_type) else {
+            FxDiagnostic::add_verify_error(&metadata.location, FxDiagnosticKind::EmbedUnsupportedMimeType, diagarg![mime_type]);
+            return None;
+        };
+
+        let mut valid = true;
+        for (key, _, location) in &attributes {
+            if ["source", "mimeType"].contains(&key.as_str()) {
+                continue;
+            }
+            if !kind.applicable_attributes().contains(&key.as_str()) {
+                FxDiagnostic::add_verify_error(location, FxDiagnosticKind::EmbedInapplicableAttribute, diagarg![key.clone(), mime_type.clone()]);
+                valid = false;
+            } else if UNSUPPORTED_ATTRIBUTES.contains(&key.as_str()) {
+                FxDiagnostic::add_verify_error(location, FxDiagnosticKind::EmbedUnsupportedAttribute, diagarg![key.clone()]);
+                valid = false;
+            }
+        }
+        let font_name = attribute("fontName").or(attribute("fontFamily"));
+        if kind == EmbedKind::Font && font_name.is_none() {
+            FxDiagnostic::add_verify_error(&metadata.location, FxDiagnosticKind::EmbedMustSpecifyAttribute, diagarg!["fontName".to_owned(), mime_type.clone()]);
+            valid = false;
+        }
+        if !valid {
+            return None;
+        }
+
+        let Ok(data) = std::fs::read(&path) else {
+            FxDiagnostic::add_verify_error(&metadata.location, FxDiagnosticKind::EmbedSourceNotFound, diagarg![source]);
+            return None;
+        };
+
+        let mut movie_size = None;
+        let well_formed = match kind {
+            EmbedKind::Movie => {
+                movie_size = hydroper_swf::decompress_swf(&data[..]).ok().map(|swf| {
+                    let stage_size = swf.header.stage_size();
+                    (stage_size.width().to_pixels() as u32, stage_size.height().to_pixels() as u32)
+                });
+                movie_size.is_some()
+            },
+            EmbedKind::Image => match mime_type.as_str() {
+                "image/png" => data.starts_with(b"\x89PNG"),
+                "image/jpeg" => data.starts_with(b"\xFF\xD8"),
+                "image/gif" => data.starts_with(b"GIF8"),
+                _ => decode_bmp(&data).is_some(),
+            },
+            EmbedKind::BinaryData => true,
+            EmbedKind::Font => {
+                if !data.starts_with(b"OTTO") {
+                    FxDiagnostic::add_verify_error(&metadata.location, FxDiagnosticKind::EmbedFontMustBeCff, diagarg![source]);
+                    return None;
+                }
+                true
+            },
+        };
+        if !well_formed {
+            FxDiagnostic::add_verify_error(&metadata.location, FxDiagnosticKind::EmbedInvalidSource, diagarg![source, mime_type]);
+            return None;
+        }
+
+        Some(EmbeddedAsset {
+            class_name,
+            synthesized,
+            source: path,
+            mime_type,
+            kind,
+            data,
+            font_name,
+            font_bold: attribute("fontWeight").map(|w| w == "bold").unwrap_or(false),
+            font_italic: attribute("fontStyle").map(|s| s == "italic").unwrap_or(false),
+            movie_size,
+        })
+    }
+
+    /// Resolves a source path relative to the directory of a compilation unit.
+    pub fn resolve_source(cu: &Rc<CompilationUnit>, source: &str) -> PathBuf {
+        let source = Path::new(source);
+        if source.is_absolute() {
+            return source.to_owned();
+        }
+        match cu.file_path().as_ref().and_then(|p| Path::new(p).parent()) {
+            Some(dir) => dir.join(source),
+            None => source.to_owned(),
+        }
+    }
+
+    fn define_asset_class(&self, package: &Thingy, local_name: &str, base: &str) -> Thingy {
+        let public_ns = package.public_ns().unwrap();
+        let name = self.0.factory().create_qname(&public_ns, local_name.to_owned());
+        let class = self.0.factory().create_class_type(name.clone(), &self.0.top_level_package().public_ns().unwrap());
+        class.set_parent(Some(package.clone()));
+        if let Some(base) = self.0.lookup_class(base) {
+            class.set_extends_class(Some(base));
+        }
+        package.properties(self.0).set(name, class.clone());
+        class
+    }
+
+    fn extends_class_named(&self, class: &Thingy, name: &str) -> bool {
+        let mut base = class.extends_class(self.0);
+        while let Some(c) = base {
+            if !c.is::<ClassType>() {
+                return false;
+            }
+            if fully_qualified_class_name(&c) == name {
+                return true;
+            }
+            base = c.extends_class(self.0);
+        }
+        false
+    }
+}
+
+fn split_class_name(name: &str) -> (&str, &str) {
+    match name.rfind('.') {
+        Some(i) => (&name[..i], &name[i + 1..]),
+        None => ("", name),
+    }
+}
+
+fn fully_qualified_class_name(class: &Thingy) -> String {
+    let local_name = class.name().local_name();
+    match class.parent().filter(|p| p.is::<Package>()).map(|p| p.fully_qualified_name()) {
+        Some(package_name) if !package_name.is_empty() => format!("{}.{}", package_name, local_name),
+        _ => local_name,
+    }
+}
+
+/// Decodes an uncompressed 24-bit or 32-bit BMP image into premultiplied
+/// ARGB pixels, returning its width, height and pixels.
+fn decode_bmp(bytes: &[u8]) -> Option<(u16, u16, Vec<u8>)> {
+    let u16_at = |i: usize| bytes.get(i..i + 2).map(|b| u16::from_le_bytes([b[0], b[1]]));
+    let u32_at = |i: usize| bytes.get(i..i + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+    if !bytes.starts_with(b"BM") {
+        return None;
+    }
+    let pixels_offset = u32_at(10)? as usize;
+    let width = u32_at(18)? as i32;
+    let height = u32_at(22)? as i32;
+    let bits_per_pixel = u16_at(28)?;
+    let compression = u32_at(30)?;
+    let has_alpha = match (bits_per_pixel, compression) {
+        (24, 0) => false,
+        (32, 0) => false,
+        // BI_BITFIELDS, assumed to be in the BGRA order
+        (32, 3) => true,
+        _ => return None,
+    };
+    let (width, top_down) = (u16::try_from(width).ok()?, height < 0);
+    let height = u16::try_from(height.unsigned_abs()).ok()?;
+    let bytes_per_pixel = bits_per_pixel as usize / 8;
+    let row_size = (width as usize * bytes_per_pixel).div_ceil(4) * 4;
+
+    let mut argb = Vec::with_capacity(width as usize * height as usize * 4);
+    for y in 0..height as usize {
+        let row = if top_down { y } else { height as usize - 1 - y };
+        let row_offset = pixels_offset + row * row_size;
+        for x in 0..width as usize {
+            let p = bytes.get(row_offset + x * bytes_per_pixel..row_offset + (x + 1) * bytes_per_pixel)?;
+            let a = if has_alpha { p[3] } else { 0xFF };
+            let premultiply = |c: u8| (c as u16 * a as u16 / 0xFF) as u8;
+            argb.extend_from_slice(&[a, premultiply(p[2]), premultiply(p[1]), premultiply(p[0])]);
+        }
+    }
+    Some((width, height, argb))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    #[test]
+    fn test_embed() {
+        let dir = std::env::temp_dir().join(format!("razen_test_embed_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("data.bin"), [1, 2, 3]).unwrap();
+        std::fs::write(dir.join("logo.png"), b"\x89PNG\r\n\x1a\n").unwrap();
+
+        let host = SemanticHost::new(SemanticHostOptions::default());
+        let top_public_ns = host.top_level_package().public_ns().unwrap();
+        let class_type = define_class(&host, &host.top_level_package(), "Class", None);
+        let byte_array = define_class(&host, &host.factory().create_package(["flash", "utils"]), "ByteArray", None);
+        let package = host.factory().create_package(["com", "example"]);
+        let class = define_class(&host, &package, "Assets", None);
+
+        let cu = CompilationUnit::new(Some(dir.join("Assets.as").to_string_lossy().into_owned()), r#"
+            package com.example {
+                public class Assets {
+                    [Embed(source="data.bin", mimeType="application/octet-stream")]
+                    public static var Data: Class;
+                    [Embed("logo.png")]
+                    public static var Logo: Class;
+                    [Embed(source="missing.png")]
+                    public static var Missing: Class;
+                    [Embed(source="data.bin")]
+                    public static var Unknown: Class;
+                    [Embed(source="logo.png", fontName="Arial")]
+                    public static var Inapplicable: Class;
+                    [Embed(source="font.ttf", fontName="Sans")]
+                    public static var TrueTypeFont: Class;
+                    [Embed(source="data.bin", mimeType="application/octet-stream")]
+                    public var NotStatic: Class;
+                }
+            }
+        "#.into());
+        let program = ParserFacade(&cu, ParserOptions::default()).parse_program();
+        let Directive::ClassDefinition(defn) = program.packages[0].block.directives[0].as_ref() else {
+            panic!();
+        };
+        let mut assets = vec![];
+        for drtv in &defn.block.directives {
+            let Directive::VariableDefinition(var_defn) = drtv.as_ref() else {
+                panic!();
+            };
+            let Expression::QualifiedIdentifier(id) = var_defn.bindings[0].destructuring.destructuring.as_ref() else {
+                panic!();
+            };
+            let name = host.factory().create_qname(&top_public_ns, id.to_identifier_name().unwrap().0);
+            let slot = host.factory().create_variable_slot(&name, false, &class_type);
+            if let Some(asset) = Embed(&host).verify_variable_definition(&class, var_defn, &slot) {
+                assets.push((asset, slot));
+            }
+        }
+
+        let kinds: Vec<Option<FxDiagnosticKind>> = cu.diagnostics().iter().map(|d| FxDiagnostic(d).fx_kind()).collect();
+        assert!(kinds == vec![
+            Some(FxDiagnosticKind::EmbedSourceNotFound),
+            Some(FxDiagnosticKind::EmbedUnknownMimeType),
+            Some(FxDiagnosticKind::EmbedInapplicableAttribute),
+            Some(FxDiagnosticKind::EmbedTrueTypeFontUnsupported),
+            Some(FxDiagnosticKind::EmbedMustBeStaticClassVariable),
+        ]);
+
+        assert_eq!(assets.len(), 2);
+        let (data, data_slot) = &assets[0];
+        assert_eq!(data.kind, EmbedKind::BinaryData);
+        assert_eq!(data.class_name, "com.example.Assets_Data");
+        assert_eq!(data.data, [1, 2, 3]);
+        let asset_class = data_slot.var_constant().unwrap().referenced_type();
+        assert!(asset_class.extends_class(&host) == Some(byte_array));
+        assert!(package.properties(&host).get(&asset_class.name()).unwrap() == asset_class);
+        let (logo, _) = &assets[1];
+        assert_eq!((logo.kind, logo.mime_type.as_str()), (EmbedKind::Image, "image/png"));
+        assert_eq!(host.embedded_assets().len(), 2);
+
+        // Character tags and SymbolClass links
+        let bytes = SwfMovie::new(SwfMovieOptions::default(), "com.example.Main".into()).write_with_assets(&[], &host.embedded_assets());
+        let swf_buf = hydroper_swf::decompress_swf(&bytes[..]).unwrap();
+        let swf = hydroper_swf::parse_swf(&swf_buf).unwrap();
+        assert!(swf.tags.iter().any(|t| matches!(t, hydroper_swf::Tag::DefineBinaryData(d) if d.id == 1 && d.data == [1, 2, 3])));
+        assert!(swf.tags.iter().any(|t| matches!(t, hydroper_swf::Tag::DefineBitsJpeg2 { id: 2, .. })));
+        let links = swf.tags.iter().find_map(|t| match t {
+            hydroper_swf::Tag::SymbolClass(links) => Some(links.clone()),
+            _ => None,
+        }).unwrap();
+        let links: Vec<(u16, String)> = links.iter().map(|l| (l.id, l.class_name.to_string_lossy(hydroper_swf::UTF_8))).collect();
+        assert_eq!(links, [
+            (1, "com.example.Assets_Data".to_owned()),
+            (2, "com.example.Assets_Logo".to_owned()),
+            (0, "com.example.Main".to_owned()),
+        ]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
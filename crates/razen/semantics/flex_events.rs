@@ -0,0 +1,183 @@
+use crate::ns::*;
+
+/// Semantics of the `[Event]` meta-data.
+///
+/// Classes and interfaces declare the events they dispatch through meta-data
+/// in the form `[Event(name="eventName", type="flash.events.MouseEvent")]`, where
+/// `type` defaults to `flash.events.Event`. The type may be given as a fully qualified
+/// name or, if it is in the same package or in the top-level package, as a local name.
+///
+/// Declared events are stored into `Thingy::flex_events()`. Events are inherited from
+/// superclasses and interfaces, as returned by `lookup()` and `all()`.
+pub struct FlexEvents<'a>(pub &'a SemanticHost);
+
+impl<'a> FlexEvents<'a> {
+    pub const EVENT_CLASS: &'static str = "flash.events.Event";
+
+    /// Processes the `[Event]` meta-data of a class or interface,
+    /// reporting malformed meta-data, unknown types and duplicate event names.
+    pub fn apply(&self, type_thingy: &Thingy) {
+        let mut events = type_thingy.flex_events();
+        let event_class = self.lookup_class(Self::EVENT_CLASS, None);
+        for metadata in type_thingy.metadata().iter() {
+            if metadata.name.0 != "Event" {
+                continue;
+            }
+            let Some((name, type_name)) = Self::parse_metadata(&metadata) else {
+                FxDiagnostic::add_verify_error(&metadata.location, FxDiagnosticKind::MalformedEventMetadata, diagarg![]);
+                continue;
+            };
+            let package = type_thingy.parent().filter(|p| p.is::<Package>());
+            let type_name = type_name.unwrap_or(Self::EVENT_CLASS.into());
+            let Some(event_type) = self.lookup_class(&type_name, package.as_ref()) else {
+                FxDiagnostic::add_verify_error(&metadata.location, FxDiagnosticKind::UnknownEventType, diagarg![type_name]);
+                continue;
+            };
+            if let Some(event_class) = &event_class {
+                if !event_type.is_equals_or_subtype_of(event_class, self.0).unwrap_or(false) {
+                    FxDiagnostic::add_verify_error(&metadata.location, FxDiagnosticKind::EventTypeMustExtendEvent, diagarg![type_name]);
+                    continue;
+                }
+            }
+            if events.has(&name) {
+                FxDiagnostic::add_verify_error(&metadata.location, FxDiagnosticKind::DuplicateEventName, diagarg![name]);
+                continue;
+            }
+            events.set(name, event_type);
+        }
+    }
+
+    /// Returns the `name` and optional `type` of an `[Event]` meta-data,
+    /// or `None` if it is malformed.
+    pub fn parse_metadata(metadata: &Rc<Metadata>) -> Option<(String, Option<String>)> {
+        let mut name = None;
+        let mut type_name = None;
+        for entry in metadata.entries.as_ref()? {
+            let value = match entry.value.as_ref() {
+                MetadataValue::IdentifierString((s, _)) => s.clone(),
+                MetadataValue::String((s, _)) => s.clone(),
+            };
+            match entry.key.as_ref().map(|(k, _)| k.as_str()) {
+                Some("name") if name.is_none() => name = Some(value),
+                Some("type") if type_name.is_none() => type_name = Some(value),
+                // Other documented keys, not used by the compiler
+                Some("deprecatedMessage" | "deprecatedReplacement" | "deprecatedSince") => {},
+                _ => return None,
+            }
+        }
+        Some((name.filter(|n| !n.is_empty())?, type_name))
+    }
+
+    /// Looks up an event declared by a type or inherited from
+    /// its superclasses and interfaces, returning the event type.
+    pub fn lookup(&self, type_thingy: &Thingy, name: &str) -> Option<Thingy> {
+        self.declaring_types(type_thingy).iter().find_map(|t| t.flex_events().get(&name.to_owned()))
+    }
+
+    /// Returns all events of a type, including inherited events,
+    /// in the order they are found from the type to its ascending types,
+    /// sorted by name within each type.
+    pub fn all(&self, type_thingy: &Thingy) -> Vec<(String, Thingy)> {
+        let mut r: Vec<(String, Thingy)> = vec![];
+        for t in self.declaring_types(type_thingy) {
+            let mut events: Vec<(String, Thingy)> = t.flex_events().borrow().iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+            events.sort_by(|(a, _), (b, _)| a.cmp(b));
+            for (name, event_type) in events {
+                if !r.iter().any(|(n, _)| *n == name) {
+                    r.push((name, event_type));
+                }
+            }
+        }
+        r
+    }
+
+    /// Returns a type followed by its ascending types, nearest first.
+    fn declaring_types(&self, type_thingy: &Thingy) -> Vec<Thingy> {
+        let mut r = vec![type_thingy.clone()];
+        let mut i = 0;
+        while i < r.len() {
+            for t in r[i].direct_ascending_types(self.0) {
+                if (t.is::<ClassType>() || t.is::<InterfaceType>()) && !r.contains(&t) {
+                    r.push(t);
+                }
+            }
+            i += 1;
+        }
+        r
+    }
+
+    /// Looks up a class by its fully qualified name. An unqualified name
+    /// is looked up in `package` before the top-level package.
+    fn lookup_class(&self, name: &str, package: Option<&Thingy>) -> Option<Thingy> {
+        let in_package = package.filter(|_| !name.contains('.')).and_then(|p| {
+            let name = self.0.factory().create_qname(&p.public_ns()?, name.to_owned());
+            p.properties(self.0).get(&name).filter(|c| c.is::<ClassType>())
+        });
+        in_package.or_else(|| self.0.lookup_class(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    #[test]
+    fn test_flex_events() {
+        let host = SemanticHost::new(SemanticHostOptions::default());
+        let flash_events = host.factory().create_package(["flash", "events"]);
+        let event = define_class(&host, &flash_events, "Event", None);
+        let mouse_event = define_class(&host, &flash_events, "MouseEvent", Some(&event));
+        let package = host.factory().create_package(["com", "example"]);
+        define_class(&host, &package, "NotAnEvent", None);
+        let interface = host.factory().create_interface_type(host.factory().create_qname(&package.public_ns().unwrap(), "IClickable".into()));
+        interface.set_parent(Some(package.clone()));
+        let base = define_class(&host, &package, "Base", None);
+        let class = define_class(&host, &package, "Button", Some(&base));
+        class.implements(&host).push(interface.clone());
+
+        let cu = CompilationUnit::new(None, r#"
+            package com.example {
+                [Event(name="click", type="flash.events.MouseEvent")]
+                interface IClickable {}
+
+                [Event(name="change")]
+                class Base {}
+
+                [Event(name="open", type="NotAnEvent")]
+                [Event(name="close", type="Unknown")]
+                [Event(type="flash.events.Event")]
+                [Event(name="select")]
+                [Event(name="select")]
+                class Button {}
+            }
+        "#.into());
+        let program = ParserFacade(&cu, ParserOptions::default()).parse_program();
+        for (i, t) in [&interface, &base, &class].into_iter().enumerate() {
+            let attributes = match program.packages[0].block.directives[i].as_ref() {
+                Directive::InterfaceDefinition(defn) => &defn.attributes,
+                Directive::ClassDefinition(defn) => &defn.attributes,
+                _ => panic!(),
+            };
+            for m in Attribute::find_metadata(attributes) {
+                t.metadata().push(m);
+            }
+            FlexEvents(&host).apply(t);
+        }
+
+        let kinds: Vec<Option<FxDiagnosticKind>> = cu.diagnostics().iter().map(|d| FxDiagnostic(d).fx_kind()).collect();
+        assert!(kinds == vec![
+            Some(FxDiagnosticKind::EventTypeMustExtendEvent),
+            Some(FxDiagnosticKind::UnknownEventType),
+            Some(FxDiagnosticKind::MalformedEventMetadata),
+            Some(FxDiagnosticKind::DuplicateEventName),
+        ]);
+        assert!(!class.flex_events().has(&"open".to_owned()));
+
+        assert!(FlexEvents(&host).lookup(&class, "select") == Some(event.clone()));
+        assert!(FlexEvents(&host).lookup(&class, "change") == Some(event.clone()));
+        assert!(FlexEvents(&host).lookup(&class, "click") == Some(mouse_event.clone()));
+        assert!(FlexEvents(&host).lookup(&base, "click").is_none());
+        let names: Vec<String> = FlexEvents(&host).all(&class).into_iter().map(|(n, _)| n).collect();
+        assert_eq!(names, ["select", "click", "change"]);
+    }
+}
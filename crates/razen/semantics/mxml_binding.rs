@@ -0,0 +1,94 @@
+use std::ops::Range;
+use crate::ns::*;
+
+/// A data binding of a MXML document, recorded on the document class
+/// through `Thingy::mxml_bindings()` once verified.
+///
+/// A binding is either one-way, in the form `{expression}`, or two-way,
+/// in the form `@{expression}`. Text with embedded bindings, such as
+/// `label="Hello, {name}!"`, is a one-way binding whose result is the
+/// concatenation of its text and of its expressions.
+pub struct MxmlBinding {
+    /// The tag of the component whose property is the destination.
+    pub element: Rc<MxmlElement>,
+    /// The destination property name.
+    pub property_name: String,
+    /// The view state the binding is specific to, as in `label.over="{expression}"`.
+    pub state: Option<String>,
+    /// The static type of the destination property.
+    pub property_type: Thingy,
+    /// The code the source expressions are parsed from.
+    pub code: Rc<MxmlCode>,
+    /// The source expressions, in the order they appear.
+    pub sources: Vec<Rc<Expression>>,
+    /// The text surrounding the source expressions, if the binding is
+    /// embedded in text; there is always one more part than sources.
+    pub text_parts: Option<Vec<String>>,
+    /// The bindable properties accessed by the source expressions,
+    /// along with the event dispatched when each of them changes.
+    pub watched_properties: Vec<(Thingy, String)>,
+    pub two_way: bool,
+    pub location: Location,
+}
+
+impl MxmlBinding {
+    /// Splits a value into text parts and the byte ranges of the `{...}`
+    /// expressions it embeds, relative to the value, or returns `None`
+    /// if it embeds none. `\{` does not start an expression.
+    pub fn split(value: &str) -> Option<(Vec<String>, Vec<Range<usize>>)> {
+        let mut text_parts = vec![];
+        let mut ranges = vec![];
+        let mut text = String::new();
+        let mut chars = value.char_indices().peekable();
+        while let Some((i, ch)) = chars.next() {
+            if ch == '\\' && chars.peek().is_some_and(|(_, ch)| *ch == '{') {
+                text.push('{');
+                chars.next();
+                continue;
+            }
+            if ch != '{' {
+                text.push(ch);
+                continue;
+            }
+            // Find the matching brace.
+            let mut depth = 1;
+            let mut end = None;
+            for (j, ch) in chars.by_ref() {
+                if ch == '{' {
+                    depth += 1;
+                } else if ch == '}' {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = Some(j);
+                        break;
+                    }
+                }
+            }
+            let end = end?;
+            text_parts.push(std::mem::take(&mut text));
+            ranges.push(i + 1..end);
+        }
+        text_parts.push(text);
+        if ranges.is_empty() { None } else { Some((text_parts, ranges)) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    #[test]
+    fn test_split() {
+        assert!(MxmlBinding::split("plain text").is_none());
+        assert!(MxmlBinding::split("\\{escaped}").is_none());
+        assert!(MxmlBinding::split("{unterminated").is_none());
+        let (parts, ranges) = MxmlBinding::split("{a.b}").unwrap();
+        assert_eq!(parts, ["", ""]);
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(ranges[0], 1..4);
+        let (parts, ranges) = MxmlBinding::split("Hello, {f({x: 1})}! \\{").unwrap();
+        assert_eq!(parts, ["Hello, ", "! {"]);
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(ranges[0], 8..17);
+    }
+}
@@ -0,0 +1,136 @@
+use crate::ns::*;
+
+/// ActionScript code embedded in a MXML document, such as an event handler
+/// or the content of a `<fx:Script>` tag.
+///
+/// Attribute values and character data may contain XML entities, as in
+/// `click="if (a &amp;&amp; b) f()"`. Code without entities is parsed in place,
+/// in the compilation unit of the document. Code with entities is decoded into
+/// its own compilation unit, along with the offset in the document of every byte
+/// of the decoded text, through which `original_location()` maps locations in the
+/// code back to the document. The verifier nests the compilation unit of decoded
+/// code in the document's, so that its diagnostics are reported with the document.
+pub struct MxmlCode {
+    /// The compilation unit to parse.
+    pub compilation_unit: Rc<CompilationUnit>,
+    /// The byte range to parse in the compilation unit, or `None` for the whole unit.
+    pub byte_range: Option<(usize, usize)>,
+    /// For decoded code, the compilation unit of the document and the offset
+    /// in it of every byte of the decoded text, followed by the end offset.
+    offset_map: Option<(Rc<CompilationUnit>, Vec<usize>)>,
+}
+
+impl MxmlCode {
+    pub fn new(compilation_unit: &Rc<CompilationUnit>, byte_range: Option<(usize, usize)>) -> Rc<Self> {
+        Rc::new(Self {
+            compilation_unit: compilation_unit.clone(),
+            byte_range,
+            offset_map: None,
+        })
+    }
+
+    /// Code in the byte range `start..end` of a MXML document, such as an attribute
+    /// value or character data, decoding its XML entities. The compilation unit of
+    /// decoded code is not nested in the document's.
+    pub fn decode(document: &Rc<CompilationUnit>, start: usize, end: usize) -> Rc<Self> {
+        let text = &document.text()[start..end];
+        let (decoded, offsets) = Self::decode_entities(text, start);
+        if decoded == text {
+            return Self::new(document, Some((start, end)));
+        }
+        let compilation_unit = CompilationUnit::new(document.file_path(), decoded);
+        compilation_unit.set_compiler_options(document.compiler_options());
+        Rc::new(Self {
+            compilation_unit,
+            byte_range: None,
+            offset_map: Some((document.clone(), offsets)),
+        })
+    }
+
+    /// Whether the code is decoded into its own compilation unit.
+    pub fn is_decoded(&self) -> bool {
+        self.offset_map.is_some()
+    }
+
+    /// The location of the code in its compilation unit.
+    pub fn location(&self) -> Location {
+        let (start, end) = self.byte_range.unwrap_or((0, self.compilation_unit.text().len()));
+        Location::with_offsets(&self.compilation_unit, start, end)
+    }
+
+    /// The code, with its XML entities decoded.
+    pub fn text(&self) -> String {
+        let text = self.compilation_unit.text();
+        match self.byte_range {
+            Some((start, end)) => text[start..end].to_owned(),
+            None => text.clone(),
+        }
+    }
+
+    /// Maps a location in the code to its location in the document.
+    pub fn original_location(&self, location: &Location) -> Location {
+        let Some((document, offsets)) = &self.offset_map else {
+            return location.clone();
+        };
+        if !Rc::ptr_eq(&location.compilation_unit(), &self.compilation_unit) {
+            return location.clone();
+        }
+        let map = |offset: usize| offsets[offset.min(offsets.len() - 1)];
+        Location::with_offsets(document, map(location.first_offset()), map(location.last_offset()))
+    }
+
+    /// Decodes the XML entities of text at the offset `start` of a document, returning
+    /// the decoded text and the offset in the document of each of its bytes, followed
+    /// by the end offset. An `&` that does not start an entity is kept as is.
+    fn decode_entities(text: &str, start: usize) -> (String, Vec<usize>) {
+        let mut decoded = String::new();
+        let mut offsets = vec![];
+        let mut i = 0;
+        while i < text.len() {
+            let rest = &text[i..];
+            let entity = rest.strip_prefix('&')
+                .and_then(|r| r.find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '#')).filter(|j| r[*j..].starts_with(';')))
+                .map(|j| &rest[..j + 2])
+                .map(|entity| (entity, unescape_xml(entity)))
+                .filter(|(entity, ch)| ch != entity);
+            let (length, ch) = match entity {
+                Some((entity, ch)) => (entity.len(), ch),
+                None => {
+                    let ch = rest.chars().next().unwrap();
+                    (ch.len_utf8(), ch.to_string())
+                },
+            };
+            offsets.extend(std::iter::repeat_n(start + i, ch.len()));
+            decoded.push_str(&ch);
+            i += length;
+        }
+        offsets.push(start + text.len());
+        (decoded, offsets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    #[test]
+    fn test_mxml_code() {
+        let document = CompilationUnit::new(None, "<a b=\"x &amp;&amp; &lt;y &unknown; &\"/>".into());
+        let code = MxmlCode::decode(&document, 6, 36);
+        assert_eq!(code.text(), "x && <y &unknown; &");
+        assert!(code.is_decoded());
+        assert_eq!(code.location().text(), "x && <y &unknown; &");
+
+        // `<y` is `&lt;y` in the document.
+        let location = code.original_location(&Location::with_offsets(&code.compilation_unit, 5, 7));
+        assert_eq!(location.text(), "&lt;y");
+        let location = code.original_location(&Location::with_offsets(&code.compilation_unit, 2, 4));
+        assert_eq!(location.text(), "&amp;&amp;");
+
+        // Code without entities is parsed in place.
+        let code = MxmlCode::decode(&document, 1, 2);
+        assert!(!code.is_decoded() && Rc::ptr_eq(&code.compilation_unit, &document));
+        assert_eq!(code.byte_range, Some((1, 2)));
+        assert_eq!(code.text(), "a");
+    }
+}
@@ -0,0 +1,83 @@
+use std::path::Path;
+use crate::ns::*;
+
+/// Resolution of MXML components.
+///
+/// A MXML tag resolves to a component class through the namespace URI of its name:
+///
+/// * `*` stands for the top-level package, as in `xmlns:local="*"`.
+/// * A URI in the form `com.example.*` stands for the package `com.example`.
+/// * Any other URI, such as `library://ns.hydroper.com/razen/spark`, stands for a
+///   component manifest, defined through `SemanticHost::define_mxml_component()`.
+///
+/// Tags in a language namespace, such as `<fx:Script>`, do not name components.
+pub struct MxmlComponents<'a>(pub &'a SemanticHost);
+
+impl<'a> MxmlComponents<'a> {
+    /// The MXML language namespaces.
+    pub const LANGUAGE_NAMESPACES: [&'static str; 2] = [
+        "http://ns.adobe.com/mxml/2009",
+        "http://ns.hydroper.com/razen",
+    ];
+
+    pub fn is_language_namespace(uri: &str) -> bool {
+        Self::LANGUAGE_NAMESPACES.contains(&uri)
+    }
+
+    /// Resolves a component class, returning `None` if it is not found.
+    pub fn resolve(&self, uri: &str, local_name: &str) -> Option<Thingy> {
+        let qualified_name = if uri == "*" {
+            local_name.to_owned()
+        } else if let Some(package_name) = uri.strip_suffix(".*") {
+            format!("{package_name}.{local_name}")
+        } else {
+            self.0.mxml_component_name(uri, local_name)?
+        };
+        self.0.lookup_class(&qualified_name)
+    }
+
+    /// Returns the package name and class name of a MXML document. The package is
+    /// determined by the directory hierarchy of the file path relative to one of the
+    /// `sourcePath` directories; a document outside of them is in the top-level package.
+    pub fn document_class_name(file_path: &str, source_path: &[String]) -> (Vec<String>, String) {
+        let path = Path::new(file_path);
+        let class_name = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        for directory in source_path {
+            if let Ok(relative) = path.strip_prefix(directory) {
+                let package_name = relative.parent()
+                    .map(|p| p.components().map(|c| c.as_os_str().to_string_lossy().into_owned()).collect())
+                    .unwrap_or_default();
+                return (package_name, class_name);
+            }
+        }
+        (vec![], class_name)
+    }
+
+    /// Looks up a style declared through `[Style(name="styleName")]` meta-data
+    /// by a class or one of its superclasses, returning the meta-data.
+    pub fn lookup_style(&self, class: &Thingy, name: &str) -> Option<Rc<Metadata>> {
+        for class in class.descending_class_hierarchy(self.0).collect::<Vec<_>>() {
+            if class.is::<UnresolvedThingy>() {
+                break;
+            }
+            for metadata in class.metadata().iter() {
+                if metadata.name.0 != "Style" {
+                    continue;
+                }
+                let style_name = metadata.entries.as_ref().and_then(|entries| entries.iter().find_map(|entry| {
+                    if entry.key.as_ref().map(|(k, _)| k.as_str()) != Some("name") {
+                        return None;
+                    }
+                    match entry.value.as_ref() {
+                        MetadataValue::IdentifierString((s, _)) => Some(s.clone()),
+                        MetadataValue::String((s, _)) => Some(s.clone()),
+                    }
+                }));
+                if style_name.as_deref() == Some(name) {
+                    return Some(metadata);
+                }
+            }
+        }
+        None
+    }
+}
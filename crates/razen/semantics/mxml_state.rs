@@ -0,0 +1,64 @@
+use crate::ns::*;
+
+/// A view state of a MXML document, declared by a tag of the `states`
+/// property of the document's root tag, as in `<s:State name="over"/>`.
+///
+/// The states of a document are recorded on its class through
+/// `Thingy::mxml_states()`, along with the overrides that apply to each of them.
+pub struct MxmlState {
+    pub name: String,
+    /// The state groups the state belongs to, from its `stateGroups` attribute.
+    pub state_groups: Vec<String>,
+    pub location: Location,
+    pub overrides: SharedArray<MxmlStateOverride>,
+}
+
+/// A change that applies to a MXML document when it enters a view state.
+#[derive(Clone)]
+pub enum MxmlStateOverride {
+    /// A component tag present only in the state, due to
+    /// its `includeIn` or `excludeFrom` attribute.
+    AddChild(Rc<MxmlElement>),
+    /// A property value specific to the state, in the form `property.state="value"`.
+    SetProperty(MxmlStateValue),
+    /// A style value specific to the state.
+    SetStyle(MxmlStateValue),
+    /// An event handler specific to the state.
+    SetEventHandler(MxmlStateValue),
+}
+
+/// A property, style or event handler value specific to a view state.
+#[derive(Clone)]
+pub struct MxmlStateValue {
+    /// The component tag the value applies to.
+    pub element: Rc<MxmlElement>,
+    pub name: String,
+    /// The text value, which is empty if the value is given by component tags.
+    pub value: String,
+    pub location: Location,
+}
+
+impl MxmlState {
+    pub fn new(name: &str, state_groups: Vec<String>, location: Location) -> Self {
+        Self {
+            name: name.to_owned(),
+            state_groups,
+            location,
+            overrides: SharedArray::new(),
+        }
+    }
+
+    /// Returns the states a name refers to, which is either
+    /// the name of a state or the name of a state group.
+    pub fn resolve(states: &[Rc<MxmlState>], name: &str) -> Vec<Rc<MxmlState>> {
+        states.iter().filter(|s| s.name == name || s.state_groups.iter().any(|g| g == name)).cloned().collect()
+    }
+
+    /// Splits a MXML attribute or tag name in the form `name.state`.
+    pub fn split_name(name: &str) -> (&str, Option<&str>) {
+        match name.split_once('.') {
+            Some((name, state)) => (name, Some(state)),
+            None => (name, None),
+        }
+    }
+}
@@ -1,6 +1,5 @@
 use crate::ns::*;
 
-use num_traits::FromPrimitive;
 // use num_traits::{One, Zero};
 // use num_bigint::BigInt;
 use std::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Rem, Shl, Shr, Sub};
@@ -29,11 +28,11 @@ impl Add for NumberVariant {
             },
             Self::Int(v) => {
                 let rhs = rhs.force_int();
-                Self::Int(v.checked_add(rhs).unwrap_or(0))
+                Self::Int(v.wrapping_add(rhs))
             },
             Self::Uint(v) => {
                 let rhs = rhs.force_uint();
-                Self::Uint(v.checked_add(rhs).unwrap_or(0))
+                Self::Uint(v.wrapping_add(rhs))
             },
         }
     }
@@ -53,11 +52,11 @@ impl Sub for NumberVariant {
             },
             Self::Int(v) => {
                 let rhs = rhs.force_int();
-                Self::Int(v.checked_sub(rhs).unwrap_or(0))
+                Self::Int(v.wrapping_sub(rhs))
             },
             Self::Uint(v) => {
                 let rhs = rhs.force_uint();
-                Self::Uint(v.checked_sub(rhs).unwrap_or(0))
+                Self::Uint(v.wrapping_sub(rhs))
             },
         }
     }
@@ -77,11 +76,11 @@ impl Mul for NumberVariant {
             },
             Self::Int(v) => {
                 let rhs = rhs.force_int();
-                Self::Int(v.checked_mul(rhs).unwrap_or(0))
+                Self::Int(v.wrapping_mul(rhs))
             },
             Self::Uint(v) => {
                 let rhs = rhs.force_uint();
-                Self::Uint(v.checked_mul(rhs).unwrap_or(0))
+                Self::Uint(v.wrapping_mul(rhs))
             },
         }
     }
@@ -141,8 +140,8 @@ impl std::ops::Neg for NumberVariant {
         match self {
             Self::Float(v) => Self::Float(-v),
             Self::Number(v) => Self::Number(-v),
-            Self::Int(v) => Self::Int(-v),
-            Self::Uint(v) => Self::Uint(v),
+            Self::Int(v) => Self::Int(v.wrapping_neg()),
+            Self::Uint(v) => Self::Uint(v.wrapping_neg()),
         }
     }
 }
@@ -152,12 +151,12 @@ impl BitAnd for NumberVariant {
     fn bitand(self, rhs: Self) -> Self::Output {
         match self {
             Self::Float(v) => {
-                let rhs = rhs.force_float();
-                Self::Float(f32::from_u32(unsafe { v.to_int_unchecked::<u32>() } & unsafe { rhs.to_int_unchecked::<u32>() }).unwrap_or(0.0))
+                let rhs = rhs.force_int();
+                Self::Float((to_int32(v as f64) & rhs) as f32)
             },
             Self::Number(v) => {
-                let rhs = rhs.force_double();
-                Self::Number(f64::from_u32(unsafe { v.to_int_unchecked::<u32>() } & unsafe { rhs.to_int_unchecked::<u32>() }).unwrap_or(0.0))
+                let rhs = rhs.force_int();
+                Self::Number((to_int32(v) & rhs) as f64)
             },
             Self::Int(v) => {
                 let rhs = rhs.force_int();
@@ -176,12 +175,12 @@ impl BitXor for NumberVariant {
     fn bitxor(self, rhs: Self) -> Self::Output {
         match self {
             Self::Float(v) => {
-                let rhs = rhs.force_float();
-                Self::Float(f32::from_u32(unsafe { v.to_int_unchecked::<u32>() } ^ unsafe { rhs.to_int_unchecked::<u32>() }).unwrap_or(0.0))
+                let rhs = rhs.force_int();
+                Self::Float((to_int32(v as f64) ^ rhs) as f32)
             },
             Self::Number(v) => {
-                let rhs = rhs.force_double();
-                Self::Number(f64::from_u32(unsafe { v.to_int_unchecked::<u32>() } ^ unsafe { rhs.to_int_unchecked::<u32>() }).unwrap_or(0.0))
+                let rhs = rhs.force_int();
+                Self::Number((to_int32(v) ^ rhs) as f64)
             },
             Self::Int(v) => {
                 let rhs = rhs.force_int();
@@ -200,12 +199,12 @@ impl BitOr for NumberVariant {
     fn bitor(self, rhs: Self) -> Self::Output {
         match self {
             Self::Float(v) => {
-                let rhs = rhs.force_float();
-                Self::Float(f32::from_u32(unsafe { v.to_int_unchecked::<u32>() } | unsafe { rhs.to_int_unchecked::<u32>() }).unwrap_or(0.0))
+                let rhs = rhs.force_int();
+                Self::Float((to_int32(v as f64) | rhs) as f32)
             },
             Self::Number(v) => {
-                let rhs = rhs.force_double();
-                Self::Number(f64::from_u32(unsafe { v.to_int_unchecked::<u32>() } | unsafe { rhs.to_int_unchecked::<u32>() }).unwrap_or(0.0))
+                let rhs = rhs.force_int();
+                Self::Number((to_int32(v) | rhs) as f64)
             },
             Self::Int(v) => {
                 let rhs = rhs.force_int();
@@ -219,54 +218,49 @@ impl BitOr for NumberVariant {
     }
 }
 
+/// The shift count is masked to the five lowest bits, as in ECMAScript.
 impl Shl for NumberVariant {
     type Output = Self;
     fn shl(self, rhs: Self) -> Self::Output {
+        let count = rhs.force_uint() & 0x1F;
         match self {
-            Self::Float(v) => {
-                let rhs = rhs.force_float();
-                Self::Float(f32::from_u32(unsafe { v.to_int_unchecked::<u32>() }.checked_shl(unsafe { rhs.to_int_unchecked::<u32>() }).unwrap_or(0)).unwrap_or(0.0))
-            },
-            Self::Number(v) => {
-                let rhs = rhs.force_double();
-                Self::Number(f64::from_u32(unsafe { v.to_int_unchecked::<u32>() }.checked_shl(unsafe { rhs.to_int_unchecked::<u32>() }).unwrap_or(0)).unwrap_or(0.0))
-            },
-            Self::Int(v) => {
-                let rhs = rhs.force_int();
-                Self::Int(v.checked_shl(rhs.try_into().unwrap_or(0)).unwrap_or(0))
-            },
-            Self::Uint(v) => {
-                let rhs = rhs.force_uint();
-                Self::Uint(v.checked_shl(rhs).unwrap_or(0))
-            },
+            Self::Float(v) => Self::Float((to_int32(v as f64) << count) as f32),
+            Self::Number(v) => Self::Number((to_int32(v) << count) as f64),
+            Self::Int(v) => Self::Int(v << count),
+            Self::Uint(v) => Self::Uint(v << count),
         }
     }
 }
 
+/// The shift count is masked to the five lowest bits, as in ECMAScript.
 impl Shr for NumberVariant {
     type Output = Self;
     fn shr(self, rhs: Self) -> Self::Output {
+        let count = rhs.force_uint() & 0x1F;
         match self {
-            Self::Float(v) => {
-                let rhs = rhs.force_float();
-                Self::Float(f32::from_u32(unsafe { v.to_int_unchecked::<u32>() }.checked_shr(unsafe { rhs.to_int_unchecked::<u32>() }).unwrap_or(0)).unwrap_or(0.0))
-            },
-            Self::Number(v) => {
-                let rhs = rhs.force_double();
-                Self::Number(f64::from_u32(unsafe { v.to_int_unchecked::<u32>() }.checked_shr(unsafe { rhs.to_int_unchecked::<u32>() }).unwrap_or(0)).unwrap_or(0.0))
-            },
-            Self::Int(v) => {
-                let rhs = rhs.force_int();
-                Self::Int(v.checked_shr(rhs.try_into().unwrap_or(0)).unwrap_or(0))
-            },
-            Self::Uint(v) => {
-                let rhs = rhs.force_uint();
-                Self::Uint(v.checked_shr(rhs).unwrap_or(0))
-            },
+            Self::Float(v) => Self::Float((to_int32(v as f64) >> count) as f32),
+            Self::Number(v) => Self::Number((to_int32(v) >> count) as f64),
+            Self::Int(v) => Self::Int(v >> count),
+            Self::Uint(v) => Self::Uint(v >> count),
         }
     }
 }
 
+/// Converts a number to a 32-bit signed integer as the ECMAScript
+/// `ToInt32` operation, wrapping around on overflow.
+fn to_int32(v: f64) -> i32 {
+    to_uint32(v) as i32
+}
+
+/// Converts a number to a 32-bit unsigned integer as the ECMAScript
+/// `ToUint32` operation, wrapping around on overflow.
+fn to_uint32(v: f64) -> u32 {
+    if !v.is_finite() {
+        return 0;
+    }
+    v.trunc().rem_euclid(4294967296.0) as u32
+}
+
 impl NumberVariant {
     pub fn zero(type_thing: &Thingy, host: &SemanticHost) -> Self {
         if type_thing == &host.number_type() {
@@ -402,35 +396,22 @@ impl NumberVariant {
 
     pub fn bitwise_not(&self) -> Self {
         match self {
-            Self::Float(v) => Self::Float(f32::from_u32(unsafe { !v.to_int_unchecked::<u32>() }).unwrap_or(0.0)),
-            Self::Number(v) => Self::Number(f64::from_u32(unsafe { !v.to_int_unchecked::<u32>() }).unwrap_or(0.0)),
+            Self::Float(v) => Self::Float(!to_int32(*v as f64) as f32),
+            Self::Number(v) => Self::Number(!to_int32(*v) as f64),
             Self::Int(v) => Self::Int(!v),
             Self::Uint(v) => Self::Uint(!v),
         }
     }
     
+    /// Shifts right filling with zeroes, masking the shift count
+    /// to the five lowest bits, as in ECMAScript.
     pub fn shift_right_unsigned(&self, rhs: &Self) -> Self {
+        let count = rhs.force_uint() & 0x1F;
         match self {
-            Self::Float(v) => {
-                let rhs = rhs.force_float();
-                Self::Float(f32::from_u32(unsafe { v.to_int_unchecked::<u32>() }.checked_shr(unsafe { rhs.to_int_unchecked::<u32>() }).unwrap_or(0)).unwrap_or(0.0))
-            },
-            Self::Number(v) => {
-                let rhs = rhs.force_double();
-                Self::Number(f64::from_u32(unsafe { v.to_int_unchecked::<u32>() }.checked_shr(unsafe { rhs.to_int_unchecked::<u32>() }).unwrap_or(0)).unwrap_or(0.0))
-            },
-            Self::Int(v) => {
-                let rhs = rhs.force_int();
-                let uint1: u32 = (*v).try_into().unwrap_or(0);
-                let uint2: u32 = rhs.try_into().unwrap_or(0);
-                let v = uint1.checked_shr(uint2).unwrap_or(0);
-                let v: i32 = v.try_into().unwrap_or(0);
-                Self::Int(v)
-            },
-            Self::Uint(v) => {
-                let rhs = rhs.force_uint();
-                Self::Uint(v.checked_shr(rhs).unwrap_or(0))
-            },
+            Self::Float(v) => Self::Float((to_uint32(*v as f64) >> count) as f32),
+            Self::Number(v) => Self::Number((to_uint32(*v) >> count) as f64),
+            Self::Int(v) => Self::Int(((*v as u32) >> count) as i32),
+            Self::Uint(v) => Self::Uint(v >> count),
         }
     }
 
@@ -486,7 +467,7 @@ impl NumberVariant {
         let number_type = host.number_type().defer()?;
         let float_type = host.float_type().defer()?;
         let int_type = host.int_type().defer()?;
-        let uint_type = host.int_type().defer()?;
+        let uint_type = host.uint_type().defer()?;
 
         Ok(if target_type == &number_type {
             Self::Number(self.force_double())
@@ -571,34 +552,24 @@ impl NumberVariant {
         }
     }
 
+    /// Converts to `int`, wrapping around on overflow.
     pub fn force_int(&self) -> i32 {
         match self {
-            Self::Float(v) =>
-                if v.is_infinite() {
-                    if v.is_sign_negative() { i32::MIN } else { i32::MAX }
-                } else if v.is_nan() { 0 } else { unsafe { v.to_int_unchecked() } },
-            Self::Number(v) =>
-                if v.is_infinite() {
-                    if v.is_sign_negative() { i32::MIN } else { i32::MAX }
-                } else if v.is_nan() { 0 } else { unsafe { v.to_int_unchecked() } },
-            Self::Int(v) => (*v).try_into().unwrap_or(0),
-            Self::Uint(v) => (*v).try_into().unwrap_or(0),
+            Self::Float(v) => to_int32(*v as f64),
+            Self::Number(v) => to_int32(*v),
+            Self::Int(v) => *v,
+            Self::Uint(v) => *v as i32,
         }
     }
 
+    /// Converts to `uint`, wrapping around on overflow.
     pub fn force_uint(&self) -> u32 {
         match self {
-            Self::Float(v) =>
-                if v.is_infinite() {
-                    if v.is_sign_negative() { u32::MIN } else { u32::MAX }
-                } else if v.is_nan() { 0 } else { unsafe { v.to_int_unchecked() } },
-            Self::Number(v) =>
-                if v.is_infinite() {
-                    if v.is_sign_negative() { u32::MIN } else { u32::MAX }
-                } else if v.is_nan() { 0 } else { unsafe { v.to_int_unchecked() } },
+            Self::Float(v) => to_uint32(*v as f64),
+            Self::Number(v) => to_uint32(*v),
             // Self::BigInt(v) => Self::Int(v.try_into().unwrap_or(0)),
-            Self::Int(v) => (*v).try_into().unwrap_or(0),
-            Self::Uint(v) => (*v).try_into().unwrap_or(0),
+            Self::Int(v) => *v as u32,
+            Self::Uint(v) => *v,
         }
     }
 }
\ No newline at end of file
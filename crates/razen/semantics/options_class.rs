@@ -0,0 +1,264 @@
+use crate::ns::*;
+
+/// Semantics of `[Options]` classes.
+///
+/// An `[Options]` class may be constructed from an object initializer
+/// given its type as the context type:
+///
+/// ```as3
+/// [Options]
+/// class ConnectOptions {
+///     var host: String;
+///     var port: Number = 80;
+///     var secure: Boolean;
+///     var retry: RetryOptions? = null;
+/// }
+///
+/// connect({ host: "example.com", secure: true });
+/// ```
+///
+/// Every instance variable, including variables inherited from base classes,
+/// is an option. An option must be specified unless it has an initializer or
+/// its type includes `null` or `undefined`.
+pub struct OptionsClass<'a>(pub &'a SemanticHost);
+
+impl<'a> OptionsClass<'a> {
+    /// Marks a class as an options class if it has the `[Options]` meta-data,
+    /// and marks instance variables declared with an initializer
+    /// in the given class block.
+    ///
+    /// This is to be invoked once the instance variables of a class definition
+    /// are declared; the verifier does not declare class definitions yet.
+    pub fn apply(&self, class: &Thingy, block: Option<&Rc<Block>>) {
+        if !class.metadata().iter().any(|m| m.name.0 == "Options") {
+            return;
+        }
+        class.set_is_options_class(true);
+
+        let Some(block) = block else {
+            return;
+        };
+        let prototype = class.prototype(self.0);
+        for drtv in &block.directives {
+            let Directive::VariableDefinition(defn) = drtv.as_ref() else {
+                continue;
+            };
+            if Attribute::find_static(&defn.attributes).is_some() {
+                continue;
+            }
+            for binding in &defn.bindings {
+                if binding.initializer.is_none() {
+                    continue;
+                }
+                let Expression::QualifiedIdentifier(id) = binding.destructuring.destructuring.as_ref() else {
+                    continue;
+                };
+                let Some((local_name, _)) = id.to_identifier_name() else {
+                    continue;
+                };
+                if let Ok(Some(slot)) = prototype.get_in_any_ns(&local_name) {
+                    if slot.is::<OriginalVariableSlot>() {
+                        slot.set_has_initializer(true);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the options of a class: its instance variables and the instance
+    /// variables of its base classes, nearest first.
+    pub fn options(&self, class: &Thingy) -> Vec<Thingy> {
+        let mut r = vec![];
+        let mut names: Vec<QName> = vec![];
+        let mut c = Some(class.clone());
+        while let Some(c1) = c {
+            if !c1.is_class_type_possibly_after_sub() {
+                break;
+            }
+            for (name, slot) in c1.prototype(self.0).borrow().iter() {
+                if slot.is::<VariableSlot>() && !names.contains(name) {
+                    names.push(name.clone());
+                    r.push(slot.clone());
+                }
+            }
+            c = c1.extends_class(self.0);
+        }
+        r
+    }
+
+    /// Returns the option name closest to a misspelled name, if any is close enough.
+    pub fn closest_option_name(&self, class: &Thingy, name: &str) -> Option<String> {
+        let max_distance = (name.chars().count() / 3).max(1);
+        self.options(class).iter()
+            .map(|slot| slot.name().local_name())
+            .map(|candidate| (edit_distance(name, &candidate), candidate))
+            .filter(|(d, _)| *d <= max_distance)
+            .min()
+            .map(|(_, candidate)| candidate)
+    }
+}
+
+/// Edit distance between two strings, by characters, counting
+/// a transposition of two adjacent characters as a single edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            d[i][j] = (d[i - 1][j] + 1).min(d[i][j - 1] + 1).min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    #[test]
+    fn test_options_class() {
+        let host = Rc::new(SemanticHost::new(SemanticHostOptions::default()));
+        let object_type = define_class(&host, &host.top_level_package(), "Object", None);
+        for name in ["String", "Boolean", "Number", "int", "uint", "float", "Namespace"] {
+            define_class(&host, &host.top_level_package(), name, Some(&object_type));
+        }
+
+        let cu = CompilationUnit::new(None, r#"
+            package {
+                [Options]
+                class BaseOptions {
+                    var secure: Boolean;
+                }
+                [Options]
+                class ConnectOptions extends BaseOptions {
+                    var port: Number;
+                    var timeout: Number = 30;
+                }
+            }
+        "#.into());
+        let program = ParserFacade(&cu, ParserOptions::default()).parse_program();
+        let public_ns = host.top_level_package().public_ns().unwrap();
+        let mut classes = vec![];
+        for (i, fields) in [vec!["secure"], vec!["port", "timeout"]].into_iter().enumerate() {
+            let Directive::ClassDefinition(defn) = program.packages[0].block.directives[i].as_ref() else {
+                panic!();
+            };
+            let class = define_class(&host, &host.top_level_package(), &defn.name.0, None);
+            class.set_extends_class(Some(classes.last().unwrap_or(&object_type).clone()));
+            for m in Attribute::find_metadata(&defn.attributes) {
+                class.metadata().push(m);
+            }
+            for field in fields {
+                let field_type = if field == "secure" { host.boolean_type() } else { host.number_type() };
+                let name = host.factory().create_qname(&public_ns, field.into());
+                class.prototype(&host).set(name.clone(), host.factory().create_variable_slot(&name, false, &field_type));
+            }
+            OptionsClass(&host).apply(&class, Some(&defn.block));
+            classes.push(class);
+        }
+        let options_class = classes[1].clone();
+        assert!(options_class.is_options_class());
+        assert_eq!(OptionsClass(&host).options(&options_class).len(), 3);
+
+        let cu = CompilationUnit::new(None, "{ prot: 80 }".into());
+        let exp = ParserFacade(&cu, ParserOptions::default()).parse_expression();
+        let mut verifier = Verifier::new(&host);
+        let scope = host.factory().create_scope();
+        scope.import_list().push(host.factory().create_package_wildcard_import(&host.top_level_package(), None));
+        verifier.set_scope(&scope);
+        let _ = verifier.verify_expression(&exp, &VerifierExpressionContext {
+            context_type: Some(options_class.clone()),
+            ..default()
+        });
+
+        cu.sort_diagnostics();
+        let messages: Vec<String> = cu.nested_diagnostics().iter().map(|d| FxDiagnostic(d).format_message_english()).collect();
+        assert_eq!(messages, [
+            "Must specify option port, secure.",
+            "Unknown option prot for ConnectOptions. Did you mean port?",
+        ]);
+    }
+
+    #[test]
+    fn test_nested_and_enum_options() {
+        let host = Rc::new(SemanticHost::new(SemanticHostOptions::default()));
+        let object_type = define_class(&host, &host.top_level_package(), "Object", None);
+        for name in ["String", "Boolean", "Number", "int", "uint", "float", "Namespace"] {
+            define_class(&host, &host.top_level_package(), name, Some(&object_type));
+        }
+        let public_ns = host.top_level_package().public_ns().unwrap();
+
+        let mode = host.factory().create_enum_type(host.factory().create_qname(&public_ns, "Mode".into()), &public_ns);
+        mode.set_parent(Some(host.top_level_package()));
+        let fast_name = host.factory().create_qname(&public_ns, "FAST".into());
+        let fast = host.factory().create_variable_slot(&fast_name, true, &mode);
+        fast.set_parent(Some(mode.clone()));
+        mode.properties(&host).set(fast_name, fast.clone());
+        mode.enum_member_slot_mapping().set("fast".into(), fast);
+
+        let cu = CompilationUnit::new(None, r#"
+            package {
+                [Options]
+                class RetryOptions {
+                    var attempts: Number;
+                }
+                [Options]
+                class ConnectOptions {
+                    var retry: RetryOptions;
+                    var mode: Mode;
+                }
+            }
+        "#.into());
+        let program = ParserFacade(&cu, ParserOptions::default()).parse_program();
+        let retry_options = define_class(&host, &host.top_level_package(), "RetryOptions", None);
+        let connect_options = define_class(&host, &host.top_level_package(), "ConnectOptions", None);
+        let fields = [vec![("attempts", host.number_type())], vec![("retry", retry_options.clone()), ("mode", mode.clone())]];
+        for (i, (class, fields)) in [&retry_options, &connect_options].into_iter().zip(fields).enumerate() {
+            let Directive::ClassDefinition(defn) = program.packages[0].block.directives[i].as_ref() else {
+                panic!();
+            };
+            class.set_extends_class(Some(object_type.clone()));
+            for m in Attribute::find_metadata(&defn.attributes) {
+                class.metadata().push(m);
+            }
+            for (field, field_type) in fields {
+                let name = host.factory().create_qname(&public_ns, field.into());
+                class.prototype(&host).set(name.clone(), host.factory().create_variable_slot(&name, false, &field_type));
+            }
+            OptionsClass(&host).apply(class, Some(&defn.block));
+        }
+
+        let verify = |source: &str| -> Vec<String> {
+            let cu = CompilationUnit::new(None, source.into());
+            let exp = ParserFacade(&cu, ParserOptions::default()).parse_expression();
+            let mut verifier = Verifier::new(&host);
+            let scope = host.factory().create_scope();
+            scope.import_list().push(host.factory().create_package_wildcard_import(&host.top_level_package(), None));
+            verifier.set_scope(&scope);
+            let _ = verifier.verify_expression(&exp, &VerifierExpressionContext {
+                context_type: Some(connect_options.clone()),
+                ..default()
+            });
+            cu.sort_diagnostics();
+            cu.nested_diagnostics().iter().map(|d| FxDiagnostic(d).format_message_english()).collect()
+        };
+
+        assert_eq!(verify(r#"{ retry: { attempts: 3 }, mode: "fast" }"#), Vec::<String>::new());
+        assert_eq!(verify(r#"{ retry: { atempts: 3 }, mode: "slow" }"#), [
+            "Must specify option attempts.",
+            "Unknown option atempts for RetryOptions. Did you mean attempts?",
+            "Found no member slow in Mode.",
+        ]);
+    }
+}
@@ -75,7 +75,7 @@ impl<'a> PropertyLookup<'a> {
             };
 
             // Qualifier must be a compile-time namespace, otherwise return static dynamic reference.
-            if qual.as_ref().map(|q| q.is_namespace_or_ns_constant()).unwrap_or(true) {
+            if !qual.as_ref().map(|q| q.is_namespace_or_ns_constant()).unwrap_or(true) {
                 let k = map_defer_error(PropertyLookupKey::LocalName(local_name).computed_or_local_name(self.0))?;
                 return Ok(Some(self.0.factory().create_static_dynamic_reference_value(base, qual, &k)));
             }
@@ -108,7 +108,7 @@ impl<'a> PropertyLookup<'a> {
             };
 
             // Qualifier must be a compile-time namespace, otherwise return static dynamic reference.
-            if qual.as_ref().map(|q| q.is_namespace_or_ns_constant()).unwrap_or(true) {
+            if !qual.as_ref().map(|q| q.is_namespace_or_ns_constant()).unwrap_or(true) {
                 let k = map_defer_error(PropertyLookupKey::LocalName(key).computed_or_local_name(self.0))?;
                 return Ok(Some(self.0.factory().create_static_dynamic_reference_value(base, qual, &k)));
             }
@@ -118,7 +118,20 @@ impl<'a> PropertyLookup<'a> {
 
         // For a value
         if base.is::<Value>() {
-            let base_type = defer(&base.static_type(self.0))?;
+            let mut base_type = defer(&base.static_type(self.0))?;
+
+            // Members of a value whose type is a type parameter are those of
+            // its bound, or those of Object if it is unbounded.
+            let mut visited_type_params = vec![];
+            while base_type.escape_of_non_nullable().is::<TypeParameterType>() {
+                let type_param = base_type.escape_of_non_nullable();
+                base_type = match type_param.bound() {
+                    Some(bound) if !visited_type_params.contains(&type_param) => defer(&bound)?,
+                    _ => defer(&self.0.object_type())?,
+                };
+                visited_type_params.push(type_param);
+            }
+
             let base_esc_type = base_type.escape_of_non_nullable();
 
             if base_esc_type.is::<InvalidationThingy>() {
@@ -151,11 +164,11 @@ impl<'a> PropertyLookup<'a> {
                 }
                 
                 // Attempt to index a tuple
-                if double_key.is_some() && base_esc_type.is::<TupleType>() {
-                    let index: usize = unsafe { double_key.unwrap().to_int_unchecked() };
-                    if index >= base_type.element_types().length() {
+                if let Some(double_key) = double_key.filter(|_| base_esc_type.is::<TupleType>()) {
+                    if double_key < 0.0 || double_key.fract() != 0.0 || double_key >= base_esc_type.element_types().length() as f64 {
                         return Ok(None);
                     }
+                    let index = double_key as usize;
                     return Ok(Some(self.0.factory().create_tuple_reference_value(&base, index)));
                 }
 
@@ -220,7 +233,9 @@ impl<'a> PropertyLookup<'a> {
                     }
                 }
             } else if base_esc_type.is_interface_type_possibly_after_sub() {
-                for itrfc in base_esc_type.all_ascending_types(self.0).iter().rev() {
+                let mut itrfcs = vec![base_esc_type.clone()];
+                itrfcs.extend(base_esc_type.all_ascending_types(self.0).into_iter().rev());
+                for itrfc in itrfcs.iter() {
                     // Defer if unresolved
                     defer(itrfc)?;
 
@@ -247,7 +262,7 @@ impl<'a> PropertyLookup<'a> {
             };
 
             // Qualifier must be a compile-time namespace.
-            if qual.as_ref().map(|q| q.is_namespace_or_ns_constant()).unwrap_or(true) {
+            if !qual.as_ref().map(|q| q.is_namespace_or_ns_constant()).unwrap_or(true) {
                 return Ok(None);
             }
 
@@ -478,4 +493,42 @@ impl<'a> PropertyLookup<'a> {
 
         Ok(r)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    #[test]
+    fn test_lookup_qualifier() {
+        let host = SemanticHost::new(SemanticHostOptions::default());
+        let public_ns = host.top_level_package().public_ns().unwrap();
+        define_class(&host, &host.top_level_package(), "String", None);
+        let package = host.factory().create_package(["com", "example"]);
+        let class = define_class(&host, &package, "Foo", None);
+        let x_name = host.factory().create_qname(&public_ns, "x".into());
+        let x = host.factory().create_variable_slot(&x_name, false, &host.any_type());
+        x.set_parent(Some(class.clone()));
+        class.properties(&host).set(x_name, x.clone());
+        let y_name = host.factory().create_qname(&package.public_ns().unwrap(), "y".into());
+        let y = host.factory().create_variable_slot(&y_name, false, &host.any_type());
+        y.set_parent(Some(package.clone()));
+        package.properties(&host).set(y_name, y.clone());
+
+        let open_ns_set = SharedArray::new();
+        let lookup = |base: &Thingy, qual: Option<Thingy>, local_name: &str| {
+            PropertyLookup(&host).lookup_in_object(base, &open_ns_set, qual, &PropertyLookupKey::LocalName(local_name.into())).ok().flatten()
+        };
+        let dynamic_qual = host.factory().create_value(&host.any_type());
+
+        // Unqualified names and compile-time namespaces resolve statically.
+        assert!(lookup(&class, None, "x").unwrap().is::<StaticReferenceValue>());
+        assert!(lookup(&class, Some(public_ns.clone()), "x").unwrap().is::<StaticReferenceValue>());
+        assert!(lookup(&class, None, "x").unwrap().property() == x);
+        assert!(lookup(&package, None, "y").unwrap().property() == y);
+
+        // Other qualifiers resolve dynamically, or not at all in packages.
+        assert!(lookup(&class, Some(dynamic_qual.clone()), "x").unwrap().is::<StaticDynamicReferenceValue>());
+        assert!(lookup(&package, Some(dynamic_qual), "y").is_none());
+    }
+}
@@ -7,9 +7,13 @@ pub struct SemanticHost {
     project_path: Option<String>,
     config_constants: SharedMap<String, String>,
     config_constants_eval: SharedMap<String, Thingy>,
+    config_constant_locations: SharedMap<String, Location>,
     env_cache: RefCell<Option<Rc<HashMap<String, String>>>>,
 
     unused_things: Rc<RefCell<Vec<Thingy>>>,
+    embedded_assets: RefCell<Vec<Rc<EmbeddedAsset>>>,
+    mxml_manifests: RefCell<HashMap<String, HashMap<String, String>>>,
+    style_sheets: RefCell<Vec<Rc<CssDocument>>>,
 
     pub(crate) explicit_namespaces: RefCell<HashMap<String, Thingy>>,
     pub(crate) user_namespaces: RefCell<HashMap<String, Thingy>>,
@@ -85,6 +89,7 @@ impl SemanticHost {
             project_path: options.project_path.clone(),
             config_constants: SharedMap::new(),
             config_constants_eval: SharedMap::new(),
+            config_constant_locations: SharedMap::new(),
             env_cache: RefCell::new(None),
 
             explicit_namespaces,
@@ -97,6 +102,9 @@ impl SemanticHost {
             unresolved_thingy,
 
             unused_things: Rc::new(RefCell::new(vec![])),
+            embedded_assets: RefCell::new(vec![]),
+            mxml_manifests: RefCell::new(HashMap::new()),
+            style_sheets: RefCell::new(vec![]),
 
             meta_prop,
             meta_env_prop,
@@ -203,6 +211,7 @@ impl SemanticHost {
     pub fn clear_config_constants(&self) {
         self.config_constants.clone().clear();
         self.config_constants_eval.clone().clear();
+        self.config_constant_locations.clone().clear();
     }
 
     #[inline(always)]
@@ -210,6 +219,13 @@ impl SemanticHost {
         self.config_constants_eval.clone()
     }
 
+    /// The locations where configuration constants are defined,
+    /// for constants defined in a file.
+    #[inline(always)]
+    pub(crate) fn config_constant_locations(&self) -> SharedMap<String, Location> {
+        self.config_constant_locations.clone()
+    }
+
     pub fn empty_empty_qname(&self) -> QName {
         self.empty_empty_qname.borrow().as_ref().unwrap().clone()
     }
@@ -241,6 +257,58 @@ impl SemanticHost {
         p
     }
 
+    /// Looks up a class by its fully qualified name, such as `flash.events.Event`.
+    pub fn lookup_class(&self, name: &str) -> Option<Thingy> {
+        let (package_name, local_name) = name.rsplit_once('.').unwrap_or(("", name));
+        let package = self.factory().create_package(package_name.split('.').filter(|s| !s.is_empty()));
+        let name = self.factory().create_qname(&package.public_ns()?, local_name.to_owned());
+        package.properties(self).get(&name).filter(|c| c.is::<ClassType>())
+    }
+
+    /// Assets embedded through the `[Embed]` meta-data, in the order
+    /// they were verified.
+    pub fn embedded_assets(&self) -> Vec<Rc<EmbeddedAsset>> {
+        self.embedded_assets.borrow().clone()
+    }
+
+    pub fn add_embedded_asset(&self, asset: Rc<EmbeddedAsset>) {
+        self.embedded_assets.borrow_mut().push(asset);
+    }
+
+    /// Defines a component of a MXML component manifest, mapping a local name
+    /// in a namespace URI to a fully qualified class name such as
+    /// `spark.components.Button`.
+    pub fn define_mxml_component(&self, uri: &str, local_name: &str, qualified_name: &str) {
+        self.mxml_manifests.borrow_mut().entry(uri.to_owned()).or_default().insert(local_name.to_owned(), qualified_name.to_owned());
+    }
+
+    /// Returns the fully qualified class name of a component
+    /// defined in a MXML component manifest.
+    pub fn mxml_component_name(&self, uri: &str, local_name: &str) -> Option<String> {
+        self.mxml_manifests.borrow().get(uri).and_then(|m| m.get(local_name).cloned())
+    }
+
+    /// Style sheets applied to every component, such as those of a theme.
+    pub fn style_sheets(&self) -> Vec<Rc<CssDocument>> {
+        self.style_sheets.borrow().clone()
+    }
+
+    pub fn add_style_sheet(&self, style_sheet: Rc<CssDocument>) {
+        self.style_sheets.borrow_mut().push(style_sheet);
+    }
+
+    /// Computes the styles in effect for a component class from the style sheets
+    /// of `style_sheets()`, without a parent component or style names.
+    pub fn computed_styles(&self, class: &Thingy) -> ComputedStyles {
+        StyleInheritance(self).compute(class, &self.style_sheets(), None, None)
+    }
+
+    /// Computes the styles in effect for each component tag of a verified
+    /// MXML document. See `StyleInheritance::compute_mxml()`.
+    pub fn computed_mxml_styles(&self, mxml: &Rc<Mxml>) -> Vec<(Rc<MxmlElement>, ComputedStyles)> {
+        StyleInheritance(self).compute_mxml(mxml)
+    }
+
     pub fn invalidation_thingy(&self) -> Thingy {
         self.invalidation_thingy.clone()
     }
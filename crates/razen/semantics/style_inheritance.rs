@@ -0,0 +1,421 @@
+use crate::ns::*;
+
+/// Computes the styles in effect for components.
+///
+/// Style values are applied in increasing order of precedence:
+///
+/// 1. declarations of `global` rules;
+/// 2. inheritable styles of the parent component, which are styles declared with
+///    `inherit="yes"` and, for styles not declared by `[Style]` meta-data,
+///    those of `ComputedStyles::INHERITING_STYLES`;
+/// 3. declarations of type selectors matching the class or one of its superclasses,
+///    from the most general class to the class itself;
+/// 4. declarations of selectors whose class conditions all name one of the style names
+///    of the component, given by the `styleName` attribute of a MXML tag: first those
+///    without a type, as in `.emphasized`, then those with a type matching the class or
+///    one of its superclasses, as in `s|Button.emphasized`, from the most general class;
+/// 5. style attributes of a MXML tag.
+///
+/// Later style sheets and later rules override earlier ones. Rules whose selectors
+/// have other conditions, as in `s|Button:over`, or combinators, as well as the rules
+/// of `@media` queries, are not applied.
+pub struct StyleInheritance<'a>(pub &'a SemanticHost);
+
+/// The styles in effect for a component.
+#[derive(Clone, Default)]
+pub struct ComputedStyles {
+    styles: Vec<ComputedStyle>,
+}
+
+/// A style in effect for a component.
+#[derive(Clone)]
+pub struct ComputedStyle {
+    pub name: String,
+    pub value: ComputedStyleValue,
+    pub origin: StyleOrigin,
+    /// The `[Style]` meta-data declaring the style for the component. A style
+    /// without a definition is not declared by the component class.
+    pub definition: Option<StyleDefinition>,
+}
+
+#[derive(Clone)]
+pub enum ComputedStyleValue {
+    Css(Rc<CssPropertyValue>),
+    /// The text of a MXML attribute and its location.
+    Text(String, Location),
+}
+
+/// Where the value of a computed style comes from.
+#[derive(Clone, PartialEq)]
+pub enum StyleOrigin {
+    /// A `global` rule.
+    Global,
+    /// The parent component.
+    Inherited,
+    /// A type selector matching the given class.
+    TypeSelector(Thingy),
+    /// A selector with class conditions matching the style names of the component,
+    /// as written in the style sheet, such as `s|Button.emphasized`.
+    ClassSelector(String),
+    /// A MXML attribute.
+    Attribute,
+}
+
+impl ComputedStyles {
+    /// Styles that are inherited from parent components when
+    /// no `[Style]` meta-data declares them.
+    pub const INHERITING_STYLES: [&'static str; 5] = ["color", "fontFamily", "fontSize", "fontStyle", "fontWeight"];
+
+    pub fn get(&self, name: &str) -> Option<&ComputedStyle> {
+        self.styles.iter().find(|s| s.name == name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ComputedStyle> {
+        self.styles.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.styles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.styles.is_empty()
+    }
+
+    /// Sets a style, overriding any previous value of the same name.
+    pub fn set(&mut self, style: ComputedStyle) {
+        if let Some(existing) = self.styles.iter_mut().find(|s| s.name == style.name) {
+            *existing = style;
+        } else {
+            self.styles.push(style);
+        }
+    }
+}
+
+impl ComputedStyle {
+    /// Indicates whether the style flows from a component to its children.
+    pub fn is_inheritable(&self) -> bool {
+        match &self.definition {
+            Some(definition) => definition.inherit,
+            None => ComputedStyles::INHERITING_STYLES.contains(&self.name.as_str()),
+        }
+    }
+}
+
+impl<'a> StyleInheritance<'a> {
+    /// Computes the styles in effect for a component class given a list of style sheets,
+    /// the styles of its parent component and its space-separated style names.
+    pub fn compute(&self, class: &Thingy, style_sheets: &[Rc<CssDocument>], parent: Option<&ComputedStyles>, style_name: Option<&str>) -> ComputedStyles {
+        let mut styles = ComputedStyles::default();
+
+        for style_sheet in style_sheets {
+            Self::for_each_rule(style_sheet, |rule, _| {
+                if rule.selectors.iter().any(|s| Self::is_global_selector(s)) {
+                    self.apply_rule(&mut styles, class, rule, StyleOrigin::Global);
+                }
+            });
+        }
+
+        if let Some(parent) = parent {
+            for style in parent.iter().filter(|s| s.is_inheritable()) {
+                styles.set(ComputedStyle {
+                    name: style.name.clone(),
+                    value: style.value.clone(),
+                    origin: StyleOrigin::Inherited,
+                    definition: StyleSheets(self.0).lookup_style(class, &style.name),
+                });
+            }
+        }
+
+        let hierarchy: Vec<Thingy> = class.descending_class_hierarchy(self.0)
+            .take_while(|c| !c.is::<UnresolvedThingy>())
+            .collect();
+        for matched_class in hierarchy.iter().rev() {
+            for style_sheet in style_sheets {
+                Self::for_each_rule(style_sheet, |rule, namespaces| {
+                    if rule.selectors.iter().any(|s| self.simple_type_selector(s, namespaces).as_ref() == Some(matched_class)) {
+                        self.apply_rule(&mut styles, class, rule, StyleOrigin::TypeSelector(matched_class.clone()));
+                    }
+                });
+            }
+        }
+
+        let style_names: Vec<&str> = style_name.map(|s| s.split_whitespace().collect()).unwrap_or_default();
+        if style_names.is_empty() {
+            return styles;
+        }
+        let matched_classes = std::iter::once(None).chain(hierarchy.iter().rev().map(Some));
+        for matched_class in matched_classes {
+            for style_sheet in style_sheets {
+                Self::for_each_rule(style_sheet, |rule, namespaces| {
+                    let selector = rule.selectors.iter().find_map(|s| match self.class_selector(s, namespaces, &style_names) {
+                        Some((base, type_class)) if type_class.as_ref() == matched_class => Some(base),
+                        _ => None,
+                    });
+                    if let Some(selector) = selector {
+                        self.apply_rule(&mut styles, class, rule, StyleOrigin::ClassSelector(selector.location.text()));
+                    }
+                });
+            }
+        }
+
+        styles
+    }
+
+    /// Computes the styles in effect for each component tag of a verified MXML
+    /// document, in document order, from the style sheets of `SemanticHost::style_sheets()`
+    /// followed by those of the document class. The root tag is computed as the
+    /// document class. Tags inside `<fx:Declarations>` are not visual and are skipped.
+    pub fn compute_mxml(&self, mxml: &Rc<Mxml>) -> Vec<(Rc<MxmlElement>, ComputedStyles)> {
+        let mut result = vec![];
+        let Some(document) = self.0.node_mapping().get(mxml).filter(|c| c.is::<ClassType>()) else {
+            return result;
+        };
+        let mut style_sheets = self.0.style_sheets();
+        style_sheets.extend(document.style_sheets().iter());
+        if let Some(root) = child_elements(&mxml.content).into_iter().next() {
+            self.compute_element(&root, &document, &style_sheets, None, &mut result);
+        }
+        result
+    }
+
+    fn compute_element(&self, element: &Rc<MxmlElement>, class: &Thingy, style_sheets: &[Rc<CssDocument>], parent: Option<&ComputedStyles>, result: &mut Vec<(Rc<MxmlElement>, ComputedStyles)>) {
+        let style_name = MxmlSubverifier::find_attribute(element, "styleName");
+        let mut styles = self.compute(class, style_sheets, parent, style_name.as_ref().map(|a| a.value.0.as_str()));
+        for attr in &element.attributes {
+            if attr.xmlns || attr.name.prefix.is_some() {
+                continue;
+            }
+            // State-specific values do not apply to the base state.
+            let (name, state) = MxmlState::split_name(&attr.name.name);
+            if state.is_some() {
+                continue;
+            }
+            if let Some(definition) = StyleSheets(self.0).lookup_style(class, name) {
+                styles.set(ComputedStyle {
+                    name: name.to_owned(),
+                    value: ComputedStyleValue::Text(attr.value.0.clone(), attr.value.1.clone()),
+                    origin: StyleOrigin::Attribute,
+                    definition: Some(definition),
+                });
+            }
+        }
+        result.push((element.clone(), styles.clone()));
+        self.compute_children(element, style_sheets, &styles, result);
+    }
+
+    fn compute_children(&self, element: &Rc<MxmlElement>, style_sheets: &[Rc<CssDocument>], parent: &ComputedStyles, result: &mut Vec<(Rc<MxmlElement>, ComputedStyles)>) {
+        for child in child_elements(element.content.as_deref().unwrap_or(&[])) {
+            let Ok((uri, _)) = child.name.resolve_name(&child.namespace) else {
+                continue;
+            };
+            if MxmlComponents::is_language_namespace(&uri) {
+                continue;
+            }
+            match self.0.node_mapping().get(&child).filter(|c| c.is::<ClassType>()) {
+                Some(class) => self.compute_element(&child, &class, style_sheets, Some(parent), result),
+                // Property tags, as in `<s:layout>`, are transparent.
+                None => self.compute_children(&child, style_sheets, parent, result),
+            }
+        }
+    }
+
+    fn apply_rule(&self, styles: &mut ComputedStyles, class: &Thingy, rule: &CssRule, origin: StyleOrigin) {
+        for property in &rule.properties {
+            let name = &property.name.0;
+            styles.set(ComputedStyle {
+                name: name.clone(),
+                value: ComputedStyleValue::Css(property.value.clone()),
+                origin: origin.clone(),
+                definition: StyleSheets(self.0).lookup_style(class, name),
+            });
+        }
+    }
+
+    /// Calls a function for the rules of a style sheet that are not
+    /// inside a media query, with the namespaces defined before each rule.
+    fn for_each_rule(style_sheet: &Rc<CssDocument>, mut f: impl FnMut(&CssRule, &HashMap<String, String>)) {
+        let mut namespaces: HashMap<String, String> = HashMap::new();
+        for directive in &style_sheet.directives {
+            match directive.as_ref() {
+                CssDirective::NamespaceDefinition(defn) => {
+                    namespaces.insert(defn.prefix.0.clone(), defn.uri.0.clone());
+                },
+                CssDirective::Rule(rule) => f(rule, &namespaces),
+                _ => {},
+            }
+        }
+    }
+
+    fn is_global_selector(selector: &CssSelector) -> bool {
+        matches!(selector, CssSelector::Base(base)
+            if base.namespace_prefix.is_none()
+            && base.conditions.is_empty()
+            && base.element_name.as_ref().is_some_and(|(name, _)| name == StyleSheets::GLOBAL_SELECTOR))
+    }
+
+    /// Resolves a type selector without conditions.
+    fn simple_type_selector(&self, selector: &CssSelector, namespaces: &HashMap<String, String>) -> Option<Thingy> {
+        match selector {
+            CssSelector::Base(base) if base.conditions.is_empty() => StyleSheets(self.0).resolve_type_selector(base, namespaces),
+            _ => None,
+        }
+    }
+
+    /// Matches a selector whose conditions are all class conditions naming one of
+    /// `style_names`, returning it along with the class named by its type, if any.
+    fn class_selector<'b>(&self, selector: &'b CssSelector, namespaces: &HashMap<String, String>, style_names: &[&str]) -> Option<(&'b CssBaseSelector, Option<Thingy>)> {
+        let CssSelector::Base(base) = selector else {
+            return None;
+        };
+        if base.conditions.is_empty() || !base.conditions.iter().all(|c| matches!(c.as_ref(), CssSelectorCondition::Class((name, _)) if style_names.contains(&name.as_str()))) {
+            return None;
+        }
+        if !StyleSheets::is_type_selector(base) {
+            return Some((base, None));
+        }
+        Some((base, Some(StyleSheets(self.0).resolve_type_selector(base, namespaces)?)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    fn css(text: &str) -> Rc<CssDocument> {
+        let cu = CompilationUnit::new(None, text.into());
+        CssParserFacade(&cu, ParserOptions::default()).parse_document()
+    }
+
+    fn value_text(style: &ComputedStyle) -> String {
+        match &style.value {
+            ComputedStyleValue::Css(value) => match value.as_ref() {
+                CssPropertyValue::Number(n) => n.value.to_string(),
+                CssPropertyValue::String(s) => s.value.clone(),
+                CssPropertyValue::Color(c) => format!("#{:06X}", c.color_int),
+                _ => panic!(),
+            },
+            ComputedStyleValue::Text(text, _) => text.clone(),
+        }
+    }
+
+    fn describe(styles: &ComputedStyles) -> Vec<String> {
+        let mut names: Vec<String> = styles.iter().map(|s| {
+            let origin = match &s.origin {
+                StyleOrigin::Global => "global".to_owned(),
+                StyleOrigin::Inherited => "inherited".to_owned(),
+                StyleOrigin::TypeSelector(class) => class.to_string(),
+                StyleOrigin::ClassSelector(selector) => selector.clone(),
+                StyleOrigin::Attribute => "attribute".to_owned(),
+            };
+            format!("{}={} ({origin})", s.name, value_text(s))
+        }).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_style_inheritance() {
+        let host = Rc::new(SemanticHost::new(SemanticHostOptions::default()));
+        let top_level = host.top_level_package();
+        let object_type = define_class(&host, &top_level, "Object", None);
+        for name in ["Class", "Function", "String", "Boolean", "Number", "int", "uint", "float", "Namespace", "Array", "XML", "XMLList"] {
+            define_class(&host, &top_level, name, Some(&object_type));
+        }
+        define_class(&host, &host.factory().create_package(["flash", "utils"]), "Dictionary", Some(&object_type));
+        let spark = host.factory().create_package(["spark", "components"]);
+        let component = define_class(&host, &spark, "UIComponent", Some(&object_type));
+        let group = define_class(&host, &spark, "Group", Some(&component));
+        define_class(&host, &spark, "Application", Some(&group));
+        let button = define_class(&host, &spark, "Button", Some(&component));
+        define_property(&host, &component, "styleName", false, &host.string_type());
+        for name in ["Application", "Group", "Button", "UIComponent"] {
+            host.define_mxml_component("library://ns.hydroper.com/razen/spark", name, &format!("spark.components.{name}"));
+        }
+
+        let cu = CompilationUnit::new(None, r#"
+            package spark.components {
+                [Style(name="color", type="uint", format="Color", inherit="yes")]
+                [Style(name="fontSize", type="Number", inherit="yes")]
+                [Style(name="backgroundColor", type="uint", format="Color", inherit="no")]
+                class UIComponent {}
+
+                [Style(name="cornerRadius", type="Number")]
+                class Button {}
+            }
+        "#.into());
+        let program = ParserFacade(&cu, ParserOptions::default()).parse_program();
+        for (i, class) in [&component, &button].into_iter().enumerate() {
+            let Directive::ClassDefinition(defn) = program.packages[0].block.directives[i].as_ref() else {
+                panic!();
+            };
+            for m in Attribute::find_metadata(&defn.attributes) {
+                class.metadata().push(m);
+            }
+        }
+
+        host.add_style_sheet(css(r#"
+            @namespace s "library://ns.hydroper.com/razen/spark";
+            global { fontFamily: "Arial"; }
+            s|UIComponent { fontSize: 10; }
+            s|Button { cornerRadius: 2; }
+            s|Button.emphasized { cornerRadius: 8; }
+            .emphasized { fontFamily: "Verdana"; cornerRadius: 4; }
+            s|Group.emphasized { fontSize: 20; }
+        "#));
+        assert_eq!(describe(&host.computed_styles(&button)), [
+            "cornerRadius=2 (spark.components.Button)",
+            "fontFamily=Arial (global)",
+            "fontSize=10 (spark.components.UIComponent)",
+        ]);
+        assert!(host.computed_styles(&button).get("fontFamily").unwrap().definition.is_none());
+
+        let mxml_cu = CompilationUnit::new(Some("/project/src/Main.mxml".into()), r##"<?xml version="1.0"?>
+<s:Application xmlns:fx="http://ns.adobe.com/mxml/2009" xmlns:s="library://ns.hydroper.com/razen/spark" color="#FF0000">
+    <fx:Style><![CDATA[
+        @namespace s "library://ns.hydroper.com/razen/spark";
+        s|Group { backgroundColor: #00FF00; fontSize: 12; }
+    ]]></fx:Style>
+    <s:Group>
+        <s:Button fontSize="14"/>
+        <s:Button styleName="large emphasized"/>
+    </s:Group>
+</s:Application>"##.into());
+        mxml_cu.set_compiler_options(Some(Rc::new(CompilerOptions {
+            source_path: vec!["/project/src".into()],
+            ..default()
+        })));
+        let mxml = ParserFacade(&mxml_cu, ParserOptions::default()).parse_mxml();
+        Verifier::new(&host).verify_mxml_list(std::slice::from_ref(&mxml));
+        let messages: Vec<String> = mxml_cu.nested_diagnostics().iter().map(|d| FxDiagnostic(d).format_message_english()).collect();
+        assert!(messages.is_empty(), "{messages:?}");
+
+        let computed = host.computed_mxml_styles(&mxml);
+        assert_eq!(computed.len(), 4);
+        assert_eq!(describe(&computed[0].1), [
+            "backgroundColor=#00FF00 (spark.components.Group)",
+            "color=#FF0000 (attribute)",
+            "fontFamily=Arial (global)",
+            "fontSize=12 (spark.components.Group)",
+        ]);
+        assert_eq!(describe(&computed[1].1), [
+            "backgroundColor=#00FF00 (spark.components.Group)",
+            "color=#FF0000 (inherited)",
+            "fontFamily=Arial (inherited)",
+            "fontSize=12 (spark.components.Group)",
+        ]);
+        assert_eq!(describe(&computed[2].1), [
+            "color=#FF0000 (inherited)",
+            "cornerRadius=2 (spark.components.Button)",
+            "fontFamily=Arial (inherited)",
+            "fontSize=14 (attribute)",
+        ]);
+        assert!(computed[2].1.get("color").unwrap().definition.as_ref().is_some_and(|d| d.inherit));
+        assert_eq!(describe(&computed[3].1), [
+            "color=#FF0000 (inherited)",
+            "cornerRadius=8 (s|Button.emphasized)",
+            "fontFamily=Verdana (.emphasized)",
+            "fontSize=10 (spark.components.UIComponent)",
+        ]);
+    }
+}
@@ -0,0 +1,378 @@
+use std::fs;
+use crate::ns::*;
+
+/// Semantics of CSS style sheets and of the `[Style]` and `[CSS]` meta-data.
+///
+/// Components declare their styles through meta-data in the form
+/// `[Style(name="color", type="uint", format="Color", inherit="yes")]`, read as a `StyleDefinition`.
+///
+/// Style sheets are applied to a class either by the `<fx:Style>` tags of the MXML
+/// document defining it or by `[CSS("./style.css")]` meta-data, whose path is relative
+/// to the source file. Both are recorded in `Thingy::style_sheets()`.
+///
+/// Verifying a style sheet resolves:
+///
+/// * type selectors, as in `s|Button`, through the namespaces defined by `@namespace`
+///   and `MxmlComponents`. An unprefixed type selector names a class of the top-level
+///   package, except for `global`, which matches every component;
+/// * `ClassReference("com.example.Skin")` values to classes;
+/// * `PropertyReference("name")` values to properties of the document class;
+/// * `Embed(source="image.png")` values to files relative to the style sheet.
+///
+/// The declarations of a rule whose selectors all resolve to classes are checked
+/// against the `[Style]` meta-data of these classes and their superclasses.
+pub struct StyleSheets<'a>(pub &'a SemanticHost);
+
+/// A style declared by `[Style]` meta-data.
+#[derive(Clone)]
+pub struct StyleDefinition {
+    pub name: String,
+    /// The `type` attribute, such as `Number` or `uint`.
+    pub style_type: Option<String>,
+    /// The `format` attribute, such as `Color` or `Length`.
+    pub format: Option<String>,
+    /// Whether the style is inherited from parent components, given by `inherit="yes"`.
+    pub inherit: bool,
+    pub metadata: Rc<Metadata>,
+}
+
+impl StyleDefinition {
+    /// Reads `[Style]` meta-data, returning `None` if it has no `name`.
+    pub fn from_metadata(metadata: &Rc<Metadata>) -> Option<Self> {
+        if metadata.name.0 != "Style" {
+            return None;
+        }
+        let attribute = |key: &str| metadata.entries.as_ref()?.iter().find_map(|entry| {
+            if entry.key.as_ref().map(|(k, _)| k.as_str()) != Some(key) {
+                return None;
+            }
+            match entry.value.as_ref() {
+                MetadataValue::IdentifierString((s, _)) => Some(s.clone()),
+                MetadataValue::String((s, _)) => Some(s.clone()),
+            }
+        });
+        Some(Self {
+            name: attribute("name")?,
+            style_type: attribute("type"),
+            format: attribute("format"),
+            inherit: attribute("inherit").as_deref() == Some("yes"),
+            metadata: metadata.clone(),
+        })
+    }
+
+    /// Indicates whether a CSS value is assignable to the style.
+    pub fn accepts(&self, value: &CssPropertyValue) -> bool {
+        match value {
+            CssPropertyValue::Invalidated(_) | CssPropertyValue::PropertyReference(_) => true,
+            CssPropertyValue::Array(array) => {
+                matches!(self.style_type.as_deref(), None | Some("Array") | Some("Object") | Some("*"))
+                    || (array.elements.len() == 1 && self.accepts(&array.elements[0]))
+            },
+            _ => match self.style_type.as_deref() {
+                Some("Number") => matches!(value, CssPropertyValue::Number(_) | CssPropertyValue::Color(_) | CssPropertyValue::RgbColor(_)),
+                Some("int") | Some("uint") => match value {
+                    CssPropertyValue::Number(number) => number.value.fract() == 0.0,
+                    _ => matches!(value, CssPropertyValue::Color(_) | CssPropertyValue::RgbColor(_)),
+                },
+                Some("Boolean") => matches!(value, CssPropertyValue::Identifier(id) if id.value == "true" || id.value == "false"),
+                Some("String") => matches!(value, CssPropertyValue::String(_) | CssPropertyValue::Identifier(_) | CssPropertyValue::MultiValue(_)),
+                Some("Class") => matches!(value, CssPropertyValue::ClassReference(_) | CssPropertyValue::Embed(_)),
+                _ => true,
+            },
+        }
+    }
+}
+
+impl<'a> StyleSheets<'a> {
+    /// The type selector matching every component.
+    pub const GLOBAL_SELECTOR: &'static str = "global";
+
+    /// Looks up a style declared by a class or one of its superclasses.
+    pub fn lookup_style(&self, class: &Thingy, name: &str) -> Option<StyleDefinition> {
+        MxmlComponents(self.0).lookup_style(class, name).and_then(|m| StyleDefinition::from_metadata(&m))
+    }
+
+    /// Loads the style sheets given by the `[CSS]` meta-data of a class into
+    /// `Thingy::style_sheets()`, reporting malformed meta-data and unreadable files.
+    /// The style sheets are to be verified through `verify_class()`.
+    ///
+    /// MXML documents invoke both when verified. For ActionScript classes, they are to be
+    /// invoked once the meta-data of a class definition is attached and its members are
+    /// declared, respectively; the verifier does not declare class definitions yet.
+    pub fn apply(&self, class: &Thingy) {
+        let mut style_sheets = class.style_sheets();
+        for metadata in class.metadata().iter() {
+            if metadata.name.0 != "CSS" {
+                continue;
+            }
+            let source = metadata.entries.as_ref().and_then(|entries| match entries.as_slice() {
+                [entry] if entry.key.as_ref().is_none_or(|(k, _)| k == "source") => match entry.value.as_ref() {
+                    MetadataValue::String((s, _)) => Some(s.clone()),
+                    MetadataValue::IdentifierString(_) => None,
+                },
+                _ => None,
+            });
+            let Some(source) = source else {
+                FxDiagnostic::add_verify_error(&metadata.location, FxDiagnosticKind::MalformedCssMetadata, diagarg![]);
+                continue;
+            };
+            let cu = metadata.location.compilation_unit();
+            let path = Embed::resolve_source(&cu, &source);
+            let Ok(text) = fs::read_to_string(&path) else {
+                FxDiagnostic::add_verify_error(&metadata.location, FxDiagnosticKind::CouldNotReadSource, diagarg![source]);
+                continue;
+            };
+            let css_cu = CompilationUnit::new(Some(path.to_string_lossy().into_owned()), text);
+            css_cu.set_compiler_options(cu.compiler_options());
+            cu.add_nested_compilation_unit(css_cu.clone());
+            style_sheets.push(CssParserFacade(&css_cu, ParserOptions::default()).parse_document());
+        }
+    }
+
+    /// Verifies the style sheets applied to a class.
+    pub fn verify_class(&self, class: &Thingy) {
+        for style_sheet in class.style_sheets().iter() {
+            self.verify(&style_sheet, Some(class));
+        }
+    }
+
+    /// Verifies a style sheet. `document` is the class whose properties
+    /// `PropertyReference()` values refer to.
+    pub fn verify(&self, style_sheet: &Rc<CssDocument>, document: Option<&Thingy>) {
+        let mut namespaces: HashMap<String, String> = HashMap::new();
+        for directive in &style_sheet.directives {
+            match directive.as_ref() {
+                CssDirective::NamespaceDefinition(defn) => {
+                    namespaces.insert(defn.prefix.0.clone(), defn.uri.0.clone());
+                },
+                CssDirective::Rule(rule) => self.verify_rule(rule, &namespaces, document),
+                CssDirective::MediaQuery(query) => {
+                    for rule in &query.rules {
+                        self.verify_rule(rule, &namespaces, document);
+                    }
+                },
+                CssDirective::FontFace(font_face) => {
+                    for property in &font_face.properties {
+                        self.verify_value(&property.value, document);
+                    }
+                },
+                CssDirective::Invalidated(_) => {},
+            }
+        }
+    }
+
+    /// Returns the classes matched by the subjects of the selectors of a rule, or
+    /// `None` if a selector does not match by type, as `.className` and `global` do.
+    pub fn rule_classes(&self, rule: &CssRule, namespaces: &HashMap<String, String>) -> Option<Vec<Thingy>> {
+        let mut classes = vec![];
+        for selector in &rule.selectors {
+            let mut subject = selector.as_ref();
+            while let CssSelector::Combinator(combinator) = subject {
+                subject = combinator.right.as_ref();
+            }
+            let CssSelector::Base(base) = subject else {
+                return None;
+            };
+            classes.push(self.resolve_type_selector(base, namespaces)?);
+        }
+        Some(classes)
+    }
+
+    /// Indicates whether a base selector names a type, which `.className` and `global` do not.
+    pub fn is_type_selector(selector: &CssBaseSelector) -> bool {
+        selector.element_name.as_ref().is_some_and(|(name, _)| selector.namespace_prefix.is_some() || name != Self::GLOBAL_SELECTOR)
+    }
+
+    /// Resolves the class named by a type selector.
+    pub fn resolve_type_selector(&self, selector: &CssBaseSelector, namespaces: &HashMap<String, String>) -> Option<Thingy> {
+        if !Self::is_type_selector(selector) {
+            return None;
+        }
+        let (name, _) = selector.element_name.as_ref()?;
+        let uri = match &selector.namespace_prefix {
+            Some((prefix, _)) => namespaces.get(prefix)?.as_str(),
+            None => "*",
+        };
+        MxmlComponents(self.0).resolve(uri, name)
+    }
+
+    fn verify_rule(&self, rule: &CssRule, namespaces: &HashMap<String, String>, document: Option<&Thingy>) {
+        for selector in &rule.selectors {
+            self.verify_selector(selector, namespaces);
+        }
+        let classes = self.rule_classes(rule, namespaces);
+        for property in &rule.properties {
+            self.verify_value(&property.value, document);
+            for class in classes.iter().flatten() {
+                let name = &property.name.0;
+                match self.lookup_style(class, name) {
+                    None => {
+                        FxDiagnostic::add_warning(&property.name.1, FxDiagnosticKind::UnknownStyle, diagarg![name.clone(), class.clone()]);
+                    },
+                    Some(style) if !style.accepts(&property.value) => {
+                        FxDiagnostic::add_verify_error(&property.value.location(), FxDiagnosticKind::CssValueNotAssignableToStyle, diagarg![name.clone(), style.style_type.unwrap_or_default()]);
+                    },
+                    Some(_) => {},
+                }
+            }
+        }
+    }
+
+    fn verify_selector(&self, selector: &Rc<CssSelector>, namespaces: &HashMap<String, String>) {
+        match selector.as_ref() {
+            CssSelector::Base(base) => {
+                if let Some((prefix, location)) = &base.namespace_prefix {
+                    if !namespaces.contains_key(prefix) {
+                        FxDiagnostic::add_verify_error(location, FxDiagnosticKind::UndefinedCssNamespacePrefix, diagarg![prefix.clone()]);
+                        return;
+                    }
+                }
+                if Self::is_type_selector(base) && self.resolve_type_selector(base, namespaces).is_none() {
+                    let (name, location) = base.element_name.clone().unwrap();
+                    let name = match &base.namespace_prefix {
+                        Some((prefix, _)) => format!("{prefix}|{name}"),
+                        None => name,
+                    };
+                    FxDiagnostic::add_verify_error(&location, FxDiagnosticKind::UnresolvedCssTypeSelector, diagarg![name]);
+                }
+            },
+            CssSelector::Combinator(combinator) => {
+                self.verify_selector(&combinator.left, namespaces);
+                self.verify_selector(&combinator.right, namespaces);
+            },
+            CssSelector::Invalidated(_) => {},
+        }
+    }
+
+    fn verify_value(&self, value: &Rc<CssPropertyValue>, document: Option<&Thingy>) {
+        match value.as_ref() {
+            CssPropertyValue::Array(array) => {
+                for element in &array.elements {
+                    self.verify_value(element, document);
+                }
+            },
+            CssPropertyValue::MultiValue(multi_value) => {
+                for value in &multi_value.values {
+                    self.verify_value(value, document);
+                }
+            },
+            CssPropertyValue::ClassReference(reference) => {
+                let (name, location) = &reference.name;
+                if name != "null" && self.0.lookup_class(name).is_none() {
+                    FxDiagnostic::add_verify_error(location, FxDiagnosticKind::UnresolvedCssClassReference, diagarg![name.clone()]);
+                }
+            },
+            CssPropertyValue::PropertyReference(reference) => {
+                let Some(document) = document else {
+                    return;
+                };
+                let (name, location) = &reference.name;
+                let value = self.0.factory().create_value(document);
+                let found = PropertyLookup(self.0).lookup_in_object(&value, &SharedArray::new(), None, &PropertyLookupKey::LocalName(name.clone()));
+                if !matches!(found, Ok(Some(_)) | Err(PropertyLookupError::Defer)) {
+                    FxDiagnostic::add_verify_error(location, FxDiagnosticKind::UndefinedPropertyWithStaticType, diagarg![name.clone(), document.clone()]);
+                }
+            },
+            CssPropertyValue::Embed(embed) => {
+                let source = embed.entries.iter()
+                    .find(|e| e.key.as_ref().is_none_or(|(k, _)| k == "source"))
+                    .map(|e| e.value.clone());
+                let Some((source, location)) = source else {
+                    FxDiagnostic::add_verify_error(&embed.location, FxDiagnosticKind::EmbedMustSpecifySource, diagarg![]);
+                    return;
+                };
+                if !Embed::resolve_source(&embed.location.compilation_unit(), &source).is_file() {
+                    FxDiagnostic::add_verify_error(&location, FxDiagnosticKind::CouldNotReadSource, diagarg![source]);
+                }
+            },
+            _ => {},
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    #[test]
+    fn test_style_sheets() {
+        let dir = std::env::temp_dir().join(format!("razen_test_style_sheets_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("main.css"), r#"
+            @namespace s "library://ns.hydroper.com/razen/spark";
+            s|Button { color: #FF0000; borderWeight: 2; skinClass: ClassReference("spark.skins.Missing"); }
+            s|Button { color: "red"; fontSize: 12; borderWeight: 1.5; }
+            x|Button {}
+            s|Unknown {}
+            global { color: PropertyReference("title"); backgroundImage: PropertyReference("missing"); }
+        "#).unwrap();
+
+        let host = SemanticHost::new(SemanticHostOptions::default());
+        let top_level = host.top_level_package();
+        let object_type = define_class(&host, &top_level, "Object", None);
+        for name in ["XML", "XMLList"] {
+            define_class(&host, &top_level, name, Some(&object_type));
+        }
+        define_class(&host, &host.factory().create_package(["flash", "utils"]), "Dictionary", Some(&object_type));
+        let spark = host.factory().create_package(["spark", "components"]);
+        let base = define_class(&host, &spark, "UIComponent", Some(&object_type));
+        let button = define_class(&host, &spark, "Button", Some(&base));
+        host.define_mxml_component("library://ns.hydroper.com/razen/spark", "Button", "spark.components.Button");
+        let main = define_class(&host, &top_level, "Main", Some(&object_type));
+        let qname = host.factory().create_qname(&top_level.public_ns().unwrap(), "title".into());
+        let title = host.factory().create_variable_slot(&qname, false, &object_type);
+        title.set_parent(Some(main.clone()));
+        main.prototype(&host).set(qname, title);
+
+        let cu = CompilationUnit::new(Some(dir.join("Main.as").to_string_lossy().into_owned()), r#"
+            package spark.components {
+                [Style(name="color", type="uint", format="Color", inherit="yes")]
+                [Style(name="borderWeight", type="int")]
+                class UIComponent {}
+
+                [Style(name="skinClass", type="Class")]
+                class Button {}
+            }
+            package {
+                [CSS("./main.css")]
+                [CSS("./missing.css")]
+                [CSS(name)]
+                class Main {}
+            }
+        "#.into());
+        let program = ParserFacade(&cu, ParserOptions::default()).parse_program();
+        for (class, (p, i)) in [&base, &button, &main].into_iter().zip([(0, 0), (0, 1), (1, 0)]) {
+            let Directive::ClassDefinition(defn) = program.packages[p].block.directives[i].as_ref() else {
+                panic!();
+            };
+            for m in Attribute::find_metadata(&defn.attributes) {
+                class.metadata().push(m);
+            }
+        }
+
+        StyleSheets(&host).apply(&main);
+        let kinds: Vec<Option<FxDiagnosticKind>> = cu.diagnostics().iter().map(|d| FxDiagnostic(d).fx_kind()).collect();
+        assert!(kinds == vec![
+            Some(FxDiagnosticKind::CouldNotReadSource),
+            Some(FxDiagnosticKind::MalformedCssMetadata),
+        ]);
+        assert_eq!(main.style_sheets().length(), 1);
+
+        StyleSheets(&host).verify_class(&main);
+        let css_cu = main.style_sheets().get(0).unwrap().location.compilation_unit();
+        let kinds: Vec<Option<FxDiagnosticKind>> = css_cu.diagnostics().iter().map(|d| FxDiagnostic(d).fx_kind()).collect();
+        assert!(kinds == vec![
+            Some(FxDiagnosticKind::UnresolvedCssClassReference),
+            Some(FxDiagnosticKind::CssValueNotAssignableToStyle),
+            Some(FxDiagnosticKind::UnknownStyle),
+            Some(FxDiagnosticKind::CssValueNotAssignableToStyle),
+            Some(FxDiagnosticKind::UndefinedCssNamespacePrefix),
+            Some(FxDiagnosticKind::UnresolvedCssTypeSelector),
+            Some(FxDiagnosticKind::UndefinedPropertyWithStaticType),
+        ]);
+
+        let style = StyleSheets(&host).lookup_style(&button, "color").unwrap();
+        assert_eq!((style.style_type.as_deref(), style.format.as_deref(), style.inherit), (Some("uint"), Some("Color"), true));
+        assert!(StyleSheets(&host).lookup_style(&base, "skinClass").is_none());
+    }
+}
@@ -65,10 +65,22 @@ smodel! {
         /// Indicates whether a variable slot is optional for an object initializer
         /// applied to an `[Options]` class.
         pub fn is_opt_variable_for_options_class(&self, host: &SemanticHost) -> Result<bool, DeferError> {
+            if self.has_initializer() {
+                return Ok(true);
+            }
             let st = self.static_type(host).defer()?;
             Ok(st.includes_null(host)? || st.includes_undefined(host)?)
         }
 
+        /// Indicates whether a variable slot is declared with an initializer.
+        pub fn has_initializer(&self) -> bool {
+            false
+        }
+
+        pub fn set_has_initializer(&self, value: bool) {
+            panic!();
+        }
+
         /// Escapes out of a nullable type layer.
         pub fn escape_of_nullable(&self) -> Thingy {
             self.clone()
@@ -353,6 +365,22 @@ smodel! {
             panic!();
         }
 
+        /// Data bindings of the MXML document defining a class.
+        pub fn mxml_bindings(&self) -> SharedArray<Rc<MxmlBinding>> {
+            panic!();
+        }
+
+        /// View states of the MXML document defining a class.
+        pub fn mxml_states(&self) -> SharedArray<Rc<MxmlState>> {
+            panic!();
+        }
+
+        /// Style sheets applied to a class, from the `<fx:Style>` tags of the MXML
+        /// document defining it or from `[CSS]` meta-data.
+        pub fn style_sheets(&self) -> SharedArray<Rc<CssDocument>> {
+            panic!();
+        }
+
         pub fn set_bindable_event(&self, name: Option<String>) {
             panic!();
         }
@@ -691,6 +719,15 @@ smodel! {
         pub fn set_type_params(&self, list: Option<SharedArray<Thingy>>) {
         }
 
+        /// The upper bound of a type parameter type, if any.
+        pub fn bound(&self) -> Option<Thingy> {
+            panic!();
+        }
+
+        pub fn set_bound(&self, bound: Option<Thingy>) {
+            panic!();
+        }
+
         pub fn enum_member_number_mapping(&self) -> SharedMap<String, NumberVariant> {
             panic!();
         }
@@ -1377,6 +1414,9 @@ smodel! {
         let ref m_properties: NameMap = NameMap::new();
         let ref m_prototype: NameMap = NameMap::new();
         let ref m_flex_events: SharedMap<String, Thingy> = SharedMap::new();
+        let ref m_mxml_bindings: SharedArray<Rc<MxmlBinding>> = SharedArray::new();
+        let ref m_mxml_states: SharedArray<Rc<MxmlState>> = SharedArray::new();
+        let ref m_style_sheets: SharedArray<Rc<CssDocument>> = SharedArray::new();
         let ref m_asdoc: Option<Rc<AsDoc>> = None;
         let ref m_metadata: SharedArray<Rc<Metadata>> = SharedArray::new();
         let ref m_location: Option<Location> = None;
@@ -1407,6 +1447,21 @@ smodel! {
             self.m_flex_events()
         }
 
+        #[inheritdoc]
+        pub override fn mxml_bindings(&self) -> SharedArray<Rc<MxmlBinding>> {
+            self.m_mxml_bindings()
+        }
+
+        #[inheritdoc]
+        pub override fn mxml_states(&self) -> SharedArray<Rc<MxmlState>> {
+            self.m_mxml_states()
+        }
+
+        #[inheritdoc]
+        pub override fn style_sheets(&self) -> SharedArray<Rc<CssDocument>> {
+            self.m_style_sheets()
+        }
+
         pub override fn private_ns(&self) -> Option<Thingy> {
             self.m_private_ns()
         }
@@ -2171,6 +2226,7 @@ smodel! {
     pub struct TypeParameterType: Type {
         let ref m_name: Option<QName> = None;
         let ref m_location: Option<Location> = None;
+        let ref m_bound: Option<Thingy> = None;
 
         pub(crate) fn TypeParameterType(name: QName) {
             super();
@@ -2189,6 +2245,14 @@ smodel! {
             self.set_m_location(loc);
         }
 
+        pub override fn bound(&self) -> Option<Thingy> {
+            self.m_bound()
+        }
+
+        pub override fn set_bound(&self, bound: Option<Thingy>) {
+            self.set_m_bound(bound);
+        }
+
         pub override fn includes_undefined(&self) -> Result<bool, DeferError> {
             Ok(false)
         }
@@ -2256,6 +2320,16 @@ smodel! {
             self.set_m_flags(v);
         }
 
+        pub override fn has_initializer(&self) -> bool {
+            self.m_flags().contains(VariableSlotFlags::HAS_INITIALIZER)
+        }
+
+        pub override fn set_has_initializer(&self, value: bool) {
+            let mut v = self.m_flags();
+            v.set(VariableSlotFlags::HAS_INITIALIZER, value);
+            self.set_m_flags(v);
+        }
+
         pub override fn write_only(&self, host: &SemanticHost) -> bool {
             false
         }
@@ -2349,6 +2423,10 @@ smodel! {
             self.origin().read_only(host)
         }
 
+        pub override fn has_initializer(&self) -> bool {
+            self.origin().has_initializer()
+        }
+
         pub override fn write_only(&self, host: &SemanticHost) -> bool {
             false
         }
@@ -2639,6 +2717,7 @@ smodel! {
         let ref m_of_virtual_slot: Option<Thingy> = None;
         let ref m_overriden_by: SharedArray<Thingy> = SharedArray::new();
         let ref m_overrides_method: Option<Thingy> = None;
+        let ref m_type_params: Option<SharedArray<Thingy>> = None;
         let m_flags: MethodSlotFlags = MethodSlotFlags::empty();
 
         pub(crate) fn OriginalMethodSlot(name: &QName, signature: &Thingy) {
@@ -2651,6 +2730,14 @@ smodel! {
             self.m_name().unwrap()
         }
 
+        pub override fn type_params(&self) -> Option<SharedArray<Thingy>> {
+            self.m_type_params()
+        }
+
+        pub override fn set_type_params(&self, list: Option<SharedArray<Thingy>>) {
+            self.set_m_type_params(list);
+        }
+
         pub override fn is_final(&self) -> bool {
             self.m_flags().contains(MethodSlotFlags::IS_FINAL)
         }
@@ -2835,6 +2922,10 @@ smodel! {
             self.origin().is_abstract()
         }
 
+        pub override fn type_params(&self) -> Option<SharedArray<Thingy>> {
+            self.origin().type_params()
+        }
+
         pub override fn is_overriding(&self) -> bool {
             self.m_is_overriding()
         }
@@ -3236,6 +3327,8 @@ smodel! {
     pub struct Constant: Value {
         pub(crate) fn Constant(static_type: &Thingy) {
             super(static_type);
+            // The inherited constructor is not invoked from a further subtype.
+            self.set_static_type(static_type.clone());
         }
     }
 
@@ -3372,6 +3465,8 @@ smodel! {
     pub struct ReferenceValue: Value {
         pub(crate) fn ReferenceValue(static_type: &Thingy) {
             super(static_type);
+            // The inherited constructor is not invoked from a further subtype.
+            self.set_static_type(static_type.clone());
         }
     }
 
@@ -3457,6 +3552,8 @@ smodel! {
 
         pub(crate) fn FixtureReferenceValue(base: &Thingy, property: &Thingy, static_type: &Thingy) {
             super(static_type);
+            // The inherited constructor is not invoked from a further subtype.
+            self.set_static_type(static_type.clone());
             self.set_m_base(Some(base.clone()));
             self.set_m_property(Some(property.clone()));
         }
@@ -3967,6 +4064,7 @@ bitflags! {
     #[derive(Copy, Clone, PartialEq, Eq)]
     struct VariableSlotFlags: u16 {
         const READ_ONLY     = 0b00000010;
+        const HAS_INITIALIZER = 0b00000100;
     }
 }
 
@@ -4091,7 +4189,7 @@ impl<'a> Iterator for DescendingClassHierarchy<'a> {
                 self.0 = None;
             } else {
                 self.0 = r.extends_class(self.1);
-                if self.0.as_ref().unwrap() == &self.2 {
+                if self.0.as_ref() == Some(&self.2) {
                     self.0 = None;
                 }
             }
@@ -317,7 +317,7 @@ impl<'a> ThingyFactory<'a> {
             &indirect_type_params,
             &indirect_substitute_types.clone());
 
-        let list = vasub_list.get_mut(origin).unwrap().get_mut(&indirect_type_params).unwrap();
+        let list = vasub_list.get_mut(origin).unwrap().entry(indirect_type_params.clone()).or_default();
         list.push(vasub.clone().into());
 
         vasub.into()
@@ -367,7 +367,7 @@ impl<'a> ThingyFactory<'a> {
             &indirect_type_params,
             &indirect_substitute_types.clone());
 
-        let list = visub_list.get_mut(origin).unwrap().get_mut(&indirect_type_params).unwrap();
+        let list = visub_list.get_mut(origin).unwrap().entry(indirect_type_params.clone()).or_default();
         list.push(visub.clone().into());
 
         visub.into()
@@ -417,7 +417,7 @@ impl<'a> ThingyFactory<'a> {
             &indirect_type_params,
             &indirect_substitute_types.clone());
 
-        let list = mssub_list.get_mut(origin).unwrap().get_mut(&indirect_type_params).unwrap();
+        let list = mssub_list.get_mut(origin).unwrap().entry(indirect_type_params.clone()).or_default();
         list.push(mssub.clone().into());
 
         mssub.into()
@@ -542,7 +542,7 @@ impl<'a> ThingyFactory<'a> {
     }
 
     pub fn create_tuple_reference_value(&self, base: &Thingy, index: usize) -> Thingy {
-        let st = base.static_type(self.0).element_types().get(index).unwrap();
+        let st = base.static_type(self.0).escape_of_non_nullable().element_types().get(index).unwrap();
         TupleReferenceValue::new(&self.0.arena, base, index, &st).into()
     }
 
@@ -75,6 +75,18 @@ pub enum TypeConversionVariant {
     /// Involved types either both include null or both do not include null.
     FunctionToStructuralFunction,
 
+    /// Implicit conversion from a tuple type to a tuple type of
+    /// the same length whose element types are covariant.
+    ///
+    /// Involved types either both include null or both do not include null.
+    TupleToCovariantTuple,
+
+    /// Implicit conversion from a tuple type to `Array.<T>`, where
+    /// every element type of the tuple is covariant to `T`.
+    ///
+    /// Involved types either both include null or both do not include null.
+    TupleToArray,
+
     /// Explicit conversion, where base type is a type parameter type,
     /// possibly having been marked non-nullable.
     FromTypeParameter,
@@ -120,6 +132,8 @@ impl TypeConversionVariant {
             Self::AsIsToNullable,
             Self::NonNullableToAsIs,
             Self::FunctionToStructuralFunction,
+            Self::TupleToCovariantTuple,
+            Self::TupleToArray,
         ].contains(self)
     }
 }
@@ -267,9 +281,60 @@ impl<'a> TypeConversions<'a> {
             }
         }
 
+        if from_type_esc.is::<TupleType>() {
+            let both_include_null = from_type.includes_null(self.0)? && target_type.includes_null(self.0)?;
+            let both_dont_include_null = !from_type.includes_null(self.0)? && !target_type.includes_null(self.0)?;
+
+            // TupleToCovariantTuple
+            if target_type_esc.is::<TupleType>() && (both_include_null || both_dont_include_null)
+            && self.is_covariant_element_type(&from_type_esc, &target_type_esc)? {
+                return Ok(Some(self.0.factory().create_conversion_value(value, TypeConversionVariant::TupleToCovariantTuple, optional, target_type)?));
+            }
+
+            // TupleToArray
+            if let Some(el_type) = target_type_esc.array_element_type(self.0)? {
+                let mut covariant = true;
+                for from_el_type in from_type_esc.element_types().iter() {
+                    covariant = covariant && self.is_covariant_element_type(&from_el_type, &el_type)?;
+                }
+                if covariant && (both_include_null || both_dont_include_null) {
+                    return Ok(Some(self.0.factory().create_conversion_value(value, TypeConversionVariant::TupleToArray, optional, target_type)?));
+                }
+            }
+        }
+
         Ok(None)
     }
 
+    /// Determines whether an element type is covariant to another element type,
+    /// that is, whether it is equal to or a subtype of the other type without
+    /// changing whether it includes null. Tuple types are covariant to
+    /// tuple types of the same length with covariant element types.
+    fn is_covariant_element_type(&self, from_type: &Thingy, target_type: &Thingy) -> Result<bool, DeferError> {
+        if from_type == target_type || target_type.is::<AnyType>() {
+            return Ok(true);
+        }
+        if from_type.includes_null(self.0)? != target_type.includes_null(self.0)? {
+            return Ok(false);
+        }
+        let from_type_esc = from_type.escape_of_nullable_or_non_nullable();
+        let target_type_esc = target_type.escape_of_nullable_or_non_nullable();
+        if from_type_esc.is::<TupleType>() && target_type_esc.is::<TupleType>() {
+            let from_el_types = from_type_esc.element_types();
+            let target_el_types = target_type_esc.element_types();
+            if from_el_types.length() != target_el_types.length() {
+                return Ok(false);
+            }
+            for (from_el_type, target_el_type) in from_el_types.iter().zip(target_el_types.iter()) {
+                if !self.is_covariant_element_type(&from_el_type, &target_el_type)? {
+                    return Ok(false);
+                }
+            }
+            return Ok(true);
+        }
+        from_type_esc.is_subtype_of(&target_type_esc, self.0)
+    }
+
     pub fn explicit(&self, value: &Thingy, target_type: &Thingy, optional: bool) -> Result<Option<Thingy>, DeferError> {
         let from_type = value.static_type(self.0);
         if &from_type == target_type {
@@ -393,4 +458,65 @@ impl<'a> TypeConversions<'a> {
 
         Ok(None)
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    #[test]
+    fn test_tuple_conversions() {
+        let host = Rc::new(SemanticHost::new(SemanticHostOptions::default()));
+        let top_level_package = host.top_level_package();
+        let object_type = define_class(&host, &top_level_package, "Object", None);
+        for name in ["Class", "Function", "String", "Boolean", "Number", "int", "uint", "float", "Namespace", "XML", "XMLList", "Array"] {
+            define_class(&host, &top_level_package, name, Some(&object_type));
+        }
+        define_class(&host, &host.factory().create_package(["flash", "utils"]), "Dictionary", Some(&object_type));
+        define_class(&host, &host.factory().create_package(["__AS3__", "vec"]), "Vector", Some(&object_type));
+        let public_ns = top_level_package.public_ns().unwrap();
+        let array_type = host.array_type();
+        array_type.set_type_params(Some(shared_array![host.factory().create_type_parameter_type(&host.factory().create_qname(&public_ns, "T".into()))]));
+
+        let string_type = host.string_type();
+        let int_type = host.int_type();
+        let tuple = |types: &[&Thingy]| host.factory().create_tuple_type(types.iter().map(|t| (*t).clone()).collect());
+        let array_of = |t: &Thingy| host.factory().create_type_after_substitution(&array_type, &shared_array![t.clone()]);
+        let convert = |from_type: &Thingy, target_type: &Thingy| -> Option<TypeConversionVariant> {
+            let value = host.factory().create_value(from_type);
+            TypeConversions(&host).implicit(&value, target_type, false).unwrap().map(|v| v.conversion_variant())
+        };
+
+        let int_and_string = tuple(&[&int_type, &string_type]);
+        let string_and_string = tuple(&[&string_type, &string_type]);
+        assert!(convert(&int_and_string, &tuple(&[&int_type, &object_type])) == Some(TypeConversionVariant::TupleToCovariantTuple));
+        assert!(convert(&int_and_string, &tuple(&[&int_type, &int_type])).is_none());
+        assert!(convert(&int_and_string, &tuple(&[&int_type])).is_none());
+        assert!(convert(&string_and_string, &array_of(&object_type)) == Some(TypeConversionVariant::TupleToArray));
+        assert!(convert(&int_and_string, &array_of(&string_type)).is_none());
+
+        // var t: [Number, String]
+        let t = host.factory().create_variable_slot(&host.factory().create_qname(&public_ns, "t".into()), false, &tuple(&[&host.number_type(), &string_type]));
+        t.set_parent(Some(top_level_package.clone()));
+        top_level_package.properties(&host).set(t.name(), t.clone());
+
+        let scope = host.factory().create_package_scope(&top_level_package);
+        let verify = |source: &str| -> (Option<Thingy>, Vec<String>) {
+            let cu = CompilationUnit::new(None, source.into());
+            let exp = ParserFacade(&cu, ParserOptions::default()).parse_expression();
+            let mut verifier = Verifier::new(&host);
+            verifier.set_scope(&scope);
+            let r = verifier.verify_expression(&exp, &default());
+            let messages = cu.diagnostics().iter().map(|d| FxDiagnostic(d).format_message_english()).collect();
+            (r, messages)
+        };
+
+        let (r, messages) = verify("t[1]");
+        assert!(messages.is_empty(), "{messages:?}");
+        assert!(r.unwrap().static_type(&host) == string_type);
+
+        let (r, messages) = verify("t[2]");
+        assert!(r.is_none());
+        assert_eq!(messages, ["Index 2 is out of range for the tuple type [Number, String]."]);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,219 @@
+use crate::ns::*;
+
+/// Inference of the type arguments of a method with type parameters
+/// from the types of the arguments at a call site.
+///
+/// Each parameter type is unified with the static type of its argument, collecting
+/// candidate types for the type parameters it mentions. For example, given
+/// `map.<T, U>(list: Vector.<T>, f: (T) => U): Vector.<U>`, the call
+/// `map(list, function(x: int): String { ... })` where `list` is a `Vector.<int>`
+/// collects `T: int` and `U: String`.
+///
+/// A type parameter is inferred if it has a single candidate type, or if one of
+/// its candidate types is a supertype of the others. The `*` type is only a candidate
+/// if there is no other, since arguments are verified with the type parameters
+/// replaced by `*` in their context type.
+pub struct TypeArgumentInference<'a> {
+    host: &'a SemanticHost,
+    type_params: SharedArray<Thingy>,
+    candidates: Vec<Vec<Thingy>>,
+    any_candidate: Vec<bool>,
+}
+
+/// Failure of type argument inference.
+#[derive(Clone, Debug)]
+pub struct TypeArgumentInferenceError {
+    /// The type parameter that could not be inferred.
+    pub type_param: Thingy,
+    /// The conflicting candidate types, empty if there is none.
+    pub candidates: Vec<Thingy>,
+}
+
+impl<'a> TypeArgumentInference<'a> {
+    pub fn new(host: &'a SemanticHost, type_params: &SharedArray<Thingy>) -> Self {
+        Self {
+            host,
+            type_params: type_params.clone(),
+            candidates: type_params.iter().map(|_| vec![]).collect(),
+            any_candidate: type_params.iter().map(|_| false).collect(),
+        }
+    }
+
+    /// Determines whether a type mentions any of the type parameters.
+    pub fn mentions_type_params(&self, type_thingy: &Thingy) -> bool {
+        if type_thingy.is::<TypeParameterType>() {
+            self.type_params.includes(type_thingy)
+        } else if type_thingy.is::<NullableType>() || type_thingy.is::<NonNullableType>() {
+            self.mentions_type_params(&type_thingy.base())
+        } else if type_thingy.is::<FunctionType>() {
+            type_thingy.params().iter().any(|p| self.mentions_type_params(&p.static_type))
+                || self.mentions_type_params(&type_thingy.result_type())
+        } else if type_thingy.is::<TupleType>() {
+            type_thingy.element_types().iter().any(|t| self.mentions_type_params(&t))
+        } else if type_thingy.is::<TypeAfterSubstitution>() {
+            type_thingy.substitute_types().iter().any(|t| self.mentions_type_params(&t))
+        } else {
+            false
+        }
+    }
+
+    /// Unifies a parameter type with the static type of an argument,
+    /// collecting candidate types for the mentioned type parameters.
+    pub fn unify(&mut self, param_type: &Thingy, argument_type: &Thingy) {
+        if argument_type.is::<InvalidationThingy>() || argument_type.is::<UnresolvedThingy>() {
+            return;
+        }
+        if param_type.is::<TypeParameterType>() {
+            if let Some(i) = self.type_params.index_of(param_type) {
+                let argument_type = argument_type.escape_of_nullable_or_non_nullable();
+                if argument_type == self.host.any_type() {
+                    self.any_candidate[i] = true;
+                } else if !self.candidates[i].contains(&argument_type) {
+                    self.candidates[i].push(argument_type);
+                }
+            }
+        } else if param_type.is::<NullableType>() || param_type.is::<NonNullableType>() {
+            self.unify(&param_type.base(), &argument_type.escape_of_nullable_or_non_nullable());
+        } else if param_type.is::<FunctionType>() {
+            if !argument_type.is::<FunctionType>() {
+                return;
+            }
+            for (p, a) in param_type.params().iter().zip(argument_type.params().iter()) {
+                self.unify(&p.static_type, &a.static_type);
+            }
+            self.unify(&param_type.result_type(), &argument_type.result_type());
+        } else if param_type.is::<TupleType>() {
+            if !argument_type.is::<TupleType>() {
+                return;
+            }
+            for (p, a) in param_type.element_types().iter().zip(argument_type.element_types().iter()) {
+                self.unify(&p, &a);
+            }
+        } else if param_type.is::<TypeAfterSubstitution>() {
+            // Find the argument type or one of its ascending types
+            // having the same origin.
+            let origin = param_type.origin();
+            let argument_type = argument_type.escape_of_nullable_or_non_nullable();
+            let mut types = vec![argument_type.clone()];
+            types.extend(argument_type.all_ascending_types(self.host));
+            let Some(argument_type) = types.into_iter().find(|t| t.is::<TypeAfterSubstitution>() && t.origin() == origin) else {
+                return;
+            };
+            for (p, a) in param_type.substitute_types().iter().zip(argument_type.substitute_types().iter()) {
+                self.unify(&p, &a);
+            }
+        }
+    }
+
+    /// Finishes inference, returning the type arguments.
+    pub fn finish(&self) -> Result<SharedArray<Thingy>, TypeArgumentInferenceError> {
+        let mut r = shared_array![];
+        for (i, (type_param, candidates)) in self.type_params.iter().zip(self.candidates.iter()).enumerate() {
+            if candidates.is_empty() && self.any_candidate[i] {
+                r.push(self.host.any_type());
+                continue;
+            }
+            let inferred = candidates.iter().find(|c| {
+                candidates.iter().all(|c1| c1.is_equals_or_subtype_of(c, self.host).unwrap_or(false))
+            });
+            let Some(inferred) = inferred else {
+                return Err(TypeArgumentInferenceError {
+                    type_param,
+                    candidates: candidates.clone(),
+                });
+            };
+            r.push(inferred.clone());
+        }
+        Ok(r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    fn function_type(host: &SemanticHost, params: &[&Thingy], result_type: &Thingy) -> Thingy {
+        let params = params.iter().map(|t| Rc::new(SemanticFunctionTypeParameter {
+            kind: ParameterKind::Required,
+            static_type: (*t).clone(),
+        })).collect();
+        host.factory().create_function_type(params, result_type.clone())
+    }
+
+    #[test]
+    fn test_type_argument_inference() {
+        let host = Rc::new(SemanticHost::new(SemanticHostOptions::default()));
+        let object_type = define_class(&host, &host.top_level_package(), "Object", None);
+        for name in ["Class", "Function", "String", "Boolean", "Number", "int", "uint", "float", "Namespace"] {
+            define_class(&host, &host.top_level_package(), name, Some(&object_type));
+        }
+        let ns = host.top_level_package().public_ns().unwrap();
+        let list_type = define_class(&host, &host.top_level_package(), "List", None);
+        list_type.set_extends_class(Some(object_type.clone()));
+        let e = host.factory().create_type_parameter_type(&host.factory().create_qname(&ns, "E".into()));
+        list_type.set_type_params(Some(shared_array![e]));
+        let array_type = define_class(&host, &host.top_level_package(), "Array", None);
+        array_type.set_extends_class(Some(object_type.clone()));
+        array_type.set_type_params(Some(shared_array![host.factory().create_type_parameter_type(&host.factory().create_qname(&ns, "T".into()))]));
+
+        // function map.<T, U>(list: List.<T>, f: (T) => U): List.<U>
+        let t = host.factory().create_type_parameter_type(&host.factory().create_qname(&ns, "T".into()));
+        let u = host.factory().create_type_parameter_type(&host.factory().create_qname(&ns, "U".into()));
+        let list_of_t = host.factory().create_type_after_substitution(&list_type, &shared_array![t.clone()]);
+        let list_of_u = host.factory().create_type_after_substitution(&list_type, &shared_array![u.clone()]);
+        let sig = function_type(&host, &[&list_of_t, &function_type(&host, &[&t], &u)], &list_of_u);
+        let map = host.factory().create_method_slot(&host.factory().create_qname(&ns, "map".into()), &sig);
+        map.set_parent(Some(host.top_level_package()));
+        map.set_type_params(Some(shared_array![t.clone(), u.clone()]));
+        host.top_level_package().properties(&host).set(map.name(), map.clone());
+
+        // function first.<T, U>(pair: [T, U]): T
+        let sig = function_type(&host, &[&host.factory().create_tuple_type(vec![t.clone(), u.clone()])], &t);
+        let first = host.factory().create_method_slot(&host.factory().create_qname(&ns, "first".into()), &sig);
+        first.set_parent(Some(host.top_level_package()));
+        first.set_type_params(Some(shared_array![t.clone(), u.clone()]));
+        host.top_level_package().properties(&host).set(first.name(), first.clone());
+
+        let list_of_int = host.factory().create_type_after_substitution(&list_type, &shared_array![host.int_type()]);
+        let list_var = host.factory().create_variable_slot(&host.factory().create_qname(&ns, "list".into()), false, &list_of_int);
+        list_var.set_parent(Some(host.top_level_package()));
+        host.top_level_package().properties(&host).set(list_var.name(), list_var.clone());
+
+        // var f: (int) => String; var g: (String) => String
+        for (name, param_type) in [("f", host.int_type()), ("g", host.string_type())] {
+            let var = host.factory().create_variable_slot(&host.factory().create_qname(&ns, name.into()), false, &function_type(&host, &[&param_type], &host.string_type()));
+            var.set_parent(Some(host.top_level_package()));
+            host.top_level_package().properties(&host).set(var.name(), var.clone());
+        }
+
+        let scope = host.factory().create_package_scope(&host.top_level_package());
+        let verify = |source: &str| -> (Option<Thingy>, Vec<String>) {
+            let cu = CompilationUnit::new(None, source.into());
+            let exp = ParserFacade(&cu, ParserOptions::default()).parse_expression();
+            let mut verifier = Verifier::new(&host);
+            verifier.set_scope(&scope);
+            let r = verifier.verify_expression(&exp, &default());
+            let messages = cu.diagnostics().iter().map(|d| FxDiagnostic(d).format_message_english()).collect();
+            (r, messages)
+        };
+
+        let (r, messages) = verify("map(list, f)");
+        assert!(messages.is_empty(), "{messages:?}");
+        let result_type = r.unwrap().static_type(&host);
+        assert!(result_type.origin() == list_type);
+        assert!(result_type.substitute_types().get(0).unwrap() == host.string_type());
+
+        // T: int and String
+        let (_, messages) = verify("map(list, g)");
+        assert_eq!(messages, ["Could not infer type argument T from the candidate types int, String."]);
+
+        // U has no candidate
+        let (_, messages) = verify("map(list, 10)");
+        assert_eq!(messages, ["Could not infer type argument U."]);
+
+        // The array literal is verified against the tuple parameter type.
+        let (r, messages) = verify("first([10, 'a'])");
+        assert!(messages.is_empty(), "{messages:?}");
+        assert!(r.is_some());
+    }
+}
@@ -0,0 +1,311 @@
+use crate::ns::*;
+
+/// Declaration of type parameters of generic classes and interfaces.
+///
+/// ```as3
+/// public class Box.<T> {
+///     public var value: T;
+/// }
+///
+/// const box: Box.<String> = new Box.<String>();
+/// ```
+///
+/// Type parameters are declared as properties of the scope of the class or interface
+/// block. A parameterized type is applied to type arguments by the `Box.<String>`
+/// expression, which results into a type after substitution whose members
+/// are substituted lazily as they are looked up.
+///
+/// Functions cannot be parameterized, since the parser does not accept a type
+/// parameter list in function definitions.
+///
+/// A type parameter may have an upper bound, a class or interface type that
+/// its type arguments must be equal to or a subtype of. Members of a value whose
+/// type is a bounded type parameter are looked up in its bound. The parser does not
+/// accept a bound in a type parameter list, so bounds are given through `declare_bound()`.
+pub struct TypeParameters<'a>(pub &'a SemanticHost);
+
+impl<'a> TypeParameters<'a> {
+    /// Declares the type parameters of a class or interface, defining them
+    /// in the given scope and reporting duplicate type parameters.
+    /// Returns the type parameters.
+    ///
+    /// If the type already has type parameters, this returns them
+    /// and does nothing else.
+    ///
+    /// This is to be invoked once a class or interface definition is declared;
+    /// the verifier does not declare class and interface definitions yet.
+    pub fn declare(&self, type_thingy: &Thingy, list: &[Rc<TypeParameter>], scope: &Thingy) -> SharedArray<Thingy> {
+        if let Some(type_params) = type_thingy.type_params() {
+            return type_params;
+        }
+        let ns = self.0.top_level_package().public_ns().unwrap();
+        let mut type_params: SharedArray<Thingy> = shared_array![];
+        let mut scope_properties = scope.properties(self.0);
+        for param in list {
+            let name = self.0.factory().create_qname(&ns, param.name.0.clone());
+            if type_params.iter().any(|p| p.name() == name) {
+                FxDiagnostic::add_verify_error(&param.name.1, FxDiagnosticKind::DuplicateTypeParameter, diagarg![param.name.0.clone()]);
+                continue;
+            }
+            let type_param = self.0.factory().create_type_parameter_type(&name);
+            type_param.set_location(Some(param.location.clone()));
+            scope_properties.set(name, type_param.clone());
+            type_params.push(type_param);
+        }
+        if type_params.length() != 0 {
+            type_thingy.set_type_params(Some(type_params.clone()));
+        }
+        type_params
+    }
+
+    /// Declares the upper bound of a type parameter. The bound must be a class type,
+    /// an interface type or another type parameter not bounded by this one, otherwise
+    /// this reports an error at the given location and leaves the type parameter unbounded.
+    pub fn declare_bound(&self, type_param: &Thingy, bound: &Thingy, location: &Location) {
+        let mut b = bound.clone();
+        let valid = loop {
+            if b.is_class_type_possibly_after_sub() || b.is_interface_type_possibly_after_sub() {
+                break true;
+            }
+            if !b.is::<TypeParameterType>() || &b == type_param {
+                break false;
+            }
+            match b.bound() {
+                Some(b1) => b = b1,
+                None => break true,
+            }
+        };
+        if !valid {
+            FxDiagnostic::add_verify_error(location, FxDiagnosticKind::InvalidTypeParameterBound, diagarg![type_param.clone()]);
+            return;
+        }
+        type_param.set_bound(Some(bound.clone()));
+    }
+
+    /// Determines whether a type argument satisfies the bound of a type parameter,
+    /// where the bound has already been substituted. A type argument that is itself
+    /// a type parameter satisfies the bound if its own bound does.
+    pub fn satisfies_bound(&self, type_arg: &Thingy, bound: &Thingy) -> Result<bool, DeferError> {
+        let mut type_arg = type_arg.escape_of_non_nullable();
+        let mut visited = vec![];
+        loop {
+            if type_arg.is::<InvalidationThingy>() || type_arg.is_equals_or_subtype_of(bound, self.0)? {
+                return Ok(true);
+            }
+            if !type_arg.is::<TypeParameterType>() || visited.contains(&type_arg) {
+                return Ok(false);
+            }
+            visited.push(type_arg.clone());
+            let Some(type_arg_bound) = type_arg.bound() else {
+                return Ok(false);
+            };
+            type_arg = type_arg_bound.escape_of_non_nullable();
+        }
+    }
+
+    /// Checks type arguments against the bounds of type parameters, substituting
+    /// the type parameters in the bounds. Returns the index and substituted bound
+    /// of each type argument that does not satisfy its bound.
+    pub fn unsatisfied_bounds(&self, type_params: &SharedArray<Thingy>, type_args: &SharedArray<Thingy>) -> Result<Vec<(usize, Thingy)>, DeferError> {
+        let mut r = vec![];
+        for (i, (type_param, type_arg)) in type_params.iter().zip(type_args.iter()).enumerate() {
+            let Some(bound) = type_param.bound() else {
+                continue;
+            };
+            let bound = bound.type_substitution(self.0, type_params, type_args);
+            if !self.satisfies_bound(&type_arg, &bound)? {
+                r.push((i, bound));
+            }
+        }
+        Ok(r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    #[test]
+    fn test_type_parameters() {
+        let host = Rc::new(SemanticHost::new(SemanticHostOptions::default()));
+        let object_type = define_class(&host, &host.top_level_package(), "Object", None);
+        for name in ["Class", "String", "Boolean", "Number", "int", "uint", "float", "Namespace"] {
+            define_class(&host, &host.top_level_package(), name, Some(&object_type));
+        }
+
+        let cu = CompilationUnit::new(None, r#"
+            package {
+                public class Box.<T> {}
+                public class Pair.<K, K> {}
+            }
+        "#.into());
+        let program = ParserFacade(&cu, ParserOptions::default()).parse_program();
+        let public_ns = host.top_level_package().public_ns().unwrap();
+        let mut classes = vec![];
+        for drtv in &program.packages[0].block.directives {
+            let Directive::ClassDefinition(defn) = drtv.as_ref() else {
+                panic!();
+            };
+            let class = define_class(&host, &host.top_level_package(), &defn.name.0, None);
+            class.set_extends_class(Some(object_type.clone()));
+            let scope = host.factory().create_class_scope(&class);
+            TypeParameters(&host).declare(&class, defn.type_parameters.as_ref().unwrap(), &scope);
+            classes.push(class);
+        }
+        let box_type = classes[0].clone();
+        let t = box_type.type_params().unwrap().get(0).unwrap();
+        assert!(t.is::<TypeParameterType>());
+        assert_eq!(classes[1].type_params().unwrap().length(), 1);
+
+        // var value: T
+        let value_name = host.factory().create_qname(&public_ns, "value".into());
+        box_type.prototype(&host).set(value_name.clone(), host.factory().create_variable_slot(&value_name, false, &t));
+
+        let kinds: Vec<Option<FxDiagnosticKind>> = cu.diagnostics().iter().map(|d| FxDiagnostic(d).fx_kind()).collect();
+        assert!(kinds == vec![Some(FxDiagnosticKind::DuplicateTypeParameter)]);
+
+        let scope = host.factory().create_scope();
+        scope.import_list().push(host.factory().create_package_wildcard_import(&host.top_level_package(), None));
+        let verify = |source: &str| -> (Option<Thingy>, Vec<Option<FxDiagnosticKind>>) {
+            let cu = CompilationUnit::new(None, source.into());
+            let exp = ParserFacade(&cu, ParserOptions::default()).parse_expression();
+            let mut verifier = Verifier::new(&host);
+            verifier.set_scope(&scope);
+            let r = verifier.verify_expression(&exp, &default());
+            let kinds = cu.diagnostics().iter().map(|d| FxDiagnostic(d).fx_kind()).collect();
+            (r, kinds)
+        };
+
+        // Members are substituted lazily
+        let (r, kinds) = verify("Box.<String>");
+        assert!(kinds.is_empty());
+        let box_of_string = r.unwrap().referenced_type();
+        assert!(box_of_string.is::<TypeAfterSubstitution>());
+        assert!(box_of_string.origin() == box_type);
+        let value = box_of_string.prototype(&host).get(&value_name).unwrap();
+        assert!(value.static_type(&host) == host.string_type());
+
+        let (r, kinds) = verify("Box.<String, Number>");
+        assert!(r.is_none());
+        assert!(kinds == vec![Some(FxDiagnosticKind::IncorrectNumArgumentsNoMoreThan)]);
+
+        let (r, kinds) = verify("String.<Number>");
+        assert!(r.is_none());
+        assert!(kinds == vec![Some(FxDiagnosticKind::NonParameterizedType)]);
+    }
+
+    #[test]
+    fn test_bounded_type_parameters() {
+        let host = Rc::new(SemanticHost::new(SemanticHostOptions::default()));
+        let object_type = define_class(&host, &host.top_level_package(), "Object", None);
+        for name in ["Class", "Function", "String", "Boolean", "Number", "int", "uint", "float", "Namespace", "XML", "XMLList"] {
+            define_class(&host, &host.top_level_package(), name, Some(&object_type));
+        }
+        let public_ns = host.top_level_package().public_ns().unwrap();
+        let flash_utils = host.factory().create_package(["flash", "utils"]);
+        let dictionary = host.factory().create_class_type(host.factory().create_qname(&flash_utils.public_ns().unwrap(), "Dictionary".into()), &public_ns);
+        dictionary.set_parent(Some(flash_utils.clone()));
+        dictionary.set_extends_class(Some(object_type.clone()));
+        flash_utils.properties(&host).set(dictionary.name(), dictionary);
+
+        // interface IComparable { function compareTo(other: Object): int }
+        let comparable = host.factory().create_interface_type(host.factory().create_qname(&public_ns, "IComparable".into()));
+        comparable.set_parent(Some(host.top_level_package()));
+        host.top_level_package().properties(&host).set(comparable.name(), comparable.clone());
+        let compare_to_name = host.factory().create_qname(&public_ns, "compareTo".into());
+        let compare_to_sig = host.factory().create_function_type(vec![Rc::new(SemanticFunctionTypeParameter {
+            kind: ParameterKind::Required,
+            static_type: object_type.clone(),
+        })], host.int_type());
+        let compare_to = host.factory().create_method_slot(&compare_to_name, &compare_to_sig);
+        compare_to.set_parent(Some(comparable.clone()));
+        comparable.prototype(&host).set(compare_to_name, compare_to.clone());
+
+        let version = define_class(&host, &host.top_level_package(), "Version", None);
+        version.set_extends_class(Some(object_type.clone()));
+        version.implements(&host).push(comparable.clone());
+
+        let cu = CompilationUnit::new(None, r#"
+            package {
+                public class Sorter.<T, U> {}
+            }
+        "#.into());
+        let program = ParserFacade(&cu, ParserOptions::default()).parse_program();
+        let Directive::ClassDefinition(defn) = program.packages[0].block.directives[0].as_ref() else {
+            panic!();
+        };
+        let sorter = define_class(&host, &host.top_level_package(), "Sorter", None);
+        sorter.set_extends_class(Some(object_type.clone()));
+        let list = defn.type_parameters.as_ref().unwrap();
+        let type_params = TypeParameters(&host).declare(&sorter, list, &host.factory().create_class_scope(&sorter));
+        let t = type_params.get(0).unwrap();
+        let u = type_params.get(1).unwrap();
+
+        // T extends IComparable; U extends U is invalid
+        TypeParameters(&host).declare_bound(&t, &comparable, &list[0].location);
+        TypeParameters(&host).declare_bound(&u, &u, &list[1].location);
+        assert!(t.bound() == Some(comparable.clone()));
+        assert!(u.bound().is_none());
+        let kinds: Vec<Option<FxDiagnosticKind>> = cu.diagnostics().iter().map(|d| FxDiagnostic(d).fx_kind()).collect();
+        assert!(kinds == vec![Some(FxDiagnosticKind::InvalidTypeParameterBound)]);
+
+        // var item: T
+        let item = host.factory().create_variable_slot(&host.factory().create_qname(&public_ns, "item".into()), false, &t);
+        item.set_parent(Some(host.top_level_package()));
+        host.top_level_package().properties(&host).set(item.name(), item.clone());
+
+        let scope = host.factory().create_package_scope(&host.top_level_package());
+        let verify = |source: &str| -> (Option<Thingy>, Vec<String>) {
+            let cu = CompilationUnit::new(None, source.into());
+            let exp = ParserFacade(&cu, ParserOptions::default()).parse_expression();
+            let mut verifier = Verifier::new(&host);
+            verifier.set_scope(&scope);
+            let r = verifier.verify_expression(&exp, &default());
+            let messages = cu.diagnostics().iter().map(|d| FxDiagnostic(d).format_message_english()).collect();
+            (r, messages)
+        };
+
+        // Members are looked up through the bound
+        let (r, messages) = verify("item.compareTo");
+        assert!(messages.is_empty(), "{messages:?}");
+        assert!(r.unwrap().property() == compare_to);
+
+        let (r, messages) = verify("Sorter.<Version, String>");
+        assert!(messages.is_empty(), "{messages:?}");
+        assert!(r.is_some());
+
+        let (r, messages) = verify("Sorter.<String, String>");
+        assert!(r.is_none());
+        assert_eq!(messages, ["Type argument String does not satisfy the bound IComparable of type parameter T."]);
+
+        // Every unsatisfied bound is reported, whether type arguments are given or inferred.
+        let a = host.factory().create_type_parameter_type(&host.factory().create_qname(&public_ns, "A".into()));
+        let b = host.factory().create_type_parameter_type(&host.factory().create_qname(&public_ns, "B".into()));
+        a.set_bound(Some(comparable.clone()));
+        b.set_bound(Some(comparable.clone()));
+        let pair = define_class(&host, &host.top_level_package(), "Pair", None);
+        pair.set_extends_class(Some(object_type.clone()));
+        pair.set_type_params(Some(shared_array![a.clone(), b.clone()]));
+        let (r, messages) = verify("Pair.<String, Number>");
+        assert!(r.is_none());
+        assert_eq!(messages, [
+            "Type argument String does not satisfy the bound IComparable of type parameter A.",
+            "Type argument Number does not satisfy the bound IComparable of type parameter B.",
+        ]);
+
+        // function compare.<A, B>(a: A, b: B): int
+        let params = [&a, &b].into_iter().map(|t| Rc::new(SemanticFunctionTypeParameter {
+            kind: ParameterKind::Required,
+            static_type: t.clone(),
+        })).collect();
+        let compare = host.factory().create_method_slot(&host.factory().create_qname(&public_ns, "compare".into()), &host.factory().create_function_type(params, host.int_type()));
+        compare.set_parent(Some(host.top_level_package()));
+        compare.set_type_params(Some(shared_array![a, b]));
+        host.top_level_package().properties(&host).set(compare.name(), compare);
+        let (_, messages) = verify("compare('x', 10)");
+        assert_eq!(messages, [
+            "Type argument String does not satisfy the bound IComparable of type parameter A.",
+            "Type argument Number does not satisfy the bound IComparable of type parameter B.",
+        ]);
+    }
+}
@@ -0,0 +1,24 @@
+//! Fixtures shared by the unit tests.
+
+use crate::ns::*;
+
+/// Defines a class in a package, optionally extending another class.
+pub fn define_class(host: &SemanticHost, package: &Thingy, name: &str, base: Option<&Thingy>) -> Thingy {
+    let ns = package.public_ns().unwrap();
+    let class = host.factory().create_class_type(host.factory().create_qname(&ns, name.into()), &ns);
+    class.set_parent(Some(package.clone()));
+    if let Some(base) = base {
+        class.set_extends_class(Some(base.clone()));
+    }
+    package.properties(host).set(class.name(), class.clone());
+    class
+}
+
+/// Defines a public instance variable in a class.
+pub fn define_property(host: &SemanticHost, class: &Thingy, name: &str, read_only: bool, static_type: &Thingy) -> Thingy {
+    let qname = host.factory().create_qname(&host.top_level_package().public_ns().unwrap(), name.into());
+    let slot = host.factory().create_variable_slot(&qname, read_only, static_type);
+    slot.set_parent(Some(class.clone()));
+    class.prototype(host).set(qname, slot.clone());
+    slot
+}
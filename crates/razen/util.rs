@@ -1,3 +1,6 @@
+use std::rc::Rc;
+use as3_parser::ns::{MxmlContent, MxmlElement};
+
 pub use smodel::util::{
     SharedArray,
     SharedMap,
@@ -8,4 +11,13 @@ pub use by_address::ByAddress;
 pub use maplit::{
     hashmap,
     hashset,
-};
\ No newline at end of file
+};
+
+/// Returns the elements among XML content, skipping character data,
+/// comments and processing instructions.
+pub fn child_elements(content: &[Rc<MxmlContent>]) -> Vec<Rc<MxmlElement>> {
+    content.iter().filter_map(|c| match c.as_ref() {
+        MxmlContent::Element(e) => Some(e.clone()),
+        _ => None,
+    }).collect()
+}
\ No newline at end of file
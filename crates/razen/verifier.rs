@@ -29,4 +29,7 @@ mod statement;
 pub(crate) use statement::*;
 
 mod control_flow;
-pub(crate) use control_flow::*;
\ No newline at end of file
+pub(crate) use control_flow::*;
+
+mod mxml;
+pub(crate) use mxml::*;
\ No newline at end of file
@@ -53,6 +53,113 @@ impl ArgumentsSubverifier {
             Ok(())
         }
     }
+
+    /// Verifies the arguments that are passed to parameters mentioning type parameters
+    /// of a method, inferring the type arguments from the argument types. Returns the
+    /// method signature after substitution, or `None` if inference failed.
+    ///
+    /// Remaining arguments are not verified and are expected to be verified by
+    /// `verify()` against the returned signature.
+    pub fn verify_inferring_type_arguments(verifier: &mut Subverifier, arguments: &[Rc<Expression>], signature: &Thingy, type_params: &SharedArray<Thingy>, location: &Location) -> Result<Option<Thingy>, DeferError> {
+        let host = verifier.host.clone();
+        let mut inference = TypeArgumentInference::new(&host, type_params);
+        let param_types = Self::param_types(&host, signature, arguments.len())?;
+
+        // The context type of an argument is its parameter type,
+        // with the type parameters yet unknown.
+        let mut unknown_types = SharedArray::<Thingy>::new();
+        for _ in 0..type_params.length() {
+            unknown_types.push(host.any_type());
+        }
+
+        let mut values: Vec<(Rc<Expression>, Thingy)> = vec![];
+        for (arg, param_type) in arguments.iter().zip(param_types.iter()) {
+            let Some(param_type) = param_type.as_ref().filter(|t| inference.mentions_type_params(t)) else {
+                continue;
+            };
+            let context_type = param_type.type_substitution(&host, type_params, &unknown_types);
+            if let Some(v) = verifier.verify_expression(arg, &VerifierExpressionContext {
+                context_type: Some(context_type),
+                ..default()
+            })? {
+                inference.unify(param_type, &v.static_type(&host));
+                values.push((arg.clone(), v));
+            }
+        }
+
+        let substitute_types = match inference.finish() {
+            Ok(t) => t,
+            Err(TypeArgumentInferenceError { type_param, candidates }) => {
+                if candidates.is_empty() {
+                    verifier.add_verify_error(location, FxDiagnosticKind::CouldNotInferTypeArgument, diagarg![type_param]);
+                } else {
+                    let candidates = candidates.iter().map(|t| t.to_string()).collect::<Vec<_>>().join(", ");
+                    verifier.add_verify_error(location, FxDiagnosticKind::CouldNotInferTypeArgumentFromCandidates, diagarg![type_param, candidates]);
+                }
+                return Ok(None);
+            },
+        };
+        // Every unsatisfied bound is reported at a distinct argument its type parameter
+        // was inferred from, since diagnostics at an equal offset are discarded.
+        let unsatisfied_bounds = TypeParameters(&host).unsatisfied_bounds(type_params, &substitute_types)?;
+        let mut reported_arguments: Vec<usize> = vec![];
+        for (i, bound) in &unsatisfied_bounds {
+            let type_param = type_params.get(*i).unwrap();
+            let mentions = TypeArgumentInference::new(&host, &shared_array![type_param.clone()]);
+            let argument = (0..param_types.len()).find(|j| {
+                !reported_arguments.contains(j) && param_types[*j].as_ref().is_some_and(|t| mentions.mentions_type_params(t))
+            });
+            let location = match argument {
+                Some(j) => {
+                    reported_arguments.push(j);
+                    arguments[j].location()
+                },
+                None => location.clone(),
+            };
+            verifier.add_verify_error(&location, FxDiagnosticKind::TypeArgumentDoesNotSatisfyBound, diagarg![substitute_types.get(*i).unwrap(), bound.clone(), type_param]);
+        }
+        if !unsatisfied_bounds.is_empty() {
+            return Ok(None);
+        }
+        let signature = signature.type_substitution(&host, type_params, &substitute_types);
+
+        // Coerce the arguments that were verified.
+        let param_types = Self::param_types(&host, &signature, arguments.len())?;
+        for (arg, v) in values {
+            let i = arguments.iter().position(|a| Rc::ptr_eq(a, &arg)).unwrap();
+            let param_type = param_types[i].clone().unwrap();
+            let Some(v1) = TypeConversions(&host).implicit(&v, &param_type, false)? else {
+                verifier.add_verify_error(&arg.location(), FxDiagnosticKind::ImplicitCoercionToUnrelatedType, diagarg![v.static_type(&host), param_type]);
+                host.node_invalidation_mapping().set(&arg, Some(()));
+                continue;
+            };
+            host.node_mapping().set(&arg, Some(v1));
+        }
+
+        Ok(Some(signature))
+    }
+
+    /// Returns the parameter type corresponding to each argument,
+    /// or `None` for exceeding arguments.
+    fn param_types(host: &SemanticHost, signature: &Thingy, argument_count: usize) -> Result<Vec<Option<Thingy>>, DeferError> {
+        let mut r = vec![];
+        let mut rest_elem_type: Option<Thingy> = None;
+        let sig_params = signature.params();
+        let mut sig_params = sig_params.iter();
+        for _ in 0..argument_count {
+            if let Some(sig_param) = sig_params.next() {
+                if sig_param.kind == ParameterKind::Rest {
+                    rest_elem_type = sig_param.static_type.array_element_type(host)?;
+                    r.push(rest_elem_type.clone());
+                } else {
+                    r.push(Some(sig_param.static_type.clone()));
+                }
+            } else {
+                r.push(rest_elem_type.clone());
+            }
+        }
+        Ok(r)
+    }
 }
 
 fn map_defer_error<T>(result: Result<T, DeferError>) -> Result<T, VerifierArgumentsError> {
@@ -45,6 +45,11 @@ impl ExpSubverifier {
     pub fn verify_qualified_identifier_as_exp(verifier: &mut Subverifier, id: &QualifiedIdentifier, context: &VerifierExpressionContext) -> Result<Option<Thingy>, DeferError> {
         // Check for inline constants
         if let Some((name, cdata)) = Self::filter_inline_constant(verifier, id) {
+            let Some(cdata) = cdata else {
+                verifier.add_verify_error(&id.location, FxDiagnosticKind::CannotResolveConfigConstant, diagarg![name]);
+                return Ok(None);
+            };
+
             // Defer
             verifier.host.string_type().defer()?;
             verifier.host.non_null_primitive_types()?;
@@ -97,7 +102,10 @@ impl ExpSubverifier {
         verifier.reference_post_processing(r, context)
     }
 
-    fn filter_inline_constant(verifier: &mut Subverifier, id: &QualifiedIdentifier) -> Option<(String, String)> {
+    /// Detects a reference to a configuration constant, returning its name and
+    /// character data, or no character data if the constant is undefined
+    /// in a namespace used by other configuration constants.
+    fn filter_inline_constant(verifier: &mut Subverifier, id: &QualifiedIdentifier) -> Option<(String, Option<String>)> {
         let QualifiedIdentifier { qualifier, id, .. } = id;
 
         if let Some(qualifier) = qualifier {
@@ -107,7 +115,10 @@ impl ExpSubverifier {
             if let (Some(inlinekqid), Some(inlinekln)) = (inlinekqid, inlinekln) {
                 let inlinekid = format!("{}::{}", inlinekqid, inlinekln);
                 if let Some(cdata) = verifier.host.config_constants().get(&inlinekid) {
-                    return Some((inlinekid, cdata));
+                    return Some((inlinekid, Some(cdata)));
+                }
+                if ConfigConstants(&verifier.host).is_config_namespace(&inlinekqid) {
+                    return Some((inlinekid, None));
                 }
             }
         }
@@ -118,7 +129,7 @@ impl ExpSubverifier {
     pub fn eval_config_constant(verifier: &mut Subverifier, location: &Location, name: String, mut cdata: String) -> Option<Thingy> {
         if let Some(v) = verifier.host.config_constants_eval().get(&name) {
             if v.is::<InvalidationThingy>() {
-                verifier.add_verify_error(location, FxDiagnosticKind::CouldNotExpandInlineConstant, diagarg![]);
+                Self::report_unexpanded_config_constant(verifier, location, &name);
                 return None;
             }
             return Some(v);
@@ -132,7 +143,7 @@ impl ExpSubverifier {
         if ["true", "false"].contains(&cdata.as_str()) {
             let boolean_type = verifier.host.boolean_type();
             if boolean_type.is::<UnresolvedThingy>() {
-                verifier.add_verify_error(location, FxDiagnosticKind::CouldNotExpandInlineConstant, diagarg![]);
+                Self::report_unexpanded_config_constant(verifier, location, &name);
                 return None;
             }
             let v = verifier.host.factory().create_boolean_constant(cdata == "true", &boolean_type);
@@ -143,7 +154,7 @@ impl ExpSubverifier {
         if cdata == "Infinity" {
             let number_type = verifier.host.number_type();
             if number_type.is::<UnresolvedThingy>() {
-                verifier.add_verify_error(location, FxDiagnosticKind::CouldNotExpandInlineConstant, diagarg![]);
+                Self::report_unexpanded_config_constant(verifier, location, &name);
                 return None;
             }
             let v = verifier.host.factory().create_number_constant(NumberVariant::Number(f64::INFINITY), &number_type);
@@ -154,7 +165,7 @@ impl ExpSubverifier {
         if cdata == "NaN" {
             let number_type = verifier.host.number_type();
             if number_type.is::<UnresolvedThingy>() {
-                verifier.add_verify_error(location, FxDiagnosticKind::CouldNotExpandInlineConstant, diagarg![]);
+                Self::report_unexpanded_config_constant(verifier, location, &name);
                 return None;
             }
             let v = verifier.host.factory().create_number_constant(NumberVariant::Number(f64::NAN), &number_type);
@@ -170,7 +181,7 @@ impl ExpSubverifier {
         // which must be a compile-time constant.
         let exp = ParserFacade(&cu, ParserOptions::default()).parse_expression();
         if cu.invalidated() {
-            verifier.add_verify_error(location, FxDiagnosticKind::CouldNotExpandInlineConstant, diagarg![]);
+            Self::report_unexpanded_config_constant(verifier, location, &name);
             return None;
         }
         let kscope = verifier.scope();
@@ -178,22 +189,31 @@ impl ExpSubverifier {
         let cval = verifier.verify_expression(&exp, &default());
         verifier.set_scope(&kscope);
         let Ok(cval) = cval else {
-            verifier.add_verify_error(location, FxDiagnosticKind::CouldNotExpandInlineConstant, diagarg![]);
+            Self::report_unexpanded_config_constant(verifier, location, &name);
             return None;
         };
         if let Some(cval) = cval.as_ref() {
             if !cval.is::<Constant>() {
-                verifier.add_verify_error(location, FxDiagnosticKind::CouldNotExpandInlineConstant, diagarg![]);
+                Self::report_unexpanded_config_constant(verifier, location, &name);
                 return None;
             }
             verifier.host.config_constants_eval().set(name.clone(), cval.clone());
         } else {
-            verifier.add_verify_error(location, FxDiagnosticKind::CouldNotExpandInlineConstant, diagarg![]);
+            Self::report_unexpanded_config_constant(verifier, location, &name);
             return None;
         }
         cval
     }
 
+    /// Reports a configuration constant that could not be expanded, both at
+    /// the reference and, if the constant is defined in a file, at its definition.
+    fn report_unexpanded_config_constant(verifier: &mut Subverifier, location: &Location, name: &str) {
+        verifier.add_verify_error(location, FxDiagnosticKind::CouldNotExpandInlineConstant, diagarg![]);
+        if let Some(defn_location) = ConfigConstants(&verifier.host).location_of(name) {
+            verifier.add_verify_error(&defn_location, FxDiagnosticKind::CannotResolveConfigConstant, diagarg![name.to_owned()]);
+        }
+    }
+
     pub fn verify_null_literal(verifier: &mut Subverifier, literal: &NullLiteral, context: &VerifierExpressionContext) -> Result<Option<Thingy>, DeferError> {
         if let Some(t) = context.context_type.as_ref() {
             if t.includes_null(&verifier.host)? {
@@ -637,7 +657,14 @@ impl ExpSubverifier {
         }
         let r = r.unwrap();
         if r.is_none() {
-            panic!();
+            let base_type = base.static_type(&verifier.host).escape_of_non_nullable();
+            if base_type.is::<TupleType>() && key.is::<NumberConstant>() {
+                verifier.add_verify_error(&member_exp.key.location(), FxDiagnosticKind::TupleIndexOutOfRange, diagarg![key.number_value().force_double().to_string(), base_type]);
+                return Ok(None);
+            }
+            let name = if key.is::<StringConstant>() { key.string_value() } else { member_exp.key.location().text() };
+            verifier.add_verify_error(&member_exp.key.location(), FxDiagnosticKind::UndefinedPropertyWithStaticType, diagarg![name, base.static_type(&verifier.host)]);
+            return Ok(None);
         }
         let r = r.unwrap();
 
@@ -818,6 +845,20 @@ impl ExpSubverifier {
 
         if base.is::<FixtureReferenceValue>() && base.property().is::<MethodSlot>() {
             let sig = base.property().signature(&verifier.host).defer()?;
+
+            // Infer type arguments
+            let sig = if let Some(type_params) = base.property().type_params() {
+                let Some(sig) = ArgumentsSubverifier::verify_inferring_type_arguments(verifier, &exp.arguments, &sig, &type_params, &exp.base.location())? else {
+                    for arg in &exp.arguments {
+                        verifier.verify_expression(arg, &default())?;
+                    }
+                    return Ok(None);
+                };
+                sig
+            } else {
+                sig
+            };
+
             match ArgumentsSubverifier::verify(verifier, &exp.arguments, &sig) {
                 Ok(_) => {},
                 Err(VerifierArgumentsError::Defer) => {
@@ -929,6 +970,14 @@ impl ExpSubverifier {
             return Ok(None);
         }
 
+        let unsatisfied_bounds = TypeParameters(&verifier.host).unsatisfied_bounds(&type_params, &resolvee_args)?;
+        for (i, bound) in &unsatisfied_bounds {
+            verifier.add_verify_error(&exp.arguments[*i].location(), FxDiagnosticKind::TypeArgumentDoesNotSatisfyBound, diagarg![resolvee_args.get(*i).unwrap(), bound.clone(), type_params.get(*i).unwrap()]);
+        }
+        if !unsatisfied_bounds.is_empty() {
+            return Ok(None);
+        }
+
         Ok(Some(verifier.host.factory().create_type_after_substitution(&base, &resolvee_args).wrap_property_reference(&verifier.host)?))
     }
 
@@ -993,6 +1042,9 @@ impl ExpSubverifier {
                 Ok(Some(verifier.host.factory().create_undefined_constant(&verifier.host.any_type())))
             },
             Operator::Typeof => {
+                if let Some(k) = ConstantFolding(&verifier.host).fold_unary(exp.operator, &val)? {
+                    return Ok(Some(k));
+                }
                 Ok(Some(verifier.host.factory().create_value(&verifier.host.string_type().defer()?)))
             },
             Operator::Yield => {
@@ -1005,8 +1057,8 @@ impl ExpSubverifier {
                     verifier.add_verify_error(&exp.expression.location(), FxDiagnosticKind::OperandMustBeNumber, diagarg![]);
                     return Ok(None);
                 }
-                if val.is::<NumberConstant>() {
-                    return Ok(Some(val.clone()));
+                if let Some(k) = ConstantFolding(&verifier.host).fold_unary(exp.operator, &val)? {
+                    return Ok(Some(k));
                 }
                 Ok(Some(verifier.host.factory().create_value(&val_st)))
             },
@@ -1016,12 +1068,12 @@ impl ExpSubverifier {
                     verifier.add_verify_error(&exp.expression.location(), FxDiagnosticKind::OperandMustBeNumber, diagarg![]);
                     return Ok(None);
                 }
-                if val.is::<NumberConstant>() {
-                    // Numeric literal has already been negated.
-                    if matches!(exp.expression.as_ref(), Expression::NumericLiteral(_)) {
-                        return Ok(Some(val.clone()));
-                    }
-                    return Ok(Some(verifier.host.factory().create_number_constant(-val.number_value(), &val_st)));
+                // Numeric literal has already been negated.
+                if val.is::<NumberConstant>() && matches!(exp.expression.as_ref(), Expression::NumericLiteral(_)) {
+                    return Ok(Some(val.clone()));
+                }
+                if let Some(k) = ConstantFolding(&verifier.host).fold_unary(exp.operator, &val)? {
+                    return Ok(Some(k));
                 }
                 Ok(Some(verifier.host.factory().create_value(&val_st)))
             },
@@ -1031,25 +1083,14 @@ impl ExpSubverifier {
                     verifier.add_verify_error(&exp.expression.location(), FxDiagnosticKind::OperandMustBeNumber, diagarg![]);
                     return Ok(None);
                 }
-                if val.is::<NumberConstant>() {
-                    return Ok(Some(verifier.host.factory().create_number_constant(val.number_value().bitwise_not(), &val_st)));
+                if let Some(k) = ConstantFolding(&verifier.host).fold_unary(exp.operator, &val)? {
+                    return Ok(Some(k));
                 }
                 Ok(Some(verifier.host.factory().create_value(&val_st)))
             },
             Operator::LogicalNot => {
-                if val.is::<Constant>() {
-                    if val.is::<BooleanConstant>() {
-                        return Ok(Some(verifier.host.factory().create_boolean_constant(!val.boolean_value(), &verifier.host.boolean_type().defer()?)));
-                    } else if val.is::<NumberConstant>() {
-                        let mv = val.number_value();
-                        return Ok(Some(verifier.host.factory().create_boolean_constant(mv.is_zero() || mv.is_nan(), &verifier.host.boolean_type().defer()?)));
-                    } else if val.is::<StringConstant>() {
-                        return Ok(Some(verifier.host.factory().create_boolean_constant(val.string_value().is_empty(), &verifier.host.boolean_type().defer()?)));
-                    } else if val.is::<UndefinedConstant>() {
-                        return Ok(Some(verifier.host.factory().create_boolean_constant(true, &verifier.host.boolean_type().defer()?)));
-                    } else if val.is::<NullConstant>() {
-                        return Ok(Some(verifier.host.factory().create_boolean_constant(true, &verifier.host.boolean_type().defer()?)));
-                    }
+                if let Some(k) = ConstantFolding(&verifier.host).fold_unary(exp.operator, &val)? {
+                    return Ok(Some(k));
                 }
                 Ok(Some(verifier.host.factory().create_value(&verifier.host.boolean_type().defer()?)))
             },
@@ -1122,11 +1163,8 @@ impl ExpSubverifier {
                 let object_type = verifier.host.object_type().defer()?;
                 let numeric_types = verifier.host.numeric_types()?;
 
-                if left.is::<NumberConstant>() && right.is::<NumberConstant>() {
-                    return Ok(Some(verifier.host.factory().create_number_constant(left.number_value() + right.number_value(), &left_st)));
-                }
-                if left.is::<StringConstant>() && right.is::<StringConstant>() {
-                    return Ok(Some(verifier.host.factory().create_string_constant(left.string_value() + &right.string_value(), &left_st)));
+                if let Some(k) = ConstantFolding(&verifier.host).fold_binary(exp.operator, &left, &right)? {
+                    return Ok(Some(k));
                 }
                 if numeric_types.contains(&left_st) || left_st.escape_of_non_nullable() == object_type {
                     return Ok(Some(verifier.host.factory().create_value(&left_st)));
@@ -1143,8 +1181,8 @@ impl ExpSubverifier {
                     verifier.add_verify_error(&exp.location, FxDiagnosticKind::UnrelatedMathOperation, diagarg![left_st]);
                     return Ok(None);
                 }
-                if left.is::<NumberConstant>() && right.is::<NumberConstant>() {
-                    return Ok(Some(verifier.host.factory().create_number_constant(left.number_value() - right.number_value(), &left_st)));
+                if let Some(k) = ConstantFolding(&verifier.host).fold_binary(exp.operator, &left, &right)? {
+                    return Ok(Some(k));
                 }
                 Ok(Some(verifier.host.factory().create_value(&left_st)))
             },
@@ -1158,13 +1196,19 @@ impl ExpSubverifier {
                     verifier.add_verify_error(&exp.location, FxDiagnosticKind::UnrelatedMathOperation, diagarg![left_st]);
                     return Ok(None);
                 }
-                if left.is::<NumberConstant>() && right.is::<NumberConstant>() {
-                    return Ok(Some(verifier.host.factory().create_number_constant(left.number_value() * right.number_value(), &left_st)));
+                if let Some(k) = ConstantFolding(&verifier.host).fold_binary(exp.operator, &left, &right)? {
+                    return Ok(Some(k));
                 }
                 Ok(Some(verifier.host.factory().create_value(&left_st)))
             },
             Operator::Divide => {
-                let Some(right) = verifier.imp_coerce_exp(&exp.right, &left_st)? else {
+                // Dividing int or uint results into a Number.
+                let result_type = if [verifier.host.int_type().defer()?, verifier.host.uint_type().defer()?].contains(&left_st_esc) {
+                    verifier.host.number_type().defer()?
+                } else {
+                    left_st.clone()
+                };
+                let Some(right) = verifier.imp_coerce_exp(&exp.right, &result_type)? else {
                     return Ok(None);
                 };
                 if ![verifier.host.any_type(), verifier.host.object_type().defer()?].contains(&left_st_esc)
@@ -1173,10 +1217,10 @@ impl ExpSubverifier {
                     verifier.add_verify_error(&exp.location, FxDiagnosticKind::UnrelatedMathOperation, diagarg![left_st]);
                     return Ok(None);
                 }
-                if left.is::<NumberConstant>() && right.is::<NumberConstant>() {
-                    return Ok(Some(verifier.host.factory().create_number_constant(left.number_value() / right.number_value(), &left_st)));
+                if let Some(k) = ConstantFolding(&verifier.host).fold_binary(exp.operator, &left, &right)? {
+                    return Ok(Some(k));
                 }
-                Ok(Some(verifier.host.factory().create_value(&left_st)))
+                Ok(Some(verifier.host.factory().create_value(&result_type)))
             },
             Operator::Remainder => {
                 let Some(right) = verifier.imp_coerce_exp(&exp.right, &left_st)? else {
@@ -1188,13 +1232,13 @@ impl ExpSubverifier {
                     verifier.add_verify_error(&exp.location, FxDiagnosticKind::UnrelatedMathOperation, diagarg![left_st]);
                     return Ok(None);
                 }
-                if left.is::<NumberConstant>() && right.is::<NumberConstant>() {
-                    return Ok(Some(verifier.host.factory().create_number_constant(left.number_value() % right.number_value(), &left_st)));
+                if let Some(k) = ConstantFolding(&verifier.host).fold_binary(exp.operator, &left, &right)? {
+                    return Ok(Some(k));
                 }
                 Ok(Some(verifier.host.factory().create_value(&left_st)))
             },
             Operator::Power => {
-                let Some(_) = verifier.imp_coerce_exp(&exp.right, &left_st)? else {
+                let Some(right) = verifier.imp_coerce_exp(&exp.right, &left_st)? else {
                     return Ok(None);
                 };
                 if ![verifier.host.any_type(), verifier.host.object_type().defer()?].contains(&left_st_esc)
@@ -1203,6 +1247,9 @@ impl ExpSubverifier {
                     verifier.add_verify_error(&exp.location, FxDiagnosticKind::UnrelatedMathOperation, diagarg![left_st]);
                     return Ok(None);
                 }
+                if let Some(k) = ConstantFolding(&verifier.host).fold_binary(exp.operator, &left, &right)? {
+                    return Ok(Some(k));
+                }
                 Ok(Some(verifier.host.factory().create_value(&left_st)))
             },
             Operator::BitwiseAnd => {
@@ -1215,8 +1262,8 @@ impl ExpSubverifier {
                     verifier.add_verify_error(&exp.location, FxDiagnosticKind::UnrelatedMathOperation, diagarg![left_st]);
                     return Ok(None);
                 }
-                if left.is::<NumberConstant>() && right.is::<NumberConstant>() {
-                    return Ok(Some(verifier.host.factory().create_number_constant(left.number_value() & right.number_value(), &left_st)));
+                if let Some(k) = ConstantFolding(&verifier.host).fold_binary(exp.operator, &left, &right)? {
+                    return Ok(Some(k));
                 }
                 Ok(Some(verifier.host.factory().create_value(&left_st)))
             },
@@ -1230,8 +1277,8 @@ impl ExpSubverifier {
                     verifier.add_verify_error(&exp.location, FxDiagnosticKind::UnrelatedMathOperation, diagarg![left_st]);
                     return Ok(None);
                 }
-                if left.is::<NumberConstant>() && right.is::<NumberConstant>() {
-                    return Ok(Some(verifier.host.factory().create_number_constant(left.number_value() ^  right.number_value(), &left_st)));
+                if let Some(k) = ConstantFolding(&verifier.host).fold_binary(exp.operator, &left, &right)? {
+                    return Ok(Some(k));
                 }
                 Ok(Some(verifier.host.factory().create_value(&left_st)))
             },
@@ -1245,8 +1292,8 @@ impl ExpSubverifier {
                     verifier.add_verify_error(&exp.location, FxDiagnosticKind::UnrelatedMathOperation, diagarg![left_st]);
                     return Ok(None);
                 }
-                if left.is::<NumberConstant>() && right.is::<NumberConstant>() {
-                    return Ok(Some(verifier.host.factory().create_number_constant(left.number_value() | right.number_value(), &left_st)));
+                if let Some(k) = ConstantFolding(&verifier.host).fold_binary(exp.operator, &left, &right)? {
+                    return Ok(Some(k));
                 }
                 Ok(Some(verifier.host.factory().create_value(&left_st)))
             },
@@ -1260,8 +1307,8 @@ impl ExpSubverifier {
                     verifier.add_verify_error(&exp.location, FxDiagnosticKind::UnrelatedMathOperation, diagarg![left_st]);
                     return Ok(None);
                 }
-                if left.is::<NumberConstant>() && right.is::<NumberConstant>() {
-                    return Ok(Some(verifier.host.factory().create_number_constant(left.number_value() << right.number_value(), &left_st)));
+                if let Some(k) = ConstantFolding(&verifier.host).fold_binary(exp.operator, &left, &right)? {
+                    return Ok(Some(k));
                 }
                 Ok(Some(verifier.host.factory().create_value(&left_st)))
             },
@@ -1275,8 +1322,8 @@ impl ExpSubverifier {
                     verifier.add_verify_error(&exp.location, FxDiagnosticKind::UnrelatedMathOperation, diagarg![left_st]);
                     return Ok(None);
                 }
-                if left.is::<NumberConstant>() && right.is::<NumberConstant>() {
-                    return Ok(Some(verifier.host.factory().create_number_constant(left.number_value() >> right.number_value(), &left_st)));
+                if let Some(k) = ConstantFolding(&verifier.host).fold_binary(exp.operator, &left, &right)? {
+                    return Ok(Some(k));
                 }
                 Ok(Some(verifier.host.factory().create_value(&left_st)))
             },
@@ -1290,8 +1337,8 @@ impl ExpSubverifier {
                     verifier.add_verify_error(&exp.location, FxDiagnosticKind::UnrelatedMathOperation, diagarg![left_st]);
                     return Ok(None);
                 }
-                if left.is::<NumberConstant>() && right.is::<NumberConstant>() {
-                    return Ok(Some(verifier.host.factory().create_number_constant(left.number_value().shift_right_unsigned(&right.number_value()), &left_st)));
+                if let Some(k) = ConstantFolding(&verifier.host).fold_binary(exp.operator, &left, &right)? {
+                    return Ok(Some(k));
                 }
                 Ok(Some(verifier.host.factory().create_value(&left_st)))
             },
@@ -1315,14 +1362,8 @@ impl ExpSubverifier {
                     verifier.add_warning(&exp.location, FxDiagnosticKind::NanComparison, diagarg![]);
                 }
 
-                if left.is::<NumberConstant>() && right.is::<NumberConstant>() {
-                    return Ok(Some(verifier.host.factory().create_boolean_constant(left.number_value() == right.number_value(), &boolean_type)));
-                }
-                if left.is::<StringConstant>() && right.is::<StringConstant>() {
-                    return Ok(Some(verifier.host.factory().create_boolean_constant(left.string_value() == right.string_value(), &boolean_type)));
-                }
-                if left.is::<BooleanConstant>() && right.is::<BooleanConstant>() {
-                    return Ok(Some(verifier.host.factory().create_boolean_constant(left.boolean_value() == right.boolean_value(), &boolean_type)));
+                if let Some(k) = ConstantFolding(&verifier.host).fold_binary(exp.operator, &left, &right)? {
+                    return Ok(Some(k));
                 }
                 Ok(Some(verifier.host.factory().create_value(&boolean_type)))
             },
@@ -1346,14 +1387,8 @@ impl ExpSubverifier {
                     verifier.add_warning(&exp.location, FxDiagnosticKind::NanComparison, diagarg![]);
                 }
 
-                if left.is::<NumberConstant>() && right.is::<NumberConstant>() {
-                    return Ok(Some(verifier.host.factory().create_boolean_constant(left.number_value() != right.number_value(), &boolean_type)));
-                }
-                if left.is::<StringConstant>() && right.is::<StringConstant>() {
-                    return Ok(Some(verifier.host.factory().create_boolean_constant(left.string_value() != right.string_value(), &boolean_type)));
-                }
-                if left.is::<BooleanConstant>() && right.is::<BooleanConstant>() {
-                    return Ok(Some(verifier.host.factory().create_boolean_constant(left.boolean_value() != right.boolean_value(), &boolean_type)));
+                if let Some(k) = ConstantFolding(&verifier.host).fold_binary(exp.operator, &left, &right)? {
+                    return Ok(Some(k));
                 }
                 Ok(Some(verifier.host.factory().create_value(&boolean_type)))
             },
@@ -1373,8 +1408,8 @@ impl ExpSubverifier {
                     verifier.add_warning(&exp.location, FxDiagnosticKind::ComparisonBetweenUnrelatedTypes, diagarg![left_st.clone(), right_st.clone()]);
                 }
 
-                if left.is::<NumberConstant>() && right.is::<NumberConstant>() {
-                    return Ok(Some(verifier.host.factory().create_boolean_constant(left.number_value() < right.number_value(), &boolean_type)));
+                if let Some(k) = ConstantFolding(&verifier.host).fold_binary(exp.operator, &left, &right)? {
+                    return Ok(Some(k));
                 }
                 Ok(Some(verifier.host.factory().create_value(&boolean_type)))
             },
@@ -1394,8 +1429,8 @@ impl ExpSubverifier {
                     verifier.add_warning(&exp.location, FxDiagnosticKind::ComparisonBetweenUnrelatedTypes, diagarg![left_st.clone(), right_st.clone()]);
                 }
 
-                if left.is::<NumberConstant>() && right.is::<NumberConstant>() {
-                    return Ok(Some(verifier.host.factory().create_boolean_constant(left.number_value() > right.number_value(), &boolean_type)));
+                if let Some(k) = ConstantFolding(&verifier.host).fold_binary(exp.operator, &left, &right)? {
+                    return Ok(Some(k));
                 }
                 Ok(Some(verifier.host.factory().create_value(&boolean_type)))
             },
@@ -1415,8 +1450,8 @@ impl ExpSubverifier {
                     verifier.add_warning(&exp.location, FxDiagnosticKind::ComparisonBetweenUnrelatedTypes, diagarg![left_st.clone(), right_st.clone()]);
                 }
 
-                if left.is::<NumberConstant>() && right.is::<NumberConstant>() {
-                    return Ok(Some(verifier.host.factory().create_boolean_constant(left.number_value() <= right.number_value(), &boolean_type)));
+                if let Some(k) = ConstantFolding(&verifier.host).fold_binary(exp.operator, &left, &right)? {
+                    return Ok(Some(k));
                 }
                 Ok(Some(verifier.host.factory().create_value(&boolean_type)))
             },
@@ -1436,8 +1471,8 @@ impl ExpSubverifier {
                     verifier.add_warning(&exp.location, FxDiagnosticKind::ComparisonBetweenUnrelatedTypes, diagarg![left_st.clone(), right_st.clone()]);
                 }
 
-                if left.is::<NumberConstant>() && right.is::<NumberConstant>() {
-                    return Ok(Some(verifier.host.factory().create_boolean_constant(left.number_value() >= right.number_value(), &boolean_type)));
+                if let Some(k) = ConstantFolding(&verifier.host).fold_binary(exp.operator, &left, &right)? {
+                    return Ok(Some(k));
                 }
                 Ok(Some(verifier.host.factory().create_value(&boolean_type)))
             },
@@ -1476,8 +1511,8 @@ impl ExpSubverifier {
                 let right_st = right.static_type(&verifier.host);
                 let boolean_type = verifier.host.boolean_type().defer()?;
 
-                if left.is::<BooleanConstant>() && right.is::<BooleanConstant>() {
-                    return Ok(Some(verifier.host.factory().create_boolean_constant(left.boolean_value() && right.boolean_value(), &boolean_type)));
+                if let Some(k) = ConstantFolding(&verifier.host).fold_binary(exp.operator, &left, &right)? {
+                    return Ok(Some(k));
                 }
                 if left_st == boolean_type && left_st == right_st {
                     return Ok(Some(verifier.host.factory().create_value(&boolean_type)));
@@ -1491,6 +1526,9 @@ impl ExpSubverifier {
                 let right_st = right.static_type(&verifier.host);
                 let boolean_type = verifier.host.boolean_type().defer()?;
 
+                if let Some(k) = ConstantFolding(&verifier.host).fold_binary(exp.operator, &left, &right)? {
+                    return Ok(Some(k));
+                }
                 if left_st == boolean_type && left_st == right_st {
                     return Ok(Some(verifier.host.factory().create_value(&boolean_type)));
                 }
@@ -1503,8 +1541,8 @@ impl ExpSubverifier {
                 let right_st = right.static_type(&verifier.host);
                 let boolean_type = verifier.host.boolean_type().defer()?;
 
-                if left.is::<BooleanConstant>() && right.is::<BooleanConstant>() {
-                    return Ok(Some(verifier.host.factory().create_boolean_constant(left.boolean_value() || right.boolean_value(), &boolean_type)));
+                if let Some(k) = ConstantFolding(&verifier.host).fold_binary(exp.operator, &left, &right)? {
+                    return Ok(Some(k));
                 }
                 if left_st == boolean_type && left_st == right_st {
                     return Ok(Some(verifier.host.factory().create_value(&boolean_type)));
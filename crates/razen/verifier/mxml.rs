@@ -0,0 +1,1249 @@
+use std::fs;
+use std::path::Path;
+use crate::ns::*;
+
+/// MXML document verification.
+///
+/// Every MXML document declares a class extending the component class of its
+/// root tag, in the package determined by `CompilerOptions::source_path`.
+/// The tags of the document resolve to component classes as described
+/// in `MxmlComponents`, and are mapped to them in the node mapping.
+///
+/// Attributes and property tags are checked against the properties, styles and events
+/// of their component. Event handlers are verified in an activation where `this` is the
+/// document and `event` is the dispatched event, and `<fx:Script>` blocks are verified
+/// in the document's class scope.
+///
+/// View states are declared by the `states` property tag of the root tag. Component tags
+/// may be restricted to states through `includeIn` or `excludeFrom`, and values may be
+/// specific to a state through the `name.state` syntax. The states and their overrides
+/// are recorded in `Thingy::mxml_states()` of the document class.
+///
+/// Every component tag with an `id` attribute, including the non-visual components of
+/// `<fx:Declarations>`, declares a bindable public variable of the document class typed as
+/// its component class. `<fx:Metadata>` meta-data is attached to the document class, and
+/// the style sheets of `<fx:Style>` tags and `[CSS]` meta-data are recorded in
+/// `Thingy::style_sheets()` and verified through `StyleSheets`.
+pub(crate) struct MxmlSubverifier;
+
+impl MxmlSubverifier {
+    /// Language tags that may appear as children of the root tag.
+    const ROOT_LANGUAGE_TAGS: [&'static str; 4] = ["Script", "Declarations", "Metadata", "Style"];
+
+    const CDATA_START: &'static str = "<![CDATA[";
+    const CDATA_END: &'static str = "]]>";
+
+    /// Declares the class of a MXML document. Returns `None` if the
+    /// root tag does not resolve to a component class yet.
+    pub fn declare_document(verifier: &mut Subverifier, mxml: &Rc<Mxml>) -> Option<Thingy> {
+        let host = verifier.host.clone();
+        if let Some(class) = host.node_mapping().get(mxml) {
+            return Some(class);
+        }
+        let root = child_elements(&mxml.content).into_iter().next()?;
+        let (uri, local_name) = root.name.resolve_name(&root.namespace).ok()?;
+        let base_class = MxmlComponents(&host).resolve(&uri, &local_name)?;
+        host.node_mapping().set(&root, Some(base_class.clone()));
+
+        let cu = mxml.location.compilation_unit();
+        let source_path = cu.compiler_options()
+            .and_then(|o| Rc::downcast::<CompilerOptions>(o).ok())
+            .map(|o| o.source_path.clone())
+            .unwrap_or_default();
+        let (package_name, class_name) = MxmlComponents::document_class_name(&cu.file_path().unwrap_or_default(), &source_path);
+        let package = host.factory().create_package(package_name.iter().map(|s| s.as_str()));
+        let ns = package.public_ns().unwrap();
+        let name = host.factory().create_qname(&ns, class_name.clone());
+
+        let class = if package.properties(&host).has(&name) {
+            verifier.add_verify_error(&root.name.location, FxDiagnosticKind::DuplicateClassDefinition, diagarg![class_name]);
+            host.invalidation_thingy()
+        } else {
+            let class = host.factory().create_class_type(name.clone(), &host.top_level_package().public_ns().unwrap());
+            class.set_parent(Some(package.clone()));
+            class.set_extends_class(Some(base_class));
+            package.properties(&host).set(name, class.clone());
+            Self::declare_metadata(verifier, &root, &class);
+            Self::declare_style_sheets(verifier, &root, &class);
+            class
+        };
+        host.node_mapping().set(mxml, Some(class.clone()));
+        Some(class)
+    }
+
+    pub fn verify_mxml(verifier: &mut Subverifier, mxml: &Rc<Mxml>) -> Result<(), DeferError> {
+        let Some(root) = child_elements(&mxml.content).into_iter().next() else {
+            return Ok(());
+        };
+        let Some(class) = Self::declare_document(verifier, mxml) else {
+            Self::resolve_component(verifier, &root);
+            return Ok(());
+        };
+        if class.is::<InvalidationThingy>() {
+            return Ok(());
+        }
+        let base_class = class.extends_class(&verifier.host).unwrap();
+        Self::declare_states(verifier, &root, &class);
+
+        let kscope = verifier.scope.clone();
+        verifier.set_scope(&Self::document_scope(&verifier.host, &class));
+        let mut any_defer = Self::declare_ids(verifier, &root, &base_class, &class).is_err();
+        Bindable(&verifier.host).apply_to_class(&class);
+        let r = Self::verify_scripts(verifier, &root).is_err();
+        any_defer = any_defer || r;
+        let r = Self::verify_component(verifier, &root, &base_class, &class, true).is_err();
+        any_defer = any_defer || r;
+        verifier.scope = kscope;
+
+        if any_defer {
+            return Err(DeferError(None));
+        }
+        StyleSheets(&verifier.host).verify_class(&class);
+        Ok(())
+    }
+
+    /// Attaches the meta-data of the `<fx:Metadata>` tags of a document to its class,
+    /// reporting malformed meta-data.
+    fn declare_metadata(verifier: &mut Subverifier, root: &Rc<MxmlElement>, class: &Thingy) {
+        let mut metadata = class.metadata();
+        for element in Self::language_tags(root, "Metadata") {
+            for code in Self::tag_code(verifier, &element) {
+                let directives = ParserFacade(&code.compilation_unit, ParserOptions {
+                    byte_range: code.byte_range,
+                    ..default()
+                }).parse_directives(ParserDirectiveContext::Default);
+                for drtv in directives {
+                    let Directive::ExpressionStatement(stmt) = drtv.as_ref() else {
+                        if !matches!(drtv.as_ref(), Directive::Invalidated(_)) {
+                            verifier.add_verify_error(&drtv.location(), FxDiagnosticKind::UnrecognizedMxmlMetadata, diagarg![]);
+                        }
+                        continue;
+                    };
+                    let mut list = vec![];
+                    if !Self::refine_metadata_list(&stmt.expression, &mut list) {
+                        verifier.add_verify_error(&stmt.expression.location(), FxDiagnosticKind::UnrecognizedMxmlMetadata, diagarg![]);
+                        continue;
+                    }
+                    for m in list {
+                        metadata.push(m);
+                    }
+                }
+            }
+        }
+        FlexEvents(&verifier.host).apply(class);
+        StyleSheets(&verifier.host).apply(class);
+    }
+
+    /// Refines a sequence of meta-data, as in `[A]` or `[A][B]`,
+    /// returning `false` if it is malformed.
+    fn refine_metadata_list(exp: &Rc<Expression>, list: &mut Vec<Rc<Metadata>>) -> bool {
+        match exp.as_ref() {
+            Expression::ArrayLiteral(ArrayLiteral { elements, .. }) => {
+                let [Element::Expression(exp)] = elements.as_slice() else {
+                    return false;
+                };
+                let Some(metadata) = Self::refine_metadata(exp) else {
+                    return false;
+                };
+                list.push(metadata);
+                true
+            },
+            Expression::ComputedMember(ComputedMemberExpression { base, key, .. }) => {
+                if !Self::refine_metadata_list(base, list) {
+                    return false;
+                }
+                let Some(metadata) = Self::refine_metadata(key) else {
+                    return false;
+                };
+                list.push(metadata);
+                true
+            },
+            _ => false,
+        }
+    }
+
+    /// Refines a meta-data in the form `Name` or `Name(entries)`, following the rules of the parser.
+    fn refine_metadata(exp: &Rc<Expression>) -> Option<Rc<Metadata>> {
+        let (name, entries) = match exp.as_ref() {
+            Expression::Call(CallExpression { base, arguments, .. }) => {
+                let entries = arguments.iter().map(Self::refine_metadata_entry).collect::<Option<Vec<_>>>()?;
+                (base.to_identifier_name()?, Some(entries))
+            },
+            _ => (exp.to_identifier_name()?, None),
+        };
+        Some(Rc::new(Metadata {
+            location: exp.location(),
+            asdoc: None,
+            name,
+            entries,
+        }))
+    }
+
+    fn refine_metadata_entry(exp: &Rc<Expression>) -> Option<Rc<MetadataEntry>> {
+        let (key, value) = match exp.as_ref() {
+            Expression::Assignment(AssignmentExpression { compound: None, left, right, .. }) => {
+                let Expression::StringLiteral(StringLiteral { value, .. }) = right.as_ref() else {
+                    return None;
+                };
+                (Some(left.to_identifier_name()?), MetadataValue::String((value.clone(), right.location())))
+            },
+            Expression::StringLiteral(StringLiteral { value, .. }) => (None, MetadataValue::String((value.clone(), exp.location()))),
+            _ => (None, MetadataValue::IdentifierString(exp.to_identifier_name()?)),
+        };
+        Some(Rc::new(MetadataEntry {
+            location: exp.location(),
+            key,
+            value: Rc::new(value),
+        }))
+    }
+
+    /// Parses the `<fx:Style>` tags of a document, recording
+    /// the resulting style sheets on its class.
+    fn declare_style_sheets(verifier: &mut Subverifier, root: &Rc<MxmlElement>, class: &Thingy) {
+        let mut style_sheets = class.style_sheets();
+        for element in Self::language_tags(root, "Style") {
+            for code in Self::tag_code(verifier, &element) {
+                style_sheets.push(CssParserFacade(&code.compilation_unit, ParserOptions {
+                    byte_range: code.byte_range,
+                    ..default()
+                }).parse_document());
+            }
+        }
+    }
+
+    /// Declares the view states of a document from the `states` property tag of its root tag,
+    /// replacing the states of a previous pass.
+    fn declare_states(verifier: &mut Subverifier, root: &Rc<MxmlElement>, document: &Thingy) {
+        let mut states = document.mxml_states();
+        states.clear();
+        let uri = root.name.resolve_prefix(&root.namespace).ok();
+        for child in child_elements(root.content.as_deref().unwrap_or(&[])) {
+            if child.name.name != "states" || child.name.resolve_prefix(&child.namespace).ok() != uri {
+                continue;
+            }
+            for state_element in child_elements(child.content.as_deref().unwrap_or(&[])) {
+                let Some(name) = Self::find_attribute(&state_element, "name") else {
+                    continue;
+                };
+                let state_groups = Self::find_attribute(&state_element, "stateGroups")
+                    .map(|a| Self::split_state_names(&a.value.0))
+                    .unwrap_or_default();
+                if states.iter().any(|s| s.name == name.value.0) {
+                    verifier.add_verify_error(&name.value.1, FxDiagnosticKind::DuplicateMxmlState, diagarg![name.value.0.clone()]);
+                    continue;
+                }
+                states.push(Rc::new(MxmlState::new(&name.value.0, state_groups, state_element.location.clone())));
+            }
+        }
+    }
+
+    /// Declares a public variable of the document class for the `id` attribute
+    /// of every component tag inside a tag, typed as the component class.
+    fn declare_ids(verifier: &mut Subverifier, element: &Rc<MxmlElement>, component: &Thingy, document: &Thingy) -> Result<(), DeferError> {
+        let uri = element.name.resolve_prefix(&element.namespace).ok();
+        for child in child_elements(element.content.as_deref().unwrap_or(&[])) {
+            let Ok((child_uri, child_name)) = child.name.resolve_name(&child.namespace) else {
+                continue;
+            };
+            let components = if MxmlComponents::is_language_namespace(&child_uri) {
+                if child_name != "Declarations" {
+                    continue;
+                }
+                child_elements(child.content.as_deref().unwrap_or(&[]))
+            } else if uri.as_ref() == Some(&child_uri) && Self::is_member(verifier, component, MxmlState::split_name(&child_name).0)? {
+                child_elements(child.content.as_deref().unwrap_or(&[]))
+            } else {
+                vec![child]
+            };
+            for element in components {
+                // Unresolved components are reported during verification.
+                let Some(class) = element.name.resolve_name(&element.namespace).ok().and_then(|(uri, local_name)| MxmlComponents(&verifier.host).resolve(&uri, &local_name)) else {
+                    continue;
+                };
+                Self::declare_id(verifier, &element, &class, document)?;
+                Self::declare_ids(verifier, &element, &class, document)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Declares the variable of an `id` attribute, reporting it if the document
+    /// class or one of its base classes already has a property of that name.
+    fn declare_id(verifier: &mut Subverifier, element: &Rc<MxmlElement>, class: &Thingy, document: &Thingy) -> Result<(), DeferError> {
+        let host = verifier.host.clone();
+        let Some(id) = Self::find_attribute(element, "id") else {
+            return Ok(());
+        };
+        if host.node_mapping().has(&id) {
+            return Ok(());
+        }
+        if Self::lookup_property(verifier, document, &id.value.0)?.is_some() {
+            verifier.add_verify_error(&id.value.1, FxDiagnosticKind::DuplicateMxmlId, diagarg![id.value.0.clone()]);
+            host.node_mapping().set(&id, Some(host.invalidation_thingy()));
+            return Ok(());
+        }
+        let name = host.factory().create_qname(&host.top_level_package().public_ns().unwrap(), id.value.0.clone());
+        let slot = host.factory().create_variable_slot(&name, false, class);
+        slot.set_parent(Some(document.clone()));
+        slot.set_bindable_event(Some(Bindable::DEFAULT_EVENT.into()));
+        document.prototype(&host).set(name, slot.clone());
+        host.node_mapping().set(&id, Some(slot));
+        Ok(())
+    }
+
+    /// Returns the states referred to by a state or state group name,
+    /// reporting the name if it is unknown.
+    fn resolve_state(verifier: &mut Subverifier, document: &Thingy, name: &str, location: &Location) -> Option<Vec<Rc<MxmlState>>> {
+        let states: Vec<Rc<MxmlState>> = document.mxml_states().iter().collect();
+        let resolved = MxmlState::resolve(&states, name);
+        if resolved.is_empty() {
+            verifier.add_verify_error(location, FxDiagnosticKind::UnknownMxmlState, diagarg![name.to_owned()]);
+            return None;
+        }
+        Some(resolved)
+    }
+
+    fn add_state_override(states: &[Rc<MxmlState>], state_override: MxmlStateOverride) {
+        for state in states {
+            state.overrides.clone().push(state_override.clone());
+        }
+    }
+
+    /// Verifies the `includeIn` or `excludeFrom` attribute of a component tag,
+    /// adding the tag to the overrides of the states it is present in.
+    fn verify_state_inclusion(verifier: &mut Subverifier, element: &Rc<MxmlElement>, document: &Thingy) {
+        let include_in = Self::find_attribute(element, "includeIn");
+        let exclude_from = Self::find_attribute(element, "excludeFrom");
+        if let (Some(_), Some(exclude_from)) = (&include_in, &exclude_from) {
+            verifier.add_verify_error(&exclude_from.name.location, FxDiagnosticKind::ConflictingMxmlStateInclusion, diagarg![]);
+            return;
+        }
+        let Some(attr) = include_in.as_ref().or(exclude_from.as_ref()) else {
+            return;
+        };
+        let mut named = vec![];
+        for name in Self::split_state_names(&attr.value.0) {
+            named.extend(Self::resolve_state(verifier, document, &name, &attr.value.1).unwrap_or_default());
+        }
+        let states: Vec<Rc<MxmlState>> = document.mxml_states().iter().collect();
+        let present: Vec<Rc<MxmlState>> = states.into_iter()
+            .filter(|s| named.iter().any(|n| Rc::ptr_eq(n, s)) == include_in.is_some())
+            .collect();
+        Self::add_state_override(&present, MxmlStateOverride::AddChild(element.clone()));
+    }
+
+    /// Splits a comma-separated list of state names.
+    fn split_state_names(value: &str) -> Vec<String> {
+        value.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()).map(|s| s.to_owned()).collect()
+    }
+
+    /// Finds an unprefixed attribute of a tag.
+    pub fn find_attribute(element: &Rc<MxmlElement>, name: &str) -> Option<Rc<MxmlAttribute>> {
+        element.attributes.iter().find(|a| !a.xmlns && a.name.prefix.is_none() && a.name.name == name).cloned()
+    }
+
+    /// Returns the scope chain of a document class:
+    /// a class scope inside its package scope.
+    fn document_scope(host: &SemanticHost, class: &Thingy) -> Thingy {
+        let mut scope = host.factory().create_package_scope(&host.top_level_package());
+        let package = class.parent().unwrap();
+        if package != host.top_level_package() {
+            let package_scope = host.factory().create_package_scope(&package);
+            package_scope.set_parent(Some(scope));
+            scope = package_scope;
+        }
+        let class_scope = host.factory().create_class_scope(class);
+        class_scope.set_parent(Some(scope));
+        class_scope
+    }
+
+    /// Resolves the component class of a tag, reporting it if it is not found.
+    fn resolve_component(verifier: &mut Subverifier, element: &Rc<MxmlElement>) -> Option<Thingy> {
+        let host = verifier.host.clone();
+        if let Some(class) = host.node_mapping().get(element) {
+            return Some(class);
+        }
+        // Undefined prefixes are reported by the parser.
+        let (uri, local_name) = element.name.resolve_name(&element.namespace).ok()?;
+        let Some(class) = MxmlComponents(&host).resolve(&uri, &local_name) else {
+            verifier.add_verify_error(&element.name.location, FxDiagnosticKind::UnresolvedMxmlComponent, diagarg![element.name.to_string(&element.namespace)]);
+            return None;
+        };
+        host.node_mapping().set(element, Some(class.clone()));
+        Some(class)
+    }
+
+    fn verify_component(verifier: &mut Subverifier, element: &Rc<MxmlElement>, component: &Thingy, document: &Thingy, is_root: bool) -> Result<(), DeferError> {
+        let mut any_defer = false;
+
+        if !is_root {
+            Self::verify_state_inclusion(verifier, element, document);
+        }
+
+        for attr in &element.attributes {
+            if attr.xmlns {
+                continue;
+            }
+            if attr.name.prefix.is_some() {
+                let uri = attr.name.resolve_prefix(&element.namespace).ok();
+                if !uri.as_deref().is_some_and(MxmlComponents::is_language_namespace) {
+                    verifier.add_verify_error(&attr.name.location, FxDiagnosticKind::UnknownMxmlAttribute, diagarg![attr.name.to_string(&element.namespace), component.clone()]);
+                }
+                continue;
+            }
+            // Identifiers are declared by the document.
+            if attr.name.name == "id" || (!is_root && ["includeIn", "excludeFrom"].contains(&attr.name.name.as_str())) {
+                continue;
+            }
+            let r = Self::verify_member_value(verifier, element, component, document, &attr.name.name, &attr.name.location, &attr.value.0, &attr.value.1, None).is_err();
+            any_defer = any_defer || r;
+        }
+
+        let uri = element.name.resolve_prefix(&element.namespace).ok();
+        for child in child_elements(element.content.as_deref().unwrap_or(&[])) {
+            let Ok((child_uri, child_name)) = child.name.resolve_name(&child.namespace) else {
+                continue;
+            };
+
+            if MxmlComponents::is_language_namespace(&child_uri) {
+                if !Self::ROOT_LANGUAGE_TAGS.contains(&child_name.as_str()) {
+                    verifier.add_verify_error(&child.name.location, FxDiagnosticKind::UnknownMxmlLanguageTag, diagarg![child_name]);
+                } else if !is_root {
+                    verifier.add_verify_error(&child.name.location, FxDiagnosticKind::MxmlLanguageTagMustBeChildOfRoot, diagarg![child_name]);
+                } else if child_name == "Declarations" {
+                    // Non-visual components
+                    for declaration in child_elements(child.content.as_deref().unwrap_or(&[])) {
+                        if let Some(class) = Self::resolve_component(verifier, &declaration) {
+                            let r = Self::verify_component(verifier, &declaration, &class, document, false).is_err();
+                            any_defer = any_defer || r;
+                        }
+                    }
+                }
+                continue;
+            }
+
+            // Property, style or event tag
+            if uri.as_ref() == Some(&child_uri) && Self::is_member(verifier, component, MxmlState::split_name(&child_name).0)? {
+                let r = Self::verify_member_tag(verifier, element, &child, component, document, &child_name).is_err();
+                any_defer = any_defer || r;
+                continue;
+            }
+
+            if let Some(child_class) = Self::resolve_component(verifier, &child) {
+                let r = Self::verify_component(verifier, &child, &child_class, document, false).is_err();
+                any_defer = any_defer || r;
+            }
+        }
+
+        if any_defer { Err(DeferError(None)) } else { Ok(()) }
+    }
+
+    /// Verifies a tag that assigns a property or style, or handles an event, of its parent component.
+    fn verify_member_tag(verifier: &mut Subverifier, parent: &Rc<MxmlElement>, element: &Rc<MxmlElement>, component: &Thingy, document: &Thingy, name: &str) -> Result<(), DeferError> {
+        let children = child_elements(element.content.as_deref().unwrap_or(&[]));
+        if children.is_empty() {
+            let text = element.inner_text();
+            // The value of a single text or CDATA node may be a binding.
+            let value_location = match element.content.as_deref() {
+                Some([content]) => match content.as_ref() {
+                    MxmlContent::Characters((_, location)) => location.clone(),
+                    MxmlContent::CData((_, location)) => Location::with_offsets(&location.compilation_unit(), location.first_offset() + Self::CDATA_START.len(), location.last_offset() - Self::CDATA_END.len()),
+                    _ => element.location.clone(),
+                },
+                _ => element.location.clone(),
+            };
+            return Self::verify_member_value(verifier, parent, component, document, name, &element.name.location, text.trim(), &value_location, Some(element));
+        }
+
+        let (property_name, state) = MxmlState::split_name(name);
+        if let Some(state) = state {
+            let Some(states) = Self::resolve_state(verifier, document, state, &element.name.location) else {
+                return Ok(());
+            };
+            Self::add_state_override(&states, MxmlStateOverride::SetProperty(MxmlStateValue {
+                element: parent.clone(),
+                name: property_name.to_owned(),
+                value: String::new(),
+                location: element.location.clone(),
+            }));
+        }
+
+        let mut any_defer = false;
+        let property = Self::lookup_property(verifier, component, property_name)?;
+        for child in &children {
+            let Some(child_class) = Self::resolve_component(verifier, child) else {
+                continue;
+            };
+            if let Some((property_type, _)) = property.as_ref() {
+                // A single component is assigned as is; several components form an Array.
+                let property_type = property_type.escape_of_nullable_or_non_nullable();
+                let host = verifier.host.clone();
+                let array_type = host.array_type().defer()?;
+                if children.len() == 1 && property_type != array_type && !child_class.is_equals_or_subtype_of(&property_type, &host)? {
+                    verifier.add_verify_error(&child.name.location, FxDiagnosticKind::ImplicitCoercionToUnrelatedType, diagarg![child_class.clone(), property_type]);
+                }
+            }
+            let r = Self::verify_component(verifier, child, &child_class, document, false).is_err();
+            any_defer = any_defer || r;
+        }
+        if any_defer { Err(DeferError(None)) } else { Ok(()) }
+    }
+
+    /// Verifies the text value of a property, style or event of a component. `tag` is
+    /// the property tag giving the value, or `None` if an attribute gives it.
+    #[allow(clippy::too_many_arguments)]
+    fn verify_member_value(verifier: &mut Subverifier, element: &Rc<MxmlElement>, component: &Thingy, document: &Thingy, full_name: &str, name_location: &Location, value: &str, value_location: &Location, tag: Option<&Rc<MxmlElement>>) -> Result<(), DeferError> {
+        let host = verifier.host.clone();
+        let (name, state) = MxmlState::split_name(full_name);
+        let states = match state {
+            Some(state) => {
+                let Some(states) = Self::resolve_state(verifier, document, state, name_location) else {
+                    return Ok(());
+                };
+                states
+            },
+            None => vec![],
+        };
+        let state_value = MxmlStateValue {
+            element: element.clone(),
+            name: name.to_owned(),
+            value: value.to_owned(),
+            location: value_location.clone(),
+        };
+
+        if let Some((property_type, read_only)) = Self::lookup_property(verifier, component, name)? {
+            if read_only {
+                verifier.add_verify_error(name_location, FxDiagnosticKind::EntityIsReadOnly, diagarg![]);
+            } else if !Self::verify_binding(verifier, element, document, name, state, &property_type, value, value_location, tag)? {
+                Self::verify_text_value(verifier, value, value_location, &property_type)?;
+            }
+            Self::add_state_override(&states, MxmlStateOverride::SetProperty(state_value));
+            return Ok(());
+        }
+
+        if let Some(event_type) = FlexEvents(&host).lookup(component, name) {
+            Self::add_state_override(&states, MxmlStateOverride::SetEventHandler(state_value));
+            let directives = Self::event_handler_code(verifier, element, full_name, value_location, tag);
+            return Self::verify_event_handler(verifier, &directives, name, document, &event_type);
+        }
+
+        if MxmlComponents(&host).lookup_style(component, name).is_some() {
+            Self::add_state_override(&states, MxmlStateOverride::SetStyle(state_value));
+            return Ok(());
+        }
+
+        verifier.add_verify_error(name_location, FxDiagnosticKind::UnknownMxmlAttribute, diagarg![name.to_owned(), component.clone()]);
+        Ok(())
+    }
+
+    fn is_member(verifier: &mut Subverifier, component: &Thingy, name: &str) -> Result<bool, DeferError> {
+        let host = verifier.host.clone();
+        Ok(Self::lookup_property(verifier, component, name)?.is_some()
+            || FlexEvents(&host).lookup(component, name).is_some()
+            || MxmlComponents(&host).lookup_style(component, name).is_some())
+    }
+
+    /// Looks up an instance property of a component, returning its
+    /// static type and whether it is read-only.
+    fn lookup_property(verifier: &mut Subverifier, component: &Thingy, name: &str) -> Result<Option<(Thingy, bool)>, DeferError> {
+        let host = verifier.host.clone();
+        let value = host.factory().create_value(component);
+        let open_ns_set = verifier.scope().concat_open_ns_set_of_scope_chain();
+        match PropertyLookup(&host).lookup_in_object(&value, &open_ns_set, None, &PropertyLookupKey::LocalName(name.to_owned())) {
+            Ok(Some(r)) => {
+                if r.is::<DynamicReferenceValue>() || r.is::<XmlReferenceValue>() || r.is::<InvalidationThingy>() {
+                    return Ok(None);
+                }
+                let static_type = r.static_type(&host).defer()?;
+                Ok(Some((static_type, r.read_only(&host))))
+            },
+            Ok(None) => Ok(None),
+            Err(PropertyLookupError::Defer) => Err(DeferError(None)),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Verifies a value if it is a data binding, returning whether it is. `location` is the location
+    /// of the value, which includes the quotes if `tag` is `None`, that is, if an attribute gives it.
+    /// The source expressions are parsed from the value with its XML entities decoded.
+    ///
+    /// The source expressions are verified in an activation where `this` is the document. A
+    /// one-way binding is coerced to the property type, and text with embedded bindings is a `String`.
+    /// The source of a two-way binding must be writable and the property type must be coercible back
+    /// to its type. Accessed properties that are not `[Bindable]` are reported as warnings.
+    #[allow(clippy::too_many_arguments)]
+    fn verify_binding(verifier: &mut Subverifier, element: &Rc<MxmlElement>, document: &Thingy, property_name: &str, state: Option<&str>, property_type: &Thingy, value: &str, location: &Location, tag: Option<&Rc<MxmlElement>>) -> Result<bool, DeferError> {
+        let host = verifier.host.clone();
+        let is_two_way = |value: &str| value.trim().starts_with("@{") && value.trim().ends_with('}');
+        if !is_two_way(value) && MxmlBinding::split(value).is_none() {
+            return Ok(false);
+        }
+
+        let key = (NodeAsKey(element.clone()), format!("{property_name}.{}", state.unwrap_or_default()));
+        let code = match verifier.mxml_binding_code.get(&key) {
+            Some((code, _)) => code.clone(),
+            None => Self::value_code(location, tag),
+        };
+        let text = code.text();
+        let start = code.byte_range.map_or(0, |(start, _)| start);
+        let two_way = is_two_way(&text);
+        let (text_parts, ranges) = if two_way {
+            let start = start + (text.len() - text.trim_start().len());
+            (None, vec![(start + 2, start + text.trim().len() - 1)])
+        } else {
+            let Some((text_parts, ranges)) = MxmlBinding::split(&text) else {
+                return Ok(false);
+            };
+            let embedded = ranges.len() > 1 || text_parts.iter().any(|p| !p.trim().is_empty());
+            let ranges = ranges.into_iter().map(|r| (start + r.start, start + r.end)).collect();
+            (embedded.then_some(text_parts), ranges)
+        };
+
+        let sources = match verifier.mxml_binding_code.get(&key) {
+            Some((_, sources)) => sources.clone(),
+            None => {
+                let sources: Rc<[Rc<Expression>]> = ranges.iter().map(|range| {
+                    ParserFacade(&code.compilation_unit, ParserOptions {
+                        byte_range: Some(*range),
+                        ..default()
+                    }).parse_expression()
+                }).collect();
+                verifier.mxml_binding_code.insert(key, (code.clone(), sources.clone()));
+                sources
+            },
+        };
+
+        let signature = host.factory().create_function_type(vec![], host.void_type());
+        let activation = Self::create_document_activation(&host, document, property_name, &signature);
+        verifier.inherit_and_enter_scope(&activation);
+        let r = Self::verify_binding_sources(verifier, &sources, property_type, text_parts.is_some(), two_way, location);
+        verifier.exit_scope();
+        r?;
+
+        let mut watched_properties = vec![];
+        for source in sources.iter() {
+            Self::collect_watched_properties(verifier, source, &mut watched_properties);
+        }
+        let mut bindings = document.mxml_bindings();
+        if !bindings.iter().any(|b| Rc::ptr_eq(&b.element, element) && b.property_name == property_name && b.state.as_deref() == state) {
+            bindings.push(Rc::new(MxmlBinding {
+                element: element.clone(),
+                property_name: property_name.to_owned(),
+                state: state.map(|s| s.to_owned()),
+                property_type: property_type.clone(),
+                code,
+                sources: sources.to_vec(),
+                text_parts,
+                watched_properties,
+                two_way,
+                location: location.clone(),
+            }));
+        }
+        Ok(true)
+    }
+
+    /// Returns the code of a value, decoding its XML entities unless it is in
+    /// a CDATA section. `location` and `tag` are as in `verify_binding()`.
+    fn value_code(location: &Location, tag: Option<&Rc<MxmlElement>>) -> Rc<MxmlCode> {
+        let cu = location.compilation_unit();
+        match tag {
+            None => Self::decode_code(&cu, location.first_offset() + 1, location.last_offset() - 1),
+            Some(tag) if matches!(tag.content.as_deref(), Some([content]) if matches!(content.as_ref(), MxmlContent::CData(_))) => {
+                MxmlCode::new(&cu, Some((location.first_offset(), location.last_offset())))
+            },
+            Some(_) => Self::decode_code(&cu, location.first_offset(), location.last_offset()),
+        }
+    }
+
+    fn verify_binding_sources(verifier: &mut Subverifier, sources: &[Rc<Expression>], property_type: &Thingy, embedded: bool, two_way: bool, location: &Location) -> Result<(), DeferError> {
+        let host = verifier.host.clone();
+        if embedded {
+            for source in sources {
+                verifier.verify_expression(source, &default())?;
+            }
+            let string_type = host.string_type().defer()?;
+            if TypeConversions(&host).implicit(&host.factory().create_value(&string_type), property_type, false)?.is_none() {
+                verifier.add_verify_error(location, FxDiagnosticKind::ImplicitCoercionToUnrelatedType, diagarg![string_type, property_type.clone()]);
+            }
+            return Ok(());
+        }
+
+        let source = &sources[0];
+        let Some(v) = verifier.verify_expression(source, &VerifierExpressionContext {
+            context_type: Some(property_type.clone()),
+            ..default()
+        })? else {
+            return Ok(());
+        };
+        let source_type = v.static_type(&host);
+        if TypeConversions(&host).implicit(&v, property_type, false)?.is_none() {
+            verifier.add_verify_error(&source.location(), FxDiagnosticKind::ImplicitCoercionToUnrelatedType, diagarg![source_type.clone(), property_type.clone()]);
+            return Ok(());
+        }
+        if two_way {
+            if v.read_only(&host) {
+                verifier.add_verify_error(&source.location(), FxDiagnosticKind::TwoWayBindingSourceNotWritable, diagarg![]);
+            } else if TypeConversions(&host).implicit(&host.factory().create_value(property_type), &source_type, false)?.is_none() {
+                verifier.add_verify_error(&source.location(), FxDiagnosticKind::ImplicitCoercionToUnrelatedType, diagarg![property_type.clone(), source_type]);
+            }
+        }
+        Ok(())
+    }
+
+    /// Collects the bindable properties accessed by a verified expression,
+    /// warning about accessed variables and accessors that are not bindable.
+    fn collect_watched_properties(verifier: &mut Subverifier, exp: &Rc<Expression>, watched: &mut Vec<(Thingy, String)>) {
+        match exp.as_ref() {
+            Expression::QualifiedIdentifier(_) => {},
+            Expression::Member(e) => Self::collect_watched_properties(verifier, &e.base, watched),
+            Expression::ComputedMember(e) => {
+                Self::collect_watched_properties(verifier, &e.base, watched);
+                Self::collect_watched_properties(verifier, &e.key, watched);
+            },
+            Expression::Paren(e) => Self::collect_watched_properties(verifier, &e.expression, watched),
+            Expression::Unary(e) => Self::collect_watched_properties(verifier, &e.expression, watched),
+            Expression::Binary(e) => {
+                Self::collect_watched_properties(verifier, &e.left, watched);
+                Self::collect_watched_properties(verifier, &e.right, watched);
+            },
+            Expression::Sequence(e) => {
+                Self::collect_watched_properties(verifier, &e.left, watched);
+                Self::collect_watched_properties(verifier, &e.right, watched);
+            },
+            Expression::Conditional(e) => {
+                Self::collect_watched_properties(verifier, &e.test, watched);
+                Self::collect_watched_properties(verifier, &e.consequent, watched);
+                Self::collect_watched_properties(verifier, &e.alternative, watched);
+            },
+            Expression::Call(e) => {
+                Self::collect_watched_properties(verifier, &e.base, watched);
+                for argument in &e.arguments {
+                    Self::collect_watched_properties(verifier, argument, watched);
+                }
+            },
+            _ => return,
+        }
+        if !matches!(exp.as_ref(), Expression::QualifiedIdentifier(_) | Expression::Member(_)) {
+            return;
+        }
+        let Some(reference) = verifier.host.node_mapping().get(exp).filter(|r| r.is::<FixtureReferenceValue>()) else {
+            return;
+        };
+        let property = reference.property();
+        if !(property.is::<VariableSlot>() || property.is::<VirtualSlot>()) {
+            return;
+        }
+        if let Some(event) = property.bindable_event() {
+            if !watched.iter().any(|(p, _)| *p == property) {
+                watched.push((property, event));
+            }
+        } else if !(property.is::<VariableSlot>() && property.read_only(&verifier.host)) {
+            verifier.add_warning(&exp.location(), FxDiagnosticKind::BindingSourceNotBindable, diagarg![property.name().to_string()]);
+        }
+    }
+
+    /// Verifies that a text value is assignable to a type.
+    fn verify_text_value(verifier: &mut Subverifier, value: &str, location: &Location, target_type: &Thingy) -> Result<(), DeferError> {
+        let host = verifier.host.clone();
+        let target_type = target_type.escape_of_nullable_or_non_nullable();
+        let value = value.trim();
+        let valid = if target_type == host.boolean_type().defer()? {
+            value == "true" || value == "false"
+        } else if host.floating_point_types()?.contains(&target_type) {
+            value.strip_suffix('%').unwrap_or(value).trim().parse::<f64>().is_ok()
+        } else if target_type == host.int_type().defer()? {
+            Self::parse_integer(value).is_some_and(|v| i32::try_from(v).is_ok())
+        } else if target_type == host.uint_type().defer()? {
+            Self::parse_integer(value).is_some_and(|v| u32::try_from(v).is_ok())
+        } else if [host.any_type(), host.object_type().defer()?, host.string_type().defer()?, host.class_type().defer()?, host.array_type().defer()?].contains(&target_type) {
+            // Text other than an `[...]` list is a single-element Array.
+            true
+        } else {
+            verifier.add_verify_error(location, FxDiagnosticKind::MxmlTypeNotRepresentableInText, diagarg![target_type]);
+            return Ok(());
+        };
+        if !valid {
+            verifier.add_verify_error(location, FxDiagnosticKind::MxmlTextNotAssignable, diagarg![value.to_owned(), target_type]);
+        }
+        Ok(())
+    }
+
+    /// Parses an integer with an optional sign, possibly hexadecimal
+    /// in the form `0xFF` or `#FF`.
+    fn parse_integer(value: &str) -> Option<i64> {
+        let (negative, value) = match value.strip_prefix('-') {
+            Some(value) => (true, value),
+            None => (false, value.strip_prefix('+').unwrap_or(value)),
+        };
+        let (radix, digits) = match value.strip_prefix("0x").or(value.strip_prefix("0X")).or(value.strip_prefix('#')) {
+            Some(hex) => (16, hex),
+            None => (10, value),
+        };
+        // `from_str_radix` accepts a sign of its own.
+        if digits.starts_with(['-', '+']) {
+            return None;
+        }
+        let v = i64::from_str_radix(digits, radix).ok()?;
+        Some(if negative { -v } else { v })
+    }
+
+    /// Returns the parsed code of an event handler given by the attribute `name`
+    /// of a component tag, or by the property tag `tag`.
+    fn event_handler_code(verifier: &mut Subverifier, element: &Rc<MxmlElement>, name: &str, value_location: &Location, tag: Option<&Rc<MxmlElement>>) -> Rc<Vec<Rc<Directive>>> {
+        let key = match tag {
+            Some(tag) => (NodeAsKey(tag.clone()), String::new()),
+            None => (NodeAsKey(element.clone()), name.to_owned()),
+        };
+        if let Some(directives) = verifier.mxml_handler_code.get(&key) {
+            return directives.clone();
+        }
+        let code = match tag {
+            Some(tag) => Self::tag_code(verifier, tag),
+            // The code of an attribute excludes the quotes.
+            None => vec![Self::decode_code(&value_location.compilation_unit(), value_location.first_offset() + 1, value_location.last_offset() - 1)],
+        };
+        let directives = Rc::new(Self::parse_code(&code, ParserDirectiveContext::Default));
+        verifier.mxml_handler_code.insert(key, directives.clone());
+        directives
+    }
+
+    /// Verifies an event handler in an activation of a method taking the event,
+    /// where `this` is the document.
+    fn verify_event_handler(verifier: &mut Subverifier, directives: &[Rc<Directive>], name: &str, document: &Thingy, event_type: &Thingy) -> Result<(), DeferError> {
+        let host = verifier.host.clone();
+
+        let ns = host.top_level_package().public_ns().unwrap();
+        let signature = host.factory().create_function_type(vec![Rc::new(SemanticFunctionTypeParameter {
+            kind: ParameterKind::Required,
+            static_type: event_type.clone(),
+        })], host.void_type());
+        let activation = Self::create_document_activation(&host, document, name, &signature);
+        let event = host.factory().create_variable_slot(&host.factory().create_qname(&ns, "event".into()), false, event_type);
+        event.set_parent(Some(activation.clone()));
+        activation.properties(&host).set(event.name(), event);
+
+        verifier.inherit_and_enter_scope(&activation);
+        let r = Self::verify_code(verifier, directives);
+        verifier.exit_scope();
+        r
+    }
+
+    /// Creates the activation of a method of a document, where `this` is the document.
+    fn create_document_activation(host: &SemanticHost, document: &Thingy, name: &str, signature: &Thingy) -> Thingy {
+        let ns = host.top_level_package().public_ns().unwrap();
+        let method = host.factory().create_method_slot(&host.factory().create_qname(&ns, name.to_owned()), signature);
+        let activation = host.factory().create_activation(&method);
+        activation.set_this(Some(host.factory().create_this_object(document)));
+        activation
+    }
+
+    /// Verifies the `<fx:Script>` tags of a document in its class scope.
+    fn verify_scripts(verifier: &mut Subverifier, root: &Rc<MxmlElement>) -> Result<(), DeferError> {
+        let mut any_defer = false;
+        for child in Self::language_tags(root, "Script") {
+            let directives = match verifier.mxml_script_code.get(&NodeAsKey(child.clone())) {
+                Some(directives) => directives.clone(),
+                None => {
+                    let directives = Rc::new(Self::parse_script(verifier, &child));
+                    verifier.mxml_script_code.insert(NodeAsKey(child.clone()), directives.clone());
+                    directives
+                },
+            };
+            let r = DirectiveSubverifier::verify_directives(verifier, &directives).is_err();
+            any_defer = any_defer || r;
+        }
+        if any_defer { Err(DeferError(None)) } else { Ok(()) }
+    }
+
+    /// Parses the code of a `<fx:Script>` tag.
+    fn parse_script(verifier: &mut Subverifier, element: &Rc<MxmlElement>) -> Vec<Rc<Directive>> {
+        let cu = element.location.compilation_unit();
+        let class_name = cu.file_path()
+            .and_then(|p| Path::new(&p).file_stem().map(|s| s.to_string_lossy().into_owned()))
+            .unwrap_or_default();
+        let context = ParserDirectiveContext::ClassBlock { name: class_name };
+        let code = Self::tag_code(verifier, element);
+        Self::parse_code(&code, context)
+    }
+
+    /// Parses pieces of code as a single sequence of directives.
+    fn parse_code(code: &[Rc<MxmlCode>], context: ParserDirectiveContext) -> Vec<Rc<Directive>> {
+        code.iter().flat_map(|code| {
+            ParserFacade(&code.compilation_unit, ParserOptions {
+                byte_range: code.byte_range,
+                ..default()
+            }).parse_directives(context.clone())
+        }).collect()
+    }
+
+    /// Returns the code of a tag, either inline, in text or CDATA nodes, or in the file
+    /// given by the `source` attribute, which is added as a nested compilation unit.
+    fn tag_code(verifier: &mut Subverifier, element: &Rc<MxmlElement>) -> Vec<Rc<MxmlCode>> {
+        let cu = element.location.compilation_unit();
+        if let Some(source) = Self::find_attribute(element, "source") {
+            let directory = cu.file_path().and_then(|p| Path::new(&p).parent().map(|d| d.to_owned())).unwrap_or_default();
+            let path = directory.join(&source.value.0).to_string_lossy().into_owned();
+            let Ok(text) = fs::read_to_string(&path) else {
+                verifier.add_verify_error(&source.value.1, FxDiagnosticKind::CouldNotReadSource, diagarg![source.value.0.clone()]);
+                return vec![];
+            };
+            let source_cu = CompilationUnit::new(Some(path), text);
+            source_cu.set_compiler_options(cu.compiler_options());
+            cu.add_nested_compilation_unit(source_cu.clone());
+            return vec![MxmlCode::new(&source_cu, None)];
+        }
+        element.content.as_deref().unwrap_or(&[]).iter().filter_map(|content| match content.as_ref() {
+            MxmlContent::CData((_, location)) => Some(MxmlCode::new(&cu, Some((location.first_offset() + Self::CDATA_START.len(), location.last_offset() - Self::CDATA_END.len())))),
+            MxmlContent::Characters((_, location)) => Some(Self::decode_code(&cu, location.first_offset(), location.last_offset())),
+            _ => None,
+        }).collect()
+    }
+
+    /// Decodes the code in the byte range `start..end` of a document, nesting
+    /// the compilation unit of decoded code in the document's.
+    fn decode_code(document: &Rc<CompilationUnit>, start: usize, end: usize) -> Rc<MxmlCode> {
+        let code = MxmlCode::decode(document, start, end);
+        if code.is_decoded() {
+            document.add_nested_compilation_unit(code.compilation_unit.clone());
+        }
+        code
+    }
+
+    /// Returns the language tags of a given name that are children of the root tag.
+    pub fn language_tags(root: &Rc<MxmlElement>, name: &str) -> Vec<Rc<MxmlElement>> {
+        child_elements(root.content.as_deref().unwrap_or(&[])).into_iter().filter(|child| {
+            child.name.resolve_name(&child.namespace).is_ok_and(|(uri, local_name)| MxmlComponents::is_language_namespace(&uri) && local_name == name)
+        }).collect()
+    }
+
+    /// Verifies code such as an event handler.
+    fn verify_code(verifier: &mut Subverifier, directives: &[Rc<Directive>]) -> Result<(), DeferError> {
+        let mut any_defer = false;
+        for drtv in directives {
+            let r = if let Directive::ExpressionStatement(stmt) = drtv.as_ref() {
+                verifier.verify_expression(&stmt.expression, &default()).map(|_| ())
+            } else {
+                DirectiveSubverifier::verify_directive(verifier, drtv)
+            }.is_err();
+            any_defer = any_defer || r;
+        }
+        if any_defer { Err(DeferError(None)) } else { Ok(()) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ns::*;
+
+    fn create_host() -> Rc<SemanticHost> {
+        let host = Rc::new(SemanticHost::new(SemanticHostOptions::default()));
+        let top_level = host.top_level_package();
+        let object_type = define_class(&host, &top_level, "Object", None);
+        for name in ["Class", "Function", "String", "Boolean", "Number", "int", "uint", "float", "Namespace", "Array", "XML", "XMLList"] {
+            define_class(&host, &top_level, name, Some(&object_type));
+        }
+        define_class(&host, &host.factory().create_package(["flash", "utils"]), "Dictionary", Some(&object_type));
+        host
+    }
+
+    fn verify(host: &Rc<SemanticHost>, text: &str) -> (Thingy, Vec<String>) {
+        let cu = CompilationUnit::new(Some("/project/src/com/example/Main.mxml".into()), text.into());
+        cu.set_compiler_options(Some(Rc::new(CompilerOptions {
+            source_path: vec!["/project/src".into()],
+            ..default()
+        })));
+        let mxml = ParserFacade(&cu, ParserOptions::default()).parse_mxml();
+        let mut verifier = Verifier::new(host);
+        verifier.verify_mxml_list(std::slice::from_ref(&mxml));
+        let messages = cu.nested_diagnostics().iter().map(|d| FxDiagnostic(d).format_message_english()).collect();
+        (host.node_mapping().get(&mxml).unwrap(), messages)
+    }
+
+    #[test]
+    fn test_mxml() {
+        let host = create_host();
+        let object_type = host.object_type();
+        let flash_events = host.factory().create_package(["flash", "events"]);
+        let event = define_class(&host, &flash_events, "Event", Some(&object_type));
+        let mouse_event = define_class(&host, &flash_events, "MouseEvent", Some(&event));
+        define_property(&host, &mouse_event, "localX", false, &host.number_type());
+
+        let spark = host.factory().create_package(["spark", "components"]);
+        let group = define_class(&host, &spark, "Group", Some(&object_type));
+        let application = define_class(&host, &spark, "Application", Some(&group));
+        let title = define_property(&host, &application, "title", false, &host.string_type());
+        title.set_bindable_event(Some("titleChange".into()));
+        define_property(&host, &application, "status", false, &host.string_type());
+        define_property(&host, &application, "version", true, &host.string_type());
+        let button = define_class(&host, &spark, "Button", Some(&object_type));
+        define_property(&host, &button, "label", false, &host.string_type());
+        define_property(&host, &button, "enabled", false, &host.boolean_type());
+        define_property(&host, &button, "width", false, &host.number_type());
+        define_property(&host, &button, "measured", true, &host.number_type());
+        let cu = CompilationUnit::new(None, "[Event(name=\"click\", type=\"flash.events.MouseEvent\")] [Style(name=\"color\")] class Button {}".into());
+        let program = ParserFacade(&cu, ParserOptions::default()).parse_program();
+        let Directive::ClassDefinition(defn) = program.directives[0].as_ref() else {
+            panic!();
+        };
+        for m in Attribute::find_metadata(&defn.attributes) {
+            button.metadata().push(m);
+        }
+        FlexEvents(&host).apply(&button);
+        assert!(button.flex_events().get(&"click".to_owned()) == Some(mouse_event.clone()));
+        for name in ["Application", "Button"] {
+            host.define_mxml_component("library://ns.hydroper.com/razen/spark", name, &format!("spark.components.{name}"));
+        }
+        define_class(&host, &host.factory().create_package(["com", "example"]), "Helper", Some(&object_type));
+
+        let (class, messages) = verify(&host, r#"<?xml version="1.0"?>
+<s:Application xmlns:fx="http://ns.adobe.com/mxml/2009" xmlns:s="library://ns.hydroper.com/razen/spark" xmlns:c="com.example.*">
+    <fx:Script><![CDATA[
+        private function initialize(): void {}
+    ]]></fx:Script>
+    <fx:Script source="Missing.as"/>
+    <s:Button id="go" label="Go" enabled="yes" width="50%" color="red" click="event.localX" size="10"/>
+    <s:Button measured="1" click="event.foo"/>
+    <s:Button>
+        <s:label>Hello</s:label>
+        <s:width>wide</s:width>
+    </s:Button>
+    <s:Unknown/>
+    <c:Helper/>
+    <fx:Foo/>
+    <s:Button label="{title}" width="{title}"/>
+    <s:Button label="Hello, {status}! \{}"/>
+    <s:Button label="@{version}"/>
+    <s:Button>
+        <s:label><![CDATA[{version}]]></s:label>
+    </s:Button>
+</s:Application>"#);
+        assert_eq!(messages, [
+            "Could not read the source 'Missing.as'.",
+            "The text 'yes' is not a valid Boolean value.",
+            "'size' is not a property, style or event of spark.components.Button.",
+            "Entity is read-only.",
+            "Access of possibly undefined property foo through a reference with static type flash.events.MouseEvent.",
+            "The text 'wide' is not a valid Number value.",
+            "Could not resolve the MXML component 'library://ns.hydroper.com/razen/spark:Unknown'.",
+            "Unknown MXML language tag: 'Foo'.",
+            "Implicit coercion of a value of type String to an unrelated type Number.",
+            "Data binding will not be able to detect assignments to 'status'.",
+            "The source of a two-way binding must be writable.",
+        ]);
+
+        assert!(class.parent() == Some(host.factory().create_package(["com", "example"])));
+        assert_eq!(class.name().local_name(), "Main");
+        assert!(class.extends_class(&host) == Some(application));
+
+        let bindings: Vec<Rc<MxmlBinding>> = class.mxml_bindings().iter().collect();
+        assert_eq!(bindings.len(), 5);
+        assert_eq!(bindings[0].property_name, "label");
+        assert!(bindings[0].watched_properties == [(title, "titleChange".to_owned())]);
+        assert!(bindings[0].text_parts.is_none());
+        assert!(bindings[2].watched_properties.is_empty());
+        assert_eq!(bindings[2].text_parts.as_deref(), Some(["Hello, ".to_owned(), "! {}".to_owned()].as_slice()));
+        assert!(bindings[3].two_way);
+        // Constants do not need to be watched.
+        assert!(bindings[4].watched_properties.is_empty() && !bindings[4].two_way);
+    }
+
+    #[test]
+    fn test_mxml_states() {
+        let host = create_host();
+        let object_type = host.object_type();
+        let spark = host.factory().create_package(["spark", "components"]);
+        let application = define_class(&host, &spark, "Application", Some(&object_type));
+        define_property(&host, &application, "states", false, &host.array_type());
+        let state = define_class(&host, &spark, "State", Some(&object_type));
+        define_property(&host, &state, "name", false, &host.string_type());
+        define_property(&host, &state, "stateGroups", false, &host.array_type());
+        let button = define_class(&host, &spark, "Button", Some(&object_type));
+        define_property(&host, &button, "label", false, &host.string_type());
+        define_property(&host, &button, "enabled", false, &host.boolean_type());
+        for name in ["Application", "State", "Button"] {
+            host.define_mxml_component("library://ns.hydroper.com/razen/spark", name, &format!("spark.components.{name}"));
+        }
+
+        let (class, messages) = verify(&host, r#"<?xml version="1.0"?>
+<s:Application xmlns:s="library://ns.hydroper.com/razen/spark">
+    <s:states>
+        <s:State name="normal"/>
+        <s:State name="over" stateGroups="hovered"/>
+        <s:State name="down" stateGroups="hovered"/>
+        <s:State name="over"/>
+    </s:states>
+    <s:Button label="Normal" label.hovered="Hovered" enabled.down="maybe" label.disabled="Disabled"/>
+    <s:Button includeIn="down, missing"/>
+    <s:Button excludeFrom="over"/>
+    <s:Button includeIn="normal" excludeFrom="down"/>
+    <s:Button>
+        <s:label.normal>Label</s:label.normal>
+    </s:Button>
+</s:Application>"#);
+        assert_eq!(messages, [
+            "Duplicate state: 'over'.",
+            "The text 'maybe' is not a valid Boolean value.",
+            "Unknown state: 'disabled'.",
+            "Unknown state: 'missing'.",
+            "'includeIn' and 'excludeFrom' cannot be used on the same tag.",
+        ]);
+
+        let states: Vec<Rc<MxmlState>> = class.mxml_states().iter().collect();
+        let names: Vec<&str> = states.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["normal", "over", "down"]);
+        assert_eq!(states[1].state_groups, ["hovered"]);
+
+        let describe = |state: &MxmlState| -> Vec<String> {
+            state.overrides.iter().map(|o| match o {
+                MxmlStateOverride::AddChild(element) => format!("add {}", element.location.first_line_number()),
+                MxmlStateOverride::SetProperty(v) => format!("{}={}", v.name, v.value),
+                MxmlStateOverride::SetStyle(v) => format!("style {}={}", v.name, v.value),
+                MxmlStateOverride::SetEventHandler(v) => format!("handler {}={}", v.name, v.value),
+            }).collect()
+        };
+        assert_eq!(describe(&states[0]), ["add 11", "label=Label"]);
+        assert_eq!(describe(&states[1]), ["label=Hovered"]);
+        assert_eq!(describe(&states[2]), ["label=Hovered", "enabled=maybe", "add 10", "add 11"]);
+    }
+
+    #[test]
+    fn test_mxml_declarations() {
+        let host = create_host();
+        let object_type = host.object_type();
+        define_class(&host, &host.factory().create_package(["flash", "events"]), "Event", Some(&object_type));
+        let spark = host.factory().create_package(["spark", "components"]);
+        define_class(&host, &spark, "Application", Some(&object_type));
+        let button = define_class(&host, &spark, "Button", Some(&object_type));
+        define_property(&host, &button, "label", false, &host.string_type()).set_bindable_event(Some("propertyChange".into()));
+        let group = define_class(&host, &spark, "RadioButtonGroup", Some(&object_type));
+        define_property(&host, &group, "selectedValue", false, &object_type);
+        for name in ["Application", "Button", "RadioButtonGroup"] {
+            host.define_mxml_component("library://ns.hydroper.com/razen/spark", name, &format!("spark.components.{name}"));
+        }
+
+        let (class, messages) = verify(&host, r#"<?xml version="1.0"?>
+<s:Application xmlns:fx="http://ns.adobe.com/mxml/2009" xmlns:s="library://ns.hydroper.com/razen/spark">
+    <fx:Metadata>
+        [Event(name="done", type="flash.events.Event")]
+        [Event("bad")]
+    </fx:Metadata>
+    <fx:Metadata>[1]</fx:Metadata>
+    <fx:Style><![CDATA[
+        @namespace s "library://ns.hydroper.com/razen/spark";
+        s|Button { color: #FF0000; }
+    ]]></fx:Style>
+    <fx:Declarations>
+        <s:RadioButtonGroup id="group" selectedValue="{first.label}"/>
+    </fx:Declarations>
+    <fx:Script><![CDATA[
+        private function firstLabel(): String {
+            return first.label;
+        }
+    ]]></fx:Script>
+    <s:Button id="first" label="First"/>
+    <s:Button id="first" label="{group}"/>
+</s:Application>"#);
+        assert_eq!(messages, [
+            "Unrecognized meta-data syntax.",
+            "Malformed Event meta-data.",
+            "Duplicate identifier: 'first'.",
+            "Implicit coercion of a value of type spark.components.RadioButtonGroup to an unrelated type String.",
+            "'color' is not a style of spark.components.Button.",
+        ]);
+
+        assert!(class.flex_events().has(&"done".to_owned()));
+        assert_eq!(class.style_sheets().length(), 1);
+
+        let ns = host.top_level_package().public_ns().unwrap();
+        let first = class.prototype(&host).get(&host.factory().create_qname(&ns, "first".into())).unwrap();
+        assert!(first.static_type(&host) == button);
+        assert_eq!(first.bindable_event(), Some("propertyChange".into()));
+        let group_field = class.prototype(&host).get(&host.factory().create_qname(&ns, "group".into())).unwrap();
+        assert!(group_field.static_type(&host) == group);
+        assert_eq!(class.mxml_bindings().length(), 2);
+    }
+
+    #[test]
+    fn test_mxml_bindable() {
+        let host = create_host();
+        let object_type = host.object_type();
+        let spark = host.factory().create_package(["spark", "components"]);
+        define_class(&host, &spark, "Application", Some(&object_type));
+        let button = define_class(&host, &spark, "Button", Some(&object_type));
+        for name in ["Application", "Button"] {
+            host.define_mxml_component("library://ns.hydroper.com/razen/spark", name, &format!("spark.components.{name}"));
+        }
+
+        let (class, messages) = verify(&host, r#"<?xml version="1.0"?>
+<s:Application xmlns:fx="http://ns.adobe.com/mxml/2009" xmlns:s="library://ns.hydroper.com/razen/spark">
+    <fx:Metadata>[Bindable("firstChange")]</fx:Metadata>
+    <s:Button id="first"/>
+</s:Application>"#);
+        assert!(messages.is_empty(), "{messages:?}");
+
+        let ns = host.top_level_package().public_ns().unwrap();
+        let first = class.prototype(&host).get(&host.factory().create_qname(&ns, "first".into())).unwrap();
+        assert!(first.is::<VirtualSlot>());
+        assert_eq!(first.bindable_event(), Some("firstChange".into()));
+        let backing = Bindable(&host).backing_variable(&first).unwrap();
+        assert!(backing.static_type(&host) == button);
+    }
+
+    #[test]
+    fn test_mxml_code() {
+        let host = create_host();
+        let object_type = host.object_type();
+        let flash_events = host.factory().create_package(["flash", "events"]);
+        let event = define_class(&host, &flash_events, "Event", Some(&object_type));
+        let mouse_event = define_class(&host, &flash_events, "MouseEvent", Some(&event));
+        define_property(&host, &mouse_event, "localX", false, &host.number_type());
+        let spark = host.factory().create_package(["spark", "components"]);
+        let group = define_class(&host, &spark, "Group", Some(&object_type));
+        define_property(&host, &group, "title", false, &host.string_type()).set_bindable_event(Some("titleChange".into()));
+        define_class(&host, &spark, "Application", Some(&group));
+        let button = define_class(&host, &spark, "Button", Some(&object_type));
+        define_property(&host, &button, "tabIndex", false, &host.int_type());
+        define_property(&host, &button, "label", false, &host.string_type());
+        let cu = CompilationUnit::new(None, "[Event(name=\"click\", type=\"flash.events.MouseEvent\")] class Button {}".into());
+        let program = ParserFacade(&cu, ParserOptions::default()).parse_program();
+        let Directive::ClassDefinition(defn) = program.directives[0].as_ref() else {
+            panic!();
+        };
+        for m in Attribute::find_metadata(&defn.attributes) {
+            button.metadata().push(m);
+        }
+        FlexEvents(&host).apply(&button);
+        for name in ["Application", "Button"] {
+            host.define_mxml_component("library://ns.hydroper.com/razen/spark", name, &format!("spark.components.{name}"));
+        }
+
+        let (class, messages) = verify(&host, r#"<?xml version="1.0"?>
+<s:Application xmlns:fx="http://ns.adobe.com/mxml/2009" xmlns:s="library://ns.hydroper.com/razen/spark">
+    <fx:Script>
+        private var ready: Boolean = true &amp;&amp; false;
+    </fx:Script>
+    <s:Button click="event.localX &amp;&amp; '&lt;&gt;' &amp;&amp; event.foo" tabIndex="-5"/>
+    <s:Button tabIndex="--5">
+        <s:click>event.bar</s:click>
+    </s:Button>
+    <s:Button tabIndex="+0x-5"/>
+    <s:Button id="title"/>
+    <s:Button label="{title &amp;&amp; title}"/>
+    <s:Button label="&lt;{title + '&gt;'}"/>
+</s:Application>"#);
+        assert_eq!(messages, [
+            "Duplicate identifier: 'title'.",
+            "The text '--5' is not a valid int value.",
+            "Access of possibly undefined property bar through a reference with static type flash.events.MouseEvent.",
+            "The text '+0x-5' is not a valid int value.",
+            // The decoded handler is parsed in its own compilation unit.
+            "Access of possibly undefined property foo through a reference with static type flash.events.MouseEvent.",
+        ]);
+
+        let bindings: Vec<Rc<MxmlBinding>> = class.mxml_bindings().iter().collect();
+        assert_eq!(bindings.len(), 2);
+        assert_eq!(bindings[0].sources[0].location().text(), "title && title");
+        assert_eq!(bindings[1].text_parts.as_deref(), Some(["<".to_owned(), String::new()].as_slice()));
+        assert_eq!(bindings[1].sources[0].location().text(), "title + '>'");
+    }
+}
@@ -41,7 +41,7 @@ impl ObjectLiteralSubverifier {
                     verifier.verify_expression(exp, &default())?;
                 },
                 InitializerField::Field { name, value, .. } => {
-                    if let Some(name) = field.shorthand() {
+                    if let Some(name) = field.shorthand().filter(|_| value.is_none()) {
                         let fr = verifier.host.lazy_node_mapping(field, || verifier.host.factory().create_field_resolution());
                         fr.set_shorthand_resolution(Self::verify_initializer_shorthand(verifier, name)?);
                     } else {
@@ -58,8 +58,8 @@ impl ObjectLiteralSubverifier {
 
     fn verify_object_initializer_for_options_class(verifier: &mut Subverifier, initializer: &ObjectInitializer, options_class: &Thingy) -> Result<(), DeferError> {
         let mut missing = HashSet::<Thingy>::new();
-        for (_, thingy) in options_class.prototype(&verifier.host).borrow().iter() {
-            if thingy.is::<VariableSlot>() && !thingy.is_opt_variable_for_options_class(&verifier.host)? {
+        for thingy in OptionsClass(&verifier.host).options(options_class) {
+            if !thingy.is_opt_variable_for_options_class(&verifier.host)? {
                 thingy.static_type(&verifier.host).defer()?;
                 missing.insert(thingy.clone());
             }
@@ -71,8 +71,8 @@ impl ObjectLiteralSubverifier {
                     verifier.imp_coerce_exp(exp, &options_class)?;
                     missing.clear();
                 },
-                InitializerField::Field { .. } => {
-                    if let Some(name) = field.shorthand() {
+                InitializerField::Field { value, .. } => {
+                    if let Some(name) = field.shorthand().filter(|_| value.is_none()) {
                         let variable = Self::resolve_instance_variable(verifier, &options_class, &name)?;
                         if let Some(variable) = variable.clone() {
                             missing.remove(&variable);
@@ -103,8 +103,12 @@ impl ObjectLiteralSubverifier {
             }
         }
 
-        for m in &missing {
-            verifier.add_verify_error(&initializer.location, FxDiagnosticKind::MustSpecifyOption, diagarg![m.name().to_string()]);
+        // Missing options are reported by a single diagnostic, since
+        // diagnostics at an equal offset are discarded.
+        let mut missing: Vec<String> = missing.iter().map(|m| m.name().to_string()).collect();
+        if !missing.is_empty() {
+            missing.sort();
+            verifier.add_verify_error(&initializer.location, FxDiagnosticKind::MustSpecifyOption, diagarg![missing.join(", ")]);
         }
 
         Ok(())
@@ -221,27 +225,39 @@ impl ObjectLiteralSubverifier {
         let local_name = key.local_name().unwrap();
 
         let open_ns_set = verifier.scope().concat_open_ns_set_of_scope_chain();
-        let lookup = PropertyLookup(&verifier.host).get_qname_in_ns_set_or_any_public_ns(&class.prototype(&verifier.host), &open_ns_set, qual, &local_name);
 
-        if lookup.is_err() {
-            match lookup.unwrap_err() {
-                PropertyLookupError::AmbiguousReference(name) => {
+        // Look up the class and its base classes
+        let mut lookup = None;
+        let mut class_1 = Some(class.clone());
+        while let Some(c) = class_1.filter(|c| c.is_class_type_possibly_after_sub()) {
+            let r = PropertyLookup(&verifier.host).get_qname_in_ns_set_or_any_public_ns(&c.prototype(&verifier.host), &open_ns_set, qual.clone(), &local_name);
+            match r {
+                Ok(Some(r)) => {
+                    lookup = Some(r);
+                    break;
+                },
+                Ok(None) => {},
+                Err(PropertyLookupError::AmbiguousReference(name)) => {
                     verifier.add_verify_error(&id.location, FxDiagnosticKind::AmbiguousReference, diagarg![name.clone()]);
                     return Ok(None);
                 },
-                PropertyLookupError::Defer => {
+                Err(PropertyLookupError::Defer) => {
                     return Err(DeferError(None));
                 },
-                _ => {
+                Err(_) => {
                     panic!();
                 },
             }
+            class_1 = c.extends_class(&verifier.host);
         }
 
-        let lookup = lookup.unwrap();
         let variable = lookup.and_then(|v| if v.is::<VariableSlot>() { Some(v) } else { None });
         if variable.is_none() {
-            verifier.add_verify_error(&id.location, FxDiagnosticKind::UnknownOptionForClass, diagarg![local_name]);
+            if let Some(suggestion) = OptionsClass(&verifier.host).closest_option_name(class, &local_name) {
+                verifier.add_verify_error(&id.location, FxDiagnosticKind::UnknownOptionForClassDidYouMean, diagarg![local_name, class.clone(), suggestion]);
+            } else {
+                verifier.add_verify_error(&id.location, FxDiagnosticKind::UnknownOptionForClass, diagarg![local_name, class.clone()]);
+            }
             return Ok(None);
         }
         Ok(Some(variable.unwrap()))
@@ -69,6 +69,9 @@ impl Verifier {
                 phase_of_block: HashMap::new(),
                 deferred_function_exp: SharedMap::new(),
                 definition_conflicts: SharedArray::new(),
+                mxml_script_code: HashMap::new(),
+                mxml_handler_code: HashMap::new(),
+                mxml_binding_code: HashMap::new(),
                 invalidated: false,
                 external: false,
                 // deferred_counter: 0,
@@ -112,6 +115,9 @@ impl Verifier {
         // Verify directives and then statements in the top-level of all programs.
         todo_here();
 
+        // Verify MXML documents.
+        self.verify_mxml_list(&mxml_list);
+
         // * [ ] Handle deferred function commons for lambdas.
         for _ in 0..Verifier::MAX_CYCLES {
             let mut any_defer = false;
@@ -173,6 +179,30 @@ impl Verifier {
         None
     }
 
+    /// Verifies MXML documents, declaring their classes first, as documents
+    /// may use each other as components.
+    pub(crate) fn verify_mxml_list(&mut self, mxml_list: &[Rc<Mxml>]) {
+        let mut undeclared = mxml_list.to_vec();
+        loop {
+            let count = undeclared.len();
+            undeclared.retain(|mxml| MxmlSubverifier::declare_document(&mut self.verifier, mxml).is_none());
+            if undeclared.len() == count {
+                break;
+            }
+        }
+
+        let mut rem_mxml_list = mxml_list.to_vec();
+        for _ in 0..Verifier::MAX_CYCLES {
+            rem_mxml_list.retain(|mxml| MxmlSubverifier::verify_mxml(&mut self.verifier, mxml).is_err());
+            if rem_mxml_list.is_empty() {
+                break;
+            }
+        }
+        for mxml in rem_mxml_list {
+            self.verifier.add_verify_error(&mxml.location, FxDiagnosticKind::ReachedMaximumCycles, diagarg![]);
+        }
+    }
+
     fn collect_package_definitions(program: &Rc<Program>) -> Vec<Rc<PackageDefinition>> {
         let mut r = program.packages.clone();
         for drtv in &program.directives {
@@ -229,6 +259,15 @@ pub(crate) struct Subverifier {
 
     pub definition_conflicts: SharedArray<(Thingy, Thingy)>,
 
+    /// Parsed code of MXML scripts, event handlers and data bindings.
+    pub mxml_script_code: HashMap<NodeAsKey<Rc<MxmlElement>>, Rc<Vec<Rc<Directive>>>>,
+    /// Event handlers are keyed by their component tag and attribute name,
+    /// or by their property tag and an empty name.
+    #[allow(clippy::type_complexity)]
+    pub mxml_handler_code: HashMap<(NodeAsKey<Rc<MxmlElement>>, String), Rc<Vec<Rc<Directive>>>>,
+    #[allow(clippy::type_complexity)]
+    pub mxml_binding_code: HashMap<(NodeAsKey<Rc<MxmlElement>>, String), (Rc<MxmlCode>, Rc<[Rc<Expression>]>)>,
+
     invalidated: bool,
     // pub deferred_counter: usize,
     pub scope: Option<Thingy>,
@@ -253,6 +292,9 @@ impl Subverifier {
         self.phase_of_drtv.clear();
         self.phase_of_block.clear();
         self.deferred_function_exp.clear();
+        self.mxml_script_code.clear();
+        self.mxml_handler_code.clear();
+        self.mxml_binding_code.clear();
     }
 
     pub fn lazy_init_drtv_phase(&mut self, drtv: &Rc<Directive>, initial_phase: VerifierPhase) -> VerifierPhase {